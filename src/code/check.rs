@@ -0,0 +1,221 @@
+use crate::{
+    code::{
+        execute::{ExecutionHandle, ProcessStatus},
+        snippet::SnippetLanguage,
+    },
+    presentation::builder::SnippetCommands,
+};
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    thread,
+    time::{Duration, Instant},
+};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Runs every snippet in a presentation and reports how each of them went.
+pub(crate) struct SnippetChecker {
+    timeout: Duration,
+}
+
+impl SnippetChecker {
+    pub(crate) fn new(timeout: Duration) -> Self {
+        Self { timeout }
+    }
+
+    /// Run the given snippets in order.
+    ///
+    /// Snippets that need a terminal to run are skipped.
+    pub(crate) fn check(&self, snippets: Vec<SnippetCommands>) -> SnippetCheckReport {
+        let mut results = Vec::new();
+        // The output of every successful snippet that has an id, so it can be piped into others.
+        let mut outputs: HashMap<String, Vec<u8>> = HashMap::new();
+        for commands in snippets {
+            let SnippetCommands { slide, language, attribute, executor, snippet, .. } = commands;
+            let (status, output) = match executor {
+                _ if attribute == "+acquire_terminal" => (SnippetCheckStatus::Skipped, String::new()),
+                None => (SnippetCheckStatus::Unsupported, String::new()),
+                Some(executor) => {
+                    let stdin = match &snippet.attributes.pipe_from {
+                        Some(id) => match outputs.get(id) {
+                            Some(output) => Some(output.clone()),
+                            None => {
+                                let output = format!("snippet '{id}' didn't run successfully");
+                                results.push(SnippetCheckResult {
+                                    slide,
+                                    language,
+                                    attribute,
+                                    status: SnippetCheckStatus::Failure,
+                                    output,
+                                });
+                                continue;
+                            }
+                        },
+                        None => None,
+                    };
+                    match executor.execute_async_with_stdin(&snippet, stdin) {
+                        Ok(handle) => {
+                            let (status, output) = self.wait(handle);
+                            if let (SnippetCheckStatus::Success, Some(id)) = (&status, &snippet.attributes.id) {
+                                outputs.insert(id.clone(), output.clone());
+                            }
+                            (status, String::from_utf8_lossy(&output).into_owned())
+                        }
+                        Err(e) => (SnippetCheckStatus::Failure, e.to_string()),
+                    }
+                }
+            };
+            results.push(SnippetCheckResult { slide, language, attribute, status, output });
+        }
+        let failed = results.iter().filter(|result| result.status.is_failure()).count();
+        SnippetCheckReport { snippets: results, failed }
+    }
+
+    /// Wait for a snippet to finish, killing its processes if it times out.
+    fn wait(&self, handle: ExecutionHandle) -> (SnippetCheckStatus, Vec<u8>) {
+        let start = Instant::now();
+        loop {
+            let state = handle.state.lock().unwrap();
+            let status = match state.status {
+                ProcessStatus::Success => Some(SnippetCheckStatus::Success),
+                ProcessStatus::Failure => Some(SnippetCheckStatus::Failure),
+                ProcessStatus::Running if start.elapsed() >= self.timeout => Some(SnippetCheckStatus::Timeout),
+                ProcessStatus::Running => None,
+            };
+            if let Some(status) = status {
+                let output = state.output.clone();
+                if status == SnippetCheckStatus::Timeout {
+                    drop(state);
+                    handle.kill();
+                }
+                return (status, output);
+            }
+            drop(state);
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+}
+
+/// The result of running every snippet in a presentation.
+#[derive(Debug, Serialize)]
+pub(crate) struct SnippetCheckReport {
+    pub(crate) snippets: Vec<SnippetCheckResult>,
+
+    /// The number of snippets that failed or timed out.
+    pub(crate) failed: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct SnippetCheckResult {
+    /// The slide the snippet is in, starting at 1.
+    pub(crate) slide: usize,
+
+    #[serde(serialize_with = "serialize_language")]
+    pub(crate) language: SnippetLanguage,
+
+    /// The attribute that causes this snippet to be executed.
+    pub(crate) attribute: &'static str,
+    pub(crate) status: SnippetCheckStatus,
+
+    /// The snippet's output, or the reason why it couldn't be run.
+    pub(crate) output: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum SnippetCheckStatus {
+    Success,
+    Failure,
+    Timeout,
+    Unsupported,
+    Skipped,
+}
+
+impl SnippetCheckStatus {
+    fn is_failure(&self) -> bool {
+        matches!(self, Self::Failure | Self::Timeout | Self::Unsupported)
+    }
+}
+
+fn serialize_language<S: serde::Serializer>(language: &SnippetLanguage, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format!("{language:?}"))
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod test {
+    use super::*;
+    use crate::presentation::builder::utils::Test;
+    use std::fs;
+
+    fn check(input: &str, timeout: Duration) -> SnippetCheckReport {
+        let dir = tempfile::tempdir().expect("no temp dir");
+        let path = dir.path().join("presentation.md");
+        fs::write(&path, input).expect("write failed");
+        let snippets = Test::new("").with_builder(|builder| builder.collect_snippet_commands(&path)).unwrap();
+        SnippetChecker::new(timeout).check(snippets)
+    }
+
+    #[test]
+    fn results() {
+        let input = "
+```bash +exec +id:hi
+echo hi
+```
+<!-- end_slide -->
+```bash +exec +pipe_from:hi
+cat; exit 1
+```
+```bash +acquire_terminal
+echo nope
+```";
+        let report = check(input, Duration::from_secs(5));
+        let results: Vec<_> = report
+            .snippets
+            .iter()
+            .map(|result| (result.slide, result.status.clone(), result.output.as_str()))
+            .collect();
+        let expected = &[
+            (1, SnippetCheckStatus::Success, "hi\n"),
+            (2, SnippetCheckStatus::Failure, "hi\n"),
+            (2, SnippetCheckStatus::Skipped, ""),
+        ];
+        assert_eq!(results, expected);
+        assert_eq!(report.failed, 1);
+    }
+
+    #[test]
+    fn timeout() {
+        let input = "
+```bash +exec
+sleep 5
+```";
+        let report = check(input, Duration::from_millis(50));
+        assert_eq!(report.snippets[0].status, SnippetCheckStatus::Timeout);
+        assert_eq!(report.failed, 1);
+    }
+
+    #[test]
+    fn timeout_kills_processes() {
+        let dir = tempfile::tempdir().expect("no temp dir");
+        let pid_path = dir.path().join("pid");
+        let input = format!(
+            "
+```bash +exec
+sleep 5 &
+echo $! > {}
+wait
+```",
+            pid_path.display()
+        );
+        let report = check(&input, Duration::from_millis(200));
+        assert_eq!(report.snippets[0].status, SnippetCheckStatus::Timeout);
+
+        // Processes spawned by the snippet are killed too, although they may be left as zombies
+        // if nothing reaps them.
+        let pid = fs::read_to_string(&pid_path).expect("no pid file");
+        let stat = fs::read_to_string(format!("/proc/{}/stat", pid.trim())).unwrap_or_default();
+        let state = stat.rsplit(')').next().unwrap_or_default().split_whitespace().next();
+        assert!(matches!(state, None | Some("Z")), "process still running: {stat}");
+    }
+}
@@ -8,16 +8,17 @@ use crate::{
 use once_cell::sync::Lazy;
 use os_pipe::PipeReader;
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, BTreeSet, HashMap},
+    env,
     fmt::{self, Debug},
-    fs::File,
+    fs::{self, File},
     io::{self, BufRead, BufReader, Read, Write},
     path::{Path, PathBuf},
     process::{self, Child, Stdio},
     sync::{Arc, Mutex},
     thread,
 };
-use tempfile::TempDir;
+use tempfile::{NamedTempFile, TempDir};
 
 static EXECUTORS: Lazy<BTreeMap<SnippetLanguage, LanguageSnippetExecutionConfig>> =
     Lazy::new(|| serde_yaml::from_slice(include_bytes!("../../executors.yaml")).expect("executors.yaml is broken"));
@@ -26,6 +27,8 @@ static EXECUTORS: Lazy<BTreeMap<SnippetLanguage, LanguageSnippetExecutionConfig>
 pub struct SnippetExecutor {
     executors: BTreeMap<SnippetLanguage, LanguageSnippetExecutionConfig>,
     cwd: PathBuf,
+    deterministic_paths: bool,
+    persistent_processes: PersistentProcesses,
 }
 
 impl SnippetExecutor {
@@ -41,7 +44,13 @@ impl SnippetExecutor {
                 Self::validate_executor_config(language, alternative)?;
             }
         }
-        Ok(Self { executors, cwd })
+        Ok(Self { executors, cwd, deterministic_paths: false, persistent_processes: Default::default() })
+    }
+
+    /// Write snippets into directories named after their contents rather than random ones.
+    pub fn with_deterministic_paths(mut self, deterministic_paths: bool) -> Self {
+        self.deterministic_paths = deterministic_paths;
+        self
     }
 
     pub(crate) fn language_executor(
@@ -65,9 +74,17 @@ impl SnippetExecutor {
             hidden_line_prefix: language_config.hidden_line_prefix.clone(),
             config,
             cwd: self.cwd.clone(),
+            deterministic_paths: self.deterministic_paths,
+            persistent_processes: self.persistent_processes.clone(),
+            hooks: Default::default(),
         })
     }
 
+    /// Get the processes spawned by persistent snippets.
+    pub(crate) fn persistent_processes(&self) -> &PersistentProcesses {
+        &self.persistent_processes
+    }
+
     pub(crate) fn hidden_line_prefix(&self, language: &SnippetLanguage) -> Option<&str> {
         self.executors.get(language).and_then(|lang| lang.hidden_line_prefix.as_deref())
     }
@@ -97,37 +114,129 @@ impl Default for SnippetExecutor {
     }
 }
 
+impl Drop for SnippetExecutor {
+    fn drop(&mut self) {
+        self.persistent_processes.kill_all();
+    }
+}
+
 impl Debug for SnippetExecutor {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "SnippetExecutor {{ .. }}")
     }
 }
 
+/// Commands that are run before and after every snippet is executed.
+///
+/// Each of these is a program followed by its arguments, and is run in the same directory and
+/// using the same environment as the snippet.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ExecutionHooks {
+    pub(crate) pre: Vec<String>,
+    pub(crate) post: Vec<String>,
+}
+
+impl ExecutionHooks {
+    fn run_pre(&self, env: &HashMap<String, String>, cwd: &Path) -> Result<(), CodeExecuteError> {
+        Self::run(&self.pre, "pre_exec", env, cwd)
+    }
+
+    fn run_post(&self, env: &HashMap<String, String>, cwd: &Path) -> Result<(), CodeExecuteError> {
+        Self::run(&self.post, "post_exec", env, cwd)
+    }
+
+    fn run(
+        command: &[String],
+        name: &'static str,
+        env: &HashMap<String, String>,
+        cwd: &Path,
+    ) -> Result<(), CodeExecuteError> {
+        let Some((program, args)) = command.split_first() else {
+            return Ok(());
+        };
+        let output = process::Command::new(program)
+            .args(args)
+            .envs(env)
+            .current_dir(cwd)
+            .stdin(Stdio::null())
+            .output()
+            .map_err(|e| CodeExecuteError::SpawnProcess(program.clone(), e))?;
+        if !output.status.success() {
+            let error = String::from_utf8_lossy(&output.stderr).trim_end().to_string();
+            return Err(CodeExecuteError::Hook(name, error));
+        }
+        Ok(())
+    }
+}
+
 #[derive(Clone, Debug)]
 pub(crate) struct LanguageSnippetExecutor {
     hidden_line_prefix: Option<String>,
     config: SnippetExecutorConfig,
     cwd: PathBuf,
+    deterministic_paths: bool,
+    persistent_processes: PersistentProcesses,
+    hooks: ExecutionHooks,
 }
 
 impl LanguageSnippetExecutor {
+    /// Use a different prefix to identify hidden lines than the language's one.
+    pub(crate) fn with_hidden_line_prefix(mut self, prefix: String) -> Self {
+        self.hidden_line_prefix = Some(prefix);
+        self
+    }
+
+    /// Run the given hooks around every execution.
+    pub(crate) fn with_hooks(mut self, hooks: ExecutionHooks) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// Get the commands used to execute snippets, where `$pwd` is the directory the snippet is
+    /// written into.
+    pub(crate) fn commands(&self) -> &[Vec<String>] {
+        &self.config.commands
+    }
+
+    /// Get the contents that would be written into the file that's executed.
+    pub(crate) fn executable_contents(&self, snippet: &Snippet) -> String {
+        snippet.executable_contents(self.hidden_line_prefix.as_deref())
+    }
+
     /// Execute a piece of code asynchronously.
     pub(crate) fn execute_async(&self, snippet: &Snippet) -> Result<ExecutionHandle, CodeExecuteError> {
+        self.execute_async_with_stdin(snippet, None)
+    }
+
+    /// Execute a piece of code asynchronously, writing the given input into its standard input.
+    ///
+    /// When a language uses more than one command, only the last one gets the input.
+    pub(crate) fn execute_async_with_stdin(
+        &self,
+        snippet: &Snippet,
+        stdin: Option<Vec<u8>>,
+    ) -> Result<ExecutionHandle, CodeExecuteError> {
+        let persistent_processes = snippet.attributes.persistent.then(|| self.persistent_processes.clone());
+        let processes = PersistentProcesses::default();
         let script_dir = self.write_snippet(snippet)?;
         let state: Arc<Mutex<ExecutionState>> = Default::default();
         let output_type = match snippet.attributes.representation {
             SnippetRepr::Image => OutputType::Binary,
-            _ => OutputType::Lines,
+            _ => OutputType::Lines { split_stderr: snippet.attributes.split_stderr },
         };
         let reader_handle = CommandsRunner::spawn(
             state.clone(),
             script_dir,
+            processes.clone(),
+            persistent_processes,
+            self.hooks.clone(),
+            stdin,
             self.config.commands.clone(),
             self.config.environment.clone(),
             self.cwd.clone(),
             output_type,
         );
-        let handle = ExecutionHandle { state, reader_handle };
+        let handle = ExecutionHandle { state, processes, reader_handle };
         Ok(handle)
     }
 
@@ -135,9 +244,16 @@ impl LanguageSnippetExecutor {
     pub(crate) fn execute_sync(&self, snippet: &Snippet) -> Result<(), CodeExecuteError> {
         let script_dir = self.write_snippet(snippet)?;
         let script_dir_path = script_dir.path().to_string_lossy();
+        self.hooks.run_pre(&self.config.environment, &self.cwd)?;
+        let result = self.execute_sync_commands(&script_dir_path);
+        let post_result = self.hooks.run_post(&self.config.environment, &self.cwd);
+        result.and(post_result)
+    }
+
+    fn execute_sync_commands(&self, script_dir_path: &str) -> Result<(), CodeExecuteError> {
         for mut commands in self.config.commands.clone() {
             for command in &mut commands {
-                *command = command.replace("$pwd", &script_dir_path);
+                *command = command.replace("$pwd", script_dir_path);
             }
             let (command, args) = commands.split_first().expect("no commands");
             let child = process::Command::new(command)
@@ -157,15 +273,90 @@ impl LanguageSnippetExecutor {
         Ok(())
     }
 
-    fn write_snippet(&self, snippet: &Snippet) -> Result<TempDir, CodeExecuteError> {
+    fn write_snippet(&self, snippet: &Snippet) -> Result<ScriptDirectory, CodeExecuteError> {
         let hide_prefix = self.hidden_line_prefix.as_deref();
         let code = snippet.executable_contents(hide_prefix);
-        let script_dir =
-            tempfile::Builder::default().prefix(".presenterm").tempdir().map_err(CodeExecuteError::TempDir)?;
-        let snippet_path = script_dir.path().join(&self.config.filename);
+        if self.deterministic_paths {
+            return self.write_deterministic_snippet(&code).map_err(CodeExecuteError::TempDir);
+        }
+        let dir = tempfile::Builder::default().prefix(".presenterm").tempdir().map_err(CodeExecuteError::TempDir)?;
+        let snippet_path = dir.path().join(&self.config.filename);
         let mut snippet_file = File::create(snippet_path).map_err(CodeExecuteError::TempDir)?;
         snippet_file.write_all(code.as_bytes()).map_err(CodeExecuteError::TempDir)?;
-        Ok(script_dir)
+        Ok(ScriptDirectory::Temporary(dir))
+    }
+
+    fn write_deterministic_snippet(&self, code: &str) -> io::Result<ScriptDirectory> {
+        let name = format!("{:016x}", Self::hash_snippet(&self.config.filename, code));
+        let path = Self::private_directory()?.join(name);
+        fs::create_dir_all(&path)?;
+        // Another run may be executing this same script so write it somewhere else and then
+        // atomically replace it.
+        let mut file = NamedTempFile::new_in(&path)?;
+        file.write_all(code.as_bytes())?;
+        file.persist(path.join(&self.config.filename))?;
+        Ok(ScriptDirectory::Fixed(path))
+    }
+
+    /// The directory deterministic snippet directories are created in.
+    ///
+    /// On unix this is only accessible by the current user, so nobody else can place or modify
+    /// scripts in it.
+    fn private_directory() -> io::Result<PathBuf> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+
+            let uid = unsafe { libc::getuid() };
+            let path = env::temp_dir().join(format!("presenterm-{uid}"));
+            match fs::DirBuilder::new().mode(0o700).create(&path) {
+                Ok(()) => (),
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => (),
+                Err(e) => return Err(e),
+            };
+            // The directory already existing means anyone could have created it.
+            let metadata = fs::symlink_metadata(&path)?;
+            if !metadata.is_dir() || metadata.uid() != uid || metadata.permissions().mode() & 0o077 != 0 {
+                let error = format!("{} is not a private directory owned by the current user", path.display());
+                return Err(io::Error::other(error));
+            }
+            Ok(path)
+        }
+        #[cfg(not(unix))]
+        {
+            let path = env::temp_dir().join("presenterm");
+            fs::create_dir_all(&path)?;
+            Ok(path)
+        }
+    }
+
+    // FNV-1a, as this needs to be stable across runs and builds.
+    fn hash_snippet(filename: &str, code: &str) -> u64 {
+        let mut hash: u64 = 0xcbf29ce484222325;
+        for byte in filename.bytes().chain([0]).chain(code.bytes()) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+        hash
+    }
+}
+
+/// The directory a snippet is written into before being executed.
+enum ScriptDirectory {
+    /// A randomly named directory that's deleted once the execution is done.
+    Temporary(TempDir),
+
+    /// A directory named after the snippet's contents, inside a directory private to the current
+    /// user, that's kept around so it can be reused.
+    Fixed(PathBuf),
+}
+
+impl ScriptDirectory {
+    fn path(&self) -> &Path {
+        match self {
+            Self::Temporary(dir) => dir.path(),
+            Self::Fixed(path) => path,
+        }
     }
 }
 
@@ -196,43 +387,133 @@ pub(crate) enum CodeExecuteError {
 
     #[error("error running process: {0}")]
     Running(String),
+
+    #[error("{0} hook failed: {1}")]
+    Hook(&'static str, String),
 }
 
 /// A handle for the execution of a piece of code.
 #[derive(Debug)]
 pub(crate) struct ExecutionHandle {
     pub(crate) state: Arc<Mutex<ExecutionState>>,
+    processes: PersistentProcesses,
     #[allow(dead_code)]
     reader_handle: thread::JoinHandle<()>,
 }
 
+impl ExecutionHandle {
+    /// Kill every process spawned by this execution that's still running.
+    pub(crate) fn kill(&self) {
+        self.processes.kill_all();
+    }
+}
+
+/// The processes spawned by persistent snippets.
+///
+/// These keep running regardless of which slide is being displayed and are killed when the
+/// presentation is closed. Every execution also keeps track of its own processes using this so
+/// they can be killed while they're running.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct PersistentProcesses(Arc<Mutex<Vec<PersistentProcess>>>);
+
+impl PersistentProcesses {
+    fn register(&self, command: String, child: Arc<Mutex<Child>>) {
+        self.0.lock().unwrap().push(PersistentProcess { command, child });
+    }
+
+    /// Get the process id and command of every process that's still running.
+    pub(crate) fn list(&self) -> Vec<(u32, String)> {
+        let mut processes = self.0.lock().unwrap();
+        processes.retain(|process| matches!(process.child.lock().unwrap().try_wait(), Ok(None)));
+        processes.iter().map(|process| (process.child.lock().unwrap().id(), process.command.clone())).collect()
+    }
+
+    /// Kill every process that's still running, returning how many were killed.
+    pub(crate) fn kill_all(&self) -> usize {
+        let mut killed = 0;
+        for process in self.0.lock().unwrap().drain(..) {
+            let mut child = process.child.lock().unwrap();
+            if matches!(child.try_wait(), Ok(None)) && Self::kill(&mut child).is_ok() {
+                let _ = child.wait();
+                killed += 1;
+            }
+        }
+        killed
+    }
+
+    fn kill(child: &mut Child) -> io::Result<()> {
+        #[cfg(unix)]
+        {
+            let group = -(child.id() as libc::pid_t);
+            if unsafe { libc::kill(group, libc::SIGKILL) } == 0 {
+                return Ok(());
+            }
+        }
+        child.kill()
+    }
+}
+
+#[derive(Debug)]
+struct PersistentProcess {
+    command: String,
+    child: Arc<Mutex<Child>>,
+}
+
 /// Consumes the output of a process and stores it in a shared state.
 struct CommandsRunner {
     state: Arc<Mutex<ExecutionState>>,
-    script_directory: TempDir,
+    script_directory: ScriptDirectory,
+    processes: PersistentProcesses,
+    persistent_processes: Option<PersistentProcesses>,
+    hooks: ExecutionHooks,
 }
 
 impl CommandsRunner {
+    #[allow(clippy::too_many_arguments)]
     fn spawn(
         state: Arc<Mutex<ExecutionState>>,
-        script_directory: TempDir,
+        script_directory: ScriptDirectory,
+        processes: PersistentProcesses,
+        persistent_processes: Option<PersistentProcesses>,
+        hooks: ExecutionHooks,
+        stdin: Option<Vec<u8>>,
         commands: Vec<Vec<String>>,
         env: HashMap<String, String>,
         cwd: PathBuf,
         output_type: OutputType,
     ) -> thread::JoinHandle<()> {
-        let reader = Self { state, script_directory };
-        thread::spawn(move || reader.run(commands, env, cwd, output_type))
+        let reader = Self { state, script_directory, processes, persistent_processes, hooks };
+        thread::spawn(move || reader.run(commands, stdin, env, cwd, output_type))
     }
 
-    fn run(self, commands: Vec<Vec<String>>, env: HashMap<String, String>, cwd: PathBuf, output_type: OutputType) {
+    fn run(
+        self,
+        commands: Vec<Vec<String>>,
+        mut stdin: Option<Vec<u8>>,
+        env: HashMap<String, String>,
+        cwd: PathBuf,
+        output_type: OutputType,
+    ) {
+        if let Err(e) = self.hooks.run_pre(&env, &cwd) {
+            let mut state = self.state.lock().unwrap();
+            state.push_line(e.to_string(), OutputStream::Stderr);
+            state.status = ProcessStatus::Failure;
+            return;
+        }
         let mut last_result = true;
-        for command in commands {
-            last_result = self.run_command(command, &env, &cwd, output_type);
+        let total_commands = commands.len();
+        for (index, command) in commands.into_iter().enumerate() {
+            let stdin = if index == total_commands - 1 { stdin.take() } else { None };
+            last_result = self.run_command(command, stdin, &env, &cwd, output_type);
             if !last_result {
                 break;
             }
         }
+        // Always run the post hook as it's meant to clean up after the snippet.
+        if let Err(e) = self.hooks.run_post(&env, &cwd) {
+            self.state.lock().unwrap().push_line(e.to_string(), OutputStream::Stderr);
+            last_result = false;
+        }
         let status = match last_result {
             true => ProcessStatus::Success,
             false => ProcessStatus::Failure,
@@ -243,11 +524,19 @@ impl CommandsRunner {
     fn run_command(
         &self,
         command: Vec<String>,
+        stdin: Option<Vec<u8>>,
         env: &HashMap<String, String>,
         cwd: &Path,
         output_type: OutputType,
     ) -> bool {
-        let (mut child, reader) = match self.launch_process(command, env, cwd) {
+        let split_stderr = match output_type {
+            OutputType::Lines { split_stderr } => split_stderr,
+            OutputType::Binary => false,
+        };
+        let display_command = command.join(" ");
+        let stdin_type = if stdin.is_some() { Stdio::piped() } else { Stdio::null() };
+        let (mut child, reader, stderr_reader) = match self.launch_process(command, stdin_type, env, cwd, split_stderr)
+        {
             Ok(inner) => inner,
             Err(e) => {
                 let mut state = self.state.lock().unwrap();
@@ -256,37 +545,105 @@ impl CommandsRunner {
                 return false;
             }
         };
+        // Write the input on a separate thread so a process that doesn't read it all can't block us.
+        if let (Some(input), Some(mut writer)) = (stdin, child.stdin.take()) {
+            thread::spawn(move || writer.write_all(&input));
+        }
+        // The child is shared so it can be killed while we're reading its output.
+        let child = Arc::new(Mutex::new(child));
+        self.processes.register(display_command.clone(), child.clone());
+        if let Some(processes) = &self.persistent_processes {
+            processes.register(display_command, child.clone());
+        }
+        let stderr_handle = stderr_reader.map(|reader| {
+            let state = self.state.clone();
+            thread::spawn(move || Self::process_lines(state, reader, OutputStream::Stderr))
+        });
         let _ = Self::process_output(self.state.clone(), reader, output_type);
+        if let Some(handle) = stderr_handle {
+            let _ = handle.join();
+        }
+
+        Self::wait(&child)
+    }
 
-        match child.wait() {
-            Ok(code) => code.success(),
-            _ => false,
+    // Waits for the process to exit without holding on to it so it can be killed in the meantime.
+    fn wait(child: &Mutex<Child>) -> bool {
+        #[cfg(unix)]
+        {
+            // This doesn't reap the process so its id can't be reused while it could still be killed.
+            let pid = child.lock().unwrap().id() as libc::id_t;
+            loop {
+                let mut info: libc::siginfo_t = unsafe { std::mem::zeroed() };
+                let result = unsafe { libc::waitid(libc::P_PID, pid, &mut info, libc::WEXITED | libc::WNOWAIT) };
+                if result == 0 || io::Error::last_os_error().kind() != io::ErrorKind::Interrupted {
+                    break;
+                }
+            }
+            child.lock().unwrap().wait().is_ok_and(|code| code.success())
+        }
+        #[cfg(not(unix))]
+        loop {
+            match child.lock().unwrap().try_wait() {
+                Ok(Some(code)) => return code.success(),
+                Ok(None) => (),
+                Err(_) => return false,
+            };
+            thread::sleep(std::time::Duration::from_millis(10));
         }
     }
 
     fn launch_process(
         &self,
         mut commands: Vec<String>,
+        stdin: Stdio,
         env: &HashMap<String, String>,
         cwd: &Path,
-    ) -> Result<(Child, PipeReader), CodeExecuteError> {
+        split_stderr: bool,
+    ) -> Result<(Child, PipeReader, Option<PipeReader>), CodeExecuteError> {
         let (reader, writer) = os_pipe::pipe().map_err(CodeExecuteError::Pipe)?;
-        let writer_clone = writer.try_clone().map_err(CodeExecuteError::Pipe)?;
+        let (stderr_reader, stderr_writer) = match split_stderr {
+            false => (None, writer.try_clone().map_err(CodeExecuteError::Pipe)?),
+            true => {
+                let (reader, writer) = os_pipe::pipe().map_err(CodeExecuteError::Pipe)?;
+                (Some(reader), writer)
+            }
+        };
         let script_dir = self.script_directory.path().to_string_lossy();
         for command in &mut commands {
             *command = command.replace("$pwd", &script_dir);
         }
         let (command, args) = commands.split_first().expect("no commands");
-        let child = process::Command::new(command)
-            .args(args)
-            .envs(env)
-            .current_dir(cwd)
-            .stdin(Stdio::null())
-            .stdout(writer)
-            .stderr(writer_clone)
-            .spawn()
-            .map_err(|e| CodeExecuteError::SpawnProcess(command.clone(), e))?;
-        Ok((child, reader))
+        let mut process = process::Command::new(command);
+        process.args(args).envs(env).current_dir(cwd).stdin(stdin).stdout(writer).stderr(stderr_writer);
+        // Every process gets its own process group so anything it spawns can be killed along with it.
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::CommandExt;
+            process.process_group(0);
+        }
+        let child = process.spawn().map_err(|e| CodeExecuteError::SpawnProcess(command.clone(), e))?;
+        Ok((child, reader, stderr_reader))
+    }
+
+    fn process_lines(state: Arc<Mutex<ExecutionState>>, reader: PipeReader, stream: OutputStream) -> io::Result<()> {
+        let mut reader = BufReader::new(reader);
+        let mut buffer = Vec::new();
+        loop {
+            buffer.clear();
+            if reader.read_until(b'\n', &mut buffer)? == 0 {
+                return Ok(());
+            }
+            if buffer.last() == Some(&b'\n') {
+                buffer.pop();
+                if buffer.last() == Some(&b'\r') {
+                    buffer.pop();
+                }
+            }
+            // Processes can write anything so don't choke on output that isn't valid utf8.
+            let line = String::from_utf8_lossy(&buffer).into_owned();
+            state.lock().unwrap().push_line(line, stream);
+        }
     }
 
     fn process_output(
@@ -295,15 +652,7 @@ impl CommandsRunner {
         output_type: OutputType,
     ) -> io::Result<()> {
         match output_type {
-            OutputType::Lines => {
-                let reader = BufReader::new(reader);
-                for line in reader.lines() {
-                    let mut state = state.lock().unwrap();
-                    state.output.extend(line?.into_bytes());
-                    state.output.push(b'\n');
-                }
-                Ok(())
-            }
+            OutputType::Lines { .. } => Self::process_lines(state, reader, OutputStream::Stdout),
             OutputType::Binary => {
                 let mut buffer = Vec::new();
                 reader.read_to_end(&mut buffer)?;
@@ -316,15 +665,36 @@ impl CommandsRunner {
 
 #[derive(Clone, Copy)]
 enum OutputType {
-    Lines,
+    Lines { split_stderr: bool },
     Binary,
 }
 
+#[derive(Clone, Copy)]
+enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
 /// The state of the execution of a process.
 #[derive(Clone, Default, Debug)]
 pub(crate) struct ExecutionState {
     pub(crate) output: Vec<u8>,
     pub(crate) status: ProcessStatus,
+
+    /// The indexes of the lines in the output that were written to stderr.
+    pub(crate) stderr_lines: BTreeSet<usize>,
+    line_count: usize,
+}
+
+impl ExecutionState {
+    fn push_line(&mut self, line: String, stream: OutputStream) {
+        if matches!(stream, OutputStream::Stderr) {
+            self.stderr_lines.insert(self.line_count);
+        }
+        self.output.extend(line.into_bytes());
+        self.output.push(b'\n');
+        self.line_count += 1;
+    }
 }
 
 /// The status of a process.
@@ -347,6 +717,7 @@ impl ProcessStatus {
 mod test {
     use super::*;
     use crate::code::snippet::{SnippetAttributes, SnippetExec};
+    use std::time::Duration;
 
     #[test]
     fn shell_code_execution() {
@@ -372,6 +743,40 @@ echo 'bye'"
         assert_eq!(state.output, expected);
     }
 
+    #[test]
+    fn persistent_process() {
+        let contents = r"
+echo 'started'
+sleep 30"
+            .into();
+        let snippet = Snippet {
+            contents,
+            language: SnippetLanguage::Shell,
+            attributes: SnippetAttributes {
+                execution: SnippetExec::Exec(Default::default()),
+                persistent: true,
+                ..Default::default()
+            },
+        };
+        let executor = SnippetExecutor::default();
+        let handle = executor
+            .language_executor(&snippet.language, &Default::default())
+            .unwrap()
+            .execute_async(&snippet)
+            .expect("execution failed");
+        while handle.state.lock().unwrap().output.is_empty() {
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(executor.persistent_processes().list().len(), 1);
+        assert_eq!(executor.persistent_processes().kill_all(), 1);
+        assert!(executor.persistent_processes().list().is_empty());
+
+        while !handle.state.lock().unwrap().status.is_finished() {
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert!(matches!(handle.state.lock().unwrap().status, ProcessStatus::Failure));
+    }
+
     #[test]
     fn shell_code_execution_captures_stderr() {
         let contents = r"
@@ -397,6 +802,65 @@ echo 'hello world'
         assert_eq!(state.output, expected);
     }
 
+    #[test]
+    fn shell_code_execution_invalid_utf8() {
+        let contents = r"
+printf 'hello \377 world\n'
+printf 'bye \200'
+"
+        .into();
+        let snippet = Snippet {
+            contents,
+            language: SnippetLanguage::Shell,
+            attributes: SnippetAttributes { execution: SnippetExec::Exec(Default::default()), ..Default::default() },
+        };
+        let executor = SnippetExecutor::default().language_executor(&snippet.language, &Default::default()).unwrap();
+        let handle = executor.execute_async(&snippet).expect("execution failed");
+        let state = loop {
+            let state = handle.state.lock().unwrap();
+            if state.status.is_finished() {
+                break state;
+            }
+        };
+
+        let expected = "hello \u{FFFD} world\nbye \u{FFFD}\n";
+        assert_eq!(String::from_utf8(state.output.clone()).expect("invalid utf8"), expected);
+    }
+
+    #[test]
+    fn shell_code_execution_tags_stderr() {
+        let contents = r"
+echo 'hello world'
+echo 'This message redirects to stderr' >&2
+"
+        .into();
+        let snippet = Snippet {
+            contents,
+            language: SnippetLanguage::Shell,
+            attributes: SnippetAttributes {
+                execution: SnippetExec::Exec(Default::default()),
+                split_stderr: true,
+                ..Default::default()
+            },
+        };
+        let executor = SnippetExecutor::default().language_executor(&snippet.language, &Default::default()).unwrap();
+        let handle = executor.execute_async(&snippet).expect("execution failed");
+        let state = loop {
+            let state = handle.state.lock().unwrap();
+            if state.status.is_finished() {
+                break state;
+            }
+        };
+
+        // Streams are read concurrently so lines can show up in any order.
+        let lines: Vec<_> = state.output.lines().map(|line| line.expect("invalid utf8")).collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(state.stderr_lines.len(), 1);
+        let stderr_index = *state.stderr_lines.first().unwrap();
+        assert_eq!(lines[stderr_index], "This message redirects to stderr");
+        assert_eq!(lines[1 - stderr_index], "hello world");
+    }
+
     #[test]
     fn shell_code_execution_executes_hidden_lines() {
         let contents = r"
@@ -423,6 +887,79 @@ echo 'hello world'
         assert_eq!(state.output, expected);
     }
 
+    #[test]
+    fn deterministic_paths() {
+        let snippet = Snippet {
+            contents: "echo $0".into(),
+            language: SnippetLanguage::Shell,
+            attributes: SnippetAttributes { execution: SnippetExec::Exec(Default::default()), ..Default::default() },
+        };
+        let executor = SnippetExecutor::default()
+            .with_deterministic_paths(true)
+            .language_executor(&snippet.language, &Default::default())
+            .unwrap();
+        let first = executor.write_snippet(&snippet).expect("write failed");
+        let second = executor.write_snippet(&snippet).expect("write failed");
+        assert_eq!(first.path(), second.path());
+
+        let other = Snippet { contents: "echo hi".into(), ..snippet };
+        let third = executor.write_snippet(&other).expect("write failed");
+        assert_ne!(first.path(), third.path());
+        assert_eq!(first.path().parent(), third.path().parent());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let metadata = fs::metadata(first.path().parent().unwrap()).unwrap();
+            assert_eq!(metadata.permissions().mode() & 0o777, 0o700);
+        }
+        fs::remove_dir_all(first.path()).unwrap();
+        fs::remove_dir_all(third.path()).unwrap();
+    }
+
+    fn run_with_hooks(hooks: ExecutionHooks, cwd: &Path) -> ExecutionState {
+        let snippet = Snippet {
+            contents: "cat marker && echo ran".into(),
+            language: SnippetLanguage::Shell,
+            attributes: SnippetAttributes { execution: SnippetExec::Exec(Default::default()), ..Default::default() },
+        };
+        let executor = SnippetExecutor::new(Default::default(), cwd.into())
+            .unwrap()
+            .language_executor(&snippet.language, &Default::default())
+            .unwrap()
+            .with_hooks(hooks);
+        let handle = executor.execute_async(&snippet).expect("execution failed");
+        loop {
+            let state = handle.state.lock().unwrap();
+            if state.status.is_finished() {
+                break state.clone();
+            }
+        }
+    }
+
+    #[test]
+    fn execution_hooks() {
+        let dir = tempfile::tempdir().expect("no temp dir");
+        let hooks = ExecutionHooks {
+            pre: vec!["sh".into(), "-c".into(), "echo setup > marker".into()],
+            post: vec!["rm".into(), "marker".into()],
+        };
+        let state = run_with_hooks(hooks, dir.path());
+        assert!(matches!(state.status, ProcessStatus::Success));
+        assert_eq!(state.output, b"setup\nran\n");
+        assert!(!dir.path().join("marker").exists());
+    }
+
+    #[test]
+    fn failed_pre_execution_hook() {
+        let dir = tempfile::tempdir().expect("no temp dir");
+        let hooks =
+            ExecutionHooks { pre: vec!["sh".into(), "-c".into(), "echo nope >&2; exit 1".into()], post: vec![] };
+        let state = run_with_hooks(hooks, dir.path());
+        assert!(matches!(state.status, ProcessStatus::Failure));
+        assert_eq!(state.output, b"pre_exec hook failed: nope\n");
+    }
+
     #[test]
     fn built_in_executors() {
         SnippetExecutor::new(Default::default(), PathBuf::from("./")).expect("invalid default executors");
@@ -4,17 +4,17 @@ use crate::{
         elements::{Line, Text},
         text_style::{Color, TextStyle},
     },
-    theme::CodeBlockStyle,
+    theme::{CodeBlockStyle, CodeHighlightOverride, CommentKeywordsStyle},
 };
 use flate2::read::ZlibDecoder;
 use once_cell::sync::Lazy;
 use serde::Deserialize;
-use std::{cell::RefCell, collections::BTreeMap, fs, path::Path, rc::Rc};
+use std::{cell::RefCell, collections::BTreeMap, fs, ops::Range, path::Path, rc::Rc};
 use syntect::{
     LoadingError,
-    easy::HighlightLines,
-    highlighting::{Style, Theme, ThemeSet},
-    parsing::SyntaxSet,
+    easy::ScopeRangeIterator,
+    highlighting::{HighlightState, Highlighter, RangedHighlightIterator, Style, Theme, ThemeSet},
+    parsing::{ParseState, Scope, ScopeStack, ScopeStackOp, SyntaxSet},
 };
 
 static SYNTAX_SET: Lazy<SyntaxSet> = Lazy::new(|| {
@@ -22,6 +22,8 @@ static SYNTAX_SET: Lazy<SyntaxSet> = Lazy::new(|| {
     bincode::deserialize(contents).expect("syntaxes are broken")
 });
 
+static COMMENT_SCOPE: Lazy<Scope> = Lazy::new(|| Scope::new("comment").expect("invalid scope"));
+
 static BAT_THEMES: Lazy<LazyThemeSet> = Lazy::new(|| {
     let contents = include_bytes!("../../bat/themes.bin");
     let theme_set: LazyThemeSet = bincode::deserialize(contents).expect("syntaxes are broken");
@@ -55,6 +57,15 @@ impl HighlightThemeSet {
         }
     }
 
+    /// Get the names of all the themes available in this set.
+    pub(crate) fn theme_names(&self) -> Vec<String> {
+        let themes = self.themes.borrow();
+        let mut names: Vec<_> = themes.keys().chain(BAT_THEMES.serialized_themes.keys()).cloned().collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+
     /// Register all highlighting themes in the given directory.
     pub fn register_from_directory<P: AsRef<Path>>(&mut self, path: P) -> Result<(), LoadingError> {
         let Ok(metadata) = fs::metadata(&path) else {
@@ -86,18 +97,20 @@ impl Default for HighlightThemeSet {
 }
 
 /// A snippet highlighter.
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub(crate) struct SnippetHighlighter {
     theme: Rc<Theme>,
 }
 
 impl SnippetHighlighter {
     /// Create a highlighter for a specific language.
-    pub(crate) fn language_highlighter(&self, language: &SnippetLanguage) -> LanguageHighlighter {
+    pub(crate) fn language_highlighter(&self, language: &SnippetLanguage) -> LanguageHighlighter<'_> {
         let extension = Self::language_extension(language);
         let syntax = SYNTAX_SET.find_syntax_by_extension(extension).unwrap();
-        let highlighter = HighlightLines::new(syntax, &self.theme);
-        LanguageHighlighter { highlighter }
+        let highlighter = Highlighter::new(&self.theme);
+        let highlight_state = HighlightState::new(&highlighter, ScopeStack::new());
+        let parse_state = ParseState::new(syntax);
+        LanguageHighlighter { parse_state, highlight_state, highlighter, language: language.clone() }
     }
 
     fn language_extension(language: &SnippetLanguage) -> &'static str {
@@ -128,6 +141,7 @@ impl SnippetHighlighter {
             FSharp => "fsx",
             Go => "go",
             GraphQL => "graphql",
+            Graphviz => "dot",
             Haskell => "hs",
             Html => "html",
             Java => "java",
@@ -149,6 +163,7 @@ impl SnippetHighlighter {
             Protobuf => "proto",
             Puppet => "pp",
             Python => "py",
+            Qr => "txt",
             R => "r",
             Racket => "rkt",
             Ruby => "rb",
@@ -184,7 +199,10 @@ impl Default for SnippetHighlighter {
 }
 
 pub(crate) struct LanguageHighlighter<'a> {
-    highlighter: HighlightLines<'a>,
+    parse_state: ParseState,
+    highlight_state: HighlightState,
+    highlighter: Highlighter<'a>,
+    language: SnippetLanguage,
 }
 
 impl LanguageHighlighter<'_> {
@@ -193,14 +211,86 @@ impl LanguageHighlighter<'_> {
     }
 
     pub(crate) fn style_line(&mut self, line: &str, block_style: &CodeBlockStyle) -> Line {
-        let texts: Vec<_> = self
-            .highlighter
-            .highlight_line(line, &SYNTAX_SET)
-            .unwrap()
-            .into_iter()
-            .map(|(style, tokens)| StyledTokens::new(style, tokens, block_style).apply_style())
-            .collect();
-        Line(texts)
+        let ops = self.parse_state.parse_line(line, &SYNTAX_SET).unwrap();
+        // The scopes have to be looked at before highlighting as that moves the scope stack forward.
+        let comments = Self::comment_ranges(&self.highlight_state.path, &ops, line);
+        let mut texts = Vec::new();
+        for (style, tokens, range) in
+            RangedHighlightIterator::new(&mut self.highlight_state, &ops, line, &self.highlighter)
+        {
+            let text = StyledTokens::new(style, tokens, block_style).apply_style();
+            match &block_style.comment_keywords {
+                Some(keywords) if comments.iter().any(|comment| comment.contains(&range.start)) => {
+                    texts.extend(Self::apply_comment_keywords(text, keywords));
+                }
+                _ => texts.push(text),
+            };
+        }
+        let overrides: Vec<_> =
+            block_style.overrides.iter().filter(|overrides| overrides.language == self.language).collect();
+        match overrides.is_empty() {
+            true => Line(texts),
+            false => Line(texts.into_iter().flat_map(|text| Self::apply_overrides(text, &overrides)).collect()),
+        }
+    }
+
+    // Find the ranges of the line that are part of a comment.
+    fn comment_ranges(stack: &ScopeStack, ops: &[(usize, ScopeStackOp)], line: &str) -> Vec<Range<usize>> {
+        let mut stack = stack.clone();
+        let mut ranges = Vec::new();
+        for (range, op) in ScopeRangeIterator::new(ops, line) {
+            if stack.apply(op).is_err() {
+                break;
+            }
+            if !range.is_empty() && stack.as_slice().iter().any(|scope| COMMENT_SCOPE.is_prefix_of(*scope)) {
+                ranges.push(range);
+            }
+        }
+        ranges
+    }
+
+    fn apply_comment_keywords(text: Text, keywords: &CommentKeywordsStyle) -> Vec<Text> {
+        Self::restyle_words(text, |word, style| match keywords.words.contains(word) {
+            true => keywords.style.merged(&style),
+            false => style,
+        })
+    }
+
+    // Split the text into words and recolor any of them that has an override.
+    fn apply_overrides(text: Text, overrides: &[&CodeHighlightOverride]) -> Vec<Text> {
+        Self::restyle_words(text, |word, mut style| {
+            if let Some(overrides) = overrides.iter().find(|overrides| overrides.words.contains(word)) {
+                style.colors.foreground = overrides.colors.foreground.or(style.colors.foreground);
+                style.colors.background = overrides.colors.background.or(style.colors.background);
+            }
+            style
+        })
+    }
+
+    // Split the text into words and apply the given function to the style of every one of them.
+    fn restyle_words<F>(text: Text, restyle: F) -> Vec<Text>
+    where
+        F: Fn(&str, TextStyle) -> TextStyle,
+    {
+        let mut output: Vec<Text> = Vec::new();
+        let mut push = |content: &str, style: TextStyle| match output.last_mut() {
+            Some(last) if last.style == style => last.content.push_str(content),
+            _ => output.push(Text::new(content, style)),
+        };
+        let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+        let mut rest = text.content.as_str();
+        while !rest.is_empty() {
+            let is_word = rest.starts_with(is_word_char);
+            let end = rest.find(|c| is_word_char(c) != is_word).unwrap_or(rest.len());
+            let (chunk, tail) = rest.split_at(end);
+            let style = match is_word {
+                true => restyle(chunk, text.style),
+                false => text.style,
+            };
+            push(chunk, style);
+            rest = tail;
+        }
+        output
     }
 }
 
@@ -226,11 +316,6 @@ impl<'a> StyledTokens<'a> {
     }
 }
 
-/// A theme could not be found.
-#[derive(Debug, thiserror::Error)]
-#[error("theme not found")]
-pub struct ThemeNotFound;
-
 // This code has been adapted from bat's: https://github.com/sharkdp/bat
 fn parse_color(color: syntect::highlighting::Color) -> Option<Color> {
     if color.a == 0 {
@@ -255,6 +340,8 @@ fn parse_color(color: syntect::highlighting::Color) -> Option<Color> {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::markdown::text_style::Colors;
+    use rstest::rstest;
     use strum::IntoEnumIterator;
     use tempfile::tempdir;
 
@@ -267,6 +354,46 @@ mod test {
         }
     }
 
+    #[test]
+    fn overrides() {
+        let overrides = CodeHighlightOverride {
+            language: SnippetLanguage::Rust,
+            words: ["MyType".to_string()].into(),
+            colors: Colors { foreground: Some(Color::Red), background: None },
+        };
+        let style = TextStyle::default().fg_color(Color::Blue);
+        let text = Text::new("let x: MyType = MyTypes;", style);
+        let output = LanguageHighlighter::apply_overrides(text, &[&overrides]);
+        let expected = vec![
+            Text::new("let x: ", style),
+            Text::new("MyType", TextStyle::default().fg_color(Color::Red)),
+            Text::new(" = MyTypes;", style),
+        ];
+        assert_eq!(output, expected);
+    }
+
+    #[rstest]
+    #[case::enabled(true, 1)]
+    #[case::disabled(false, 0)]
+    fn comment_keywords(#[case] enabled: bool, #[case] expected: usize) {
+        let keywords = CommentKeywordsStyle {
+            words: ["TODO".to_string()].into(),
+            style: TextStyle::default().fg_color(Color::Red).bold(),
+        };
+        let block_style = CodeBlockStyle { comment_keywords: enabled.then_some(keywords), ..Default::default() };
+        let highlighter = SnippetHighlighter::default();
+        let line = highlighter
+            .language_highlighter(&SnippetLanguage::Rust)
+            .highlight_line("let TODO = 1; // TODO: rename", &block_style);
+        // Only the one inside the comment is styled.
+        let styled = line
+            .0
+            .iter()
+            .filter(|text| text.content == "TODO" && text.style.colors.foreground == Some(Color::Red))
+            .count();
+        assert_eq!(styled, expected);
+    }
+
     #[test]
     fn default_highlighter() {
         SnippetHighlighter::default();
@@ -313,4 +440,13 @@ mod test {
         // This is a default syntect theme
         assert!(themes.load_by_name("InspiredGitHub").is_some());
     }
+
+    #[test]
+    fn theme_names() {
+        let themes = HighlightThemeSet::default();
+        let names = themes.theme_names();
+        assert!(names.iter().any(|name| name == "GitHub"));
+        assert!(names.iter().any(|name| name == "InspiredGitHub"));
+        assert_eq!(names.iter().filter(|name| *name == "base16-ocean.dark").count(), 1);
+    }
 }
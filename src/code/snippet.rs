@@ -4,22 +4,38 @@ use super::{
 };
 use crate::{
     markdown::{
-        elements::{Percent, PercentParseError},
+        elements::{Line, Percent, PercentParseError, Text},
         text::{WeightedLine, WeightedText},
         text_style::{Color, TextStyle},
     },
     presentation::ChunkMutator,
+    qr::QrErrorCorrection,
     render::{
-        operation::{AsRenderOperations, BlockLine, RenderOperation},
+        operation::{AsRenderOperations, BlockBorder, BlockLine, LineOverflow, RenderOperation, ScrollDirection},
         properties::WindowSize,
     },
-    theme::{Alignment, CodeBlockStyle},
+    theme::{Alignment, CodeBlockFade, CodeBlockStyle, PaddingRect},
+    ui::scroll::ScrollWindow,
 };
 use serde::Deserialize;
-use std::{cell::RefCell, convert::Infallible, fmt::Write, ops::Range, path::PathBuf, rc::Rc, str::FromStr};
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet},
+    convert::Infallible,
+    fmt::Write,
+    num::{NonZeroU8, NonZeroU16},
+    ops::Range,
+    path::PathBuf,
+    rc::Rc,
+    str::FromStr,
+    time::Duration,
+};
 use strum::{EnumDiscriminants, EnumIter};
+use syntect::parsing::Regex;
 use unicode_width::UnicodeWidthStr;
 
+const DEFAULT_TAB_WIDTH: u8 = 4;
+
 pub(crate) struct SnippetSplitter<'a> {
     style: &'a CodeBlockStyle,
     hidden_line_prefix: Option<&'a str>,
@@ -32,36 +48,89 @@ impl<'a> SnippetSplitter<'a> {
 
     pub(crate) fn split(&self, code: &Snippet) -> Vec<SnippetLine> {
         let mut lines = Vec::new();
-        let horizontal_padding = self.style.padding.horizontal;
-        let vertical_padding = self.style.padding.vertical;
-        if vertical_padding > 0 {
-            lines.push(SnippetLine::empty());
-        }
-        self.push_lines(code, horizontal_padding, &mut lines);
-        if vertical_padding > 0 {
-            lines.push(SnippetLine::empty());
-        }
+        let padding = self.style.padding;
+        lines.extend((0..padding.top).map(|_| SnippetLine::empty()));
+        self.push_lines(code, &padding, &mut lines);
+        lines.extend((0..padding.bottom).map(|_| SnippetLine::empty()));
         lines
     }
 
-    fn push_lines(&self, code: &Snippet, horizontal_padding: u8, lines: &mut Vec<SnippetLine>) {
+    fn push_lines(&self, code: &Snippet, padding: &PaddingRect, lines: &mut Vec<SnippetLine>) {
         if code.contents.is_empty() {
             return;
         }
 
-        let padding = " ".repeat(horizontal_padding as usize);
-        let padder = NumberPadder::new(code.visible_lines(self.hidden_line_prefix).count());
-        for (index, line) in code.visible_lines(self.hidden_line_prefix).enumerate() {
-            let mut line = line.replace('\t', "    ");
+        let right_padding_length = padding.right as u16;
+        let padding = " ".repeat(padding.left as usize);
+        let skipped_numbers = &code.attributes.skipped_line_numbers;
+        let restarts = &code.attributes.line_number_restarts;
+        let visible_lines = code.visible_lines(self.hidden_line_prefix).count() as u16;
+        let mut largest_number = 0;
+        let mut number = 0;
+        for line in 1..=visible_lines {
+            if restarts.contains(&line) {
+                number = 0;
+            }
+            if !skipped_numbers.contains(&line) {
+                number += 1;
+                largest_number = largest_number.max(number);
+            }
+        }
+        let step = code.attributes.line_numbers_step.map(u16::from).unwrap_or(1) as usize;
+        let padder = NumberPadder::new(largest_number - largest_number % step);
+        let tab = " ".repeat(code.attributes.tab_width.map(u8::from).unwrap_or(DEFAULT_TAB_WIDTH) as usize);
+        let mut index = 0;
+        let mut displayed_number = 0;
+        let mut hiding = false;
+        // A single trailing newline terminates the last line rather than starting a new, empty one.
+        for line in code.contents.lines() {
+            let Some(line) = visible_line(line, self.hidden_line_prefix) else {
+                // Only display a single marker for every group of consecutive hidden lines.
+                if let (Some(marker), false) = (&self.style.hidden_lines_marker, hiding) {
+                    let mut prefix = padding.clone();
+                    if code.attributes.line_numbers {
+                        prefix.push_str(&" ".repeat(padder.width() + 1));
+                    }
+                    let code = format!("{}\n", marker.symbol);
+                    lines.push(SnippetLine {
+                        prefix,
+                        code,
+                        right_padding_length,
+                        line_number: None,
+                        marker: true,
+                        link: None,
+                    });
+                }
+                hiding = true;
+                continue;
+            };
+            hiding = false;
+            let mut line = line.replace('\t', &tab);
             let mut prefix = padding.clone();
+            let mut link = None;
             if code.attributes.line_numbers {
-                let line_number = index + 1;
-                prefix.push_str(&padder.pad_right(line_number));
+                if restarts.contains(&(index as u16 + 1)) {
+                    displayed_number = 0;
+                }
+                // Skipped lines get an empty gutter and don't count towards the numbering.
+                if skipped_numbers.contains(&(index as u16 + 1)) {
+                    prefix.push_str(&" ".repeat(padder.width()));
+                } else {
+                    displayed_number += 1;
+                    // Lines that aren't a multiple of the step still count but get an empty gutter.
+                    if displayed_number % step == 0 {
+                        prefix.push_str(&padder.pad_right(displayed_number));
+                        link = code.attributes.line_links.get(&(index as u16 + 1)).cloned();
+                    } else {
+                        prefix.push_str(&" ".repeat(padder.width()));
+                    }
+                }
                 prefix.push(' ');
             }
             line.push('\n');
             let line_number = Some(index as u16 + 1);
-            lines.push(SnippetLine { prefix, code: line, right_padding_length: padding.len() as u16, line_number });
+            index += 1;
+            lines.push(SnippetLine { prefix, code: line, right_padding_length, line_number, marker: false, link });
         }
     }
 }
@@ -71,31 +140,46 @@ pub(crate) struct SnippetLine {
     pub(crate) code: String,
     pub(crate) right_padding_length: u16,
     pub(crate) line_number: Option<u16>,
+    pub(crate) marker: bool,
+
+    /// The url this line's number links to.
+    pub(crate) link: Option<String>,
 }
 
 impl SnippetLine {
     pub(crate) fn empty() -> Self {
-        Self { prefix: String::new(), code: "\n".into(), right_padding_length: 0, line_number: None }
+        Self {
+            prefix: String::new(),
+            code: "\n".into(),
+            right_padding_length: 0,
+            line_number: None,
+            marker: false,
+            link: None,
+        }
     }
 
     pub(crate) fn width(&self) -> usize {
         self.prefix.width() + self.code.width() + self.right_padding_length as usize
     }
 
+    /// Whether this line is part of the code rather than the padding around it.
+    pub(crate) fn is_scrollable(&self) -> bool {
+        self.line_number.is_some() || self.marker
+    }
+
     pub(crate) fn highlight(
         &self,
         code_highlighter: &mut LanguageHighlighter,
         block_style: &CodeBlockStyle,
         font_size: u8,
-    ) -> WeightedLine {
+    ) -> Line {
         let mut line = code_highlighter.highlight_line(&self.code, block_style);
         line.apply_style(&TextStyle::default().size(font_size));
-        line.into()
+        line
     }
 
-    pub(crate) fn dim(&self, dim_style: &TextStyle) -> WeightedLine {
-        let output = vec![StyledTokens { style: *dim_style, tokens: &self.code }.apply_style()];
-        output.into()
+    pub(crate) fn dim(&self, dim_style: &TextStyle) -> Line {
+        Line(vec![StyledTokens { style: *dim_style, tokens: &self.code }.apply_style()])
     }
 
     pub(crate) fn dim_prefix(&self, dim_style: &TextStyle) -> WeightedText {
@@ -110,52 +194,151 @@ pub(crate) struct HighlightContext {
     pub(crate) current: usize,
     pub(crate) block_length: u16,
     pub(crate) alignment: Alignment,
+    pub(crate) overflow: LineOverflow,
+    pub(crate) context_lines: Option<u16>,
+    pub(crate) window: Option<ScrollWindow>,
+    pub(crate) scrollbar_style: TextStyle,
+    pub(crate) fade: CodeBlockFade,
+    /// The line number displayed in each of the rows the window scrolls through.
+    pub(crate) row_line_numbers: Vec<Option<u16>>,
+}
+
+impl HighlightContext {
+    /// Switch to the given highlight group, scrolling so that its lines are visible.
+    fn set_current(&mut self, current: usize) {
+        self.current = current;
+        let group = &self.groups[current];
+        let mut rows = self
+            .row_line_numbers
+            .iter()
+            .enumerate()
+            .filter(|(_, number)| number.is_some_and(|number| group.contains(number)))
+            .map(|(row, _)| row);
+        let (Some(window), Some(first)) = (&mut self.window, rows.next()) else {
+            return;
+        };
+        let last = rows.next_back().unwrap_or(first);
+        window.reveal(first..last + 1);
+    }
 }
 
 #[derive(Debug)]
 pub(crate) struct HighlightedLine {
     pub(crate) prefix: WeightedText,
+    pub(crate) prefix_link: Option<String>,
     pub(crate) right_padding_length: u16,
     pub(crate) highlighted: WeightedLine,
     pub(crate) not_highlighted: WeightedLine,
     pub(crate) line_number: Option<u16>,
     pub(crate) context: Rc<RefCell<HighlightContext>>,
     pub(crate) block_color: Option<Color>,
+    pub(crate) border: Option<BlockBorder>,
+    pub(crate) collapsed: Option<CollapsedLine>,
+    pub(crate) row: Option<usize>,
+    pub(crate) scrollbar_padding: u16,
+}
+
+/// The marker displayed instead of a line that's too far away from the highlighted lines.
+#[derive(Debug)]
+pub(crate) struct CollapsedLine {
+    pub(crate) prefix: WeightedText,
+    pub(crate) marker: WeightedLine,
 }
 
 impl AsRenderOperations for HighlightedLine {
     fn as_render_operations(&self, _: &WindowSize) -> Vec<RenderOperation> {
         let context = self.context.borrow();
         let group = &context.groups[context.current];
+        let (indicator, fade) = match (&context.window, self.row) {
+            (Some(window), Some(row)) => {
+                if !window.visible().contains(&row) {
+                    return Vec::new();
+                }
+                let row = row - window.visible().start;
+                let fade = window.fade(row, context.fade.rows.into()) * context.fade.intensity;
+                (Some(window.indicator(row)), fade)
+            }
+            _ => (None, 0.0),
+        };
         let needs_highlight = self.line_number.map(|number| group.contains(number)).unwrap_or_default();
+        let collapsed = match (&self.collapsed, context.context_lines, self.line_number) {
+            (Some(collapsed), Some(lines), Some(number)) if !group.is_near(number, lines) => {
+                // Only the first line in a group of consecutive collapsed lines displays the marker.
+                if number > 1 && !group.is_near(number - 1, lines) {
+                    return Vec::new();
+                }
+                Some(collapsed)
+            }
+            _ => None,
+        };
         // TODO: Cow<str>?
-        let text = match needs_highlight {
-            true => self.highlighted.clone(),
-            false => self.not_highlighted.clone(),
+        let (prefix, text) = match (collapsed, needs_highlight) {
+            (Some(collapsed), _) => (collapsed.prefix.clone(), collapsed.marker.clone()),
+            (None, true) => (self.prefix.clone(), self.highlighted.clone()),
+            (None, false) => (self.prefix.clone(), self.not_highlighted.clone()),
+        };
+        let prefix_link = match collapsed {
+            Some(_) => None,
+            None => self.prefix_link.clone(),
+        };
+        // Rows at the edges of the window fade into the block's background.
+        let (prefix, text) = match self.block_color {
+            Some(background) if fade > 0.0 => {
+                let fade_style = |mut style: TextStyle| {
+                    style.colors.foreground = style.colors.foreground.map(|color| color.blend(background, fade));
+                    style
+                };
+                (prefix.map_style(fade_style), text.map_styles(fade_style))
+            }
+            _ => (prefix, text),
+        };
+        // The scrollbar goes on the right edge, so it takes the place of the right padding.
+        let (text, right_padding_length) = match indicator {
+            Some(indicator) => {
+                let padding = " ".repeat((self.scrollbar_padding + self.right_padding_length).into());
+                let suffix = Text::new(format!("{padding}{indicator}"), context.scrollbar_style);
+                (text.with_suffix(suffix), 0)
+            }
+            None => (text, self.right_padding_length),
         };
         vec![
             RenderOperation::RenderBlockLine(BlockLine {
-                prefix: self.prefix.clone(),
-                right_padding_length: self.right_padding_length,
+                prefix,
+                prefix_link,
+                right_padding_length,
                 repeat_prefix_on_wrap: false,
                 text,
                 block_length: context.block_length,
                 alignment: context.alignment,
                 block_color: self.block_color,
+                border: self.border.clone(),
+                overflow: context.overflow,
             }),
             RenderOperation::RenderLineBreak,
         ]
     }
+
+    fn scroll(&self, direction: ScrollDirection) -> Option<bool> {
+        let mut context = self.context.borrow_mut();
+        let window = context.window.as_mut()?;
+        Some(window.scroll(direction))
+    }
 }
 
 #[derive(Debug)]
 pub(crate) struct HighlightMutator {
     context: Rc<RefCell<HighlightContext>>,
+    auto_step: Option<Duration>,
 }
 
 impl HighlightMutator {
     pub(crate) fn new(context: Rc<RefCell<HighlightContext>>) -> Self {
-        Self { context }
+        Self { context, auto_step: None }
+    }
+
+    pub(crate) fn auto_step(mut self, interval: Option<Duration>) -> Self {
+        self.auto_step = interval;
+        self
     }
 }
 
@@ -165,7 +348,8 @@ impl ChunkMutator for HighlightMutator {
         if context.current == context.groups.len() - 1 {
             false
         } else {
-            context.current += 1;
+            let current = context.current + 1;
+            context.set_current(current);
             true
         }
     }
@@ -175,24 +359,30 @@ impl ChunkMutator for HighlightMutator {
         if context.current == 0 {
             false
         } else {
-            context.current -= 1;
+            let current = context.current - 1;
+            context.set_current(current);
             true
         }
     }
 
     fn reset_mutations(&self) {
-        self.context.borrow_mut().current = 0;
+        self.context.borrow_mut().set_current(0);
     }
 
     fn apply_all_mutations(&self) {
         let mut context = self.context.borrow_mut();
-        context.current = context.groups.len() - 1;
+        let current = context.groups.len() - 1;
+        context.set_current(current);
     }
 
     fn mutations(&self) -> (usize, usize) {
         let context = self.context.borrow();
         (context.current, context.groups.len())
     }
+
+    fn auto_step_interval(&self) -> Option<Duration> {
+        self.auto_step
+    }
 }
 
 pub(crate) type ParseResult<T> = Result<T, SnippetBlockParseError>;
@@ -208,13 +398,35 @@ impl SnippetParser {
 
     fn parse_block_info(input: &str) -> ParseResult<(SnippetLanguage, SnippetAttributes)> {
         let (language, input) = Self::parse_language(input);
-        let attributes = Self::parse_attributes(input)?;
+        let mut attributes = Self::parse_attributes(input)?;
+        // QR codes are only ever rendered.
+        if language == SnippetLanguage::Qr && attributes.representation == SnippetRepr::Snippet {
+            attributes.representation = SnippetRepr::Render;
+        }
+        if attributes.error_correction.is_some() && language != SnippetLanguage::Qr {
+            return Err(SnippetBlockParseError::NotQrSnippet("error_correction"));
+        }
         if attributes.width.is_some() && !matches!(attributes.representation, SnippetRepr::Render) {
             return Err(SnippetBlockParseError::NotRenderSnippet("width"));
         }
+        if attributes.output_language.is_some() && !matches!(attributes.execution, SnippetExec::Exec(_)) {
+            return Err(SnippetBlockParseError::NotExecSnippet("as"));
+        }
         Ok((language, attributes))
     }
 
+    /// Parse the attributes that are applied by default to every snippet in a presentation.
+    pub(crate) fn parse_defaults(input: &str) -> ParseResult<SnippetAttributes> {
+        let attributes = Self::parse_attributes(input)?;
+        if attributes.id.is_some() {
+            return Err(SnippetBlockParseError::NotDefaultAttribute("id"));
+        }
+        if attributes.pipe_from.is_some() {
+            return Err(SnippetBlockParseError::NotDefaultAttribute("pipe_from"));
+        }
+        Ok(attributes)
+    }
+
     fn parse_language(input: &str) -> (SnippetLanguage, &str) {
         let token = Self::next_identifier(input);
         // this always returns `Ok` given we fall back to `Unknown` if we don't know the language.
@@ -233,7 +445,7 @@ impl SnippetParser {
             }
             use SnippetAttribute::*;
             match attribute {
-                ExecReplace(_) | Image | Render if attributes.representation != SnippetRepr::Snippet => {
+                ExecReplace(_) | Image | Render | Chrome(_) if attributes.representation != SnippetRepr::Snippet => {
                     return Err(SnippetBlockParseError::MultipleRepresentation);
                 }
                 LineNumbers => attributes.line_numbers = true,
@@ -246,9 +458,18 @@ impl SnippetParser {
                     attributes.representation = SnippetRepr::ExecReplace;
                     attributes.execution = SnippetExec::Exec(spec);
                 }
+                Chrome(position) => {
+                    attributes.representation = SnippetRepr::Chrome(position);
+                    attributes.execution = SnippetExec::Exec(Default::default());
+                }
                 Id(id) => {
                     attributes.id = Some(id);
                 }
+                PipeFrom(id) => attributes.pipe_from = Some(id),
+                Prompt(prompt) => attributes.prompt = Some(prompt),
+                ErrorCorrection(level) => attributes.error_correction = Some(level),
+                As(language) => attributes.output_language = Some(language),
+                Theme(theme) => attributes.theme = Some(theme),
                 Validate(spec) => {
                     if matches!(attributes.execution, SnippetExec::None) {
                         attributes.execution = SnippetExec::Validate(spec);
@@ -261,8 +482,23 @@ impl SnippetParser {
                 Render => attributes.representation = SnippetRepr::Render,
                 AcquireTerminal(spec) => attributes.execution = SnippetExec::AcquireTerminal(spec),
                 NoBackground => attributes.no_background = true,
+                Plain => attributes.plain = true,
+                Trusted => attributes.trusted = true,
+                SplitStderr => attributes.split_stderr = true,
+                Persistent => attributes.persistent = true,
+                Compact => attributes.compact = true,
+                OutputPolicy(policy) => attributes.output_policy = Some(policy),
+                Alignment(alignment) => attributes.alignment = Some(alignment),
                 HighlightedLines(lines) => attributes.highlight_groups = lines,
+                SkippedLineNumbers(lines) => attributes.skipped_line_numbers = lines,
+                LineNumbersStep(step) => {
+                    attributes.line_numbers = true;
+                    attributes.line_numbers_step = Some(step);
+                }
                 Width(width) => attributes.width = Some(width),
+                MaxRows(rows) => attributes.max_rows = Some(rows),
+                TabWidth(width) => attributes.tab_width = Some(width),
+                AutoStep(interval) => attributes.auto_step = Some(interval),
                 ExpectedExecutionResult(result) => attributes.expected_execution_result = result,
             };
             processed_attributes.push(discriminant);
@@ -287,6 +523,16 @@ impl SnippetParser {
                     "image" => SnippetAttribute::Image,
                     "render" => SnippetAttribute::Render,
                     "no_background" => SnippetAttribute::NoBackground,
+                    "plain" => SnippetAttribute::Plain,
+                    "trusted" => SnippetAttribute::Trusted,
+                    "split_stderr" => SnippetAttribute::SplitStderr,
+                    "persistent" => SnippetAttribute::Persistent,
+                    "compact" => SnippetAttribute::Compact,
+                    "keep_output" => SnippetAttribute::OutputPolicy(SnippetOutputPolicy::Keep),
+                    "clear_output" => SnippetAttribute::OutputPolicy(SnippetOutputPolicy::Clear),
+                    "left" => SnippetAttribute::Alignment(SnippetAlignment::Left),
+                    "center" => SnippetAttribute::Alignment(SnippetAlignment::Center),
+                    "right" => SnippetAttribute::Alignment(SnippetAlignment::Right),
                     "acquire_terminal" => SnippetAttribute::AcquireTerminal(SnippetExecutorSpec::default()),
                     other => {
                         let (attribute, parameter) = other
@@ -294,20 +540,86 @@ impl SnippetParser {
                             .ok_or_else(|| SnippetBlockParseError::InvalidToken(Self::next_identifier(input).into()))?;
                         match attribute {
                             "exec" => SnippetAttribute::Exec(SnippetExecutorSpec::Alternative(parameter.to_string())),
+                            "chrome" => {
+                                let position = match parameter {
+                                    "top_left" => SnippetChromePosition::TopLeft,
+                                    "top" => SnippetChromePosition::Top,
+                                    "top_right" => SnippetChromePosition::TopRight,
+                                    "bottom_left" => SnippetChromePosition::BottomLeft,
+                                    "bottom" => SnippetChromePosition::Bottom,
+                                    "bottom_right" => SnippetChromePosition::BottomRight,
+                                    _ => {
+                                        return Err(SnippetBlockParseError::InvalidChromePosition(
+                                            parameter.to_string(),
+                                        ));
+                                    }
+                                };
+                                SnippetAttribute::Chrome(position)
+                            }
                             "exec_replace" => {
                                 SnippetAttribute::ExecReplace(SnippetExecutorSpec::Alternative(parameter.to_string()))
                             }
                             "id" => SnippetAttribute::Id(parameter.to_string()),
+                            "pipe_from" => SnippetAttribute::PipeFrom(parameter.to_string()),
+                            "theme" if !parameter.is_empty() => SnippetAttribute::Theme(parameter.to_string()),
+                            "prompt" if !parameter.is_empty() => SnippetAttribute::Prompt(parameter.to_string()),
+                            "as" if !parameter.is_empty() => {
+                                let language = parameter.parse().map_err(|_| {
+                                    SnippetBlockParseError::InvalidOutputLanguage(parameter.to_string())
+                                })?;
+                                if let SnippetLanguage::Unknown(_) = language {
+                                    return Err(SnippetBlockParseError::InvalidOutputLanguage(parameter.to_string()));
+                                }
+                                SnippetAttribute::As(language)
+                            }
                             "validate" => {
                                 SnippetAttribute::Validate(SnippetExecutorSpec::Alternative(parameter.to_string()))
                             }
                             "acquire_terminal" => SnippetAttribute::AcquireTerminal(SnippetExecutorSpec::Alternative(
                                 parameter.to_string(),
                             )),
+                            "skip_numbers" => SnippetAttribute::SkippedLineNumbers(Self::parse_line_list(parameter)?),
+                            "line_numbers_step" => {
+                                let step = parameter.parse().map_err(|_| {
+                                    SnippetBlockParseError::InvalidLineNumbersStep(parameter.to_string())
+                                })?;
+                                SnippetAttribute::LineNumbersStep(step)
+                            }
+                            "max_rows" => {
+                                let rows = parameter
+                                    .parse()
+                                    .map_err(|_| SnippetBlockParseError::InvalidMaxRows(parameter.to_string()))?;
+                                SnippetAttribute::MaxRows(rows)
+                            }
+                            "tab_width" => {
+                                let width = parameter
+                                    .parse()
+                                    .map_err(|_| SnippetBlockParseError::InvalidTabWidth(parameter.to_string()))?;
+                                SnippetAttribute::TabWidth(width)
+                            }
+                            "auto_step" => {
+                                let interval = Self::parse_duration(parameter)
+                                    .ok_or_else(|| SnippetBlockParseError::InvalidAutoStep(parameter.to_string()))?;
+                                SnippetAttribute::AutoStep(interval)
+                            }
                             "width" => {
                                 let width = parameter.parse().map_err(SnippetBlockParseError::InvalidWidth)?;
                                 SnippetAttribute::Width(width)
                             }
+                            "error_correction" => {
+                                let level = match parameter {
+                                    "low" => QrErrorCorrection::Low,
+                                    "medium" => QrErrorCorrection::Medium,
+                                    "quartile" => QrErrorCorrection::Quartile,
+                                    "high" => QrErrorCorrection::High,
+                                    _ => {
+                                        return Err(SnippetBlockParseError::InvalidErrorCorrection(
+                                            parameter.to_string(),
+                                        ));
+                                    }
+                                };
+                                SnippetAttribute::ErrorCorrection(level)
+                            }
                             "expect" => match parameter {
                                 "success" => {
                                     SnippetAttribute::ExpectedExecutionResult(ExpectedSnippetExecutionResult::Success)
@@ -339,7 +651,8 @@ impl SnippetParser {
 
     fn parse_highlight_groups(input: &str) -> ParseResult<(Vec<HighlightGroup>, &str)> {
         use SnippetBlockParseError::InvalidHighlightedLines;
-        let Some((head, tail)) = input.split_once('}') else {
+        let head = Self::split_outside_patterns(input, '}')[0];
+        let Some(tail) = input.get(head.len() + 1..) else {
             return Err(InvalidHighlightedLines("no enclosing '}'".into()));
         };
         let head = head.trim();
@@ -348,7 +661,7 @@ impl SnippetParser {
         }
 
         let mut highlight_groups = Vec::new();
-        for group in head.split('|') {
+        for group in Self::split_outside_patterns(head, '|') {
             let group = Self::parse_highlight_group(group)?;
             highlight_groups.push(group);
         }
@@ -357,12 +670,16 @@ impl SnippetParser {
 
     fn parse_highlight_group(input: &str) -> ParseResult<HighlightGroup> {
         let mut highlights = Vec::new();
-        for piece in input.split(',') {
+        for piece in Self::split_outside_patterns(input, ',') {
             let piece = piece.trim();
             if piece == "all" {
                 highlights.push(Highlight::All);
                 continue;
             }
+            if let Some(pattern) = piece.strip_prefix('/').and_then(|piece| piece.strip_suffix('/')) {
+                highlights.push(Self::parse_highlight_pattern(pattern)?);
+                continue;
+            }
             match piece.split_once('-') {
                 Some((left, right)) => {
                     let left = Self::parse_number(left)?;
@@ -381,6 +698,59 @@ impl SnippetParser {
         Ok(HighlightGroup::new(highlights))
     }
 
+    fn parse_highlight_pattern(input: &str) -> ParseResult<Highlight> {
+        let pattern = input.replace("\\/", "/");
+        if pattern.is_empty() {
+            return Err(SnippetBlockParseError::InvalidHighlightPattern(pattern, "pattern is empty".into()));
+        }
+        if let Some(e) = Regex::try_compile(&pattern) {
+            return Err(SnippetBlockParseError::InvalidHighlightPattern(pattern, e.to_string()));
+        }
+        Ok(Highlight::Pattern(pattern))
+    }
+
+    // Splits the input on a delimiter, ignoring it when it shows up within a `/pattern/`.
+    fn split_outside_patterns(input: &str, delimiter: char) -> Vec<&str> {
+        let mut pieces = Vec::new();
+        let mut in_pattern = false;
+        let mut escaped = false;
+        let mut start = 0;
+        for (index, c) in input.char_indices() {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' if in_pattern => escaped = true,
+                '/' => in_pattern = !in_pattern,
+                c if c == delimiter && !in_pattern => {
+                    pieces.push(&input[start..index]);
+                    start = index + c.len_utf8();
+                }
+                _ => (),
+            };
+        }
+        pieces.push(&input[start..]);
+        pieces
+    }
+
+    fn parse_line_list(input: &str) -> ParseResult<BTreeSet<u16>> {
+        let mut lines = BTreeSet::new();
+        for piece in input.split(',') {
+            let line = piece
+                .trim()
+                .parse()
+                .map_err(|_| SnippetBlockParseError::InvalidSkippedLineNumbers(format!("not a number: '{piece}'")))?;
+            lines.insert(line);
+        }
+        Ok(lines)
+    }
+
+    fn parse_duration(input: &str) -> Option<Duration> {
+        let duration = match input.strip_suffix("ms") {
+            Some(millis) => Duration::from_millis(millis.parse().ok()?),
+            None => Duration::try_from_secs_f64(input.strip_suffix('s')?.parse().ok()?).ok()?,
+        };
+        (!duration.is_zero()).then_some(duration)
+    }
+
     fn parse_number(input: &str) -> ParseResult<u16> {
         input
             .trim()
@@ -411,14 +781,50 @@ pub enum SnippetBlockParseError {
     #[error("invalid width: {0}")]
     InvalidWidth(PercentParseError),
 
+    #[error("invalid skipped line numbers: {0}")]
+    InvalidSkippedLineNumbers(String),
+
+    #[error("invalid highlight pattern '{0}': {1}")]
+    InvalidHighlightPattern(String, String),
+
+    #[error("invalid line numbers step: '{0}'")]
+    InvalidLineNumbersStep(String),
+
+    #[error("invalid max rows: '{0}'")]
+    InvalidMaxRows(String),
+
+    #[error("invalid tab width: '{0}'")]
+    InvalidTabWidth(String),
+
+    #[error("invalid auto step: '{0}'")]
+    InvalidAutoStep(String),
+
+    #[error("invalid output language: '{0}'")]
+    InvalidOutputLanguage(String),
+
     #[error("duplicate attribute: {0}")]
     DuplicateAttribute(&'static str),
 
-    #[error("+exec_replace +image and +render can't be used together ")]
+    #[error("+exec_replace +image +render and +chrome can't be used together ")]
     MultipleRepresentation,
 
     #[error("attribute {0} can only be set in +render blocks")]
     NotRenderSnippet(&'static str),
+
+    #[error("attribute {0} can only be set in qr blocks")]
+    NotQrSnippet(&'static str),
+
+    #[error("attribute {0} can only be set in +exec and +exec_replace blocks")]
+    NotExecSnippet(&'static str),
+
+    #[error("invalid chrome position: '{0}'")]
+    InvalidChromePosition(String),
+
+    #[error("invalid error correction level: '{0}'")]
+    InvalidErrorCorrection(String),
+
+    #[error("attribute {0} can't be used as a snippet default")]
+    NotDefaultAttribute(&'static str),
 }
 
 #[derive(EnumDiscriminants)]
@@ -426,15 +832,33 @@ enum SnippetAttribute {
     LineNumbers,
     Exec(SnippetExecutorSpec),
     ExecReplace(SnippetExecutorSpec),
+    Chrome(SnippetChromePosition),
     Validate(SnippetExecutorSpec),
     Image,
     Render,
     HighlightedLines(Vec<HighlightGroup>),
+    SkippedLineNumbers(BTreeSet<u16>),
+    LineNumbersStep(NonZeroU16),
     Width(Percent),
+    MaxRows(NonZeroU16),
+    TabWidth(NonZeroU8),
+    AutoStep(Duration),
     NoBackground,
+    Plain,
+    Trusted,
+    SplitStderr,
+    Persistent,
+    Compact,
+    OutputPolicy(SnippetOutputPolicy),
+    Alignment(SnippetAlignment),
     AcquireTerminal(SnippetExecutorSpec),
     ExpectedExecutionResult(ExpectedSnippetExecutionResult),
     Id(String),
+    PipeFrom(String),
+    Prompt(String),
+    ErrorCorrection(QrErrorCorrection),
+    As(SnippetLanguage),
+    Theme(String),
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
@@ -452,7 +876,7 @@ pub(crate) enum ExpectedSnippetExecutionResult {
 }
 
 /// A code snippet.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq)]
 pub(crate) struct Snippet {
     /// The snippet itself.
     pub(crate) contents: String,
@@ -464,6 +888,37 @@ pub(crate) struct Snippet {
     pub(crate) attributes: SnippetAttributes,
 }
 
+/// Get the text displayed for a line, or `None` if it's hidden.
+///
+/// A line that starts with the prefix's first character followed by the prefix is an escaped line that's displayed
+/// with that extra character removed, e.g. `## x` is displayed as `# x` when the prefix is `# `.
+fn visible_line<'a>(line: &'a str, hidden_line_prefix: Option<&str>) -> Option<&'a str> {
+    let Some(prefix) = hidden_line_prefix else {
+        return Some(line);
+    };
+    if let Some(line) = unescape_line(line, prefix) {
+        Some(line)
+    } else if line.starts_with(prefix) {
+        None
+    } else {
+        Some(line)
+    }
+}
+
+/// Remove the escape from a line that starts with an escaped hidden line prefix.
+fn unescape_line<'a>(line: &'a str, prefix: &str) -> Option<&'a str> {
+    let escape = prefix.chars().next()?;
+    line.strip_prefix(escape).filter(|rest| rest.starts_with(prefix))
+}
+
+/// Strip a prompt from the start of a line.
+///
+/// The prompt must be followed by a space or be the entire line so `$` doesn't strip `$HOME`.
+fn strip_prompt<'a>(line: &'a str, prompt: &str) -> Option<&'a str> {
+    let rest = line.strip_prefix(prompt)?;
+    if rest.is_empty() { Some(rest) } else { rest.strip_prefix(' ') }
+}
+
 impl Snippet {
     pub(crate) fn visible_lines<'a, 'b>(
         &'a self,
@@ -472,19 +927,49 @@ impl Snippet {
     where
         'a: 'b,
     {
-        self.contents.lines().filter(move |line| !hidden_line_prefix.is_some_and(|prefix| line.starts_with(prefix)))
+        self.contents.lines().filter_map(move |line| visible_line(line, hidden_line_prefix))
     }
 
     pub(crate) fn executable_contents(&self, hidden_line_prefix: Option<&str>) -> String {
-        if let Some(prefix) = hidden_line_prefix {
-            self.contents.lines().fold(String::new(), |mut output, line| {
-                let line = line.strip_prefix(prefix).unwrap_or(line);
-                let _ = writeln!(output, "{line}");
-                output
-            })
-        } else {
-            self.contents.to_owned()
+        let prompt = self.attributes.prompt.as_deref();
+        if hidden_line_prefix.is_none() && prompt.is_none() {
+            return self.contents.to_owned();
         }
+        self.contents.lines().fold(String::new(), |mut output, line| {
+            let line = hidden_line_prefix
+                .and_then(|prefix| unescape_line(line, prefix).or_else(|| line.strip_prefix(prefix)))
+                .unwrap_or(line);
+            let line = prompt.and_then(|prompt| strip_prompt(line, prompt)).unwrap_or(line);
+            let _ = writeln!(output, "{line}");
+            output
+        })
+    }
+
+    /// Replace every highlight pattern with the numbers of the visible lines that match it.
+    pub(crate) fn resolve_highlight_patterns(&mut self, hidden_line_prefix: Option<&str>) {
+        let lines: Vec<_> = self.contents.lines().filter_map(|line| visible_line(line, hidden_line_prefix)).collect();
+        for group in &mut self.attributes.highlight_groups {
+            let mut highlights = Vec::new();
+            for highlight in group.0.drain(..) {
+                let Highlight::Pattern(pattern) = highlight else {
+                    highlights.push(highlight);
+                    continue;
+                };
+                let regex = Regex::new(pattern);
+                for (index, line) in lines.iter().enumerate() {
+                    // `is_match` is anchored at the start of the line so search within it instead.
+                    if regex.search(line, 0, line.len(), None) {
+                        highlights.push(Highlight::Single(index as u16 + 1));
+                    }
+                }
+            }
+            group.0 = highlights;
+        }
+    }
+
+    /// Whether there's nothing to execute in this snippet.
+    pub(crate) fn is_executable_empty(&self, hidden_line_prefix: Option<&str>) -> bool {
+        self.executable_contents(hidden_line_prefix).trim().is_empty()
     }
 }
 
@@ -517,6 +1002,7 @@ pub enum SnippetLanguage {
     FSharp,
     Go,
     GraphQL,
+    Graphviz,
     Haskell,
     Html,
     Java,
@@ -538,6 +1024,7 @@ pub enum SnippetLanguage {
     Protobuf,
     Puppet,
     Python,
+    Qr,
     R,
     Racket,
     Ruby,
@@ -564,86 +1051,107 @@ pub enum SnippetLanguage {
 
 crate::utils::impl_deserialize_from_str!(SnippetLanguage);
 
+/// The info string names accepted for every language.
+const LANGUAGE_NAMES: &[(&str, SnippetLanguage)] = &[
+    ("ada", SnippetLanguage::Ada),
+    ("asp", SnippetLanguage::Asp),
+    ("awk", SnippetLanguage::Awk),
+    ("bash", SnippetLanguage::Bash),
+    ("c", SnippetLanguage::C),
+    ("cmake", SnippetLanguage::CMake),
+    ("crontab", SnippetLanguage::Crontab),
+    ("csharp", SnippetLanguage::CSharp),
+    ("clojure", SnippetLanguage::Clojure),
+    ("cpp", SnippetLanguage::Cpp),
+    ("c++", SnippetLanguage::Cpp),
+    ("css", SnippetLanguage::Css),
+    ("d2", SnippetLanguage::D2),
+    ("d", SnippetLanguage::DLang),
+    ("diff", SnippetLanguage::Diff),
+    ("docker", SnippetLanguage::Docker),
+    ("dotenv", SnippetLanguage::Dotenv),
+    ("elixir", SnippetLanguage::Elixir),
+    ("elm", SnippetLanguage::Elm),
+    ("erlang", SnippetLanguage::Erlang),
+    ("file", SnippetLanguage::File),
+    ("fish", SnippetLanguage::Fish),
+    ("fsharp", SnippetLanguage::FSharp),
+    ("go", SnippetLanguage::Go),
+    ("graphql", SnippetLanguage::GraphQL),
+    ("dot", SnippetLanguage::Graphviz),
+    ("graphviz", SnippetLanguage::Graphviz),
+    ("haskell", SnippetLanguage::Haskell),
+    ("html", SnippetLanguage::Html),
+    ("java", SnippetLanguage::Java),
+    ("javascript", SnippetLanguage::JavaScript),
+    ("js", SnippetLanguage::JavaScript),
+    ("json", SnippetLanguage::Json),
+    ("jsonnet", SnippetLanguage::Jsonnet),
+    ("julia", SnippetLanguage::Julia),
+    ("kotlin", SnippetLanguage::Kotlin),
+    ("latex", SnippetLanguage::Latex),
+    ("lua", SnippetLanguage::Lua),
+    ("make", SnippetLanguage::Makefile),
+    ("markdown", SnippetLanguage::Markdown),
+    ("mermaid", SnippetLanguage::Mermaid),
+    ("nix", SnippetLanguage::Nix),
+    ("nushell", SnippetLanguage::Nushell),
+    ("nu", SnippetLanguage::Nushell),
+    ("ocaml", SnippetLanguage::OCaml),
+    ("perl", SnippetLanguage::Perl),
+    ("php", SnippetLanguage::Php),
+    ("protobuf", SnippetLanguage::Protobuf),
+    ("puppet", SnippetLanguage::Puppet),
+    ("python", SnippetLanguage::Python),
+    ("qr", SnippetLanguage::Qr),
+    ("r", SnippetLanguage::R),
+    ("racket", SnippetLanguage::Racket),
+    ("ruby", SnippetLanguage::Ruby),
+    ("rust", SnippetLanguage::Rust),
+    ("rust-script", SnippetLanguage::RustScript),
+    ("scala", SnippetLanguage::Scala),
+    ("shell", SnippetLanguage::Shell),
+    ("sh", SnippetLanguage::Shell),
+    ("sql", SnippetLanguage::Sql),
+    ("svelte", SnippetLanguage::Svelte),
+    ("swift", SnippetLanguage::Swift),
+    ("tcl", SnippetLanguage::Tcl),
+    ("terraform", SnippetLanguage::Terraform),
+    ("toml", SnippetLanguage::Toml),
+    ("typescript", SnippetLanguage::TypeScript),
+    ("ts", SnippetLanguage::TypeScript),
+    ("typst", SnippetLanguage::Typst),
+    ("xml", SnippetLanguage::Xml),
+    ("yaml", SnippetLanguage::Yaml),
+    ("verilog", SnippetLanguage::Verilog),
+    ("vue", SnippetLanguage::Vue),
+    ("zig", SnippetLanguage::Zig),
+    ("zsh", SnippetLanguage::Zsh),
+];
+
+impl SnippetLanguage {
+    /// Get the names that can be used in a snippet's info string to refer to this language.
+    pub(crate) fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        LANGUAGE_NAMES.iter().filter(move |(_, language)| language == self).map(|(name, _)| *name)
+    }
+}
+
 impl FromStr for SnippetLanguage {
     type Err = Infallible;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        use SnippetLanguage::*;
-        let language = match s.to_lowercase().as_str() {
-            "ada" => Ada,
-            "asp" => Asp,
-            "awk" => Awk,
-            "bash" => Bash,
-            "c" => C,
-            "cmake" => CMake,
-            "crontab" => Crontab,
-            "csharp" => CSharp,
-            "clojure" => Clojure,
-            "cpp" | "c++" => Cpp,
-            "css" => Css,
-            "d2" => D2,
-            "d" => DLang,
-            "diff" => Diff,
-            "docker" => Docker,
-            "dotenv" => Dotenv,
-            "elixir" => Elixir,
-            "elm" => Elm,
-            "erlang" => Erlang,
-            "file" => File,
-            "fish" => Fish,
-            "fsharp" => FSharp,
-            "go" => Go,
-            "graphql" => GraphQL,
-            "haskell" => Haskell,
-            "html" => Html,
-            "java" => Java,
-            "javascript" | "js" => JavaScript,
-            "json" => Json,
-            "jsonnet" => Jsonnet,
-            "julia" => Julia,
-            "kotlin" => Kotlin,
-            "latex" => Latex,
-            "lua" => Lua,
-            "make" => Makefile,
-            "markdown" => Markdown,
-            "mermaid" => Mermaid,
-            "nix" => Nix,
-            "nushell" | "nu" => Nushell,
-            "ocaml" => OCaml,
-            "perl" => Perl,
-            "php" => Php,
-            "protobuf" => Protobuf,
-            "puppet" => Puppet,
-            "python" => Python,
-            "r" => R,
-            "racket" => Racket,
-            "ruby" => Ruby,
-            "rust" => Rust,
-            "rust-script" => RustScript,
-            "scala" => Scala,
-            "shell" | "sh" => Shell,
-            "sql" => Sql,
-            "svelte" => Svelte,
-            "swift" => Swift,
-            "tcl" => Tcl,
-            "terraform" => Terraform,
-            "toml" => Toml,
-            "typescript" | "ts" => TypeScript,
-            "typst" => Typst,
-            "xml" => Xml,
-            "yaml" => Yaml,
-            "verilog" => Verilog,
-            "vue" => Vue,
-            "zig" => Zig,
-            "zsh" => Zsh,
-            other => Unknown(other.to_string()),
-        };
+        let s = s.to_lowercase();
+        let language = LANGUAGE_NAMES
+            .iter()
+            .find(|(name, _)| *name == s)
+            .map(|(_, language)| language.clone())
+            .unwrap_or(SnippetLanguage::Unknown(s));
         Ok(language)
     }
 }
 
 /// Attributes for code snippets.
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub(crate) struct SnippetAttributes {
     /// The way the snippet should be represented.
     pub(crate) representation: SnippetRepr,
@@ -657,6 +1165,18 @@ pub(crate) struct SnippetAttributes {
     /// The groups of lines to highlight.
     pub(crate) highlight_groups: Vec<HighlightGroup>,
 
+    /// The lines that shouldn't get a line number when line numbers are enabled.
+    pub(crate) skipped_line_numbers: BTreeSet<u16>,
+
+    /// The lines at which line numbering starts over from 1.
+    pub(crate) line_number_restarts: BTreeSet<u16>,
+
+    /// The url each line's number links to, keyed by line.
+    pub(crate) line_links: BTreeMap<u16, String>,
+
+    /// Only display line numbers on lines whose number is a multiple of this.
+    pub(crate) line_numbers_step: Option<NonZeroU16>,
+
     /// The width of the generated image.
     ///
     /// Only valid for +render snippets.
@@ -665,11 +1185,109 @@ pub(crate) struct SnippetAttributes {
     /// Whether to add no background to a snippet.
     pub(crate) no_background: bool,
 
+    /// Whether to skip syntax highlighting and use a single color for the entire snippet.
+    pub(crate) plain: bool,
+
+    /// Whether this snippet is trusted and can be executed even if snippet execution is disabled.
+    ///
+    /// This is only honored if trusted snippets are explicitly allowed.
+    pub(crate) trusted: bool,
+
+    /// Whether to read stderr separately from stdout so it can be displayed using its own style.
+    pub(crate) split_stderr: bool,
+
+    /// Whether the process spawned when executing this snippet should keep running until the
+    /// presentation is closed.
+    pub(crate) persistent: bool,
+
+    /// Whether to display this snippet's execution status and output without any blank lines
+    /// around them.
+    pub(crate) compact: bool,
+
+    /// What happens to this snippet's output when its slide is entered again.
+    pub(crate) output_policy: Option<SnippetOutputPolicy>,
+
+    /// The maximum number of rows to display at once, scrolling through the rest.
+    pub(crate) max_rows: Option<NonZeroU16>,
+
+    /// The alignment to use for this snippet, overriding the theme's.
+    pub(crate) alignment: Option<SnippetAlignment>,
+
+    /// The number of spaces each tab is expanded into.
+    pub(crate) tab_width: Option<NonZeroU8>,
+
+    /// The interval at which highlight groups are automatically advanced.
+    pub(crate) auto_step: Option<Duration>,
+
     /// The expected execution result for a snippet.
     pub(crate) expected_execution_result: ExpectedSnippetExecutionResult,
 
     /// The identifier for a snippet.
     pub(crate) id: Option<String>,
+
+    /// The identifier of the snippet whose output is fed into this snippet's standard input.
+    pub(crate) pipe_from: Option<String>,
+
+    /// The prompt that's displayed at the start of lines but stripped before executing them.
+    pub(crate) prompt: Option<String>,
+
+    /// The error correction level to use when rendering a QR code.
+    pub(crate) error_correction: Option<QrErrorCorrection>,
+
+    /// The language to use when highlighting this snippet's output.
+    ///
+    /// Only valid for +exec and +exec_replace snippets.
+    pub(crate) output_language: Option<SnippetLanguage>,
+
+    /// The highlighter theme to use for this snippet, overriding the one in the presentation theme.
+    pub(crate) theme: Option<String>,
+}
+
+impl SnippetAttributes {
+    /// Fill in every attribute that isn't set in this snippet using the given defaults.
+    pub(crate) fn merge_defaults(&mut self, defaults: &SnippetAttributes) {
+        if self.representation == SnippetRepr::Snippet {
+            self.representation = defaults.representation.clone();
+        }
+        if matches!(self.execution, SnippetExec::None) {
+            self.execution = defaults.execution.clone();
+        }
+        if self.highlight_groups == [HighlightGroup::new(vec![Highlight::All])] {
+            self.highlight_groups = defaults.highlight_groups.clone();
+        }
+        if self.skipped_line_numbers.is_empty() {
+            self.skipped_line_numbers = defaults.skipped_line_numbers.clone();
+        }
+        if self.line_number_restarts.is_empty() {
+            self.line_number_restarts = defaults.line_number_restarts.clone();
+        }
+        // Widths only make sense for +render snippets.
+        if self.representation == SnippetRepr::Render && self.width.is_none() {
+            self.width = defaults.width.clone();
+        }
+        if self.expected_execution_result == ExpectedSnippetExecutionResult::default() {
+            self.expected_execution_result = defaults.expected_execution_result;
+        }
+        self.line_numbers |= defaults.line_numbers;
+        self.no_background |= defaults.no_background;
+        self.plain |= defaults.plain;
+        self.trusted |= defaults.trusted;
+        self.split_stderr |= defaults.split_stderr;
+        self.persistent |= defaults.persistent;
+        self.compact |= defaults.compact;
+        self.line_numbers_step = self.line_numbers_step.or(defaults.line_numbers_step);
+        self.max_rows = self.max_rows.or(defaults.max_rows);
+        self.alignment = self.alignment.or(defaults.alignment);
+        self.tab_width = self.tab_width.or(defaults.tab_width);
+        self.auto_step = self.auto_step.or(defaults.auto_step);
+        self.output_policy = self.output_policy.or(defaults.output_policy);
+        if self.prompt.is_none() {
+            self.prompt = defaults.prompt.clone();
+        }
+        if self.theme.is_none() {
+            self.theme = defaults.theme.clone();
+        }
+    }
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
@@ -679,6 +1297,51 @@ pub(crate) enum SnippetRepr {
     Image,
     Render,
     ExecReplace,
+    Chrome(SnippetChromePosition),
+}
+
+/// The position of the screen a `+chrome` snippet's output is placed at.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum SnippetChromePosition {
+    TopLeft,
+    Top,
+    TopRight,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+/// What happens to a snippet's output when navigating back to the slide it's in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum SnippetOutputPolicy {
+    /// Keep the output of the previous run.
+    Keep,
+
+    /// Clear the output so the snippet can be run again.
+    Clear,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum SnippetAlignment {
+    Left,
+    Center,
+    Right,
+}
+
+impl SnippetAlignment {
+    /// Apply this alignment on top of an existing one, keeping its margins.
+    pub(crate) fn apply(&self, alignment: Alignment) -> Alignment {
+        let margin = match alignment {
+            Alignment::Left { margin } | Alignment::Right { margin } => margin,
+            Alignment::Center { minimum_margin, .. } => minimum_margin,
+        };
+        match (self, alignment) {
+            (Self::Left, _) => Alignment::Left { margin },
+            (Self::Right, _) => Alignment::Right { margin },
+            (Self::Center, Alignment::Center { .. }) => alignment,
+            (Self::Center, _) => Alignment::Center { minimum_margin: margin, minimum_size: 0 },
+        }
+    }
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
@@ -704,11 +1367,19 @@ impl HighlightGroup {
                 Highlight::All => return true,
                 Highlight::Single(number) if number == &line_number => return true,
                 Highlight::Range(range) if range.contains(&line_number) => return true,
+                // Patterns are resolved into line numbers once the snippet's contents are known.
                 _ => continue,
             };
         }
         false
     }
+
+    /// Check whether any line in this group is at most `distance` lines away from the given one.
+    pub(crate) fn is_near(&self, line_number: u16, distance: u16) -> bool {
+        let start = line_number.saturating_sub(distance);
+        let end = line_number.saturating_add(distance);
+        (start..=end).any(|line_number| self.contains(line_number))
+    }
 }
 
 /// A highlighted set of lines
@@ -717,19 +1388,56 @@ pub(crate) enum Highlight {
     All,
     Single(u16),
     Range(Range<u16>),
+
+    /// Every line matching a regex.
+    Pattern(String),
 }
 
 #[derive(Debug, Deserialize)]
 pub(crate) struct ExternalFile {
-    pub(crate) path: PathBuf,
+    pub(crate) path: Option<PathBuf>,
     pub(crate) language: SnippetLanguage,
     pub(crate) start_line: Option<usize>,
     pub(crate) end_line: Option<usize>,
+
+    /// The files to concatenate into this snippet, as an alternative to `path`.
+    #[serde(default)]
+    pub(crate) files: Vec<ExternalFilePart>,
+
+    /// A line to place in between every file.
+    pub(crate) separator: Option<String>,
+
+    /// The url line numbers link to, where `{line}` is replaced by the line's number in the file.
+    pub(crate) line_url: Option<String>,
+
+    #[serde(default)]
+    pub(crate) numbering: ExternalFileNumbering,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ExternalFilePart {
+    pub(crate) path: PathBuf,
+    pub(crate) start_line: Option<usize>,
+    pub(crate) end_line: Option<usize>,
+    pub(crate) line_url: Option<String>,
+}
+
+/// How lines are numbered when concatenating several files.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ExternalFileNumbering {
+    /// Keep counting across files.
+    #[default]
+    Continuous,
+
+    /// Start counting from 1 on every file.
+    PerFile,
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::theme::{HiddenLinesMarkerStyle, Margin};
     use Highlight::*;
     use rstest::rstest;
 
@@ -772,11 +1480,103 @@ mod test {
         }
     }
 
+    #[rstest]
+    #[case::empty("", None, true)]
+    #[case::whitespace(" \n\t\n", None, true)]
+    #[case::hidden_only("# \n# \n", Some("# "), true)]
+    #[case::hidden_code("# let x = 1;\n", Some("# "), false)]
+    #[case::code("echo hi", None, false)]
+    fn executable_empty(#[case] contents: &str, #[case] prefix: Option<&str>, #[case] expected: bool) {
+        let code =
+            Snippet { contents: contents.into(), language: SnippetLanguage::Rust, attributes: Default::default() };
+        assert_eq!(code.is_executable_empty(prefix), expected);
+    }
+
+    #[test]
+    fn code_with_skipped_line_numbers() {
+        let code = Snippet {
+            contents: "a\nb\nc\nd\n".into(),
+            language: SnippetLanguage::Unknown("".to_string()),
+            attributes: parse_attributes("foo +line_numbers +skip_numbers:1,3"),
+        };
+        let lines = SnippetSplitter::new(&Default::default(), None).split(&code);
+        let prefixes: Vec<_> = lines.iter().map(|line| line.prefix.as_str()).collect();
+        assert_eq!(prefixes, &["  ", "1 ", "  ", "2 "]);
+
+        // highlighting still refers to the actual line positions
+        let line_numbers: Vec<_> = lines.iter().map(|line| line.line_number).collect();
+        assert_eq!(line_numbers, &[Some(1), Some(2), Some(3), Some(4)]);
+    }
+
+    #[rstest]
+    #[case::none("a\nb", &["a\n", "b\n"])]
+    #[case::one("a\nb\n", &["a\n", "b\n"])]
+    #[case::two("a\nb\n\n", &["a\n", "b\n", "\n"])]
+    #[case::crlf("a\r\nb\r\n", &["a\n", "b\n"])]
+    fn trailing_newlines(#[case] contents: &str, #[case] expected: &[&str]) {
+        let code = Snippet {
+            contents: contents.into(),
+            language: SnippetLanguage::Unknown("".to_string()),
+            attributes: Default::default(),
+        };
+        let lines = SnippetSplitter::new(&Default::default(), None).split(&code);
+        let lines: Vec<_> = lines.iter().map(|line| line.code.as_str()).collect();
+        assert_eq!(lines, expected);
+    }
+
+    #[test]
+    fn code_with_line_numbers_step() {
+        let code = Snippet {
+            contents: (1..=12).map(|line| format!("{line}\n")).collect(),
+            language: SnippetLanguage::Unknown("".to_string()),
+            attributes: parse_attributes("foo +line_numbers_step:5"),
+        };
+        let lines = SnippetSplitter::new(&Default::default(), None).split(&code);
+        let prefixes: Vec<_> = lines.iter().map(|line| line.prefix.as_str()).collect();
+        let expected = &["   ", "   ", "   ", "   ", " 5 ", "   ", "   ", "   ", "   ", "10 ", "   ", "   "];
+        assert_eq!(prefixes, expected);
+    }
+
+    #[rstest]
+    #[case::zero("foo +line_numbers_step:0")]
+    #[case::not_a_number("foo +line_numbers_step:a")]
+    fn parse_invalid_line_numbers_step(#[case] input: &str) {
+        try_parse_attributes(input).expect_err("parse succeeded");
+    }
+
+    #[rstest]
+    #[case::single("foo +skip_numbers:3", &[3])]
+    #[case::multiple("foo +skip_numbers:3,7", &[3, 7])]
+    fn parse_skip_numbers(#[case] input: &str, #[case] expected: &[u16]) {
+        let attributes = parse_attributes(input);
+        assert_eq!(attributes.skipped_line_numbers, expected.iter().copied().collect());
+    }
+
+    #[rstest]
+    #[case::empty("foo +skip_numbers:")]
+    #[case::not_a_number("foo +skip_numbers:a")]
+    #[case::trailing_comma("foo +skip_numbers:3,")]
+    fn parse_invalid_skip_numbers(#[case] input: &str) {
+        try_parse_attributes(input).expect_err("parse succeeded");
+    }
+
     #[test]
     fn unknown_language() {
         assert_eq!(parse_language("potato"), SnippetLanguage::Unknown("potato".to_string()));
     }
 
+    #[rstest]
+    #[case::single(SnippetLanguage::Rust, &["rust"])]
+    #[case::aliased(SnippetLanguage::Cpp, &["cpp", "c++"])]
+    #[case::graphviz(SnippetLanguage::Graphviz, &["dot", "graphviz"])]
+    fn language_names(#[case] language: SnippetLanguage, #[case] expected: &[&str]) {
+        let names: Vec<_> = language.names().collect();
+        assert_eq!(names, expected);
+        for name in names {
+            assert_eq!(SnippetLanguage::from_str(&name.to_uppercase()).unwrap(), language);
+        }
+    }
+
     #[test]
     fn no_attributes() {
         assert_eq!(parse_language("rust"), SnippetLanguage::Rust);
@@ -863,11 +1663,117 @@ mod test {
         assert_eq!(attributes.highlight_groups[1], HighlightGroup::new(vec![Range(6..10)]));
     }
 
+    #[test]
+    fn pattern_groups() {
+        let attributes = parse_attributes(r"bash {/unsafe/, 3 | /a|b,c}/ | /x\/y/} +line_numbers");
+        assert!(attributes.line_numbers);
+        assert_eq!(
+            attributes.highlight_groups,
+            &[
+                HighlightGroup::new(vec![Pattern("unsafe".into()), Single(3)]),
+                HighlightGroup::new(vec![Pattern("a|b,c}".into())]),
+                HighlightGroup::new(vec![Pattern("x/y".into())]),
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case::empty("bash {//}")]
+    #[case::invalid_regex("bash {/(foo/}")]
+    #[case::unterminated("bash {/foo}")]
+    fn parse_invalid_pattern_groups(#[case] input: &str) {
+        try_parse_attributes(input).expect_err("parse succeeded");
+    }
+
+    #[test]
+    fn resolve_pattern_groups() {
+        let mut snippet = Snippet {
+            contents: "fn foo() {\n# hidden unsafe\n    unsafe { bar() }\n}\nunsafe fn bar() {}\n".into(),
+            language: SnippetLanguage::Rust,
+            attributes: parse_attributes("rust {1|/^unsafe/,/unsafe/}"),
+        };
+        snippet.resolve_highlight_patterns(Some("# "));
+        assert_eq!(
+            snippet.attributes.highlight_groups,
+            &[HighlightGroup::new(vec![Single(1)]), HighlightGroup::new(vec![Single(4), Single(2), Single(4)])]
+        );
+    }
+
+    #[test]
+    fn parse_plain() {
+        let attributes = parse_attributes("rust +plain +line_numbers");
+        assert!(attributes.plain);
+        assert!(attributes.line_numbers);
+    }
+
+    #[test]
+    fn parse_trusted() {
+        let attributes = parse_attributes("bash +exec +trusted");
+        assert!(attributes.trusted);
+        assert_eq!(attributes.execution, SnippetExec::Exec(Default::default()));
+    }
+
+    #[test]
+    fn parse_split_stderr() {
+        let attributes = parse_attributes("bash +exec +split_stderr");
+        assert!(attributes.split_stderr);
+    }
+
+    #[test]
+    fn parse_persistent() {
+        let attributes = parse_attributes("bash +exec +persistent");
+        assert!(attributes.persistent);
+        assert_eq!(attributes.execution, SnippetExec::Exec(Default::default()));
+    }
+
+    #[test]
+    fn parse_compact() {
+        let attributes = parse_attributes("bash +exec +compact");
+        assert!(attributes.compact);
+    }
+
+    #[test]
+    fn parse_max_rows() {
+        let attributes = parse_attributes("bash +max_rows:15");
+        assert_eq!(attributes.max_rows, NonZeroU16::new(15));
+    }
+
+    #[rstest]
+    #[case::seconds("2s", Duration::from_secs(2))]
+    #[case::fractional_seconds("1.5s", Duration::from_millis(1500))]
+    #[case::millis("300ms", Duration::from_millis(300))]
+    fn parse_auto_step(#[case] input: &str, #[case] expected: Duration) {
+        let attributes = parse_attributes(&format!("bash +auto_step:{input}"));
+        assert_eq!(attributes.auto_step, Some(expected));
+    }
+
+    #[rstest]
+    #[case::no_unit("foo +auto_step:2")]
+    #[case::zero("foo +auto_step:0s")]
+    #[case::negative("foo +auto_step:-1s")]
+    #[case::not_a_number("foo +auto_step:as")]
+    fn parse_invalid_auto_step(#[case] input: &str) {
+        try_parse_attributes(input).expect_err("parse succeeded");
+    }
+
+    #[test]
+    fn parse_pipe_from() {
+        let attributes = parse_attributes("bash +exec +pipe_from:foo");
+        assert_eq!(attributes.pipe_from.as_deref(), Some("foo"));
+    }
+
+    #[rstest]
+    #[case::zero("foo +max_rows:0")]
+    #[case::not_a_number("foo +max_rows:a")]
+    fn parse_invalid_max_rows(#[case] input: &str) {
+        try_parse_attributes(input).expect_err("parse succeeded");
+    }
+
     #[test]
     fn parse_width() {
         let attributes = parse_attributes("mermaid +width:50% +render");
         assert_eq!(attributes.representation, SnippetRepr::Render);
-        assert_eq!(attributes.width, Some(Percent(50)));
+        assert_eq!(attributes.width, Some(Percent(50.0)));
     }
 
     #[test]
@@ -891,6 +1797,18 @@ println!("Hello world");
         assert_eq!(expected, code.visible_lines(Some("# ")).collect::<Vec<_>>());
     }
 
+    #[test]
+    fn escaped_hidden_line_prefix() {
+        let contents = "## a comment\n# echo hidden\necho hi\n".to_string();
+        let code = Snippet { contents, language: SnippetLanguage::Bash, attributes: Default::default() };
+        assert_eq!(code.visible_lines(Some("# ")).collect::<Vec<_>>(), &["# a comment", "echo hi"]);
+        assert_eq!(code.executable_contents(Some("# ")), "# a comment\necho hidden\necho hi\n");
+
+        let lines = SnippetSplitter::new(&Default::default(), Some("# ")).split(&code);
+        let lines: Vec<_> = lines.iter().map(|line| line.code.as_str()).collect();
+        assert_eq!(lines, &["# a comment\n", "echo hi\n"]);
+    }
+
     #[test]
     fn code_executable_contents() {
         let contents = r##"# fn main() {
@@ -911,6 +1829,76 @@ println!("Hello world");
         assert_eq!(expected, code.executable_contents(Some("# ")));
     }
 
+    #[rstest]
+    #[case::default("qr", None)]
+    #[case::high("qr +error_correction:high", Some(QrErrorCorrection::High))]
+    fn parse_qr(#[case] input: &str, #[case] expected: Option<QrErrorCorrection>) {
+        let attributes = parse_attributes(input);
+        assert_eq!(attributes.representation, SnippetRepr::Render);
+        assert_eq!(attributes.error_correction, expected);
+    }
+
+    #[rstest]
+    #[case::not_qr("bash +error_correction:high")]
+    #[case::invalid_level("qr +error_correction:potato")]
+    fn parse_invalid_error_correction(#[case] input: &str) {
+        SnippetParser::parse_block_info(input).expect_err("parse succeeded");
+    }
+
+    #[rstest]
+    #[case::top_left("top_left", SnippetChromePosition::TopLeft)]
+    #[case::bottom("bottom", SnippetChromePosition::Bottom)]
+    fn parse_chrome(#[case] position: &str, #[case] expected: SnippetChromePosition) {
+        let attributes = parse_attributes(&format!("bash +chrome:{position}"));
+        assert_eq!(attributes.representation, SnippetRepr::Chrome(expected));
+        assert_eq!(attributes.execution, SnippetExec::Exec(Default::default()));
+    }
+
+    #[rstest]
+    #[case::invalid_position("bash +chrome:middle")]
+    #[case::exec_replace("bash +chrome:top +exec_replace")]
+    fn parse_invalid_chrome(#[case] input: &str) {
+        SnippetParser::parse_block_info(input).expect_err("parse succeeded");
+    }
+
+    #[rstest]
+    #[case::exec("bash +exec +as:rust")]
+    #[case::exec_replace("bash +exec_replace +as:rust")]
+    fn parse_output_language(#[case] input: &str) {
+        let attributes = parse_attributes(input);
+        assert_eq!(attributes.output_language, Some(SnippetLanguage::Rust));
+    }
+
+    #[rstest]
+    #[case::not_exec("bash +as:rust")]
+    #[case::empty("bash +exec +as:")]
+    #[case::unknown("bash +exec +as:potato")]
+    fn parse_invalid_output_language(#[case] input: &str) {
+        SnippetParser::parse_block_info(input).expect_err("parse succeeded");
+    }
+
+    #[test]
+    fn parse_theme() {
+        let attributes = parse_attributes("bash +theme:base16-ocean.dark");
+        assert_eq!(attributes.theme.as_deref(), Some("base16-ocean.dark"));
+    }
+
+    #[test]
+    fn parse_prompt() {
+        let attributes = parse_attributes("bash +exec +prompt:$");
+        assert_eq!(attributes.prompt.as_deref(), Some("$"));
+    }
+
+    #[rstest]
+    #[case::no_hidden_prefix(None, "/// $ echo hidden\necho hi\necho $HOME\n\n  $ indented\n")]
+    #[case::hidden_prefix(Some("/// "), "echo hidden\necho hi\necho $HOME\n\n  $ indented\n")]
+    fn executable_contents_with_prompt(#[case] hidden_line_prefix: Option<&str>, #[case] expected: &str) {
+        let contents = "/// $ echo hidden\n$ echo hi\n$ echo $HOME\n$\n  $ indented\n".to_string();
+        let attributes = SnippetAttributes { prompt: Some("$".into()), ..Default::default() };
+        let code = Snippet { contents, language: SnippetLanguage::Bash, attributes };
+        assert_eq!(code.executable_contents(hidden_line_prefix), expected);
+    }
+
     #[test]
     fn tabs_in_snippet() {
         let snippet = Snippet { contents: "\thi".into(), language: SnippetLanguage::C, attributes: Default::default() };
@@ -918,6 +1906,56 @@ println!("Hello world");
         assert_eq!(lines[0].code, "    hi\n");
     }
 
+    #[test]
+    fn tab_width() {
+        let attributes = parse_attributes("bash +tab_width:2");
+        let snippet = Snippet { contents: "\thi".into(), language: SnippetLanguage::C, attributes };
+        let lines = SnippetSplitter::new(&Default::default(), None).split(&snippet);
+        assert_eq!(lines[0].code, "  hi\n");
+    }
+
+    #[test]
+    fn merge_defaults() {
+        let mut attributes = parse_attributes("bash +right +max_rows:3 {1}");
+        let defaults = SnippetParser::parse_defaults("+line_numbers +center +max_rows:5 +tab_width:2").unwrap();
+        attributes.merge_defaults(&defaults);
+        assert!(attributes.line_numbers);
+        assert_eq!(attributes.alignment, Some(SnippetAlignment::Right));
+        assert_eq!(attributes.max_rows, NonZeroU16::new(3));
+        assert_eq!(attributes.tab_width, NonZeroU8::new(2));
+        assert_eq!(attributes.highlight_groups, &[HighlightGroup::new(vec![Highlight::Single(1)])]);
+    }
+
+    #[rstest]
+    #[case::none("bash +exec", "", None)]
+    #[case::keep("bash +exec +keep_output", "", Some(SnippetOutputPolicy::Keep))]
+    #[case::clear("bash +exec +clear_output", "", Some(SnippetOutputPolicy::Clear))]
+    #[case::default_clear("bash +exec", "+clear_output", Some(SnippetOutputPolicy::Clear))]
+    #[case::override_default("bash +exec +keep_output", "+clear_output", Some(SnippetOutputPolicy::Keep))]
+    fn output_policy(#[case] input: &str, #[case] defaults: &str, #[case] expected: Option<SnippetOutputPolicy>) {
+        let mut attributes = parse_attributes(input);
+        attributes.merge_defaults(&SnippetParser::parse_defaults(defaults).unwrap());
+        assert_eq!(attributes.output_policy, expected);
+    }
+
+    #[test]
+    fn conflicting_output_policies() {
+        SnippetParser::parse_block_info("bash +exec +keep_output +clear_output").expect_err("parse succeeded");
+    }
+
+    #[test]
+    fn hidden_lines_marker() {
+        let contents = "# a\n# b\nc\n# d\ne".into();
+        let attributes = SnippetAttributes { line_numbers: true, ..Default::default() };
+        let snippet = Snippet { contents, language: SnippetLanguage::Rust, attributes };
+        let marker = HiddenLinesMarkerStyle { symbol: "...".into(), style: Default::default() };
+        let style = CodeBlockStyle { hidden_lines_marker: Some(marker), ..Default::default() };
+        let lines = SnippetSplitter::new(&style, Some("# ")).split(&snippet);
+        let lines: Vec<_> = lines.iter().map(|line| (line.prefix.as_str(), line.code.as_str(), line.marker)).collect();
+        let expected = &[("  ", "...\n", true), ("1 ", "c\n", false), ("  ", "...\n", true), ("2 ", "e\n", false)];
+        assert_eq!(lines, expected);
+    }
+
     #[rstest]
     #[case::exec("bash +exec:foo", SnippetExecutorSpec::Alternative("foo".to_string()))]
     #[case::exec_and_more("bash +exec:foo +line_numbers", SnippetExecutorSpec::Alternative("foo".to_string()))]
@@ -942,4 +1980,38 @@ println!("Hello world");
         let attributes = parse_attributes(&format!("bash +{input}"));
         assert_eq!(attributes.expected_execution_result, expected);
     }
+
+    #[rstest]
+    #[case::left("left", SnippetAlignment::Left)]
+    #[case::center("center", SnippetAlignment::Center)]
+    #[case::right("right", SnippetAlignment::Right)]
+    fn parse_alignment(#[case] input: &str, #[case] expected: SnippetAlignment) {
+        let attributes = parse_attributes(&format!("bash +{input}"));
+        assert_eq!(attributes.alignment, Some(expected));
+    }
+
+    #[test]
+    fn multiple_alignments() {
+        try_parse_attributes("bash +left +center").expect_err("parse succeeded");
+    }
+
+    #[rstest]
+    #[case::left_to_center(
+        SnippetAlignment::Center,
+        Alignment::Left { margin: Margin::Fixed(2) },
+        Alignment::Center { minimum_margin: Margin::Fixed(2), minimum_size: 0 }
+    )]
+    #[case::center_to_left(
+        SnippetAlignment::Left,
+        Alignment::Center { minimum_margin: Margin::Fixed(2), minimum_size: 40 },
+        Alignment::Left { margin: Margin::Fixed(2) }
+    )]
+    #[case::center_to_center(
+        SnippetAlignment::Center,
+        Alignment::Center { minimum_margin: Margin::Fixed(2), minimum_size: 40 },
+        Alignment::Center { minimum_margin: Margin::Fixed(2), minimum_size: 40 }
+    )]
+    fn apply_alignment(#[case] alignment: SnippetAlignment, #[case] base: Alignment, #[case] expected: Alignment) {
+        assert_eq!(alignment.apply(base), expected);
+    }
 }
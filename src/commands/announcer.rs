@@ -0,0 +1,70 @@
+use crate::presentation::{Presentation, SlideSummary};
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, Write},
+    path::Path,
+};
+
+/// Writes a description of every slide that's displayed into a file.
+///
+/// This is meant to be consumed by screen readers or any other tool that can read text as it's
+/// appended to a file or named pipe.
+pub struct SlideAnnouncer {
+    writer: File,
+    last_slide: Option<usize>,
+}
+
+impl SlideAnnouncer {
+    pub fn new(path: &Path) -> io::Result<Self> {
+        let writer = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self { writer, last_slide: None })
+    }
+
+    /// Announce the current slide in the given presentation, if it changed since the last call.
+    pub(crate) fn announce(&mut self, presentation: &Presentation) -> io::Result<()> {
+        let index = presentation.current_slide_index();
+        if self.last_slide == Some(index) {
+            return Ok(());
+        }
+        self.last_slide = Some(index);
+
+        let description =
+            describe(index + 1, presentation.iter_slides().count(), presentation.current_slide().summary());
+        writeln!(self.writer, "{description}")?;
+        self.writer.flush()
+    }
+}
+
+fn describe(number: usize, total: usize, summary: &SlideSummary) -> String {
+    let mut description = format!("Slide {number} of {total}");
+    if let Some(title) = &summary.title {
+        description.push_str(&format!(": {title}"));
+    }
+    description.push('.');
+    if !summary.content.is_empty() {
+        let content: Vec<_> = summary.content.iter().map(ToString::to_string).collect();
+        description.push_str(&format!(" Contents: {}.", content.join(", ")));
+    }
+    description
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::presentation::SlideContent;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::empty(None, &[], "Slide 2 of 5.")]
+    #[case::title(Some("Intro"), &[], "Slide 2 of 5: Intro.")]
+    #[case::content(
+        Some("Intro"),
+        &[SlideContent::Image, SlideContent::Text, SlideContent::Code],
+        "Slide 2 of 5: Intro. Contents: text, code, image."
+    )]
+    fn description(#[case] title: Option<&str>, #[case] content: &[SlideContent], #[case] expected: &str) {
+        let summary =
+            SlideSummary { title: title.map(ToString::to_string), content: content.iter().copied().collect() };
+        assert_eq!(describe(2, 5, &summary), expected);
+    }
+}
@@ -1,17 +1,31 @@
-use super::listener::{Command, CommandDiscriminants};
+use super::listener::{Command, CommandDiscriminants, FilterInput};
 use crate::config::KeyBindingsConfig;
 use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, poll, read};
-use std::{fmt, io, iter, mem, str::FromStr, time::Duration};
+use std::{
+    fmt, io, iter,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+/// The binding used to leave a command without any keys bound to it.
+const NO_BINDING: &str = "none";
 
 /// A keyboard command listener.
 pub struct KeyboardListener {
     bindings: CommandKeyBindings,
-    events: Vec<KeyEvent>,
+    sequence: KeySequence,
+    text_input: bool,
 }
 
 impl KeyboardListener {
     pub fn new(bindings: CommandKeyBindings) -> Self {
-        Self { bindings, events: Vec::new() }
+        let sequence = KeySequence::new(bindings.sequence_timeout);
+        Self { bindings, sequence, text_input: false }
+    }
+
+    /// Set whether typed keys should be treated as text input rather than key bindings.
+    pub(crate) fn set_text_input(&mut self, enabled: bool) {
+        self.text_input = enabled;
     }
 
     /// Polls for the next input command coming from the keyboard.
@@ -21,27 +35,79 @@ impl KeyboardListener {
 
     /// Blocks waiting for the next command.
     pub(crate) fn next_command(&mut self) -> io::Result<Option<Command>> {
-        let mut events = mem::take(&mut self.events);
-        let (command, events) = match read()? {
+        let command = match read()? {
             // Ignore release events
-            Event::Key(event) if event.kind == KeyEventKind::Release => (None, events),
+            Event::Key(event) if event.kind == KeyEventKind::Release => None,
+            Event::Key(event) if self.text_input && text_input(&event).is_some() => {
+                self.sequence.clear();
+                text_input(&event).map(Command::FilterInput)
+            }
             Event::Key(event) => {
-                events.push(event);
-                self.match_events(events)
+                let events = self.sequence.push(event, Instant::now());
+                match self.bindings.apply(events) {
+                    InputAction::Emit(command) => {
+                        self.sequence.clear();
+                        Some(command)
+                    }
+                    InputAction::Buffer => None,
+                    InputAction::Reset => {
+                        self.sequence.clear();
+                        None
+                    }
+                }
+            }
+            Event::Resize(..) => Some(Command::Redraw),
+            _ => {
+                self.sequence.clear();
+                None
             }
-            Event::Resize(..) => (Some(Command::Redraw), events),
-            _ => (None, vec![]),
         };
-        self.events = events;
         Ok(command)
     }
+}
 
-    fn match_events(&self, events: Vec<KeyEvent>) -> (Option<Command>, Vec<KeyEvent>) {
-        match self.bindings.apply(&events) {
-            InputAction::Emit(command) => (Some(command), Vec::new()),
-            InputAction::Buffer => (None, events),
-            InputAction::Reset => (None, Vec::new()),
+// Any keys that aren't text, like arrows, are still matched against key bindings.
+fn text_input(event: &KeyEvent) -> Option<FilterInput> {
+    let input = match event.code {
+        KeyCode::Char(c) if !event.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) => {
+            FilterInput::Char(c)
+        }
+        KeyCode::Backspace => FilterInput::Backspace,
+        KeyCode::Enter => FilterInput::Accept,
+        KeyCode::Esc => FilterInput::Cancel,
+        _ => return None,
+    };
+    Some(input)
+}
+
+/// The keys pressed so far that partially match a key binding.
+struct KeySequence {
+    events: Vec<KeyEvent>,
+    last_event: Option<Instant>,
+    timeout: Duration,
+}
+
+impl KeySequence {
+    fn new(timeout: Duration) -> Self {
+        Self { events: Vec::new(), last_event: None, timeout }
+    }
+
+    /// Push an event that happened at the given instant and get the sequence to be matched.
+    ///
+    /// If too much time passed since the previous event, the keys pressed before are discarded.
+    fn push(&mut self, event: KeyEvent, now: Instant) -> &[KeyEvent] {
+        if let Some(last_event) = self.last_event {
+            if now.saturating_duration_since(last_event) > self.timeout {
+                self.events.clear();
+            }
         }
+        self.last_event = Some(now);
+        self.events.push(event);
+        &self.events
+    }
+
+    fn clear(&mut self) {
+        self.events.clear();
     }
 }
 
@@ -53,6 +119,7 @@ enum InputAction {
 
 pub struct CommandKeyBindings {
     bindings: Vec<(KeyBinding, CommandDiscriminants)>,
+    sequence_timeout: Duration,
 }
 
 impl CommandKeyBindings {
@@ -86,14 +153,30 @@ impl CommandKeyBindings {
                     MatchContext::Number(number) => Command::GoToSlide(number),
                 }
             }
+            JumpBack => Command::JumpBack,
+            JumpForward => Command::JumpForward,
             RenderAsyncOperations => Command::RenderAsyncOperations,
             Exit => Command::Exit,
             Suspend => Command::Suspend,
             Reload => Command::Reload,
             HardReload => Command::HardReload,
             ToggleSlideIndex => Command::ToggleSlideIndex,
+            FilterSlideIndex => Command::FilterSlideIndex,
+            // Text input is never bound to any keys.
+            FilterInput => return InputAction::Reset,
             ToggleKeyBindingsConfig => Command::ToggleKeyBindingsConfig,
+            ToggleClock => Command::ToggleClock,
+            ToggleNotes => Command::ToggleNotes,
             CloseModal => Command::CloseModal,
+            ScrollOutputUp => Command::ScrollOutputUp,
+            ScrollOutputDown => Command::ScrollOutputDown,
+            SnapshotSlide => Command::SnapshotSlide,
+            ResetAsyncOperations => Command::ResetAsyncOperations,
+            ListPersistentProcesses => Command::ListPersistentProcesses,
+            KillPersistentProcesses => Command::KillPersistentProcesses,
+            ReloadTheme => Command::ReloadTheme,
+            ToggleLineNumbers => Command::ToggleLineNumbers,
+            CopySnippet => Command::CopySnippet,
         };
         InputAction::Emit(command)
     }
@@ -119,10 +202,14 @@ impl TryFrom<KeyBindingsConfig> for CommandKeyBindings {
     type Error = KeyBindingsValidationError;
 
     fn try_from(config: KeyBindingsConfig) -> Result<Self, Self::Error> {
-        let zip = |discriminant, bindings: Vec<KeyBinding>| bindings.into_iter().zip(iter::repeat(discriminant));
-        if !config.go_to_slide.iter().all(|k| k.expects_number()) {
+        // Disabled bindings are simply dropped so the keys they would have used are left unbound.
+        let zip = |discriminant, bindings: Vec<KeyBinding>| {
+            bindings.into_iter().filter(|binding| !binding.is_disabled()).zip(iter::repeat(discriminant))
+        };
+        if !config.go_to_slide.iter().all(|k| k.is_disabled() || k.expects_number()) {
             return Err(KeyBindingsValidationError::Invalid("go_to_slide", "<number> matcher required"));
         }
+        let sequence_timeout = Duration::from_millis(config.sequence_timeout_millis.into());
         let bindings: Vec<_> = iter::empty()
             .chain(zip(CommandDiscriminants::Next, config.next))
             .chain(zip(CommandDiscriminants::NextFast, config.next_fast))
@@ -131,16 +218,30 @@ impl TryFrom<KeyBindingsConfig> for CommandKeyBindings {
             .chain(zip(CommandDiscriminants::FirstSlide, config.first_slide))
             .chain(zip(CommandDiscriminants::LastSlide, config.last_slide))
             .chain(zip(CommandDiscriminants::GoToSlide, config.go_to_slide))
+            .chain(zip(CommandDiscriminants::JumpBack, config.jump_back))
+            .chain(zip(CommandDiscriminants::JumpForward, config.jump_forward))
             .chain(zip(CommandDiscriminants::Exit, config.exit))
             .chain(zip(CommandDiscriminants::Suspend, config.suspend))
             .chain(zip(CommandDiscriminants::HardReload, config.reload))
             .chain(zip(CommandDiscriminants::ToggleSlideIndex, config.toggle_slide_index))
+            .chain(zip(CommandDiscriminants::FilterSlideIndex, config.filter_slide_index))
             .chain(zip(CommandDiscriminants::ToggleKeyBindingsConfig, config.toggle_bindings))
             .chain(zip(CommandDiscriminants::RenderAsyncOperations, config.execute_code))
             .chain(zip(CommandDiscriminants::CloseModal, config.close_modal))
+            .chain(zip(CommandDiscriminants::ScrollOutputUp, config.scroll_output_up))
+            .chain(zip(CommandDiscriminants::ScrollOutputDown, config.scroll_output_down))
+            .chain(zip(CommandDiscriminants::SnapshotSlide, config.snapshot_slide))
+            .chain(zip(CommandDiscriminants::ResetAsyncOperations, config.reset_async_operations))
+            .chain(zip(CommandDiscriminants::ListPersistentProcesses, config.list_persistent_processes))
+            .chain(zip(CommandDiscriminants::KillPersistentProcesses, config.kill_persistent_processes))
+            .chain(zip(CommandDiscriminants::ReloadTheme, config.reload_theme))
+            .chain(zip(CommandDiscriminants::ToggleLineNumbers, config.toggle_line_numbers))
+            .chain(zip(CommandDiscriminants::ToggleClock, config.toggle_clock))
+            .chain(zip(CommandDiscriminants::ToggleNotes, config.toggle_notes))
+            .chain(zip(CommandDiscriminants::CopySnippet, config.copy_snippet))
             .collect();
         Self::validate_conflicts(bindings.iter().map(|binding| &binding.0))?;
-        Ok(Self { bindings })
+        Ok(Self { bindings, sequence_timeout })
     }
 }
 
@@ -190,12 +291,20 @@ impl KeyBinding {
     fn expects_number(&self) -> bool {
         self.0.iter().any(|m| matches!(m, KeyMatcher::Number))
     }
+
+    /// Whether this binding was explicitly set to not match anything.
+    fn is_disabled(&self) -> bool {
+        self.0.is_empty()
+    }
 }
 
 impl FromStr for KeyBinding {
     type Err = KeyBindingParseError;
 
     fn from_str(mut input: &str) -> Result<Self, Self::Err> {
+        if input == NO_BINDING {
+            return Ok(Self(Vec::new()));
+        }
         let mut matchers = Vec::new();
         let mut has_numbers = false;
         while !input.is_empty() {
@@ -215,6 +324,9 @@ impl FromStr for KeyBinding {
 
 impl fmt::Display for KeyBinding {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_disabled() {
+            return write!(f, "{NO_BINDING}");
+        }
         for matcher in &self.0 {
             write!(f, "{matcher}")?;
         }
@@ -554,6 +666,7 @@ mod test {
     #[case(&["<c-w>", "<c-w>a"])]
     #[case(&["<c-w>", "<c-w>"])]
     #[case(&["<number>", "<number>"])]
+    #[case(&["g", "gg"])]
     fn conflicts(#[case] patterns: &[&str]) {
         let bindings: Vec<_> = patterns.iter().map(|p| KeyBinding::from_str(p).unwrap()).collect();
         let result = CommandKeyBindings::validate_conflicts(bindings.iter());
@@ -569,10 +682,61 @@ mod test {
         assert!(result.is_ok(), "got error: {result:?}");
     }
 
+    #[rstest]
+    #[case::char('a'.into_event(), Some(FilterInput::Char('a')))]
+    #[case::uppercase('A'.into_event(), Some(FilterInput::Char('A')))]
+    #[case::backspace(KeyCode::Backspace.into_event(), Some(FilterInput::Backspace))]
+    #[case::enter(KeyCode::Enter.into_event(), Some(FilterInput::Accept))]
+    #[case::esc(KeyCode::Esc.into_event(), Some(FilterInput::Cancel))]
+    #[case::control('p'.into_event().with_control(), None)]
+    #[case::arrow(KeyCode::Down.into_event(), None)]
+    fn filter_text_input(#[case] event: KeyEvent, #[case] expected: Option<FilterInput>) {
+        assert_eq!(text_input(&event), expected);
+    }
+
+    #[test]
+    fn disabled_binding() {
+        let config = KeyBindingsConfig {
+            exit: vec![KeyBinding::from_str("none").unwrap()],
+            go_to_slide: vec![KeyBinding::from_str("none").unwrap()],
+            ..Default::default()
+        };
+        let bindings = CommandKeyBindings::try_from(config).expect("invalid bindings");
+        assert!(bindings.bindings.iter().all(|(_, command)| !matches!(command, CommandDiscriminants::Exit)));
+        assert!(matches!(bindings.apply(&['q'.into_event()]), InputAction::Reset));
+        assert!(matches!(bindings.apply(&['l'.into_event()]), InputAction::Emit(Command::Next)));
+    }
+
+    #[test]
+    fn sequence_timeout() {
+        let mut sequence = KeySequence::new(Duration::from_millis(100));
+        let start = Instant::now();
+        sequence.push('g'.into_event(), start);
+        let events = sequence.push('g'.into_event(), start + Duration::from_millis(50));
+        assert_eq!(events, &['g'.into_event(), 'g'.into_event()]);
+
+        let events = sequence.push('e'.into_event(), start + Duration::from_millis(200));
+        assert_eq!(events, &['e'.into_event()]);
+    }
+
+    #[test]
+    fn chord() {
+        let config = KeyBindingsConfig { last_slide: vec![KeyBinding::from_str("ge").unwrap()], ..Default::default() };
+        let bindings = CommandKeyBindings::try_from(config).expect("invalid bindings");
+        assert!(matches!(bindings.apply(&['g'.into_event()]), InputAction::Buffer));
+        assert!(matches!(
+            bindings.apply(&['g'.into_event(), 'g'.into_event()]),
+            InputAction::Emit(Command::FirstSlide)
+        ));
+        assert!(matches!(bindings.apply(&['g'.into_event(), 'e'.into_event()]), InputAction::Emit(Command::LastSlide)));
+        assert!(matches!(bindings.apply(&['g'.into_event(), 'x'.into_event()]), InputAction::Reset));
+    }
+
     #[rstest]
     #[case("<number>G")]
     #[case("<PageUp>potato")]
     #[case("<Esc><number><PageUp>")]
+    #[case("none")]
     fn display(#[case] pattern: &str) {
         let binding = KeyBinding::from_str(pattern).expect("invalid pattern");
         let rendered = binding.to_string();
@@ -23,6 +23,11 @@ impl CommandListener {
         Ok(Self { keyboard: KeyboardListener::new(bindings), speaker_notes_event_listener })
     }
 
+    /// Set whether typed keys should be treated as text input rather than key bindings.
+    pub(crate) fn set_text_input(&mut self, enabled: bool) {
+        self.keyboard.set_text_input(enabled);
+    }
+
     /// Try to get the next command.
     ///
     /// This attempts to get a command and returns `Ok(None)` on timeout.
@@ -73,6 +78,12 @@ pub(crate) enum Command {
     /// Go to one particular slide.
     GoToSlide(u32),
 
+    /// Go back to the previously visited slide.
+    JumpBack,
+
+    /// Go forward to the slide that was visited before jumping back.
+    JumpForward,
+
     /// Render any async render operations in the current slide.
     RenderAsyncOperations,
 
@@ -93,9 +104,64 @@ pub(crate) enum Command {
     /// Toggle the slide index view.
     ToggleSlideIndex,
 
+    /// Start filtering the slides in the slide index view.
+    FilterSlideIndex,
+
+    /// Text typed while filtering the slide index.
+    FilterInput(FilterInput),
+
     /// Toggle the key bindings config view.
     ToggleKeyBindingsConfig,
 
+    /// Toggle the wall clock.
+    ToggleClock,
+
+    /// Toggle the current slide's speaker notes view.
+    ToggleNotes,
+
     /// Hide the currently open modal, if any.
     CloseModal,
+
+    /// Scroll up the output of the last executed snippet in the current slide.
+    ScrollOutputUp,
+
+    /// Scroll down the output of the last executed snippet in the current slide.
+    ScrollOutputDown,
+
+    /// Save a snapshot of the current slide into a file.
+    SnapshotSlide,
+
+    /// Reset all async operations in the current slide so they can be run again.
+    ResetAsyncOperations,
+
+    /// List the processes spawned by persistent snippets.
+    ListPersistentProcesses,
+
+    /// Kill the processes spawned by persistent snippets.
+    KillPersistentProcesses,
+
+    /// Re-read and re-apply the presentation's theme.
+    ReloadTheme,
+
+    /// Toggle line numbers in every code block.
+    ToggleLineNumbers,
+
+    /// Copy the last visible snippet in the current slide into the clipboard.
+    CopySnippet,
+}
+
+/// An input while typing a filter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum FilterInput {
+    /// A character was typed.
+    Char(char),
+
+    /// The last character was deleted.
+    Backspace,
+
+    /// The filter was accepted.
+    Accept,
+
+    /// The filter was discarded.
+    Cancel,
 }
@@ -1,3 +1,4 @@
+pub(crate) mod announcer;
 pub(crate) mod keyboard;
 pub(crate) mod listener;
 pub(crate) mod speaker_notes;
@@ -1,7 +1,12 @@
 use crate::{
     code::snippet::SnippetLanguage,
     commands::keyboard::KeyBinding,
-    terminal::{GraphicsMode, emulator::TerminalEmulator, image::protocols::kitty::KittyMode},
+    render::properties::DEFAULT_CELL_ASPECT_RATIO,
+    terminal::{
+        GraphicsMode,
+        emulator::TerminalEmulator,
+        image::protocols::kitty::{DEFAULT_MAX_TRANSFERS, KittyMode},
+    },
 };
 use clap::ValueEnum;
 use serde::Deserialize;
@@ -9,7 +14,7 @@ use std::{
     collections::{BTreeMap, HashMap},
     fs, io,
     net::{IpAddr, Ipv4Addr, SocketAddr},
-    num::NonZeroU8,
+    num::{NonZeroU8, NonZeroU16},
     path::{Path, PathBuf},
 };
 
@@ -86,10 +91,34 @@ pub struct DefaultsConfig {
     #[cfg_attr(feature = "json-schema", validate(range(min = 1)))]
     pub terminal_font_size: u8,
 
+    /// The height/width ratio of a terminal cell, used to compute the size of images when the
+    /// terminal doesn't report its size in pixels.
+    #[serde(default = "default_cell_aspect_ratio")]
+    pub cell_aspect_ratio: f64,
+
     /// The image protocol to use.
     #[serde(default)]
     pub image_protocol: ImageProtocol,
 
+    /// The image protocols to try, in order, when the image protocol is detected automatically.
+    ///
+    /// The first one the terminal supports is used, falling back to ascii blocks if none are.
+    #[serde(default)]
+    pub image_protocol_order: Vec<ImageProtocol>,
+
+    /// Whether to always use unicode placeholders when displaying images using the kitty protocol.
+    ///
+    /// These are always used when running inside tmux.
+    #[serde(default)]
+    pub kitty_unicode_placeholders: bool,
+
+    /// The maximum number of images being transferred at once when using the kitty protocol.
+    ///
+    /// Any images beyond this are transferred after a short wait, which prevents some terminals
+    /// from dropping or garbling images when too many are sent at once.
+    #[serde(default = "default_kitty_max_transfers")]
+    pub kitty_max_transfers: NonZeroU16,
+
     /// Validate that the presentation does not overflow the terminal screen.
     #[serde(default)]
     pub validate_overflows: ValidateOverflows,
@@ -112,9 +141,50 @@ pub struct DefaultsConfig {
     #[serde(default)]
     pub max_rows_alignment: MaxRowsAlignment,
 
+    /// The minimum width, in columns, each column in a column layout should have.
+    ///
+    /// Columns in layouts where any of them would be narrower than this are displayed one after
+    /// the other instead. This is disabled by default.
+    #[serde(default)]
+    pub minimum_column_width: u16,
+
     /// The configuration for lists when incremental lists are enabled.
     #[serde(default)]
     pub incremental_lists: IncrementalListsConfig,
+
+    /// The behavior of the "next fast" and "previous fast" key bindings.
+    #[serde(default)]
+    pub fast_navigation: FastNavigationConfig,
+
+    /// What to do when going to a slide number past the end of the presentation.
+    #[serde(default)]
+    pub slide_out_of_range: SlideOutOfRangeConfig,
+
+    /// What to do when moving past either end of the presentation.
+    #[serde(default)]
+    pub end_of_presentation: EndOfPresentationConfig,
+
+    /// Whether the slide index should display thumbnails of every slide rather than their titles.
+    ///
+    /// This requires a terminal that supports one of the graphics protocols.
+    #[serde(default)]
+    pub slide_index_thumbnails: bool,
+
+    /// Whether to parse `$...$` and `$$...$$` math in text and render it as images.
+    #[serde(default)]
+    pub math: bool,
+
+    /// The wall clock configuration.
+    #[serde(default)]
+    pub clock: ClockConfig,
+
+    /// The countdown configuration, used when the talk's duration is set via `--duration`.
+    #[serde(default)]
+    pub countdown: CountdownConfig,
+
+    /// The minimum size the terminal needs to have for the presentation to be displayed.
+    #[serde(default)]
+    pub minimum_size: MinimumSizeConfig,
 }
 
 impl Default for DefaultsConfig {
@@ -122,17 +192,100 @@ impl Default for DefaultsConfig {
         Self {
             theme: Default::default(),
             terminal_font_size: default_terminal_font_size(),
+            cell_aspect_ratio: default_cell_aspect_ratio(),
             image_protocol: Default::default(),
+            image_protocol_order: Default::default(),
+            kitty_unicode_placeholders: false,
+            kitty_max_transfers: default_kitty_max_transfers(),
             validate_overflows: Default::default(),
             max_columns: default_u16_max(),
             max_columns_alignment: Default::default(),
             max_rows: default_u16_max(),
             max_rows_alignment: Default::default(),
+            minimum_column_width: Default::default(),
             incremental_lists: Default::default(),
+            fast_navigation: Default::default(),
+            slide_out_of_range: Default::default(),
+            end_of_presentation: Default::default(),
+            slide_index_thumbnails: false,
+            math: false,
+            clock: Default::default(),
+            countdown: Default::default(),
+            minimum_size: Default::default(),
+        }
+    }
+}
+
+/// The minimum size of the terminal.
+///
+/// A message is displayed instead of the presentation while the terminal is smaller than this.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct MinimumSizeConfig {
+    /// The minimum number of columns.
+    #[serde(default)]
+    pub columns: u16,
+
+    /// The minimum number of rows.
+    #[serde(default)]
+    pub rows: u16,
+}
+
+/// The configuration for the wall clock.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct ClockConfig {
+    /// Whether to display the clock when the presentation starts.
+    #[serde(default)]
+    pub show: bool,
+
+    /// The corner of the screen the clock is displayed in.
+    #[serde(default)]
+    pub position: ClockPosition,
+}
+
+/// The configuration for the countdown that tracks the time left in a talk.
+#[derive(Clone, Debug, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct CountdownConfig {
+    /// The corner of the screen the countdown is displayed in.
+    #[serde(default = "default_countdown_position")]
+    pub position: ClockPosition,
+
+    /// The number of minutes before the end of the talk at which the countdown turns red.
+    #[serde(default = "default_countdown_warning_minutes")]
+    pub warning_minutes: u64,
+
+    /// The title of the slide to jump to when time runs out.
+    #[serde(default)]
+    pub expired_slide: Option<String>,
+}
+
+impl Default for CountdownConfig {
+    fn default() -> Self {
+        Self {
+            position: default_countdown_position(),
+            warning_minutes: default_countdown_warning_minutes(),
+            expired_slide: None,
         }
     }
 }
 
+/// A corner of the screen.
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum ClockPosition {
+    TopLeft,
+    #[default]
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
 /// The configuration for lists when incremental lists are enabled.
 #[derive(Clone, Debug, Default, Deserialize)]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
@@ -151,6 +304,23 @@ fn default_terminal_font_size() -> u8 {
     16
 }
 
+fn default_cell_aspect_ratio() -> f64 {
+    DEFAULT_CELL_ASPECT_RATIO
+}
+
+fn default_countdown_position() -> ClockPosition {
+    // The clock goes on the top right by default so keep them apart.
+    ClockPosition::TopLeft
+}
+
+fn default_countdown_warning_minutes() -> u64 {
+    5
+}
+
+fn default_kitty_max_transfers() -> NonZeroU16 {
+    DEFAULT_MAX_TRANSFERS
+}
+
 /// The alignment to use when `defaults.max_columns` is set.
 #[derive(Clone, Copy, Debug, Default, Deserialize)]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
@@ -222,6 +392,19 @@ pub struct OptionsConfig {
     /// Assume snippets for these languages contain `+render` and render them automatically.
     #[serde(default)]
     pub auto_render_languages: Vec<SnippetLanguage>,
+
+    /// The prefix that identifies hidden lines in snippets, overriding the one for each language.
+    pub hidden_line_prefix: Option<String>,
+
+    /// The maximum number of columns paragraphs can take up before wrapping.
+    ///
+    /// If the terminal is wider than this, the text is centered within the slide.
+    pub max_prose_columns: Option<u16>,
+
+    /// The attributes applied to every snippet, e.g. `+line_numbers +no_background`.
+    ///
+    /// Attributes set in a snippet itself take precedence over these.
+    pub snippet_defaults: Option<String>,
 }
 
 #[derive(Clone, Debug, Default, Deserialize)]
@@ -243,6 +426,21 @@ pub struct SnippetConfig {
     /// Whether to validate snippets.
     #[serde(default)]
     pub validate: bool,
+
+    /// Whether to prevent terminals from rendering programming ligatures in code snippets.
+    #[serde(default)]
+    pub prevent_ligatures: bool,
+
+    /// Whether to include hidden lines when copying a snippet into the clipboard.
+    #[serde(default)]
+    pub copy_hidden_lines: bool,
+
+    /// The number of lines to display around the currently highlighted lines in a snippet.
+    ///
+    /// Any lines further away than this are collapsed into a marker. By default all lines are
+    /// displayed.
+    #[serde(default)]
+    pub highlight_context_lines: Option<u16>,
 }
 
 #[derive(Clone, Debug, Default, Deserialize)]
@@ -256,6 +454,75 @@ pub struct SnippetExecConfig {
     /// Custom snippet executors.
     #[serde(default)]
     pub custom: BTreeMap<SnippetLanguage, LanguageSnippetExecutionConfig>,
+
+    /// The maximum number of rows of output to display for an executed snippet.
+    ///
+    /// Any output beyond this can be scrolled through using the scroll output key bindings.
+    #[serde(default)]
+    pub max_output_rows: Option<u16>,
+
+    /// How to deal with output that doesn't fit in `max_output_rows`.
+    #[serde(default)]
+    pub output_overflow: SnippetOutputOverflow,
+
+    /// Whether to allow executing snippets marked as `+trusted` even if snippet execution is
+    /// disabled.
+    #[serde(default)]
+    pub allow_trusted: bool,
+
+    /// Whether to write snippets into a directory named after their contents rather than a random
+    /// one, so that any paths in their output are the same across runs.
+    #[serde(default)]
+    pub deterministic_paths: bool,
+
+    /// Whether to display the output of executed snippets without any blank lines between the
+    /// snippet, its execution status, and its output.
+    #[serde(default)]
+    pub compact: bool,
+
+    /// Whether the output of executed snippets should be at least as wide as the snippet itself.
+    #[serde(default)]
+    pub match_code_width: bool,
+
+    /// The cue to give when a snippet that was manually executed finishes running.
+    #[serde(default)]
+    pub completion_cue: CompletionCueConfig,
+}
+
+/// How to deal with the output of an executed snippet that doesn't fit in its maximum size.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum SnippetOutputOverflow {
+    /// Display a scroll bar and allow scrolling through the output.
+    #[default]
+    Scroll,
+
+    /// Only keep the last lines.
+    Tail,
+
+    /// Only keep the first lines.
+    Head,
+
+    /// Keep the first and last lines and replace everything in between with a marker.
+    #[serde(rename = "head+tail")]
+    HeadTail,
+}
+
+/// The cue given when a snippet finishes running.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum CompletionCueConfig {
+    /// Don't give any cue.
+    #[default]
+    Off,
+
+    /// Ring the terminal bell.
+    Bell,
+
+    /// Display a message in the status line.
+    Flash,
 }
 
 #[derive(Clone, Debug, Default, Deserialize)]
@@ -399,6 +666,35 @@ pub enum ImageProtocol {
 
 pub struct SixelUnsupported;
 
+#[derive(Debug, thiserror::Error)]
+pub enum ImageProtocolOrderError {
+    #[error("'auto' can't be used in the image protocol order")]
+    Auto,
+
+    #[error("sixel support was not enabled during compilation")]
+    SixelUnsupported,
+}
+
+impl DefaultsConfig {
+    /// Get the graphics modes to try, in order, when the image protocol is detected automatically.
+    ///
+    /// Returns `None` if no order was configured.
+    pub(crate) fn image_protocol_order(&self) -> Result<Option<Vec<GraphicsMode>>, ImageProtocolOrderError> {
+        if self.image_protocol_order.is_empty() {
+            return Ok(None);
+        }
+        let mut modes = Vec::new();
+        for protocol in &self.image_protocol_order {
+            let mode = match protocol {
+                ImageProtocol::Auto => return Err(ImageProtocolOrderError::Auto),
+                protocol => GraphicsMode::try_from(protocol).map_err(|_| ImageProtocolOrderError::SixelUnsupported)?,
+            };
+            modes.push(mode);
+        }
+        Ok(Some(modes))
+    }
+}
+
 impl TryFrom<&ImageProtocol> for GraphicsMode {
     type Error = SixelUnsupported;
 
@@ -410,8 +706,16 @@ impl TryFrom<&ImageProtocol> for GraphicsMode {
             }
             ImageProtocol::Iterm2 => GraphicsMode::Iterm2,
             ImageProtocol::Iterm2Multipart => GraphicsMode::Iterm2Multipart,
-            ImageProtocol::KittyLocal => GraphicsMode::Kitty { mode: KittyMode::Local },
-            ImageProtocol::KittyRemote => GraphicsMode::Kitty { mode: KittyMode::Remote },
+            ImageProtocol::KittyLocal => GraphicsMode::Kitty {
+                mode: KittyMode::Local,
+                unicode_placeholders: false,
+                max_transfers: DEFAULT_MAX_TRANSFERS,
+            },
+            ImageProtocol::KittyRemote => GraphicsMode::Kitty {
+                mode: KittyMode::Remote,
+                unicode_placeholders: false,
+                max_transfers: DEFAULT_MAX_TRANSFERS,
+            },
             ImageProtocol::AsciiBlocks => GraphicsMode::AsciiBlocks,
             #[cfg(feature = "sixel")]
             ImageProtocol::Sixel => GraphicsMode::Sixel,
@@ -460,6 +764,18 @@ pub struct KeyBindingsConfig {
     #[serde(default = "default_go_to_slide_bindings")]
     pub(crate) go_to_slide: Vec<KeyBinding>,
 
+    /// The key binding to copy the last visible snippet in the current slide into the clipboard.
+    #[serde(default = "default_copy_snippet_bindings")]
+    pub(crate) copy_snippet: Vec<KeyBinding>,
+
+    /// The key binding to go back to the previously visited slide.
+    #[serde(default = "default_jump_back_bindings")]
+    pub(crate) jump_back: Vec<KeyBinding>,
+
+    /// The key binding to go forward to the slide visited before jumping back.
+    #[serde(default = "default_jump_forward_bindings")]
+    pub(crate) jump_forward: Vec<KeyBinding>,
+
     /// The key binding to execute a piece of shell code.
     #[serde(default = "default_execute_code_bindings")]
     pub(crate) execute_code: Vec<KeyBinding>,
@@ -472,6 +788,10 @@ pub struct KeyBindingsConfig {
     #[serde(default = "default_toggle_index_bindings")]
     pub(crate) toggle_slide_index: Vec<KeyBinding>,
 
+    /// The key binding to start filtering the slides in the slide index modal.
+    #[serde(default = "default_filter_slide_index_bindings")]
+    pub(crate) filter_slide_index: Vec<KeyBinding>,
+
     /// The key binding to toggle the key bindings modal.
     #[serde(default = "default_toggle_bindings_modal_bindings")]
     pub(crate) toggle_bindings: Vec<KeyBinding>,
@@ -480,6 +800,46 @@ pub struct KeyBindingsConfig {
     #[serde(default = "default_close_modal_bindings")]
     pub(crate) close_modal: Vec<KeyBinding>,
 
+    /// The key binding to scroll the output of the last executed snippet in the slide up.
+    #[serde(default = "default_scroll_output_up_bindings")]
+    pub(crate) scroll_output_up: Vec<KeyBinding>,
+
+    /// The key binding to scroll the output of the last executed snippet in the slide down.
+    #[serde(default = "default_scroll_output_down_bindings")]
+    pub(crate) scroll_output_down: Vec<KeyBinding>,
+
+    /// The key binding to save a snapshot of the current slide into a PNG image.
+    #[serde(default = "default_snapshot_slide_bindings")]
+    pub(crate) snapshot_slide: Vec<KeyBinding>,
+
+    /// The key binding to reset the code snippets executed in the current slide.
+    #[serde(default = "default_reset_async_operations_bindings")]
+    pub(crate) reset_async_operations: Vec<KeyBinding>,
+
+    /// The key binding to list the processes spawned by persistent snippets.
+    #[serde(default = "default_list_persistent_processes_bindings")]
+    pub(crate) list_persistent_processes: Vec<KeyBinding>,
+
+    /// The key binding to kill the processes spawned by persistent snippets.
+    #[serde(default = "default_kill_persistent_processes_bindings")]
+    pub(crate) kill_persistent_processes: Vec<KeyBinding>,
+
+    /// The key binding to reload the presentation's theme.
+    #[serde(default = "default_reload_theme_bindings")]
+    pub(crate) reload_theme: Vec<KeyBinding>,
+
+    /// The key binding to toggle line numbers in every code block.
+    #[serde(default = "default_toggle_line_numbers_bindings")]
+    pub(crate) toggle_line_numbers: Vec<KeyBinding>,
+
+    /// The key binding to toggle the wall clock.
+    #[serde(default = "default_toggle_clock_bindings")]
+    pub(crate) toggle_clock: Vec<KeyBinding>,
+
+    /// The key binding to toggle the current slide's speaker notes view.
+    #[serde(default = "default_toggle_notes_bindings")]
+    pub(crate) toggle_notes: Vec<KeyBinding>,
+
     /// The key binding to close the application.
     #[serde(default = "default_exit_bindings")]
     pub(crate) exit: Vec<KeyBinding>,
@@ -487,6 +847,11 @@ pub struct KeyBindingsConfig {
     /// The key binding to suspend the application.
     #[serde(default = "default_suspend_bindings")]
     pub(crate) suspend: Vec<KeyBinding>,
+
+    /// The maximum time to wait between the keys in a key binding that uses multiple keys, like
+    /// `gg`, before discarding the keys pressed so far.
+    #[serde(default = "default_sequence_timeout_millis")]
+    pub(crate) sequence_timeout_millis: u16,
 }
 
 impl Default for KeyBindingsConfig {
@@ -499,13 +864,28 @@ impl Default for KeyBindingsConfig {
             first_slide: default_first_slide_bindings(),
             last_slide: default_last_slide_bindings(),
             go_to_slide: default_go_to_slide_bindings(),
+            copy_snippet: default_copy_snippet_bindings(),
+            jump_back: default_jump_back_bindings(),
+            jump_forward: default_jump_forward_bindings(),
             execute_code: default_execute_code_bindings(),
             reload: default_reload_bindings(),
             toggle_slide_index: default_toggle_index_bindings(),
+            filter_slide_index: default_filter_slide_index_bindings(),
             toggle_bindings: default_toggle_bindings_modal_bindings(),
             close_modal: default_close_modal_bindings(),
+            scroll_output_up: default_scroll_output_up_bindings(),
+            scroll_output_down: default_scroll_output_down_bindings(),
+            snapshot_slide: default_snapshot_slide_bindings(),
+            reset_async_operations: default_reset_async_operations_bindings(),
+            list_persistent_processes: default_list_persistent_processes_bindings(),
+            kill_persistent_processes: default_kill_persistent_processes_bindings(),
+            reload_theme: default_reload_theme_bindings(),
+            toggle_line_numbers: default_toggle_line_numbers_bindings(),
+            toggle_clock: default_toggle_clock_bindings(),
+            toggle_notes: default_toggle_notes_bindings(),
             exit: default_exit_bindings(),
             suspend: default_suspend_bindings(),
+            sequence_timeout_millis: default_sequence_timeout_millis(),
         }
     }
 }
@@ -640,6 +1020,57 @@ pub struct SlideTransitionConfig {
     pub animation: SlideTransitionStyleConfig,
 }
 
+/// The behavior of the "next fast" and "previous fast" key bindings.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(tag = "style", rename_all = "snake_case", deny_unknown_fields)]
+pub enum FastNavigationConfig {
+    /// Jump between the first and last pause in a slide before moving into the next/previous one.
+    #[default]
+    Pauses,
+
+    /// Jump a number of slides at once, ignoring any pauses.
+    Slides {
+        /// The number of slides to jump.
+        #[serde(default = "default_fast_navigation_slides")]
+        #[cfg_attr(feature = "json-schema", validate(range(min = 1)))]
+        count: u16,
+    },
+}
+
+fn default_fast_navigation_slides() -> u16 {
+    1
+}
+
+/// What to do when going to a slide number past the end of the presentation.
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum SlideOutOfRangeConfig {
+    /// Go to the last slide.
+    #[default]
+    Clamp,
+
+    /// Stay in the current slide and display an error.
+    Error,
+}
+
+/// What to do when moving past either end of the presentation.
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum EndOfPresentationConfig {
+    /// Stay in the current slide.
+    #[default]
+    Stay,
+
+    /// Go to the first slide when moving past the last one, and vice versa.
+    Wrap,
+
+    /// Stay in the current slide and display a message saying the end was reached.
+    Indicator,
+}
+
 // The slide transition style configuration.
 #[derive(Clone, Debug, Deserialize)]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
@@ -691,6 +1122,18 @@ fn default_go_to_slide_bindings() -> Vec<KeyBinding> {
     make_keybindings(["<number>G"])
 }
 
+fn default_copy_snippet_bindings() -> Vec<KeyBinding> {
+    make_keybindings(["<c-y>"])
+}
+
+fn default_jump_back_bindings() -> Vec<KeyBinding> {
+    make_keybindings(["<c-o>"])
+}
+
+fn default_jump_forward_bindings() -> Vec<KeyBinding> {
+    make_keybindings(["<tab>"])
+}
+
 fn default_execute_code_bindings() -> Vec<KeyBinding> {
     make_keybindings(["<c-e>"])
 }
@@ -703,6 +1146,10 @@ fn default_toggle_index_bindings() -> Vec<KeyBinding> {
     make_keybindings(["<c-p>"])
 }
 
+fn default_filter_slide_index_bindings() -> Vec<KeyBinding> {
+    make_keybindings(["/"])
+}
+
 fn default_toggle_bindings_modal_bindings() -> Vec<KeyBinding> {
     make_keybindings(["?"])
 }
@@ -711,6 +1158,46 @@ fn default_close_modal_bindings() -> Vec<KeyBinding> {
     make_keybindings(["<esc>"])
 }
 
+fn default_scroll_output_up_bindings() -> Vec<KeyBinding> {
+    make_keybindings(["<c-u>"])
+}
+
+fn default_scroll_output_down_bindings() -> Vec<KeyBinding> {
+    make_keybindings(["<c-d>"])
+}
+
+fn default_snapshot_slide_bindings() -> Vec<KeyBinding> {
+    make_keybindings(["<c-s>"])
+}
+
+fn default_reset_async_operations_bindings() -> Vec<KeyBinding> {
+    make_keybindings(["<c-x>"])
+}
+
+fn default_list_persistent_processes_bindings() -> Vec<KeyBinding> {
+    make_keybindings(["<c-b>"])
+}
+
+fn default_kill_persistent_processes_bindings() -> Vec<KeyBinding> {
+    make_keybindings(["<c-k>"])
+}
+
+fn default_reload_theme_bindings() -> Vec<KeyBinding> {
+    make_keybindings(["<c-t>"])
+}
+
+fn default_toggle_line_numbers_bindings() -> Vec<KeyBinding> {
+    make_keybindings(["<c-l>"])
+}
+
+fn default_toggle_clock_bindings() -> Vec<KeyBinding> {
+    make_keybindings(["<c-w>"])
+}
+
+fn default_toggle_notes_bindings() -> Vec<KeyBinding> {
+    make_keybindings(["<c-n>"])
+}
+
 fn default_exit_bindings() -> Vec<KeyBinding> {
     make_keybindings(["<c-c>", "q"])
 }
@@ -719,6 +1206,10 @@ fn default_suspend_bindings() -> Vec<KeyBinding> {
     make_keybindings(["<c-z>"])
 }
 
+fn default_sequence_timeout_millis() -> u16 {
+    1000
+}
+
 fn default_transition_duration_millis() -> u16 {
     1000
 }
@@ -751,6 +1242,7 @@ pub(crate) fn default_speaker_notes_publish_address() -> SocketAddr {
 mod test {
     use super::*;
     use crate::commands::keyboard::CommandKeyBindings;
+    use rstest::rstest;
 
     #[test]
     fn default_bindings() {
@@ -758,6 +1250,27 @@ mod test {
         CommandKeyBindings::try_from(config).expect("construction failed");
     }
 
+    #[test]
+    fn image_protocol_order() {
+        let config: DefaultsConfig =
+            serde_yaml::from_str("image_protocol_order: [kitty-local, ascii-blocks]").expect("failed to parse");
+        let modes = config.image_protocol_order().expect("invalid order").expect("no order");
+        assert!(matches!(
+            modes.as_slice(),
+            [GraphicsMode::Kitty { mode: KittyMode::Local, .. }, GraphicsMode::AsciiBlocks]
+        ));
+    }
+
+    #[rstest]
+    #[case::unknown("image_protocol_order: [potato]")]
+    #[case::auto("image_protocol_order: [auto]")]
+    fn invalid_image_protocol_order(#[case] input: &str) {
+        let result = serde_yaml::from_str::<DefaultsConfig>(input)
+            .map_err(|e| e.to_string())
+            .and_then(|config| config.image_protocol_order().map_err(|e| e.to_string()));
+        result.expect_err("order is valid");
+    }
+
     #[test]
     fn default_options_serde() {
         serde_yaml::from_str::<'_, OptionsConfig>("implicit_slide_ends: true").expect("failed to parse");
@@ -219,7 +219,7 @@ impl<'a> Exporter<'a> {
             for op in slide.iter_operations() {
                 if let RenderOperation::RenderAsync(inner) = op {
                     // Send a pollable to the poller and keep one for ourselves.
-                    poller.send(PollerCommand::Poll { pollable: inner.pollable(), slide: index });
+                    poller.send(PollerCommand::Poll { pollable: inner.pollable(), slide: index, notify: false });
                     pollables.push(inner.pollable())
                 }
             }
@@ -249,7 +249,7 @@ impl<'a> Exporter<'a> {
             for op in slide.iter_operations_mut() {
                 if let RenderOperation::RenderAsync(inner) = op {
                     // Send a pollable to the poller
-                    poller.send(PollerCommand::Poll { pollable: inner.pollable(), slide: index });
+                    poller.send(PollerCommand::Poll { pollable: inner.pollable(), slide: index, notify: false });
 
                     // Poll until it's done
                     let mut pollable = inner.pollable();
@@ -66,25 +66,8 @@ impl FontSize {
 }
 
 pub(crate) fn color_to_html(color: &Color) -> String {
-    match color {
-        Color::Black => "#000000".into(),
-        Color::DarkGrey => "#5a5a5a".into(),
-        Color::Red => "#ff0000".into(),
-        Color::DarkRed => "#8b0000".into(),
-        Color::Green => "#00ff00".into(),
-        Color::DarkGreen => "#006400".into(),
-        Color::Yellow => "#ffff00".into(),
-        Color::DarkYellow => "#8b8000".into(),
-        Color::Blue => "#0000ff".into(),
-        Color::DarkBlue => "#00008b".into(),
-        Color::Magenta => "#ff00ff".into(),
-        Color::DarkMagenta => "#8b008b".into(),
-        Color::Cyan => "#00ffff".into(),
-        Color::DarkCyan => "#008b8b".into(),
-        Color::White => "#ffffff".into(),
-        Color::Grey => "#808080".into(),
-        Color::Rgb { r, g, b } => format!("#{r:02x}{g:02x}{b:02x}"),
-    }
+    let (r, g, b) = color.to_rgb();
+    format!("#{r:02x}{g:02x}{b:02x}")
 }
 
 #[cfg(test)]
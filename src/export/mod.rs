@@ -1,3 +1,4 @@
 pub mod exporter;
 pub(crate) mod html;
 pub(crate) mod output;
+pub(crate) mod snapshot;
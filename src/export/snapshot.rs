@@ -0,0 +1,150 @@
+use super::exporter::ExportError;
+use crate::{
+    markdown::text_style::Color,
+    presentation::Slide,
+    render::{engine::RenderEngine, properties::WindowSize},
+    terminal::virt::{ImageBehavior, TerminalGrid, VirtualTerminal},
+    ui::big_text::{GLYPH_ROWS, glyph},
+};
+use image::{Rgb, RgbImage};
+use std::{
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// The number of dots every terminal cell is made of.
+///
+/// Glyphs are 5 dots wide and tall so this leaves some room around them.
+const CELL_WIDTH: u32 = 6;
+const CELL_HEIGHT: u32 = GLYPH_ROWS as u32 + 3;
+
+/// The number of pixels on each side of a dot.
+const DOT_SIZE: u32 = 2;
+
+/// Save the currently visible contents of a slide into a timestamped PNG file.
+///
+/// Only the chunks that have been revealed so far are included in the snapshot.
+pub(crate) fn save_slide_snapshot(
+    slide: &Slide,
+    dimensions: WindowSize,
+    directory: &Path,
+) -> Result<PathBuf, ExportError> {
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+    let path = directory.join(format!("presenterm-snapshot-{timestamp}.png"));
+    // Images may already be encoded for the terminal so print them as ascii rather than embedding them.
+    let mut terminal = VirtualTerminal::new(dimensions, ImageBehavior::PrintAscii);
+    let engine = RenderEngine::new(&mut terminal, dimensions, Default::default());
+    engine.render(slide.iter_visible_operations())?;
+    rasterize(&terminal.into_contents()).save(&path)?;
+    Ok(path)
+}
+
+fn rasterize(grid: &TerminalGrid) -> RgbImage {
+    let default_background = grid.background_color.unwrap_or(Color::Black);
+    let columns = grid.rows.first().map(Vec::len).unwrap_or(0).max(1) as u32;
+    let rows = grid.rows.len().max(1) as u32;
+    let (cell_width, cell_height) = (CELL_WIDTH * DOT_SIZE, CELL_HEIGHT * DOT_SIZE);
+    let mut image = RgbImage::new(columns * cell_width, rows * cell_height);
+    for (row, cells) in grid.rows.iter().enumerate() {
+        for (column, cell) in cells.iter().enumerate() {
+            let background = to_rgb(cell.style.colors.background.unwrap_or(default_background));
+            let foreground = to_rgb(cell.style.colors.foreground.unwrap_or(Color::White));
+            for y in 0..cell_height {
+                for x in 0..cell_width {
+                    let color =
+                        if is_filled(cell.character, x / DOT_SIZE, y / DOT_SIZE) { foreground } else { background };
+                    image.put_pixel(column as u32 * cell_width + x, row as u32 * cell_height + y, color);
+                }
+            }
+        }
+    }
+    image
+}
+
+/// Whether the dot at the given position within a cell is filled when drawing a character.
+fn is_filled(character: char, x: u32, y: u32) -> bool {
+    match character {
+        // Images are printed using half blocks so keep that shape.
+        '▀' => y < CELL_HEIGHT / 2,
+        '▄' => y >= CELL_HEIGHT / 2,
+        '█' => true,
+        c if c.is_whitespace() => false,
+        c if c.is_ascii_graphic() => {
+            // Glyphs are centered horizontally and leave a single empty row above them.
+            let rows = glyph(c);
+            let offset = (CELL_WIDTH - 1 - rows[0].len() as u32) / 2;
+            let (Some(x), Some(y)) = (x.checked_sub(offset), y.checked_sub(1)) else {
+                return false;
+            };
+            rows.get(y as usize).and_then(|row| row.as_bytes().get(x as usize)) == Some(&b'#')
+        }
+        // Anything else is drawn as a block in the middle of the cell.
+        _ => y > 0 && y < CELL_HEIGHT - 1 && x < CELL_WIDTH - 1,
+    }
+}
+
+fn to_rgb(color: Color) -> Rgb<u8> {
+    let (r, g, b) = color.to_rgb();
+    Rgb([r, g, b])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        markdown::text_style::TextStyle,
+        presentation::SlideChunk,
+        render::operation::RenderOperation,
+        terminal::virt::StyledChar,
+        theme::{Alignment, Margin},
+    };
+
+    fn text(text: &str) -> RenderOperation {
+        RenderOperation::RenderText { line: text.into(), alignment: Alignment::Left { margin: Margin::Fixed(0) } }
+    }
+
+    #[test]
+    fn snapshot_visible_chunks() {
+        let chunks = vec![
+            SlideChunk::new(vec![text("first"), RenderOperation::RenderLineBreak], vec![]),
+            SlideChunk::new(vec![text("second")], vec![]),
+        ];
+        let slide = Slide::new(chunks, vec![]);
+        let directory = tempfile::tempdir().expect("no temp dir");
+        let dimensions = WindowSize { rows: 5, columns: 20, width: 0, height: 0 };
+        let path = save_slide_snapshot(&slide, dimensions, directory.path()).expect("snapshot failed");
+        assert_eq!(path.extension().and_then(|e| e.to_str()), Some("png"));
+
+        let image = image::open(&path).expect("failed to read snapshot").into_rgb8();
+        let (cell_width, cell_height) = (CELL_WIDTH * DOT_SIZE, CELL_HEIGHT * DOT_SIZE);
+        assert_eq!(image.dimensions(), (20 * cell_width, 5 * cell_height));
+
+        let white = Rgb([0xff, 0xff, 0xff]);
+        let has_text = |row: u32| {
+            let rows = row * cell_height..(row + 1) * cell_height;
+            rows.into_iter().any(|y| (0..image.width()).any(|x| *image.get_pixel(x, y) == white))
+        };
+        assert!(has_text(0));
+        assert!(!has_text(1));
+    }
+
+    #[test]
+    fn rasterize_glyph() {
+        let style = TextStyle::default().fg_color(Color::Red).bg_color(Color::Blue);
+        let grid = TerminalGrid {
+            rows: vec![vec![StyledChar::new('i', style), StyledChar::new('▀', style)]],
+            background_color: None,
+            images: Default::default(),
+        };
+        let image = rasterize(&grid);
+
+        let red = Rgb([0xff, 0, 0]);
+        let blue = Rgb([0, 0, 0xff]);
+        // The 'I' glyph is 3 dots wide so it starts in the second dot of the cell.
+        let row: Vec<_> = (0..CELL_WIDTH).map(|x| *image.get_pixel(x * DOT_SIZE, DOT_SIZE)).collect();
+        assert_eq!(row, &[blue, red, red, red, blue, blue]);
+        let column: Vec<_> =
+            (0..CELL_HEIGHT).map(|y| *image.get_pixel((CELL_WIDTH + 1) * DOT_SIZE, y * DOT_SIZE)).collect();
+        assert_eq!(column, &[red, red, red, red, blue, blue, blue, blue]);
+    }
+}
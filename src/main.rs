@@ -1,11 +1,13 @@
 use crate::{
-    code::{execute::SnippetExecutor, highlighting::HighlightThemeSet},
+    code::{
+        check::SnippetChecker, execute::SnippetExecutor, highlighting::HighlightThemeSet, snippet::SnippetLanguage,
+    },
     commands::listener::CommandListener,
     config::{Config, ImageProtocol, ValidateOverflows},
     demo::ThemesDemo,
     export::exporter::Exporter,
     markdown::parse::MarkdownParser,
-    presentation::builder::{PresentationBuilderOptions, Themes},
+    presentation::builder::{PresentationBuilder, PresentationBuilderOptions, STDIN_PATH, SnippetCommands, Themes},
     presenter::{PresentMode, Presenter, PresenterOptions},
     resource::Resources,
     terminal::{
@@ -14,10 +16,14 @@ use crate::{
     },
     theme::{raw::PresentationTheme, registry::PresentationThemeRegistry},
     third_party::{ThirdPartyConfigs, ThirdPartyRender},
+    ui::countdown::parse_duration,
 };
 use anyhow::anyhow;
 use clap::{CommandFactory, Parser, error::ErrorKind};
-use commands::speaker_notes::{SpeakerNotesEventListener, SpeakerNotesEventPublisher};
+use commands::{
+    announcer::SlideAnnouncer,
+    speaker_notes::{SpeakerNotesEventListener, SpeakerNotesEventPublisher},
+};
 use comrak::Arena;
 use config::ConfigLoadError;
 use crossterm::{
@@ -26,13 +32,18 @@ use crossterm::{
 };
 use directories::ProjectDirs;
 use export::exporter::OutputDirectory;
-use render::{engine::MaxSize, properties::WindowSize};
+use render::{
+    engine::MaxSize,
+    properties::{CellSizeFallback, WindowSize},
+};
 use std::{
     env::{self, current_dir},
     io,
     path::{Path, PathBuf},
     sync::Arc,
+    time::Duration,
 };
+use strum::IntoEnumIterator;
 use terminal::emulator::TerminalEmulator;
 use theme::ThemeOptions;
 
@@ -44,6 +55,7 @@ mod export;
 mod markdown;
 mod presentation;
 mod presenter;
+mod qr;
 mod render;
 mod resource;
 mod terminal;
@@ -64,6 +76,8 @@ const DEFAULT_EXPORT_PIXELS_PER_ROW: u16 = DEFAULT_EXPORT_PIXELS_PER_COLUMN * 2;
 #[command(author, version, about = create_splash(), arg_required_else_help = true)]
 struct Cli {
     /// The path to the markdown file that contains the presentation.
+    ///
+    /// Use `-` to read the presentation from stdin.
     #[clap(group = "target")]
     path: Option<PathBuf>,
 
@@ -100,6 +114,14 @@ struct Cli {
     #[clap(long, group = "target")]
     list_themes: bool,
 
+    /// List all supported code highlighting themes.
+    #[clap(long, group = "target")]
+    list_code_themes: bool,
+
+    /// List all languages supported in code snippets along with the names they can be referred to by.
+    #[clap(long, group = "target")]
+    list_languages: bool,
+
     /// Print the theme in use.
     #[clap(long, group = "target")]
     current_theme: bool,
@@ -136,9 +158,38 @@ struct Cli {
     #[clap(short, long, group = "speaker-notes")]
     listen_speaker_notes: bool,
 
+    /// Display a preview of the next slide and a timer below the speaker notes.
+    #[clap(long, requires = "listen_speaker_notes")]
+    presenter_view: bool,
+
+    /// The duration of the talk, like `30m` or `1h15m`, displaying a countdown of the time left.
+    #[clap(long, value_parser = parse_duration, conflicts_with = "export")]
+    duration: Option<Duration>,
+
     /// Whether to validate snippets.
     #[clap(long)]
     validate_snippets: bool,
+
+    /// List every snippet the presentation would execute and the commands used to run them,
+    /// without executing anything.
+    #[clap(long, conflicts_with = "export")]
+    list_executable_snippets: bool,
+
+    /// Run every snippet the presentation would execute and print a JSON summary of the results.
+    ///
+    /// The process exits with an error if any snippet fails.
+    #[clap(long, conflicts_with_all = ["export", "list_executable_snippets"])]
+    run_snippets: bool,
+
+    /// The number of seconds a snippet can run for before it's considered failed when using
+    /// `--run-snippets`.
+    #[clap(long, default_value_t = 60, requires = "run_snippets")]
+    snippet_timeout: u64,
+
+    /// Write a description of every slide that's displayed into the given file, for use with
+    /// screen readers.
+    #[clap(long, conflicts_with = "export")]
+    announce_slides: Option<PathBuf>,
 }
 
 fn create_splash() -> String {
@@ -183,7 +234,8 @@ impl Customizations {
             Err(ConfigLoadError::NotFound) if !require_config_file => Default::default(),
             Err(e) => return Err(e.into()),
         };
-        let code_executor = SnippetExecutor::new(config.snippet.exec.custom.clone(), cwd.to_path_buf())?;
+        let code_executor = SnippetExecutor::new(config.snippet.exec.custom.clone(), cwd.to_path_buf())?
+            .with_deterministic_paths(config.snippet.exec.deterministic_paths);
         Ok(Customizations { config, themes, themes_path: Some(themes_path), code_executor })
     }
 
@@ -288,6 +340,7 @@ impl CoreComponents {
             strict_front_matter_parsing: config.options.strict_front_matter_parsing.unwrap_or(true),
             enable_snippet_execution: config.snippet.exec.enable,
             enable_snippet_execution_replace: config.snippet.exec_replace.enable,
+            allow_trusted_snippets: config.snippet.exec.allow_trusted,
             render_speaker_notes_only,
             auto_render_languages: config.options.auto_render_languages.clone(),
             theme_options: ThemeOptions { font_size_supported: TerminalEmulator::capabilities().font_size },
@@ -296,6 +349,17 @@ impl CoreComponents {
             pause_create_new_slide: false,
             list_item_newlines: config.options.list_item_newlines.map(Into::into).unwrap_or(1),
             validate_snippets: config.snippet.validate,
+            max_snippet_output_rows: config.snippet.exec.max_output_rows,
+            snippet_output_overflow: config.snippet.exec.output_overflow,
+            prevent_code_ligatures: config.snippet.prevent_ligatures,
+            copy_hidden_lines: config.snippet.copy_hidden_lines,
+            highlight_context_lines: config.snippet.highlight_context_lines,
+            hidden_line_prefix: config.options.hidden_line_prefix.clone(),
+            max_prose_columns: config.options.max_prose_columns,
+            snippet_defaults: config.options.snippet_defaults.clone(),
+            compact_snippet_output: config.snippet.exec.compact,
+            match_snippet_output_width: config.snippet.exec.match_code_width,
+            force_line_numbers: false,
         }
     }
 
@@ -304,7 +368,22 @@ impl CoreComponents {
             GraphicsMode::Raw
         } else {
             let protocol = cli.image_protocol.as_ref().unwrap_or(&config.defaults.image_protocol);
-            match GraphicsMode::try_from(protocol) {
+            let order = match config.defaults.image_protocol_order() {
+                Ok(order) => order,
+                Err(e) => Cli::command().error(ErrorKind::InvalidValue, e.to_string()).exit(),
+            };
+            let mode = match (protocol, order) {
+                (ImageProtocol::Auto, Some(order)) => {
+                    Ok(TerminalEmulator::detect().first_supported_protocol(order).unwrap_or(GraphicsMode::AsciiBlocks))
+                }
+                (protocol, _) => GraphicsMode::try_from(protocol),
+            };
+            match mode {
+                Ok(GraphicsMode::Kitty { mode, .. }) => GraphicsMode::Kitty {
+                    mode,
+                    unicode_placeholders: config.defaults.kitty_unicode_placeholders,
+                    max_transfers: config.defaults.kitty_max_transfers,
+                },
                 Ok(mode) => mode,
                 Err(_) => Cli::command()
                     .error(ErrorKind::InvalidValue, "sixel support was not enabled during compilation")
@@ -360,6 +439,32 @@ fn overflow_validation_enabled(mode: &PresentMode, config: &ValidateOverflows) -
     }
 }
 
+fn print_snippet_commands(snippets: &[SnippetCommands]) {
+    if snippets.is_empty() {
+        println!("the presentation doesn't execute any snippets");
+        return;
+    }
+    for (index, snippet) in snippets.iter().enumerate() {
+        if index > 0 {
+            println!();
+        }
+        println!("slide {}: {:?} snippet using {}", snippet.slide, snippet.language, snippet.attribute);
+        match &snippet.commands {
+            Ok(commands) => {
+                println!("  commands:");
+                for command in commands {
+                    println!("    {}", command.join(" "));
+                }
+            }
+            Err(e) => println!("  cannot be executed: {e}"),
+        }
+        println!("  contents:");
+        for line in snippet.contents.lines() {
+            println!("    {line}");
+        }
+    }
+}
+
 fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
     #[cfg(feature = "json-schema")]
     if cli.generate_config_file_schema {
@@ -380,6 +485,21 @@ fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
         let demo = ThemesDemo::new(themes, bindings)?;
         demo.run()?;
         return Ok(());
+    } else if cli.list_code_themes {
+        let Customizations { themes, .. } =
+            Customizations::load(cli.config_file.clone().map(PathBuf::from), &current_dir()?)?;
+        for name in themes.highlight.theme_names() {
+            println!("{name}");
+        }
+        return Ok(());
+    } else if cli.list_languages {
+        for language in SnippetLanguage::iter() {
+            let names: Vec<_> = language.names().collect();
+            if !names.is_empty() {
+                println!("{language:?}: {}", names.join(", "));
+            }
+        }
+        return Ok(());
     } else if cli.current_theme {
         let Customizations { config, .. } =
             Customizations::load(cli.config_file.clone().map(PathBuf::from), &current_dir()?)?;
@@ -388,14 +508,19 @@ fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
         println!("{theme_name}");
         return Ok(());
     }
-    // Disable this so we don't mess things up when generating PDFs
-    if cli.export_pdf {
+    // Disable this so we don't mess things up when generating PDFs or printing snippets
+    if cli.export_pdf || cli.list_executable_snippets || cli.run_snippets {
         TerminalEmulator::disable_capability_detection();
     }
 
     let Some(path) = cli.path.clone() else {
         Cli::command().error(ErrorKind::MissingRequiredArgument, "no path specified").exit();
     };
+    let from_stdin = path == Path::new(STDIN_PATH);
+    if from_stdin && (cli.export_pdf || cli.export_html) && cli.export_output.is_none() {
+        let message = "an output path must be specified when exporting a presentation read from stdin";
+        Cli::command().error(ErrorKind::MissingRequiredArgument, message).exit();
+    }
     let CoreComponents {
         third_party,
         code_executor,
@@ -409,13 +534,42 @@ fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
         graphics_mode,
     } = CoreComponents::new(&cli, &path)?;
     let arena = Arena::new();
-    let parser = MarkdownParser::new(&arena);
     let validate_overflows =
         overflow_validation_enabled(&present_mode, &config.defaults.validate_overflows) || cli.validate_overflows;
+    let parser =
+        MarkdownParser::new(&arena).with_math(config.defaults.math).with_footnote_validation(validate_overflows);
     if cli.validate_snippets {
         builder_options.validate_snippets = cli.validate_snippets;
     }
-    if cli.export_pdf || cli.export_html {
+    let cell_size_fallback = CellSizeFallback {
+        font_size: config.defaults.terminal_font_size,
+        aspect_ratio: config.defaults.cell_aspect_ratio,
+    };
+    if cli.list_executable_snippets || cli.run_snippets {
+        let mut third_party = third_party;
+        let snippets = PresentationBuilder::new(
+            &default_theme,
+            resources,
+            &mut third_party,
+            code_executor,
+            &themes,
+            ImageRegistry::new(printer),
+            config.bindings,
+            &parser,
+            builder_options,
+        )?
+        .collect_snippet_commands(&path)?;
+        if cli.run_snippets {
+            let report = SnippetChecker::new(Duration::from_secs(cli.snippet_timeout)).check(snippets);
+            serde_json::to_writer_pretty(io::stdout(), &report).map_err(|e| format!("failed to write report: {e}"))?;
+            println!();
+            if report.failed > 0 {
+                return Err(format!("{} snippet(s) failed", report.failed).into());
+            }
+        } else {
+            print_snippet_commands(&snippets);
+        }
+    } else if cli.export_pdf || cli.export_html {
         let dimensions = match config.export.dimensions {
             Some(dimensions) => WindowSize {
                 rows: dimensions.rows,
@@ -423,7 +577,7 @@ fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
                 height: dimensions.rows * DEFAULT_EXPORT_PIXELS_PER_ROW,
                 width: dimensions.columns * DEFAULT_EXPORT_PIXELS_PER_COLUMN,
             },
-            None => WindowSize::current(config.defaults.terminal_font_size)?,
+            None => WindowSize::current(cell_size_fallback)?,
         };
         let exporter = Exporter::new(
             parser,
@@ -450,12 +604,18 @@ fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
         let SpeakerNotesComponents { events_listener, events_publisher } =
             SpeakerNotesComponents::new(&cli, &config, &path)?;
         let command_listener = CommandListener::new(config.bindings.clone(), events_listener)?;
+        let slide_announcer = match &cli.announce_slides {
+            Some(path) => {
+                Some(SlideAnnouncer::new(path).map_err(|e| format!("failed to open '{}': {e}", path.display()))?)
+            }
+            None => None,
+        };
 
         builder_options.print_modal_background = matches!(graphics_mode, GraphicsMode::Kitty { .. });
         let options = PresenterOptions {
             builder_options,
             mode: present_mode,
-            font_size_fallback: config.defaults.terminal_font_size,
+            cell_size_fallback,
             bindings: config.bindings,
             validate_overflows,
             max_size: MaxSize {
@@ -465,6 +625,17 @@ fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
                 max_rows_alignment: config.defaults.max_rows_alignment,
             },
             transition: config.transition,
+            fast_navigation: config.defaults.fast_navigation,
+            slide_out_of_range: config.defaults.slide_out_of_range,
+            end_of_presentation: config.defaults.end_of_presentation,
+            slide_index_thumbnails: config.defaults.slide_index_thumbnails,
+            presenter_view: cli.presenter_view,
+            completion_cue: config.snippet.exec.completion_cue,
+            clock: config.defaults.clock,
+            countdown: config.defaults.countdown,
+            duration: cli.duration,
+            minimum_size: config.defaults.minimum_size,
+            minimum_column_width: config.defaults.minimum_column_width,
         };
         let presenter = Presenter::new(
             &default_theme,
@@ -477,6 +648,7 @@ fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
             printer,
             options,
             events_publisher,
+            slide_announcer,
         );
         presenter.present(&path)?;
     }
@@ -1,7 +1,7 @@
 use super::text_style::{Color, TextStyle, UndefinedPaletteColorError};
 use crate::theme::{ColorPalette, raw::RawColor};
 use comrak::nodes::AlertType;
-use std::{fmt, iter, path::PathBuf, str::FromStr};
+use std::{fmt, path::PathBuf, str::FromStr};
 use unicode_width::UnicodeWidthStr;
 
 /// A markdown element.
@@ -25,6 +25,9 @@ pub(crate) enum MarkdownElement {
     /// An image.
     Image { path: PathBuf, title: String, source_position: SourcePosition },
 
+    /// A display math formula, using `$$...$$`.
+    DisplayMath { formula: String },
+
     /// A list.
     ///
     /// All contiguous list items are merged into a single one, regardless of levels of nesting.
@@ -68,9 +71,12 @@ pub(crate) enum MarkdownElement {
 
     /// A footnote definition.
     Footnote(Line<RawColor>),
+
+    /// A definition list.
+    DefinitionList(Vec<Definition>),
 }
 
-#[derive(Clone, Copy, Debug, Default)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub struct SourcePosition {
     pub(crate) start: LineColumn,
 }
@@ -87,7 +93,7 @@ impl From<comrak::nodes::Sourcepos> for SourcePosition {
     }
 }
 
-#[derive(Clone, Copy, Debug, Default)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub(crate) struct LineColumn {
     pub(crate) line: usize,
     pub(crate) column: usize,
@@ -116,6 +122,26 @@ impl<C> Line<C> {
     pub(crate) fn width(&self) -> usize {
         self.0.iter().map(|text| text.content.width()).sum()
     }
+
+    /// Insert a zero width non-joiner between every pair of consecutive punctuation characters.
+    ///
+    /// This prevents terminals using fonts with programming ligatures from merging sequences like
+    /// `=>` into a single glyph, while keeping the width of the line unchanged.
+    pub(crate) fn break_ligatures(&mut self) {
+        let mut previous_punctuation = false;
+        for text in &mut self.0 {
+            let mut content = String::with_capacity(text.content.len());
+            for c in text.content.chars() {
+                let punctuation = c.is_ascii_punctuation();
+                if punctuation && previous_punctuation {
+                    content.push(ZERO_WIDTH_NON_JOINER);
+                }
+                content.push(c);
+                previous_punctuation = punctuation;
+            }
+            text.content = content;
+        }
+    }
 }
 
 impl Line<Color> {
@@ -197,6 +223,35 @@ pub(crate) struct ListItem {
 
     /// The type of list item.
     pub(crate) item_type: ListItemType,
+
+    /// The state of this item, if it's part of a task list.
+    pub(crate) task: Option<TaskState>,
+
+    /// An image displayed within this item.
+    pub(crate) image: Option<InlineImage>,
+}
+
+/// An image displayed within a line of text, like a list item or a table cell.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct InlineImage {
+    /// The path to the image.
+    pub(crate) path: PathBuf,
+
+    /// The index of the piece of text in the line the image is placed before.
+    pub(crate) position: usize,
+
+    /// The position in the source file this image came from.
+    pub(crate) source_position: SourcePosition,
+}
+
+/// The state of an item in a task list.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum TaskState {
+    /// The task is not done yet (`- [ ]`).
+    Unchecked,
+
+    /// The task is done (`- [x]`).
+    Checked,
 }
 
 /// The type of a list item.
@@ -227,28 +282,43 @@ impl Table {
     pub(crate) fn columns(&self) -> usize {
         self.header.0.len()
     }
-
-    /// Iterates all the text entries in a column.
-    ///
-    /// This includes the header.
-    pub(crate) fn iter_column(&self, column: usize) -> impl Iterator<Item = &Line<RawColor>> {
-        let header_element = &self.header.0[column];
-        let row_elements = self.rows.iter().map(move |row| &row.0[column]);
-        iter::once(header_element).chain(row_elements)
-    }
 }
 
 /// A table row.
 #[derive(Clone, Debug, PartialEq, Eq)]
-pub(crate) struct TableRow(pub(crate) Vec<Line<RawColor>>);
+pub(crate) struct TableRow(pub(crate) Vec<TableCell>);
 
-/// A percentage.
+/// A table cell.
 #[derive(Clone, Debug, PartialEq, Eq)]
-pub(crate) struct Percent(pub(crate) u8);
+pub(crate) struct TableCell {
+    /// The contents of this cell.
+    pub(crate) contents: Line<RawColor>,
+
+    /// An image displayed within this cell.
+    pub(crate) image: Option<InlineImage>,
+}
+
+/// A term in a definition list along with its definitions.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Definition {
+    /// The term being defined.
+    pub(crate) term: Line<RawColor>,
+
+    /// The definitions for this term, one per paragraph.
+    pub(crate) definitions: Vec<Line<RawColor>>,
+}
+
+const ZERO_WIDTH_NON_JOINER: char = '\u{200c}';
+
+/// A percentage.
+///
+/// This can be fractional, e.g. `33.3%`.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Percent(pub(crate) f64);
 
 impl Percent {
     pub(crate) fn as_ratio(&self) -> f64 {
-        self.0 as f64 / 100.0
+        self.0 / 100.0
     }
 }
 
@@ -257,8 +327,14 @@ impl FromStr for Percent {
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
         let (prefix, suffix) = input.split_once('%').ok_or(PercentParseError::Unit)?;
-        let value: u8 = prefix.parse().map_err(|_| PercentParseError::Value)?;
-        if !(1..=100).contains(&value) {
+        // Only allow plain decimal numbers, which `f64::from_str` is more lenient about.
+        let (integer, fraction) = prefix.split_once('.').unwrap_or((prefix, "0"));
+        let is_number = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+        if !is_number(integer) || !is_number(fraction) {
+            return Err(PercentParseError::Value);
+        }
+        let value: f64 = prefix.parse().map_err(|_| PercentParseError::Value)?;
+        if value <= 0.0 || value > 100.0 {
             return Err(PercentParseError::Value);
         }
         if !suffix.is_empty() {
@@ -270,7 +346,7 @@ impl FromStr for Percent {
 
 #[derive(thiserror::Error, Debug)]
 pub enum PercentParseError {
-    #[error("value must be a number between 1-100")]
+    #[error("value must be a number greater than 0 and up to 100")]
     Value,
 
     #[error("no unit provided")]
@@ -279,3 +355,47 @@ pub enum PercentParseError {
     #[error("unexpected: '{0}'")]
     Trailer(String),
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::arrow(&["a => b"], &["a =\u{200c}> b"])]
+    #[case::across_texts(&["a -", "> b"], &["a -", "\u{200c}> b"])]
+    #[case::comment(&["// !=="], &["/\u{200c}/ !\u{200c}=\u{200c}="])]
+    #[case::nothing(&["a = b"], &["a = b"])]
+    fn break_ligatures(#[case] input: &[&str], #[case] expected: &[&str]) {
+        let mut line = Line::<Color>(input.iter().map(|text| Text::from(*text)).collect());
+        let width = line.width();
+        line.break_ligatures();
+        let output: Vec<_> = line.0.iter().map(|text| text.content.as_str()).collect();
+        assert_eq!(output, expected);
+        assert_eq!(line.width(), width);
+    }
+
+    #[rstest]
+    #[case::integer("50%", 50.0)]
+    #[case::full("100%", 100.0)]
+    #[case::fractional("33.3%", 33.3)]
+    #[case::small("0.5%", 0.5)]
+    fn parse_percent(#[case] input: &str, #[case] expected: f64) {
+        let percent: Percent = input.parse().expect("parse failed");
+        assert_eq!(percent, Percent(expected));
+    }
+
+    #[rstest]
+    #[case::zero("0%")]
+    #[case::zero_fractional("0.0%")]
+    #[case::too_large("100.5%")]
+    #[case::negative("-5%")]
+    #[case::no_integer(".5%")]
+    #[case::no_fraction("5.%")]
+    #[case::exponent("1e1%")]
+    #[case::no_unit("50")]
+    #[case::trailer("50%%")]
+    fn parse_invalid_percent(#[case] input: &str) {
+        input.parse::<Percent>().expect_err("parse succeeded");
+    }
+}
@@ -1,5 +1,8 @@
 use super::{
-    elements::{Line, ListItem, ListItemType, MarkdownElement, SourcePosition, Table, TableRow, Text},
+    elements::{
+        Definition, InlineImage, Line, ListItem, ListItemType, MarkdownElement, SourcePosition, Table, TableCell,
+        TableRow, TaskState, Text,
+    },
     html::{HtmlInline, HtmlParser, ParseHtmlError},
     text_style::TextStyle,
 };
@@ -36,6 +39,8 @@ impl Default for ParserOptions {
         options.extension.wikilinks_title_before_pipe = true;
         options.extension.superscript = true;
         options.extension.footnotes = true;
+        options.extension.tasklist = true;
+        options.extension.description_lists = true;
         Self(options)
     }
 }
@@ -46,25 +51,90 @@ impl Default for ParserOptions {
 pub struct MarkdownParser<'a> {
     arena: &'a Arena<AstNode<'a>>,
     options: comrak::Options<'static>,
+    validate_footnotes: bool,
 }
 
 impl<'a> MarkdownParser<'a> {
     /// Construct a new markdown parser.
     pub fn new(arena: &'a Arena<AstNode<'a>>) -> Self {
-        Self { arena, options: ParserOptions::default().0 }
+        Self { arena, options: ParserOptions::default().0, validate_footnotes: false }
+    }
+
+    /// Enable parsing `$...$` and `$$...$$` math.
+    pub(crate) fn with_math(mut self, enabled: bool) -> Self {
+        self.options.extension.math_dollars = enabled;
+        self
+    }
+
+    /// Fail when a footnote is referenced but never defined.
+    pub(crate) fn with_footnote_validation(mut self, enabled: bool) -> Self {
+        self.validate_footnotes = enabled;
+        self
     }
 
     /// Parse the contents of a markdown file.
     pub(crate) fn parse(&self, contents: &str) -> ParseResult<Vec<MarkdownElement>> {
         let node = parse_document(self.arena, contents, &self.options);
-        let mut elements = Vec::new();
+        // Footnote definitions are moved to the end of the document so we place each of them right
+        // after the first element that references it instead.
+        let mut definitions = Vec::new();
+        let mut nodes = Vec::new();
         for node in node.children() {
-            let parsed_elements = self.parse_node(node).map_err(|e| ParseError::new(e.kind, e.sourcepos))?;
-            elements.extend(parsed_elements);
+            match &node.data.borrow().value {
+                NodeValue::FootnoteDefinition(definition) => definitions.push((definition.name.clone(), node)),
+                _ => nodes.push(node),
+            };
+        }
+        let mut elements = Vec::new();
+        for node in nodes {
+            if self.validate_footnotes {
+                Self::validate_footnote_references(node)?;
+            }
+            elements.extend(self.parse_node(node)?);
+            self.parse_referenced_footnotes(node, &mut definitions, &mut elements)?;
+        }
+        for (_, node) in definitions {
+            elements.extend(self.parse_node(node)?);
         }
         Ok(elements)
     }
 
+    fn parse_referenced_footnotes(
+        &self,
+        node: &'a AstNode<'a>,
+        definitions: &mut Vec<(String, &'a AstNode<'a>)>,
+        elements: &mut Vec<MarkdownElement>,
+    ) -> ParseResult<()> {
+        for descendant in node.descendants() {
+            let NodeValue::FootnoteReference(reference) = &descendant.data.borrow().value else {
+                continue;
+            };
+            let Some(position) = definitions.iter().position(|(name, _)| name == &reference.name) else {
+                continue;
+            };
+            let (_, definition) = definitions.remove(position);
+            elements.extend(self.parse_node(definition)?);
+            // Definitions can reference other footnotes themselves.
+            self.parse_referenced_footnotes(definition, definitions, elements)?;
+        }
+        Ok(())
+    }
+
+    fn validate_footnote_references(node: &'a AstNode<'a>) -> ParseResult<()> {
+        for descendant in node.descendants() {
+            let data = descendant.data.borrow();
+            if let NodeValue::Text(text) = &data.value {
+                let pieces = split_undefined_footnotes(text);
+                if let Some(TextPiece::UndefinedFootnote(name)) =
+                    pieces.into_iter().find(|piece| matches!(piece, TextPiece::UndefinedFootnote(_)))
+                {
+                    return Err(ParseErrorKind::UndefinedFootnote(name.to_string()).with_sourcepos(data.sourcepos));
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Parse inlines in a markdown input.
     pub(crate) fn parse_inlines(&self, line: &str) -> Result<Line<RawColor>, ParseInlinesError> {
         let node = parse_document(self.arena, line, &self.options);
@@ -89,6 +159,7 @@ impl<'a> MarkdownParser<'a> {
                 }
                 Inline::Image { .. } => return Err(ParseInlinesError("images not supported".into())),
                 Inline::LineBreak => return Err(ParseInlinesError("line breaks not supported".into())),
+                Inline::DisplayMath { formula } => output.0.push(Text::from(format!("$${formula}$$"))),
             };
         }
         Ok(output)
@@ -106,6 +177,7 @@ impl<'a> MarkdownParser<'a> {
                 MarkdownElement::List(items)
             }
             NodeValue::Table(_) => self.parse_table(node)?,
+            NodeValue::DescriptionList => self.parse_definition_list(node)?,
             NodeValue::CodeBlock(block) => Self::parse_code_block(block, data.sourcepos)?,
             NodeValue::ThematicBreak => MarkdownElement::ThematicBreak,
             NodeValue::HtmlBlock(block) => self.parse_html_block(block, data.sourcepos)?,
@@ -148,6 +220,7 @@ impl<'a> MarkdownParser<'a> {
             match inline {
                 Inline::Text(text) => lines.push(text),
                 Inline::LineBreak => lines.push(Line::from("")),
+                Inline::DisplayMath { formula } => lines.push(Line::from(format!("$${formula}$$"))),
                 Inline::Image { .. } => {}
             }
         }
@@ -183,6 +256,7 @@ impl<'a> MarkdownParser<'a> {
         for inline in inlines {
             match inline {
                 Inline::Text(text) => line.extend(text.0),
+                Inline::DisplayMath { formula } => line.push(Text::from(format!("$${formula}$$"))),
                 Inline::LineBreak | Inline::Image { .. } => {}
             }
         }
@@ -216,6 +290,12 @@ impl<'a> MarkdownParser<'a> {
                         source_position: node.data.borrow().sourcepos.into(),
                     });
                 }
+                Inline::DisplayMath { formula } => {
+                    if !paragraph_elements.is_empty() {
+                        elements.push(MarkdownElement::Paragraph(mem::take(&mut paragraph_elements)));
+                    }
+                    elements.push(MarkdownElement::DisplayMath { formula });
+                }
             }
         }
         if !paragraph_elements.is_empty() {
@@ -225,27 +305,54 @@ impl<'a> MarkdownParser<'a> {
     }
 
     fn parse_text(&self, node: &'a AstNode<'a>) -> ParseResult<Line<RawColor>> {
+        match self.parse_text_with_image(node)? {
+            (line, None) => Ok(line),
+            (_, Some(_)) => Err(ParseErrorKind::UnsupportedStructure { container: "text", element: "image" }
+                .with_sourcepos(node.data.borrow().sourcepos)),
+        }
+    }
+
+    // Parses text that can contain a single image within it.
+    fn parse_text_with_image(&self, node: &'a AstNode<'a>) -> ParseResult<(Line<RawColor>, Option<InlineImage>)> {
         let inlines = InlinesParser::new(self.arena, SoftBreak::Space, StringifyImages::No).parse(node)?;
         let mut chunks = Vec::new();
+        let mut image = None;
         for inline in inlines {
             match inline {
                 Inline::Text(text) => chunks.extend(text.0),
+                Inline::DisplayMath { formula } => chunks.push(Text::from(format!("$${formula}$$"))),
+                Inline::Image { path, .. } if image.is_none() => {
+                    let source_position = node.data.borrow().sourcepos.into();
+                    image = Some(InlineImage { path: path.into(), position: chunks.len(), source_position });
+                }
                 other => {
                     return Err(ParseErrorKind::UnsupportedStructure { container: "text", element: other.kind() }
                         .with_sourcepos(node.data.borrow().sourcepos));
                 }
             };
         }
-        Ok(Line(chunks))
+        Ok((Line(chunks), image))
     }
 
     fn parse_list(&self, root: &'a AstNode<'a>, depth: u8) -> ParseResult<Vec<ListItem>> {
         let mut elements = Vec::new();
-        for node in root.children() {
+        for (index, node) in root.children().enumerate() {
             let data = node.data.borrow();
             match &data.value {
                 NodeValue::Item(item) => {
-                    elements.extend(self.parse_list_item(item, node, depth)?);
+                    elements.extend(self.parse_list_item(item, node, depth, None)?);
+                }
+                NodeValue::TaskItem(symbol) => {
+                    // Task items don't carry the list information so pull it from the list itself.
+                    let NodeValue::List(list) = &root.data.borrow().value else {
+                        panic!("task item not in a list");
+                    };
+                    let item = NodeList { start: list.start + index, ..*list };
+                    let task = match symbol {
+                        Some(_) => TaskState::Checked,
+                        None => TaskState::Unchecked,
+                    };
+                    elements.extend(self.parse_list_item(&item, node, depth, Some(task))?);
                 }
                 other => {
                     return Err(ParseErrorKind::UnsupportedStructure {
@@ -259,7 +366,13 @@ impl<'a> MarkdownParser<'a> {
         Ok(elements)
     }
 
-    fn parse_list_item(&self, item: &NodeList, root: &'a AstNode<'a>, depth: u8) -> ParseResult<Vec<ListItem>> {
+    fn parse_list_item(
+        &self,
+        item: &NodeList,
+        root: &'a AstNode<'a>,
+        depth: u8,
+        task: Option<TaskState>,
+    ) -> ParseResult<Vec<ListItem>> {
         let item_type = match (item.list_type, item.delimiter) {
             (ListType::Bullet, _) => ListItemType::Unordered,
             (ListType::Ordered, ListDelimType::Paren) => ListItemType::OrderedParens(item.start),
@@ -270,8 +383,8 @@ impl<'a> MarkdownParser<'a> {
             let data = node.data.borrow();
             match &data.value {
                 NodeValue::Paragraph => {
-                    let contents = self.parse_text(node)?;
-                    elements.push(ListItem { contents, depth, item_type: item_type.clone() });
+                    let (contents, image) = self.parse_text_with_image(node)?;
+                    elements.push(ListItem { contents, depth, item_type: item_type.clone(), task, image });
                 }
                 NodeValue::List(_) => {
                     elements.extend(self.parse_list(node, depth + 1)?);
@@ -321,11 +434,60 @@ impl<'a> MarkdownParser<'a> {
                 }
                 .with_sourcepos(data.sourcepos));
             };
-            let text = self.parse_text(node)?;
-            cells.push(text);
+            let (contents, image) = self.parse_text_with_image(node)?;
+            cells.push(TableCell { contents, image });
         }
         Ok(TableRow(cells))
     }
+
+    fn parse_definition_list(&self, node: &'a AstNode<'a>) -> ParseResult<MarkdownElement> {
+        let mut definitions: Vec<Definition> = Vec::new();
+        // Every extra definition for a term comes in its own item that has no term in it.
+        for node in node.children().flat_map(|item| item.children()) {
+            let data = node.data.borrow();
+            match &data.value {
+                NodeValue::DescriptionTerm => {
+                    let mut term = Line::default();
+                    for paragraph in node.children() {
+                        term.0.extend(self.parse_definition_paragraph(paragraph)?.0);
+                    }
+                    definitions.push(Definition { term, definitions: Vec::new() });
+                }
+                NodeValue::DescriptionDetails => {
+                    let Some(definition) = definitions.last_mut() else {
+                        return Err(ParseErrorKind::UnsupportedStructure {
+                            container: "definition list",
+                            element: data.value.identifier(),
+                        }
+                        .with_sourcepos(data.sourcepos));
+                    };
+                    for paragraph in node.children() {
+                        definition.definitions.push(self.parse_definition_paragraph(paragraph)?);
+                    }
+                }
+                other => {
+                    return Err(ParseErrorKind::UnsupportedStructure {
+                        container: "definition list",
+                        element: other.identifier(),
+                    }
+                    .with_sourcepos(data.sourcepos));
+                }
+            };
+        }
+        Ok(MarkdownElement::DefinitionList(definitions))
+    }
+
+    fn parse_definition_paragraph(&self, node: &'a AstNode<'a>) -> ParseResult<Line<RawColor>> {
+        let data = node.data.borrow();
+        let NodeValue::Paragraph = &data.value else {
+            return Err(ParseErrorKind::UnsupportedStructure {
+                container: "definition list",
+                element: data.value.identifier(),
+            }
+            .with_sourcepos(data.sourcepos));
+        };
+        self.parse_text(node)
+    }
 }
 
 enum SoftBreak {
@@ -373,7 +535,16 @@ impl<'a> InlinesParser<'a> {
         let data = node.data.borrow();
         match &data.value {
             NodeValue::Text(text) => {
-                self.pending_text.push(Text::new(text.clone(), style));
+                for piece in split_undefined_footnotes(text) {
+                    match piece {
+                        TextPiece::Text(text) => self.pending_text.push(Text::new(text, style.clone())),
+                        // Display the marker even though there's nothing it points to.
+                        TextPiece::UndefinedFootnote(name) => {
+                            let style = TextStyle::colored(style.colors.clone()).superscript();
+                            self.pending_text.push(Text::new(name, style));
+                        }
+                    };
+                }
             }
             NodeValue::Code(code) => {
                 self.pending_text.push(Text::new(code.literal.clone(), TextStyle::default().code()));
@@ -468,6 +639,14 @@ impl<'a> InlinesParser<'a> {
                     HtmlInline::CloseSpan => return Ok(Some(HtmlStyle::Remove)),
                 };
             }
+            NodeValue::Math(math) if math.display_math => {
+                self.store_pending_text();
+                self.inlines.push(Inline::DisplayMath { formula: math.literal.trim().to_string() });
+            }
+            NodeValue::Math(math) => {
+                // The formula is kept as is so it can be displayed as text wherever it can't be rendered.
+                self.pending_text.push(Text::new(format!("${}$", math.literal), style.math()));
+            }
             NodeValue::FootnoteReference(reference) => {
                 // Keep only colors here, we don't care about e.g. italics footnotes.
                 let style = TextStyle::colored(style.colors).superscript();
@@ -502,6 +681,43 @@ impl<'a> InlinesParser<'a> {
     }
 }
 
+enum TextPiece<'a> {
+    Text(&'a str),
+    UndefinedFootnote(&'a str),
+}
+
+/// Split a piece of text into plain text and references to footnotes that have no definition.
+///
+/// References to footnotes that aren't defined anywhere are turned into text that contains the
+/// reference as-is, e.g. `[^1]`.
+fn split_undefined_footnotes(text: &str) -> Vec<TextPiece<'_>> {
+    let mut pieces = Vec::new();
+    let mut start = 0;
+    let mut offset = 0;
+    while let Some(position) = text[offset..].find("[^") {
+        let reference_start = offset + position;
+        let name_start = reference_start + 2;
+        let Some(length) = text[name_start..].find(']') else {
+            break;
+        };
+        let name = &text[name_start..name_start + length];
+        offset = name_start;
+        if name.is_empty() || name.contains('[') {
+            continue;
+        }
+        if reference_start > start {
+            pieces.push(TextPiece::Text(&text[start..reference_start]));
+        }
+        pieces.push(TextPiece::UndefinedFootnote(name));
+        start = name_start + length + 1;
+        offset = start;
+    }
+    if start < text.len() {
+        pieces.push(TextPiece::Text(&text[start..]));
+    }
+    pieces
+}
+
 enum HtmlStyle {
     Add(TextStyle<RawColor>),
     Remove,
@@ -511,6 +727,7 @@ enum Inline {
     Text(Line<RawColor>),
     Image { path: String, title: String },
     LineBreak,
+    DisplayMath { formula: String },
 }
 
 impl Inline {
@@ -519,6 +736,7 @@ impl Inline {
             Self::Text(_) => "text",
             Self::Image { .. } => "image",
             Self::LineBreak => "line break",
+            Self::DisplayMath { .. } => "display math",
         }
     }
 }
@@ -560,6 +778,9 @@ pub(crate) enum ParseErrorKind {
     /// Invalid HTML was found.
     InvalidHtml(ParseHtmlError),
 
+    /// A footnote is referenced but never defined.
+    UndefinedFootnote(String),
+
     /// An internal parsing error.
     Internal(String),
 }
@@ -573,6 +794,7 @@ impl Display for ParseErrorKind {
             }
             Self::UnfencedCodeBlock => write!(f, "only fenced code blocks are supported"),
             Self::InvalidHtml(inner) => write!(f, "invalid HTML: {inner}"),
+            Self::UndefinedFootnote(name) => write!(f, "footnote '{name}' is referenced but never defined"),
             Self::Internal(message) => write!(f, "internal error: {message}"),
         }
     }
@@ -676,6 +898,25 @@ boop
         assert_eq!(contents, "beep\nboop\n");
     }
 
+    #[test]
+    fn display_math() {
+        let arena = Arena::new();
+        let parsed =
+            MarkdownParser::new(&arena).with_math(true).parse("hi $x$\n\n$$\ny^2\n$$\n").expect("parse failed");
+        let [MarkdownElement::Paragraph(lines), MarkdownElement::DisplayMath { formula }] = parsed.as_slice() else {
+            panic!("unexpected elements: {parsed:?}")
+        };
+        assert_eq!(lines, &[Line(vec![Text::from("hi "), Text::new("$x$", TextStyle::default().math())])]);
+        assert_eq!(formula, "y^2");
+    }
+
+    #[test]
+    fn math_disabled() {
+        let parsed = parse_single("$$x$$");
+        let MarkdownElement::Paragraph(lines) = parsed else { panic!("not a paragraph: {parsed:?}") };
+        assert_eq!(lines, &[Line::from("$$x$$")]);
+    }
+
     #[test]
     fn paragraph() {
         let parsed =
@@ -850,6 +1091,26 @@ Title
         assert_eq!(next().depth, 0);
     }
 
+    #[test]
+    fn task_list() {
+        let parsed = parse_single(
+            r"
+ * [ ] One
+ * [x] Two
+    1. [X] Three
+    2. Four",
+        );
+        let MarkdownElement::List(items) = parsed else { panic!("not a list: {parsed:?}") };
+        let items: Vec<_> = items.into_iter().map(|item| (item.contents, item.item_type, item.task)).collect();
+        let expected = &[
+            (Line::from("One"), ListItemType::Unordered, Some(TaskState::Unchecked)),
+            (Line::from("Two"), ListItemType::Unordered, Some(TaskState::Checked)),
+            (Line::from("Three"), ListItemType::OrderedPeriod(1), Some(TaskState::Checked)),
+            (Line::from("Four"), ListItemType::OrderedPeriod(2), None),
+        ];
+        assert_eq!(items, expected);
+    }
+
     #[test]
     fn ordered_list_starting_non_one() {
         let parsed = parse_single(
@@ -905,6 +1166,21 @@ let q = 42;
         assert_eq!(code, "let q = 42;\n");
     }
 
+    #[rstest]
+    #[case::longer_backticks("````markdown\n```rust\nlet q = 42;\n```\n````", "```rust\nlet q = 42;\n```\n")]
+    #[case::tildes("~~~markdown\n```rust\nlet q = 42;\n```\n~~~", "```rust\nlet q = 42;\n```\n")]
+    #[case::backticks_in_tildes("~~~~markdown\n~~~\n````\n~~~\n~~~~", "~~~\n````\n~~~\n")]
+    #[case::several_levels(
+        "`````markdown\n````markdown\n```rust\n```\n````\n`````",
+        "````markdown\n```rust\n```\n````\n"
+    )]
+    fn nested_code_fences(#[case] input: &str, #[case] expected: &str) {
+        let parsed = parse_single(input);
+        let MarkdownElement::Snippet { info, code, .. } = parsed else { panic!("not a code block: {parsed:?}") };
+        assert_eq!(info, "markdown");
+        assert_eq!(code, expected);
+    }
+
     #[test]
     fn inline_code() {
         let parsed = parse_single("some `inline code`");
@@ -933,6 +1209,38 @@ let q = 42;
         assert_eq!(rows[1].0.len(), 2);
     }
 
+    #[test]
+    fn table_cell_image() {
+        let parsed = parse_single(
+            r"
+| Name | Logo |
+| ------ | ------ |
+| Potato | ![](potato.png) tasty |
+",
+        );
+        let MarkdownElement::Table(Table { rows, .. }) = parsed else { panic!("not a table: {parsed:?}") };
+        let cell = &rows[0].0[1];
+        let image = cell.image.as_ref().expect("no image");
+        assert_eq!(image.path, Path::new("potato.png"));
+        assert_eq!(image.position, 0);
+        assert_eq!(cell.contents, Line::from(" tasty"));
+    }
+
+    #[test]
+    fn list_item_image() {
+        let parsed = parse_single("* potato ![](potato.png)");
+        let MarkdownElement::List(items) = parsed else { panic!("not a list: {parsed:?}") };
+        let image = items[0].image.as_ref().expect("no image");
+        assert_eq!(image.path, Path::new("potato.png"));
+        assert_eq!(image.position, 1);
+    }
+
+    #[test]
+    fn list_item_multiple_images() {
+        let result = try_parse("* ![](a.png) ![](b.png)");
+        assert!(result.is_err());
+    }
+
     #[test]
     fn comment() {
         let parsed = parse_single(
@@ -1039,7 +1347,7 @@ hi
 mom
 ---
 
-* ![](potato.png)
+* ![](potato.png) ![](potato.png)
 ";
         let arena = Arena::new();
         let result = MarkdownParser::new(&arena).parse(input);
@@ -1122,4 +1430,72 @@ this[^1]
         let MarkdownElement::Footnote(line) = &elements[1] else { panic!("not a footnote") };
         assert_eq!(line, &Line(vec![Text::new("1", TextStyle::default().superscript()), Text::from("ref")]));
     }
+
+    #[test]
+    fn definition_list() {
+        let input = r"
+Term
+: one **bold**
+
+: two
+
+Other
+: three
+";
+        let MarkdownElement::DefinitionList(definitions) = parse_single(input) else { panic!("not a definition list") };
+        let expected = vec![
+            Definition {
+                term: Line::from("Term"),
+                definitions: vec![
+                    Line(vec![Text::from("one "), Text::new("bold", TextStyle::default().bold())]),
+                    Line::from("two"),
+                ],
+            },
+            Definition { term: Line::from("Other"), definitions: vec![Line::from("three")] },
+        ];
+        assert_eq!(definitions, expected);
+    }
+
+    #[test]
+    fn footnotes_follow_references() {
+        let input = r"
+first[^a]
+
+second[^b]
+
+[^b]: two
+[^a]: one
+";
+        let elements = parse_all(input);
+        let kinds: Vec<_> = elements
+            .iter()
+            .map(|element| match element {
+                MarkdownElement::Paragraph(_) => "paragraph",
+                MarkdownElement::Footnote(_) => "footnote",
+                _ => "other",
+            })
+            .collect();
+        assert_eq!(kinds, &["paragraph", "footnote", "paragraph", "footnote"]);
+
+        let MarkdownElement::Footnote(line) = &elements[1] else { panic!("not a footnote") };
+        assert_eq!(line, &Line(vec![Text::new("a", TextStyle::default().superscript()), Text::from("one")]));
+    }
+
+    #[test]
+    fn undefined_footnote() {
+        let elements = parse_all("this[^1]");
+        let MarkdownElement::Paragraph(line) = &elements[0] else { panic!("not a paragraph") };
+        assert_eq!(line, &[Line(vec![Text::from("this"), Text::new("1", TextStyle::default().superscript())])]);
+    }
+
+    #[test]
+    fn undefined_footnote_validation() {
+        let arena = Arena::new();
+        let result = MarkdownParser::new(&arena).with_footnote_validation(true).parse("hi\n\nthis[^1]");
+        let Err(e) = result else {
+            panic!("parsing didn't fail");
+        };
+        assert!(matches!(e.kind, ParseErrorKind::UndefinedFootnote(name) if name == "1"));
+        assert_eq!(e.sourcepos.start.line, 3);
+    }
 }
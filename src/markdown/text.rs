@@ -17,8 +17,32 @@ pub(crate) struct WeightedLine {
 
 impl WeightedLine {
     /// Split this line into chunks of at most `max_length` width.
-    pub(crate) fn split(&self, max_length: usize) -> SplitTextIter {
-        SplitTextIter::new(&self.text, max_length)
+    pub(crate) fn split(&self, max_length: usize) -> SplitTextIter<'_> {
+        SplitTextIter::new(&self.text, max_length, SplitMode::Words)
+    }
+
+    /// Split this line into chunks of at most `max_length` width, ignoring word boundaries.
+    pub(crate) fn split_characters(&self, max_length: usize) -> SplitTextIter<'_> {
+        SplitTextIter::new(&self.text, max_length, SplitMode::Characters)
+    }
+
+    /// Get a copy of this line with the given text appended to it.
+    pub(crate) fn with_suffix(&self, suffix: Text) -> Self {
+        let size = suffix.style.size.max(1);
+        let mut line = self.clone();
+        line.width += suffix.content.width() * size as usize;
+        line.font_size = line.font_size.max(size);
+        line.text.push(suffix.into());
+        line
+    }
+
+    /// Get a copy of this line with the given function applied to the style of every piece of text.
+    pub(crate) fn map_styles<F: Fn(TextStyle) -> TextStyle>(&self, f: F) -> Self {
+        let mut line = self.clone();
+        for text in &mut line.text {
+            text.text.style = f(text.text.style);
+        }
+        line
     }
 
     /// The total width of this line.
@@ -91,7 +115,7 @@ pub(crate) struct WeightedText {
 }
 
 impl WeightedText {
-    fn to_ref(&self) -> WeightedTextRef {
+    fn to_ref(&self) -> WeightedTextRef<'_> {
         WeightedTextRef { text: &self.text.content, accumulators: &self.accumulators, style: self.text.style }
     }
 
@@ -102,6 +126,13 @@ impl WeightedText {
     pub(crate) fn text(&self) -> &Text {
         &self.text
     }
+
+    /// Get a copy of this text with the given function applied to its style.
+    pub(crate) fn map_style<F: Fn(TextStyle) -> TextStyle>(&self, f: F) -> Self {
+        let mut text = self.clone();
+        text.text.style = f(text.text.style);
+        text
+    }
 }
 
 impl<S: Into<String>> From<S> for WeightedText {
@@ -131,16 +162,23 @@ impl fmt::Debug for WeightedText {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SplitMode {
+    Words,
+    Characters,
+}
+
 /// An iterator over the chunks in a [WeightedLine].
 pub(crate) struct SplitTextIter<'a> {
     texts: &'a [WeightedText],
     max_length: usize,
+    mode: SplitMode,
     current: Option<WeightedTextRef<'a>>,
 }
 
 impl<'a> SplitTextIter<'a> {
-    fn new(texts: &'a [WeightedText], max_length: usize) -> Self {
-        Self { texts, max_length, current: texts.first().map(WeightedText::to_ref) }
+    fn new(texts: &'a [WeightedText], max_length: usize, mode: SplitMode) -> Self {
+        Self { texts, max_length, mode, current: texts.first().map(WeightedText::to_ref) }
     }
 }
 
@@ -153,10 +191,23 @@ impl<'a> Iterator for SplitTextIter<'a> {
         let mut elements = Vec::new();
         let mut remaining = self.max_length as i64;
         while let Some(current) = self.current.take() {
-            let (head, rest) = current.word_split_at_length(remaining as usize);
-            // Prevent splitting a word partially. We do allow this on the first chunk as otherwise
-            // a word longer than `max_length` would never be split.
-            if !rest.text.is_empty() && !rest.text.starts_with(' ') && !elements.is_empty() {
+            let (head, rest) = match self.mode {
+                SplitMode::Words => current.word_split_at_length(remaining as usize),
+                SplitMode::Characters => {
+                    // Always take at least one character at the start of a line so we make progress.
+                    let minimum_chars = usize::from(elements.is_empty());
+                    current.character_split_at_length(remaining as usize, minimum_chars)
+                }
+            };
+            // Prevent splitting a word partially, or not at all when splitting by characters and
+            // there's no space left. We do allow this on the first chunk as otherwise a word
+            // longer than `max_length` would never be split.
+            let must_wrap = !rest.text.is_empty()
+                && match self.mode {
+                    SplitMode::Words => !rest.text.starts_with(' '),
+                    SplitMode::Characters => head.text.is_empty(),
+                };
+            if must_wrap && !elements.is_empty() {
                 self.current = Some(current);
                 break;
             }
@@ -166,7 +217,10 @@ impl<'a> Iterator for SplitTextIter<'a> {
 
             // The moment we hit a chunk we couldn't fully split, we're done.
             if !rest.text.is_empty() {
-                self.current = Some(rest.trim_start());
+                self.current = match self.mode {
+                    SplitMode::Words => Some(rest.trim_start()),
+                    SplitMode::Characters => Some(rest),
+                };
                 break;
             }
 
@@ -210,6 +264,16 @@ impl<'a> WeightedTextRef<'a> {
         (self.make_ref(0, output_chunk.len()), self.make_ref(output_chunk.len(), self.text.len()))
     }
 
+    // Splits this at the given length, regardless of where words start and end.
+    fn character_split_at_length(&self, max_length: usize, minimum_chars: usize) -> (Self, Self) {
+        if self.width() <= max_length {
+            return (self.make_ref(0, self.text.len()), self.make_ref(0, 0));
+        }
+        let max_length = (max_length / self.style.size as usize).max(minimum_chars);
+        let output_chunk = self.substr(max_length);
+        (self.make_ref(0, output_chunk.len()), self.make_ref(output_chunk.len(), self.text.len()))
+    }
+
     fn substr(&self, max_length: usize) -> &'a str {
         let last_index = self.bytes_until(max_length);
         &self.text[0..last_index]
@@ -363,6 +427,19 @@ mod test {
         assert_eq!(lines, expected);
     }
 
+    #[test]
+    fn split_characters() {
+        let text = WeightedLine {
+            text: vec![WeightedText::from("let url = "), WeightedText::from("\"https://example.com\";")],
+            width: 0,
+            font_size: 1,
+        };
+        let lines: Vec<String> =
+            text.split_characters(8).map(|line| line.iter().map(|chunk| chunk.text).collect()).collect();
+        let expected = vec!["let url ", "= \"https", "://examp", "le.com\";"];
+        assert_eq!(lines, expected);
+    }
+
     #[test]
     fn split_lines_multi() {
         let text = WeightedLine {
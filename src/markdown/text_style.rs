@@ -3,7 +3,6 @@ use crate::{
     theme::{ColorPalette, raw::RawColor},
 };
 use crossterm::style::{ContentStyle, StyledContent, Stylize};
-use hex::FromHexError;
 use serde::{Deserialize, Serialize};
 use std::{
     borrow::Cow,
@@ -52,6 +51,11 @@ where
         self.add_flag(TextFormatFlags::Code)
     }
 
+    /// Indicate this text is an inline math formula.
+    pub(crate) fn math(self) -> Self {
+        self.add_flag(TextFormatFlags::Math)
+    }
+
     /// Add strikethrough to this style.
     pub(crate) fn strikethrough(self) -> Self {
         self.add_flag(TextFormatFlags::Strikethrough)
@@ -105,6 +109,11 @@ where
         self.has_flag(TextFormatFlags::Code)
     }
 
+    /// Check whether this text is an inline math formula.
+    pub(crate) fn is_math(&self) -> bool {
+        self.has_flag(TextFormatFlags::Math)
+    }
+
     /// Merge this style with another one.
     pub(crate) fn merge(&mut self, other: &TextStyle<C>) {
         self.flags |= other.flags;
@@ -214,6 +223,7 @@ impl Iterator for AttributeIterator {
                 Strikethrough => Some(Superscript),
                 Superscript => Some(Underlined),
                 Underlined => None,
+                Math => None,
             };
             if self.flags & next_mask as u8 != 0 {
                 let attr = match next_mask {
@@ -223,6 +233,7 @@ impl Iterator for AttributeIterator {
                     Strikethrough => TextAttribute::Strikethrough,
                     Superscript => TextAttribute::Superscript,
                     Underlined => TextAttribute::Underlined,
+                    Math => panic!("math shouldn't reach here"),
                 };
                 return Some(attr);
             }
@@ -274,6 +285,7 @@ enum TextFormatFlags {
     Strikethrough = 8,
     Underlined = 16,
     Superscript = 32,
+    Math = 64,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -309,6 +321,41 @@ impl Color {
         }
     }
 
+    /// Get the RGB components of this color, approximating named colors.
+    pub(crate) fn to_rgb(self) -> (u8, u8, u8) {
+        match self {
+            Self::Black => (0x00, 0x00, 0x00),
+            Self::DarkGrey => (0x5a, 0x5a, 0x5a),
+            Self::Red => (0xff, 0x00, 0x00),
+            Self::DarkRed => (0x8b, 0x00, 0x00),
+            Self::Green => (0x00, 0xff, 0x00),
+            Self::DarkGreen => (0x00, 0x64, 0x00),
+            Self::Yellow => (0xff, 0xff, 0x00),
+            Self::DarkYellow => (0x8b, 0x80, 0x00),
+            Self::Blue => (0x00, 0x00, 0xff),
+            Self::DarkBlue => (0x00, 0x00, 0x8b),
+            Self::Magenta => (0xff, 0x00, 0xff),
+            Self::DarkMagenta => (0x8b, 0x00, 0x8b),
+            Self::Cyan => (0x00, 0xff, 0xff),
+            Self::DarkCyan => (0x00, 0x8b, 0x8b),
+            Self::White => (0xff, 0xff, 0xff),
+            Self::Grey => (0x80, 0x80, 0x80),
+            Self::Rgb { r, g, b } => (r, g, b),
+        }
+    }
+
+    /// Move this color towards another one by the given amount, from 0 to 1.
+    ///
+    /// Only RGB colors can be blended, any other color is returned as-is.
+    pub(crate) fn blend(self, other: Color, amount: f32) -> Self {
+        let (Some((r1, g1, b1)), Some((r2, g2, b2))) = (self.as_rgb(), other.as_rgb()) else {
+            return self;
+        };
+        let amount = amount.clamp(0.0, 1.0);
+        let mix = |from: u8, to: u8| (from as f32 + (to as f32 - from as f32) * amount).round() as u8;
+        Self::new(mix(r1, r2), mix(g1, g2), mix(b1, b2))
+    }
+
     pub(crate) fn from_ansi(color: u8) -> Option<Self> {
         let color = match color {
             30 | 40 => Color::Black,
@@ -390,12 +437,6 @@ impl From<Colors> for crossterm::style::Colors {
     }
 }
 
-#[derive(thiserror::Error, Debug)]
-pub(crate) enum ParseColorError {
-    #[error("invalid hex color: {0}")]
-    Hex(#[from] FromHexError),
-}
-
 trait TryIntoSuperscript {
     fn try_into_superscript(&self) -> Option<String>;
 }
@@ -483,4 +524,13 @@ mod tests {
         let attrs: Vec<_> = style.iter_attributes().collect();
         assert_eq!(attrs, expected);
     }
+
+    #[rstest]
+    #[case::none(Color::new(200, 100, 0), 0.0, Color::new(200, 100, 0))]
+    #[case::half(Color::new(200, 100, 0), 0.5, Color::new(100, 50, 0))]
+    #[case::full(Color::new(200, 100, 0), 1.0, Color::new(0, 0, 0))]
+    #[case::not_rgb(Color::Red, 0.5, Color::Red)]
+    fn blend(#[case] color: Color, #[case] amount: f32, #[case] expected: Color) {
+        assert_eq!(color.blend(Color::new(0, 0, 0), amount), expected);
+    }
 }
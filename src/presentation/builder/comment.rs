@@ -1,8 +1,12 @@
 use crate::{
-    markdown::elements::{MarkdownElement, SourcePosition},
+    markdown::{
+        elements::{MarkdownElement, SourcePosition},
+        text_style::TextStyle,
+    },
     presentation::builder::{BuildResult, LayoutState, PresentationBuilder, error::InvalidPresentation},
     render::operation::RenderOperation,
     theme::{Alignment, ElementType},
+    ui::big_text::{BigText, BigTextFont},
 };
 use serde::Deserialize;
 use std::{fmt, num::NonZeroU8, path::PathBuf, str::FromStr};
@@ -75,7 +79,19 @@ impl PresentationBuilder<'_, '_> {
             CommentCommand::NoFooter => {
                 self.slide_state.ignore_footer = true;
             }
-            CommentCommand::SpeakerNote(_) => {}
+            CommentCommand::SpeakerNote(note) => self.slide_state.speaker_notes.push(note),
+            CommentCommand::SpeakerOnly => {
+                if self.slide_state.speaker_only {
+                    return Err(self.invalid_presentation(source_position, InvalidPresentation::AlreadySpeakerOnly));
+                }
+                self.slide_state.speaker_only = true;
+            }
+            CommentCommand::EndSpeakerOnly => {
+                if !self.slide_state.speaker_only {
+                    return Err(self.invalid_presentation(source_position, InvalidPresentation::NotSpeakerOnly));
+                }
+                self.slide_state.speaker_only = false;
+            }
             CommentCommand::FontSize(size) => {
                 if size == 0 || size > 7 {
                     return Err(self.invalid_presentation(source_position, InvalidPresentation::InvalidFontSize));
@@ -95,6 +111,13 @@ impl PresentationBuilder<'_, '_> {
             CommentCommand::SkipSlide => {
                 self.slide_state.skip_slide = true;
             }
+            CommentCommand::BigText(BigTextCommand { text, font }) => {
+                let style = TextStyle::colored(self.theme.slide_title.style.colors);
+                self.chunk_operations.push(BigText::new(text, font, style).into());
+            }
+            CommentCommand::ImageRow => {
+                self.slide_state.image_row = Some(Vec::new());
+            }
             CommentCommand::ListItemNewlines(count) => {
                 self.slide_state.list_item_newlines = Some(count.into());
             }
@@ -125,6 +148,8 @@ impl PresentationBuilder<'_, '_> {
             }
             CommentCommand::EndSlide => self.terminate_slide(),
             CommentCommand::SkipSlide => self.slide_state.skip_slide = true,
+            CommentCommand::SpeakerOnly => self.slide_state.speaker_only = true,
+            CommentCommand::EndSpeakerOnly => self.slide_state.speaker_only = false,
             _ => {}
         }
     }
@@ -177,9 +202,12 @@ impl PresentationBuilder<'_, '_> {
 #[serde(rename_all = "snake_case")]
 enum CommentCommand {
     Alignment(CommentCommandAlignment),
+    BigText(BigTextCommand),
     Column(usize),
     EndSlide,
+    EndSpeakerOnly,
     FontSize(u8),
+    ImageRow,
     Include(PathBuf),
     IncrementalLists(bool),
     #[serde(rename = "column_layout")]
@@ -195,6 +223,7 @@ enum CommentCommand {
     ResetLayout,
     SkipSlide,
     SpeakerNote(String),
+    SpeakerOnly,
     SnippetOutput(String),
 }
 
@@ -218,6 +247,15 @@ enum CommentCommandAlignment {
     Right,
 }
 
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BigTextCommand {
+    text: String,
+
+    #[serde(default)]
+    font: BigTextFont,
+}
+
 #[derive(thiserror::Error, Debug)]
 pub struct CommandParseError(#[from] serde_yaml::Error);
 
@@ -255,6 +293,11 @@ mod tests {
     #[case::incremental_lists("newlines: 2", CommentCommand::NewLines(2))]
     #[case::incremental_lists("new_line", CommentCommand::NewLine)]
     #[case::incremental_lists("newline", CommentCommand::NewLine)]
+    #[case::image_row("image_row", CommentCommand::ImageRow)]
+    #[case::big_text(
+        "big_text: { text: hi, font: compact }",
+        CommentCommand::BigText(BigTextCommand { text: "hi".into(), font: BigTextFont::Compact })
+    )]
     fn command_formatting(#[case] input: &str, #[case] expected: CommentCommand) {
         let parsed: CommentCommand = input.parse().expect("deserialization failed");
         assert_eq!(parsed, expected);
@@ -508,6 +551,26 @@ hi
         assert_eq!(lines, expected);
     }
 
+    #[test]
+    fn skip_slides_front_matter() {
+        let input = "---
+skip_slides: [1, 3]
+---
+one
+<!-- end_slide -->
+two
+<!-- skip_slide -->
+<!-- end_slide -->
+three
+<!-- end_slide -->
+four
+";
+        let presentation = Test::new(input).build();
+        assert_eq!(presentation.iter_slides().count(), 1);
+        let lines = Test::new(input).render().rows(2).columns(4).into_lines();
+        assert_eq!(lines, &["    ", "four"]);
+    }
+
     #[test]
     fn skip_slide_pauses() {
         let input = "
@@ -539,6 +602,19 @@ hi
         assert_eq!(lines, expected);
     }
 
+    #[rstest]
+    #[case::block(11, &["           ", " █   █ ███ ", " █   █  █  ", " █████  █  ", " █   █  █  ", " █   █ ███ ", "next       "])]
+    #[case::compact(7, &["       ", " ▌ ▌▜▘ ", " ▛▀▌▐  ", " ▘ ▘▀▘ ", "next   ", "       ", "       "])]
+    #[case::plain(4, &["    ", " hi ", "next", "    ", "    ", "    ", "    "])]
+    fn big_text(#[case] columns: u16, #[case] expected: &[&str]) {
+        let input = "
+<!-- big_text: { text: hi } -->
+next
+";
+        let lines = Test::new(input).render().rows(7).columns(columns).into_lines();
+        assert_eq!(lines, expected);
+    }
+
     #[test]
     fn speaker_notes() {
         let input = "
@@ -552,6 +628,34 @@ hi
         assert_eq!(lines, expected);
     }
 
+    #[rstest]
+    #[case::presentation(false, &["   ", "hi ", "   ", "bye"])]
+    #[case::speaker_notes(true, &["   ", "hi ", "foo", "   "])]
+    fn speaker_only(#[case] render_speaker_notes_only: bool, #[case] expected: &[&str]) {
+        let input = "
+hi
+
+<!-- speaker_note: hi -->
+<!-- speaker_only -->
+
+foo
+
+<!-- end_speaker_only -->
+
+bye
+";
+        let options = PresentationBuilderOptions { render_speaker_notes_only, ..Default::default() };
+        let lines = Test::new(input).options(options).render().rows(4).columns(3).into_lines();
+        assert_eq!(lines, expected);
+    }
+
+    #[rstest]
+    #[case::nested("<!-- speaker_only -->\n<!-- speaker_only -->")]
+    #[case::not_started("<!-- end_speaker_only -->")]
+    fn invalid_speaker_only(#[case] input: &str) {
+        Test::new(input).expect_invalid();
+    }
+
     #[test]
     fn alignment() {
         let input = "
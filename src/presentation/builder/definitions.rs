@@ -0,0 +1,90 @@
+use crate::{
+    markdown::{
+        elements::{Definition, Text},
+        text_style::TextStyle,
+    },
+    presentation::builder::{BuildResult, PresentationBuilder},
+    render::operation::{BlockLine, RenderOperation},
+    theme::ElementType,
+};
+
+impl PresentationBuilder<'_, '_> {
+    pub(crate) fn push_definition_list(&mut self, definitions: Vec<Definition>) -> BuildResult {
+        let font_size = self.slide_font_size();
+        let prefix = " ".repeat(4_u8.div_ceil(font_size) as usize);
+        let block_length = definitions
+            .iter()
+            .flat_map(|definition| &definition.definitions)
+            .map(|line| prefix.len() + line.width())
+            .max()
+            .unwrap_or_default() as u16;
+        let block_length = block_length * font_size as u16;
+        let alignment = self.slide_state.alignment.unwrap_or_else(|| self.theme.alignment(&ElementType::Paragraph));
+        for (index, definition) in definitions.into_iter().enumerate() {
+            if index > 0 {
+                self.push_line_breaks(font_size as usize);
+            }
+            let mut term = definition.term.resolve(&self.theme.palette)?;
+            for piece in &mut term.0 {
+                piece.style = piece.style.bold();
+            }
+            self.push_text(term, ElementType::Paragraph);
+            self.push_line_breaks(font_size as usize);
+
+            for line in definition.definitions {
+                let mut text = line.resolve(&self.theme.palette)?;
+                for piece in &mut text.0 {
+                    if piece.style.is_code() {
+                        piece.style.colors = self.theme.inline_code.style.colors;
+                    }
+                    piece.style = piece.style.size(font_size);
+                }
+                self.chunk_operations.push(RenderOperation::RenderBlockLine(BlockLine {
+                    prefix: Text::new(prefix.clone(), TextStyle::default().size(font_size)).into(),
+                    prefix_link: None,
+                    right_padding_length: 0,
+                    repeat_prefix_on_wrap: true,
+                    text: text.into(),
+                    block_length,
+                    alignment,
+                    block_color: None,
+                    border: None,
+                    overflow: Default::default(),
+                }));
+                self.push_line_breaks(font_size as usize);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::presentation::builder::utils::Test;
+
+    #[test]
+    fn definition_list() {
+        let input = "
+Term
+: first
+
+: second
+";
+        let lines = Test::new(input).render().rows(5).columns(16).into_lines();
+        let expected =
+            &["                ", "Term            ", "    first       ", "    second      ", "                "];
+        assert_eq!(lines, expected);
+    }
+
+    #[test]
+    fn wrapped_definition() {
+        let input = "
+Term
+: one two three
+";
+        let lines = Test::new(input).render().rows(6).columns(12).into_lines();
+        let expected =
+            &["            ", "Term        ", "    one two ", "    three   ", "            ", "            "];
+        assert_eq!(lines, expected);
+    }
+}
@@ -24,8 +24,8 @@ pub(crate) enum BuildError {
     #[error("invalid theme: {0}")]
     InvalidTheme(#[from] LoadThemeError),
 
-    #[error("invalid code highlighter theme: '{0}'")]
-    InvalidCodeTheme(String),
+    #[error("invalid code highlighter theme '{0}', available themes are: {1}")]
+    InvalidCodeTheme(String, String),
 
     #[error("third party render failed: {0}")]
     ThirdPartyRender(#[from] ThirdPartyRenderError),
@@ -69,6 +69,9 @@ pub(crate) enum InvalidPresentation {
     #[error("invalid snippet: {0}")]
     Snippet(String),
 
+    #[error("invalid snippet defaults: {0}")]
+    SnippetDefaults(String),
+
     #[error("invalid command: {0}")]
     CommandParse(#[from] CommandParseError),
 
@@ -90,6 +93,12 @@ pub(crate) enum InvalidPresentation {
     #[error("can't enter layout column: already in it")]
     AlreadyInColumn,
 
+    #[error("speaker only content already started")]
+    AlreadySpeakerOnly,
+
+    #[error("not inside speaker only content")]
+    NotSpeakerOnly,
+
     #[error("can't enter layout column: column index too large")]
     ColumnIndexTooLarge,
 
@@ -105,8 +114,17 @@ pub(crate) enum InvalidPresentation {
     #[error("snippet identifiers can only be used in +exec blocks")]
     SnippetIdNonExec,
 
+    #[error("snippet output can only be piped into +exec blocks")]
+    SnippetPipeNonExec,
+
     #[error("snippet id '{0}' already exists")]
     SnippetAlreadyExists(String),
+
+    #[error("invalid snippet highlighter theme '{name}', available themes are: {available}")]
+    SnippetTheme { name: String, available: String },
+
+    #[error("image alias '{0}' not defined")]
+    UndefinedImageAlias(String),
 }
 
 #[derive(Debug)]
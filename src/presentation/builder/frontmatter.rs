@@ -1,4 +1,5 @@
 use crate::{
+    code::execute::ExecutionHooks,
     config::OptionsConfig,
     presentation::{
         PresentationMetadata, PresentationThemeMetadata,
@@ -6,6 +7,7 @@ use crate::{
     },
     theme::PresentationTheme,
 };
+use std::{collections::HashMap, mem, path::PathBuf};
 
 impl PresentationBuilder<'_, '_> {
     pub(crate) fn process_front_matter(&mut self, contents: &str) -> BuildResult {
@@ -21,6 +23,10 @@ impl PresentationBuilder<'_, '_> {
         if let Some(options) = metadata.options.take() {
             self.options.merge(options);
         }
+        self.image_aliases = mem::take(&mut metadata.images);
+        self.skipped_slides = metadata.skip_slides.iter().copied().collect();
+        self.execution_hooks =
+            ExecutionHooks { pre: mem::take(&mut metadata.pre_exec), post: mem::take(&mut metadata.post_exec) };
 
         {
             let footer_context = &mut self.footer_vars;
@@ -115,12 +121,51 @@ struct StrictPresentationMetadata {
 
     #[serde(default)]
     options: Option<OptionsConfig>,
+
+    #[serde(default)]
+    images: HashMap<String, PathBuf>,
+
+    #[serde(default)]
+    skip_slides: Vec<usize>,
+
+    #[serde(default)]
+    pre_exec: Vec<String>,
+
+    #[serde(default)]
+    post_exec: Vec<String>,
 }
 
 impl From<StrictPresentationMetadata> for PresentationMetadata {
     fn from(strict: StrictPresentationMetadata) -> Self {
-        let StrictPresentationMetadata { title, sub_title, event, location, date, author, authors, theme, options } =
-            strict;
-        Self { title, sub_title, event, location, date, author, authors, theme, options }
+        let StrictPresentationMetadata {
+            title,
+            sub_title,
+            event,
+            location,
+            date,
+            author,
+            authors,
+            theme,
+            options,
+            images,
+            skip_slides,
+            pre_exec,
+            post_exec,
+        } = strict;
+        Self {
+            title,
+            sub_title,
+            event,
+            location,
+            date,
+            author,
+            authors,
+            theme,
+            options,
+            images,
+            skip_slides,
+            pre_exec,
+            post_exec,
+        }
     }
 }
@@ -30,8 +30,11 @@ impl PresentationBuilder<'_, '_> {
             self.push_line_break();
         }
         if style.separator {
-            self.chunk_operations
-                .push(RenderSeparator::new(Line::default(), Default::default(), style.style.size).into());
+            self.chunk_operations.push(
+                RenderSeparator::new(Line::default(), Default::default(), style.style.size)
+                    .style(style.separator_style)
+                    .into(),
+            );
             self.push_line_break();
         }
         self.push_line_break();
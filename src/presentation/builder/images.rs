@@ -1,13 +1,14 @@
 use crate::{
-    markdown::elements::{Percent, PercentParseError, SourcePosition},
+    markdown::elements::{InlineImage, Line, Percent, PercentParseError, SourcePosition, Text},
     presentation::builder::{
-        BuildResult, PresentationBuilder,
+        BuildResult, LayoutState, PresentationBuilder,
         error::{BuildError, InvalidPresentation},
     },
     render::operation::{ImageRenderProperties, ImageSize, RenderOperation},
-    terminal::image::Image,
+    terminal::image::{Image, printer::ImageProperties},
+    ui::{floating_image::FloatingImage, image_row::ImageRow},
 };
-use std::path::PathBuf;
+use std::{path::PathBuf, rc::Rc};
 
 impl PresentationBuilder<'_, '_> {
     pub(crate) fn push_image_from_path(
@@ -16,6 +17,7 @@ impl PresentationBuilder<'_, '_> {
         title: String,
         source_position: SourcePosition,
     ) -> BuildResult {
+        let path = self.resolve_image_alias(path, source_position)?;
         let base_path = self.resource_base_path();
         let image = self.resources.image(&path, &base_path).map_err(|e| {
             self.invalid_presentation(source_position, InvalidPresentation::LoadImage { path, error: e.to_string() })
@@ -29,15 +31,102 @@ impl PresentationBuilder<'_, '_> {
             Some(percent) => ImageSize::WidthScaled { ratio: percent.as_ratio() },
             None => ImageSize::ShrinkIfNeeded,
         };
+        let caption = self.theme.image.caption.as_ref().and_then(|style| {
+            let caption = Self::strip_image_attributes(&title, &self.options.image_attribute_prefix);
+            if caption.is_empty() {
+                return None;
+            }
+            let style = style.style.size(self.slide_font_size());
+            Some(Line::from(Text::new(caption, style)).into())
+        });
         let properties = ImageRenderProperties {
             size,
             background_color: self.theme.default_style.style.colors.background,
+            shadow: self.theme.image.shadow,
+            caption,
             ..Default::default()
         };
-        self.chunk_operations.push(RenderOperation::RenderImage(image, properties));
+        // Floating images can't be placed in a column as they use a column layout themselves.
+        if attributes.float && matches!(self.slide_state.layout, LayoutState::Default) {
+            self.slide_state.floating_image = Some((image, properties));
+            // The image is rendered along with the paragraph that follows it.
+            self.slide_state.ignore_element_line_break = true;
+        } else {
+            let spacing = self.theme.image.spacing;
+            self.push_line_breaks(spacing.above as usize);
+            self.chunk_operations.push(RenderOperation::RenderImage(image, properties));
+            // The line break pushed after every element counts as the first line below the image.
+            match spacing.below {
+                0 => self.slide_state.ignore_element_line_break = true,
+                below => self.push_line_breaks(below as usize - 1),
+            };
+        }
+        Ok(())
+    }
+
+    pub(crate) fn push_floating_image(&mut self, text: Vec<RenderOperation>) {
+        let Some((image, properties)) = self.slide_state.floating_image.take() else {
+            self.chunk_operations.extend(text);
+            return;
+        };
+        let image = FloatingImage::new(image, properties, text);
+        self.chunk_operations.push(RenderOperation::RenderDynamic(Rc::new(image)));
+    }
+
+    // A floating image that isn't followed by a paragraph is displayed like any other image.
+    pub(crate) fn flush_floating_image(&mut self) {
+        if let Some((image, properties)) = self.slide_state.floating_image.take() {
+            self.chunk_operations.push(RenderOperation::RenderImage(image, properties));
+            self.push_line_break();
+        }
+    }
+
+    pub(crate) fn push_image_row_element(&mut self, path: PathBuf, source_position: SourcePosition) -> BuildResult {
+        let path = self.resolve_image_alias(path, source_position)?;
+        let base_path = self.resource_base_path();
+        let image = self.resources.image(&path, &base_path).map_err(|e| {
+            self.invalid_presentation(source_position, InvalidPresentation::LoadImage { path, error: e.to_string() })
+        })?;
+        self.slide_state.image_row.get_or_insert_default().push(image);
+        // Images in a row are rendered all at once when the row ends.
+        self.slide_state.ignore_element_line_break = true;
         Ok(())
     }
 
+    // Images within a line of text are one row tall, and terminal cells are roughly twice as tall
+    // as they are wide, so this returns the image along with the number of columns it takes up.
+    pub(crate) fn load_inline_image(&self, image: InlineImage) -> Result<(Image, u16), BuildError> {
+        let InlineImage { path, source_position, .. } = image;
+        let path = self.resolve_image_alias(path, source_position)?;
+        let base_path = self.resource_base_path();
+        let image = self.resources.image(&path, &base_path).map_err(|e| {
+            self.invalid_presentation(source_position, InvalidPresentation::LoadImage { path, error: e.to_string() })
+        })?;
+        let (width, height) = image.image().dimensions();
+        let columns = (width as f64 / height.max(1) as f64 * 2.0).round().max(1.0) as u16;
+        Ok((image, columns))
+    }
+
+    // Paths like `@logo` refer to an image alias defined in the front matter.
+    fn resolve_image_alias(&self, path: PathBuf, source_position: SourcePosition) -> Result<PathBuf, BuildError> {
+        let Some(alias) = path.to_str().and_then(|path| path.strip_prefix('@')) else {
+            return Ok(path);
+        };
+        self.image_aliases.get(alias).cloned().ok_or_else(|| {
+            self.invalid_presentation(source_position, InvalidPresentation::UndefinedImageAlias(alias.to_string()))
+        })
+    }
+
+    pub(crate) fn push_image_row(&mut self) {
+        let images = self.slide_state.image_row.take().unwrap_or_default();
+        if images.is_empty() {
+            return;
+        }
+        let row = ImageRow::new(images, self.theme.default_style.style.colors.background);
+        self.chunk_operations.push(RenderOperation::RenderDynamic(Rc::new(row)));
+        self.push_line_break();
+    }
+
     fn parse_image_attributes(
         &self,
         input: &str,
@@ -46,16 +135,24 @@ impl PresentationBuilder<'_, '_> {
     ) -> Result<ImageAttributes, BuildError> {
         let mut attributes = ImageAttributes::default();
         for attribute in input.split(',') {
-            let Some((prefix, suffix)) = attribute.split_once(attribute_prefix) else { continue };
-            if !prefix.is_empty() || (attribute_prefix.is_empty() && suffix.is_empty()) {
-                continue;
-            }
-            Self::parse_image_attribute(suffix, &mut attributes)
+            let Some(attribute) = Self::as_image_attribute(attribute, attribute_prefix) else { continue };
+            Self::parse_image_attribute(attribute, &mut attributes)
                 .map_err(|e| self.invalid_presentation(source_position, e))?;
         }
         Ok(attributes)
     }
 
+    fn strip_image_attributes(input: &str, attribute_prefix: &str) -> String {
+        let text: Vec<_> =
+            input.split(',').filter(|text| Self::as_image_attribute(text, attribute_prefix).is_none()).collect();
+        text.join(",").trim().to_string()
+    }
+
+    fn as_image_attribute<'a>(input: &'a str, attribute_prefix: &str) -> Option<&'a str> {
+        let (prefix, suffix) = input.split_once(attribute_prefix)?;
+        if !prefix.is_empty() || (attribute_prefix.is_empty() && suffix.is_empty()) { None } else { Some(suffix) }
+    }
+
     fn parse_image_attribute(input: &str, attributes: &mut ImageAttributes) -> Result<(), ImageAttributeError> {
         let Some((key, value)) = input.split_once(':') else {
             return Err(ImageAttributeError::AttributeMissing);
@@ -66,6 +163,13 @@ impl PresentationBuilder<'_, '_> {
                 attributes.width = Some(width);
                 Ok(())
             }
+            "float" => match value {
+                "right" => {
+                    attributes.float = true;
+                    Ok(())
+                }
+                _ => Err(ImageAttributeError::InvalidFloat(value.to_string())),
+            },
             _ => Err(ImageAttributeError::UnknownAttribute(key.to_string())),
         }
     }
@@ -76,6 +180,9 @@ pub(crate) enum ImageAttributeError {
     #[error("invalid width: {0}")]
     InvalidWidth(PercentParseError),
 
+    #[error("invalid float: '{0}', only 'right' is supported")]
+    InvalidFloat(String),
+
     #[error("no attribute given")]
     AttributeMissing,
 
@@ -86,20 +193,28 @@ pub(crate) enum ImageAttributeError {
 #[derive(Clone, Debug, Default, PartialEq)]
 struct ImageAttributes {
     width: Option<Percent>,
+    float: bool,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::presentation::builder::utils::Test;
+    use crate::{
+        presentation::builder::utils::Test,
+        render::{operation::ImagePosition, properties::WindowSize},
+        theme::raw,
+    };
+    use image::{DynamicImage, ImageEncoder, codecs::png::PngEncoder};
     use rstest::rstest;
+    use std::{fs, io::BufWriter, path::Path};
 
     #[rstest]
-    #[case::width("image:width:50%", Some(50))]
-    #[case::w("image:w:50%", Some(50))]
+    #[case::width("image:width:50%", Some(50.0))]
+    #[case::w("image:w:50%", Some(50.0))]
+    #[case::fractional("image:width:33.3%", Some(33.3))]
     #[case::nothing("", None)]
     #[case::no_prefix("width", None)]
-    fn image_attributes(#[case] input: &str, #[case] expectation: Option<u8>) {
+    fn image_attributes(#[case] input: &str, #[case] expectation: Option<f64>) {
         let attributes = Test::new("").with_builder(|builder| {
             builder.parse_image_attributes(input, "image:", Default::default()).expect("failed to parse")
         });
@@ -107,12 +222,154 @@ mod tests {
     }
 
     #[rstest]
-    #[case::width("width:50%", Some(50))]
+    #[case::width("width:50%", Some(50.0))]
     #[case::empty("", None)]
-    fn image_attributes_empty_prefix(#[case] input: &str, #[case] expectation: Option<u8>) {
+    fn image_attributes_empty_prefix(#[case] input: &str, #[case] expectation: Option<f64>) {
         let attributes = Test::new("").with_builder(|builder| {
             builder.parse_image_attributes(input, "", Default::default()).expect("failed to parse")
         });
         assert_eq!(attributes.width, expectation.map(Percent));
     }
+
+    #[rstest]
+    #[case::plain("a system diagram", "a system diagram")]
+    #[case::attributes("a system diagram,image:width:50%", "a system diagram")]
+    #[case::commas("one, two,image:w:10%, three", "one, two, three")]
+    #[case::only_attributes("image:width:50%", "")]
+    fn strip_image_attributes(#[case] input: &str, #[case] expected: &str) {
+        let caption = PresentationBuilder::strip_image_attributes(input, "image:");
+        assert_eq!(caption, expected);
+    }
+
+    fn write_image(path: &Path) {
+        let image = DynamicImage::new_rgba8(1, 1);
+        let mut buffer = BufWriter::new(fs::File::create(path).expect("failed to write image"));
+        PngEncoder::new(&mut buffer)
+            .write_image(image.as_bytes(), 1, 1, image.color().into())
+            .expect("failed to create image");
+    }
+
+    #[rstest]
+    #[case::stacked("", &[ImagePosition::Center, ImagePosition::Center])]
+    #[case::row("<!-- image_row -->", &[ImagePosition::Column(8), ImagePosition::Column(11)])]
+    fn image_row(#[case] prefix: &str, #[case] expected: &[ImagePosition]) {
+        let directory = tempfile::tempdir().expect("failed to create tempdir");
+        write_image(&directory.path().join("a.png"));
+        write_image(&directory.path().join("b.png"));
+        let input = format!("{prefix}\n![](a.png) ![](b.png)\n\nhi");
+        let presentation = Test::new(input).resources_path(directory.path()).build();
+        let slide = presentation.iter_slides().next().expect("no slides");
+        // 1 pixel per column/row so every image takes up a single column
+        let dimensions = WindowSize { rows: 10, columns: 20, width: 20, height: 10 };
+        let positions: Vec<_> = slide
+            .iter_operations()
+            .flat_map(|operation| match operation {
+                RenderOperation::RenderDynamic(generator) => generator.as_render_operations(&dimensions),
+                other => vec![other.clone()],
+            })
+            .filter_map(|operation| match operation {
+                RenderOperation::RenderImage(_, properties) => Some(properties.position),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(positions, expected);
+    }
+
+    #[rstest]
+    #[case::enabled(Some(true), Some("diagram"))]
+    #[case::disabled(None, None)]
+    fn image_caption(#[case] enabled: Option<bool>, #[case] expected: Option<&str>) {
+        let directory = tempfile::tempdir().expect("failed to create tempdir");
+        write_image(&directory.path().join("a.png"));
+        let mut theme = raw::PresentationTheme::default();
+        theme.image.caption.enabled = enabled;
+        let input = "![diagram,image:width:50%](a.png)";
+        let presentation = Test::new(input).theme(theme).resources_path(directory.path()).build();
+        let slide = presentation.iter_slides().next().expect("no slides");
+        let caption = slide.iter_operations().find_map(|operation| match operation {
+            RenderOperation::RenderImage(_, properties) => Some(properties.caption.clone()),
+            _ => None,
+        });
+        let expected = expected.map(|caption| Line::from(caption).into());
+        assert_eq!(caption, Some(expected));
+    }
+
+    #[rstest]
+    #[case::default(None, None, (0, 1))]
+    #[case::looser(Some(2), Some(3), (2, 3))]
+    #[case::tighter(None, Some(0), (0, 0))]
+    fn image_spacing(#[case] above: Option<u8>, #[case] below: Option<u8>, #[case] expected: (usize, usize)) {
+        let directory = tempfile::tempdir().expect("failed to create tempdir");
+        write_image(&directory.path().join("a.png"));
+        let mut theme = raw::PresentationTheme::default();
+        theme.image.spacing.above = above;
+        theme.image.spacing.below = below;
+        let input = "<!-- pause -->\n![](a.png)\n<!-- pause -->";
+        let presentation = Test::new(input).theme(theme).resources_path(directory.path()).build();
+        let slide = presentation.iter_slides().next().expect("no slides");
+        let operations: Vec<_> = slide.iter_chunks().nth(1).expect("no chunk").iter_operations().collect();
+        let is_break = |operation: &&&RenderOperation| matches!(operation, RenderOperation::RenderLineBreak);
+        let image = operations.iter().position(|operation| matches!(operation, RenderOperation::RenderImage(..)));
+        let image = image.expect("no image");
+        let above = operations[..image].iter().rev().take_while(is_break).count();
+        let below = operations[image + 1..].iter().take_while(is_break).count();
+        assert_eq!((above, below), expected);
+    }
+
+    #[rstest]
+    #[case::list("* a ![](a.png) b", &["   •  a    b"])]
+    #[case::table("| A | B |\n|---|---|\n| x | ![](a.png) |", &["A │ B", "──┼───", "x │"])]
+    fn inline_image(#[case] input: &str, #[case] expected: &[&str]) {
+        let directory = tempfile::tempdir().expect("failed to create tempdir");
+        write_image(&directory.path().join("a.png"));
+        let presentation = Test::new(input).resources_path(directory.path()).build();
+        let slide = presentation.iter_slides().next().expect("no slides");
+        assert!(slide.iter_operations().any(|operation| matches!(operation, RenderOperation::RenderDynamic(..))));
+        // The image is drawn over the blank space reserved for it.
+        let lines = Test::new(input).resources_path(directory.path()).render().rows(5).columns(16).into_lines();
+        let lines: Vec<_> = lines[1..expected.len() + 1].iter().map(|line| line.trim_end()).collect();
+        assert_eq!(lines, expected);
+    }
+
+    #[test]
+    fn image_alias() {
+        let directory = tempfile::tempdir().expect("failed to create tempdir");
+        write_image(&directory.path().join("logo.png"));
+        let input = "---\nimages:\n  logo: logo.png\n---\n![](@logo)\n\n![](@logo)";
+        let presentation = Test::new(input).resources_path(directory.path()).build();
+        let slide = presentation.iter_slides().next().expect("no slides");
+        let images =
+            slide.iter_operations().filter(|operation| matches!(operation, RenderOperation::RenderImage(..))).count();
+        assert_eq!(images, 2);
+    }
+
+    #[test]
+    fn undefined_image_alias() {
+        let input = "---\nimages:\n  logo: logo.png\n---\n![](@potato)";
+        let error = Test::new(input).expect_invalid();
+        assert!(error.to_string().contains("image alias 'potato' not defined"), "unexpected error: {error}");
+    }
+
+    #[rstest]
+    #[case::paragraph("hi", 0)]
+    #[case::heading("# hi", 1)]
+    #[case::end_of_slide("", 1)]
+    fn floating_image(#[case] next: &str, #[case] expected_images: usize) {
+        let directory = tempfile::tempdir().expect("failed to create tempdir");
+        write_image(&directory.path().join("a.png"));
+        let input = format!("![image:float:right](a.png)\n\n{next}");
+        let presentation = Test::new(input).resources_path(directory.path()).build();
+        let slide = presentation.iter_slides().next().expect("no slides");
+        let images =
+            slide.iter_operations().filter(|operation| matches!(operation, RenderOperation::RenderImage(..))).count();
+        assert_eq!(images, expected_images);
+    }
+
+    #[test]
+    fn invalid_float() {
+        let error = Test::new("").with_builder(|builder| {
+            builder.parse_image_attributes("image:float:left", "image:", Default::default()).unwrap_err()
+        });
+        assert!(error.to_string().contains("invalid float"), "unexpected error: {error}");
+    }
 }
@@ -1,14 +1,16 @@
 use crate::{
     markdown::{
-        elements::{ListItem, ListItemType, Text},
+        elements::{Line, ListItem, ListItemType, TaskState, Text},
         text_style::TextStyle,
     },
-    presentation::builder::{BuildResult, LastElement, PresentationBuilder},
+    presentation::builder::{BuildResult, LastElement, PresentationBuilder, error::BuildError},
     render::operation::{BlockLine, RenderOperation},
+    ui::inline_image::{EmbeddedImage, InlineImageLine},
 };
+use std::rc::Rc;
 
 impl<'a, 'b> PresentationBuilder<'a, 'b> {
-    pub(crate) fn push_list(&mut self, list: Vec<ListItem>) -> BuildResult {
+    pub(crate) fn push_list(&mut self, mut list: Vec<ListItem>) -> BuildResult {
         let last_chunk_operation = self.slide_chunks.last().and_then(|chunk| chunk.iter_operations().last());
         // If the last chunk ended in a list, pop the newline so we get them all next to each
         // other.
@@ -24,19 +26,29 @@ impl<'a, 'b> PresentationBuilder<'a, 'b> {
             _ => 0,
         };
 
-        let block_length =
-            list.iter().map(|l| self.list_item_prefix(l).width() + l.contents.width()).max().unwrap_or_default() as u16;
+        let mut images = Vec::new();
+        for item in &mut list {
+            images.push(self.embed_list_item_image(item)?);
+        }
+        let block_length = list
+            .iter()
+            .map(|l| {
+                let task_width = self.task_prefix(l).map(|text| text.width()).unwrap_or_default();
+                self.list_item_prefix(l).width() + task_width + l.contents.width()
+            })
+            .max()
+            .unwrap_or_default() as u16;
         let block_length = block_length * self.slide_font_size() as u16;
         let incremental_lists = self.slide_state.incremental_lists.unwrap_or(self.options.incremental_lists);
         let iter = ListIterator::new(list, start_index);
         if incremental_lists && self.options.pause_before_incremental_lists {
             self.push_pause();
         }
-        for (index, item) in iter.enumerate() {
+        for (index, (item, image)) in iter.zip(images).enumerate() {
             if index > 0 && incremental_lists {
                 self.push_pause();
             }
-            self.push_list_item(item.index, item.item, block_length)?;
+            self.push_list_item(item.index, item.item, image, block_length)?;
         }
         if incremental_lists && self.options.pause_after_incremental_lists {
             self.push_pause();
@@ -44,8 +56,29 @@ impl<'a, 'b> PresentationBuilder<'a, 'b> {
         Ok(())
     }
 
-    fn push_list_item(&mut self, index: usize, item: ListItem, block_length: u16) -> BuildResult {
+    // Reserves space for the image within a list item, if there's one.
+    fn embed_list_item_image(&self, item: &mut ListItem) -> Result<Option<EmbeddedImage>, BuildError> {
+        let Some(image) = item.image.take() else {
+            return Ok(None);
+        };
+        let position = image.position.min(item.contents.0.len());
+        let label = image.path.display().to_string();
+        let (image, columns) = self.load_inline_image(image)?;
+        let column = item.contents.0[..position].iter().map(Text::width).sum::<usize>() as u16;
+        item.contents.0.insert(position, Text::from(" ".repeat(columns as usize)));
+        Ok(Some(EmbeddedImage { image, column, columns, label }))
+    }
+
+    fn push_list_item(
+        &mut self,
+        index: usize,
+        item: ListItem,
+        image: Option<EmbeddedImage>,
+        block_length: u16,
+    ) -> BuildResult {
         let prefix = self.list_item_prefix(&item);
+        let task_prefix = self.task_prefix(&item);
+        let text_offset = prefix.width() + task_prefix.as_ref().map(Line::width).unwrap_or_default();
         let mut text = item.contents.resolve(&self.theme.palette)?;
         let font_size = self.slide_font_size();
         for piece in &mut text.0 {
@@ -54,16 +87,46 @@ impl<'a, 'b> PresentationBuilder<'a, 'b> {
             }
             piece.style = piece.style.size(font_size);
         }
+        // The image's label is shown in its place if it can't be displayed.
+        let mut fallback = image.as_ref().map(|image| EmbeddedImage::label_line(&text, std::slice::from_ref(image)));
+        if let Some(task_prefix) = task_prefix {
+            text.0.splice(0..0, task_prefix.0.clone());
+            if let Some(fallback) = &mut fallback {
+                fallback.0.splice(0..0, task_prefix.0);
+            }
+        }
         let alignment = self.slide_state.alignment.unwrap_or_default();
-        self.chunk_operations.push(RenderOperation::RenderBlockLine(BlockLine {
-            prefix: prefix.into(),
-            right_padding_length: 0,
-            repeat_prefix_on_wrap: false,
-            text: text.into(),
-            block_length,
-            alignment,
-            block_color: None,
-        }));
+        let make_operation = |text: Line| {
+            RenderOperation::RenderBlockLine(BlockLine {
+                prefix: prefix.clone().into(),
+                prefix_link: None,
+                right_padding_length: 0,
+                repeat_prefix_on_wrap: false,
+                text: text.into(),
+                block_length,
+                alignment,
+                block_color: None,
+                border: None,
+                overflow: Default::default(),
+            })
+        };
+        match (image, fallback) {
+            (Some(mut image), Some(fallback)) => {
+                image.column += text_offset as u16;
+                let background = self.theme.default_style.style.colors.background;
+                let line = InlineImageLine::new(
+                    make_operation(text),
+                    make_operation(fallback),
+                    vec![image],
+                    alignment,
+                    block_length,
+                    font_size,
+                    background,
+                );
+                self.chunk_operations.push(RenderOperation::RenderDynamic(Rc::new(line)));
+            }
+            _ => self.chunk_operations.push(make_operation(text)),
+        };
         let newlines = self.slide_state.list_item_newlines.unwrap_or(self.options.list_item_newlines);
         self.push_line_breaks(newlines as usize);
         if item.depth == 0 {
@@ -87,26 +150,38 @@ impl<'a, 'b> PresentationBuilder<'a, 'b> {
         let padding_length = (item.depth as usize + 1) * spaces_per_indent as usize;
         let mut prefix: String = " ".repeat(padding_length);
         match item.item_type {
+            // Task items use their glyph instead of a bullet.
+            ListItemType::Unordered if item.task.is_some() => (),
             ListItemType::Unordered => {
-                let delimiter = match item.depth {
-                    0 => '•',
-                    1 => '◦',
-                    _ => '▪',
-                };
-                prefix.push(delimiter);
+                prefix.push_str(self.theme.list.bullet(item.depth));
                 prefix.push_str("  ");
             }
             ListItemType::OrderedParens(value) => {
-                prefix.push_str(&value.to_string());
+                prefix.push_str(&self.theme.list.numbering(item.depth).format(value));
                 prefix.push_str(") ");
             }
             ListItemType::OrderedPeriod(value) => {
-                prefix.push_str(&value.to_string());
+                prefix.push_str(&self.theme.list.numbering(item.depth).format(value));
                 prefix.push_str(". ");
             }
         };
         Text::new(prefix, TextStyle::default().size(font_size))
     }
+
+    fn task_prefix(&self, item: &ListItem) -> Option<Line> {
+        let style = match item.task? {
+            TaskState::Checked => &self.theme.task_list.checked,
+            TaskState::Unchecked => &self.theme.task_list.unchecked,
+        };
+        // Use the same spacing as bullets if the glyph replaces them.
+        let separator = match item.item_type {
+            ListItemType::Unordered => "  ",
+            ListItemType::OrderedParens(_) | ListItemType::OrderedPeriod(_) => " ",
+        };
+        let font_size = self.slide_font_size();
+        let glyph = Text::new(style.glyph.clone(), style.style.size(font_size));
+        Some(Line(vec![glyph, Text::new(separator, TextStyle::default().size(font_size))]))
+    }
 }
 
 struct ListIterator<I> {
@@ -163,7 +238,13 @@ struct IndexedListItem {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::presentation::builder::{PresentationBuilderOptions, utils::Test};
+    use crate::{
+        presentation::{
+            Presentation,
+            builder::{PresentationBuilderOptions, utils::Test},
+        },
+        theme::raw,
+    };
     use rstest::rstest;
     use std::iter;
 
@@ -171,13 +252,55 @@ mod tests {
     fn iterate_list() {
         let iter = ListIterator::new(
             vec![
-                ListItem { depth: 0, contents: "0".into(), item_type: ListItemType::Unordered },
-                ListItem { depth: 0, contents: "1".into(), item_type: ListItemType::Unordered },
-                ListItem { depth: 1, contents: "00".into(), item_type: ListItemType::Unordered },
-                ListItem { depth: 1, contents: "01".into(), item_type: ListItemType::Unordered },
-                ListItem { depth: 1, contents: "02".into(), item_type: ListItemType::Unordered },
-                ListItem { depth: 2, contents: "001".into(), item_type: ListItemType::Unordered },
-                ListItem { depth: 0, contents: "2".into(), item_type: ListItemType::Unordered },
+                ListItem {
+                    depth: 0,
+                    contents: "0".into(),
+                    item_type: ListItemType::Unordered,
+                    task: None,
+                    image: None,
+                },
+                ListItem {
+                    depth: 0,
+                    contents: "1".into(),
+                    item_type: ListItemType::Unordered,
+                    task: None,
+                    image: None,
+                },
+                ListItem {
+                    depth: 1,
+                    contents: "00".into(),
+                    item_type: ListItemType::Unordered,
+                    task: None,
+                    image: None,
+                },
+                ListItem {
+                    depth: 1,
+                    contents: "01".into(),
+                    item_type: ListItemType::Unordered,
+                    task: None,
+                    image: None,
+                },
+                ListItem {
+                    depth: 1,
+                    contents: "02".into(),
+                    item_type: ListItemType::Unordered,
+                    task: None,
+                    image: None,
+                },
+                ListItem {
+                    depth: 2,
+                    contents: "001".into(),
+                    item_type: ListItemType::Unordered,
+                    task: None,
+                    image: None,
+                },
+                ListItem {
+                    depth: 0,
+                    contents: "2".into(),
+                    item_type: ListItemType::Unordered,
+                    task: None,
+                    image: None,
+                },
             ],
             0,
         );
@@ -190,8 +313,20 @@ mod tests {
     fn iterate_list_starting_from_other() {
         let list = ListIterator::new(
             vec![
-                ListItem { depth: 0, contents: "0".into(), item_type: ListItemType::Unordered },
-                ListItem { depth: 0, contents: "1".into(), item_type: ListItemType::Unordered },
+                ListItem {
+                    depth: 0,
+                    contents: "0".into(),
+                    item_type: ListItemType::Unordered,
+                    task: None,
+                    image: None,
+                },
+                ListItem {
+                    depth: 0,
+                    contents: "1".into(),
+                    item_type: ListItemType::Unordered,
+                    task: None,
+                    image: None,
+                },
             ],
             3,
         );
@@ -260,6 +395,39 @@ mod tests {
         assert_eq!(lines, expected);
     }
 
+    #[test]
+    fn themed_prefixes() {
+        let input = "
+* A
+    * AA
+        * AAA
+1. B
+    1. BA
+    2. BB
+        1. BBA
+";
+        let theme = raw::PresentationTheme {
+            list: raw::ListStyle {
+                bullets: vec!["-".into(), "+".into()],
+                numbering: vec![raw::ListNumbering::UpperRoman, raw::ListNumbering::LowerAlpha],
+            },
+            ..Default::default()
+        };
+        let lines = Test::new(input).theme(theme).render().rows(9).columns(16).into_lines();
+        let expected = &[
+            "                ",
+            "   -  A         ",
+            "      +  AA     ",
+            "         -  AAA ",
+            "                ",
+            "   I. B         ",
+            "      a. BA     ",
+            "      b. BB     ",
+            "         I. BBA ",
+        ];
+        assert_eq!(lines, expected);
+    }
+
     #[test]
     fn ordered_parens() {
         let input = "
@@ -381,6 +549,32 @@ hi
         assert_eq!(lines, expected);
     }
 
+    #[test]
+    fn incremental_lists_previous() {
+        let input = "
+<!-- incremental_lists: true -->
+* A
+* B
+* C
+";
+        let mut presentation = Test::new(input).build();
+        let visible_items = |presentation: &Presentation| {
+            presentation
+                .current_slide()
+                .iter_visible_operations()
+                .filter(|operation| matches!(operation, RenderOperation::RenderBlockLine(_)))
+                .count()
+        };
+        let mut visible = Vec::new();
+        for _ in 0..3 {
+            presentation.jump_next();
+            visible.push(visible_items(&presentation));
+        }
+        presentation.jump_previous();
+        visible.push(visible_items(&presentation));
+        assert_eq!(visible, &[1, 2, 3, 2]);
+    }
+
     #[test]
     fn incremental_lists_end_of_slide() {
         let input = "
@@ -413,4 +607,17 @@ other
         let expected = &["        ", "   1. A ", "        ", "        "];
         assert_eq!(lines, expected);
     }
+
+    #[test]
+    fn task_list() {
+        let input = "
+* [ ] A
+* [x] B
+* C
+    1. [x] D
+";
+        let lines = Test::new(input).render().rows(5).columns(13).into_lines();
+        let expected = &["             ", "   ☐  A      ", "   ☑  B      ", "   •  C      ", "      1. ☑ D "];
+        assert_eq!(lines, expected);
+    }
 }
@@ -1,10 +1,10 @@
 use crate::{
     code::{
-        execute::SnippetExecutor,
+        execute::{ExecutionHooks, SnippetExecutor},
         highlighting::{HighlightThemeSet, SnippetHighlighter},
         snippet::SnippetLanguage,
     },
-    config::{KeyBindingsConfig, OptionsConfig},
+    config::{KeyBindingsConfig, OptionsConfig, SnippetOutputOverflow},
     markdown::{
         elements::{Line, MarkdownElement, SourcePosition, Text},
         parse::MarkdownParser,
@@ -12,13 +12,14 @@ use crate::{
         text_style::{Color, Colors},
     },
     presentation::{
-        ChunkMutator, Modals, Presentation, PresentationState, RenderOperation, SlideBuilder, SlideChunk,
+        ChunkMutator, Modals, Presentation, PresentationState, RenderOperation, SlideBuilder, SlideChunk, SlideContent,
+        SlideSummary,
         builder::{
             error::{BuildError, InvalidPresentation},
             sources::MarkdownSources,
         },
     },
-    render::operation::MarginProperties,
+    render::operation::{ImageRenderProperties, MarginProperties},
     resource::{ResourceBasePath, Resources},
     terminal::image::{
         Image,
@@ -33,23 +34,28 @@ use crate::{
     ui::{
         execution::output::SnippetHandle,
         footer::{FooterGenerator, FooterVariables},
-        modals::{IndexBuilder, KeyBindingsModalBuilder},
+        modals::{IndexBuilder, KeyBindingsModalBuilder, NotesModalBuilder},
+        prose::RenderProse,
+        quiet_zone::RenderQuietZone,
         separator::RenderSeparator,
     },
 };
 use comrak::Arena;
 use image::DynamicImage;
 use std::{
-    collections::{HashMap, HashSet},
-    fs, io, iter, mem,
-    path::Path,
+    collections::{BTreeSet, HashMap, HashSet},
+    fs,
+    io::{self, Read},
+    iter, mem,
+    path::{Path, PathBuf},
     rc::Rc,
-    sync::Arc,
+    sync::{Arc, Mutex},
 };
 
 pub(crate) mod error;
 
 mod comment;
+mod definitions;
 mod frontmatter;
 mod heading;
 mod images;
@@ -63,8 +69,13 @@ mod table;
 #[cfg(test)]
 mod tests;
 
+pub(crate) use snippet::SnippetCommands;
+
 pub(crate) type BuildResult = Result<(), BuildError>;
 
+/// The path that can be used to read a presentation from stdin.
+pub(crate) const STDIN_PATH: &str = "-";
+
 #[derive(Default)]
 pub struct Themes {
     pub presentation: PresentationThemeRegistry,
@@ -84,6 +95,7 @@ pub struct PresentationBuilderOptions {
     pub strict_front_matter_parsing: bool,
     pub enable_snippet_execution: bool,
     pub enable_snippet_execution_replace: bool,
+    pub allow_trusted_snippets: bool,
     pub render_speaker_notes_only: bool,
     pub auto_render_languages: Vec<SnippetLanguage>,
     pub theme_options: ThemeOptions,
@@ -92,6 +104,17 @@ pub struct PresentationBuilderOptions {
     pub pause_create_new_slide: bool,
     pub list_item_newlines: u8,
     pub validate_snippets: bool,
+    pub max_snippet_output_rows: Option<u16>,
+    pub snippet_output_overflow: SnippetOutputOverflow,
+    pub prevent_code_ligatures: bool,
+    pub copy_hidden_lines: bool,
+    pub highlight_context_lines: Option<u16>,
+    pub hidden_line_prefix: Option<String>,
+    pub max_prose_columns: Option<u16>,
+    pub snippet_defaults: Option<String>,
+    pub compact_snippet_output: bool,
+    pub match_snippet_output_width: bool,
+    pub force_line_numbers: bool,
 }
 
 impl PresentationBuilderOptions {
@@ -113,6 +136,15 @@ impl PresentationBuilderOptions {
         if let Some(count) = options.list_item_newlines {
             self.list_item_newlines = count.into();
         }
+        if let Some(prefix) = options.hidden_line_prefix {
+            self.hidden_line_prefix = Some(prefix);
+        }
+        if let Some(columns) = options.max_prose_columns {
+            self.max_prose_columns = Some(columns);
+        }
+        if let Some(defaults) = options.snippet_defaults {
+            self.snippet_defaults = Some(defaults);
+        }
     }
 }
 
@@ -130,6 +162,7 @@ impl Default for PresentationBuilderOptions {
             strict_front_matter_parsing: true,
             enable_snippet_execution: false,
             enable_snippet_execution_replace: false,
+            allow_trusted_snippets: false,
             render_speaker_notes_only: false,
             auto_render_languages: Default::default(),
             theme_options: ThemeOptions { font_size_supported: false },
@@ -138,6 +171,17 @@ impl Default for PresentationBuilderOptions {
             pause_create_new_slide: false,
             list_item_newlines: 1,
             validate_snippets: false,
+            max_snippet_output_rows: None,
+            snippet_output_overflow: Default::default(),
+            prevent_code_ligatures: false,
+            copy_hidden_lines: false,
+            highlight_context_lines: None,
+            hidden_line_prefix: None,
+            max_prose_columns: None,
+            snippet_defaults: None,
+            compact_snippet_output: false,
+            match_snippet_output_width: false,
+            force_line_numbers: false,
         }
     }
 }
@@ -162,11 +206,20 @@ pub(crate) struct PresentationBuilder<'a, 'b> {
     footer_vars: FooterVariables,
     themes: &'a Themes,
     index_builder: IndexBuilder,
+    notes_builder: NotesModalBuilder,
     image_registry: ImageRegistry,
     bindings_config: KeyBindingsConfig,
     slides_without_footer: HashSet<usize>,
+    slide_chrome: HashMap<usize, Vec<RenderOperation>>,
+    slide_footnotes: HashMap<usize, Vec<RenderOperation>>,
+    skipped_slides: HashSet<usize>,
+    slides_processed: usize,
+    execution_hooks: ExecutionHooks,
     markdown_parser: &'a MarkdownParser<'b>,
     executable_snippets: HashMap<String, SnippetHandle>,
+    image_aliases: HashMap<String, PathBuf>,
+    reusable_snippet_handles: Vec<SnippetHandle>,
+    snippet_commands: Vec<SnippetCommands>,
     sources: MarkdownSources,
     options: PresentationBuilderOptions,
 }
@@ -202,23 +255,98 @@ impl<'a, 'b> PresentationBuilder<'a, 'b> {
             footer_vars: Default::default(),
             themes,
             index_builder: Default::default(),
+            notes_builder: Default::default(),
             image_registry,
             bindings_config,
             slides_without_footer: HashSet::new(),
+            slide_chrome: HashMap::new(),
+            slide_footnotes: HashMap::new(),
             markdown_parser,
             sources: Default::default(),
             executable_snippets: Default::default(),
+            image_aliases: Default::default(),
+            skipped_slides: Default::default(),
+            slides_processed: 0,
+            execution_hooks: Default::default(),
+            reusable_snippet_handles: Vec::new(),
+            snippet_commands: Vec::new(),
             options,
         })
     }
 
+    /// Reuse the given snippet handles for any identical snippets in the presentation.
+    ///
+    /// This keeps the execution state of those snippets, such as their output, around.
+    pub(crate) fn reuse_snippet_handles(mut self, handles: Vec<SnippetHandle>) -> Self {
+        self.reusable_snippet_handles = handles;
+        self
+    }
+
     /// Build a presentation from a markdown input.
+    ///
+    /// If the path is [STDIN_PATH], the presentation is read from stdin instead.
     pub(crate) fn build(self, path: &Path) -> Result<Presentation, BuildError> {
-        self.build_with_reader(path, FilesystemPresentationReader)
+        if path == Path::new(STDIN_PATH) {
+            self.build_with_reader(path, StdinPresentationReader)
+        } else {
+            self.build_with_reader(path, FilesystemPresentationReader)
+        }
+    }
+
+    /// Collect every snippet the presentation would execute, without executing any of them.
+    pub(crate) fn collect_snippet_commands(mut self, path: &Path) -> Result<Vec<SnippetCommands>, BuildError> {
+        let _guard = self.sources.enter(path).map_err(BuildError::EnterRoot)?;
+        let elements = if path == Path::new(STDIN_PATH) {
+            self.parse_input(path, StdinPresentationReader)?
+        } else {
+            self.parse_input(path, FilesystemPresentationReader)?
+        };
+        self.process_elements(elements)?;
+        Ok(self.snippet_commands)
     }
 
     /// Build a presentation from already parsed elements.
     pub(crate) fn build_from_parsed(mut self, elements: Vec<MarkdownElement>) -> Result<Presentation, BuildError> {
+        self.process_elements(elements)?;
+
+        let mut bindings_modal_builder = KeyBindingsModalBuilder::default();
+        if self.options.print_modal_background {
+            let background = self.build_modal_background()?;
+            self.index_builder.set_background(background.clone());
+            self.notes_builder.set_background(background.clone());
+            bindings_modal_builder.set_background(background);
+        };
+
+        let mut slides = Vec::new();
+        let builders = mem::take(&mut self.slide_builders);
+        self.footer_vars.total_slides = builders.len();
+        for (index, mut builder) in builders.into_iter().enumerate() {
+            self.footer_vars.current_slide = index + 1;
+            let mut footer = Vec::new();
+            if !self.slides_without_footer.contains(&index) {
+                footer.push(self.generate_footer()?);
+            }
+            footer.extend(self.slide_chrome.remove(&index).unwrap_or_default());
+            // Footnotes are rendered within the slide's margins so they go before the footer prelude.
+            let mut operations = self.slide_footnotes.remove(&index).unwrap_or_default();
+            if !footer.is_empty() {
+                operations.extend(Self::footer_prelude().into_iter().chain(footer));
+            }
+            if !operations.is_empty() {
+                builder = builder.footer(operations);
+            }
+            slides.push(builder.build());
+        }
+
+        let bindings = bindings_modal_builder.build(&self.theme, &self.bindings_config);
+        let slide_index = self.index_builder.build(&self.theme, self.presentation_state.clone());
+        let notes = self.notes_builder.build(&self.theme, self.presentation_state.clone());
+        let modals = Modals { slide_index, bindings, notes, style: self.theme.modals.clone() };
+        let presentation = Presentation::new(slides, modals, self.presentation_state);
+        Ok(presentation)
+    }
+
+    fn process_elements(&mut self, elements: Vec<MarkdownElement>) -> Result<(), BuildError> {
         let mut skip_first = false;
         if let Some(MarkdownElement::FrontMatter(contents)) = elements.first() {
             self.process_front_matter(contents)?;
@@ -228,6 +356,13 @@ impl<'a, 'b> PresentationBuilder<'a, 'b> {
         if skip_first {
             elements.next();
         }
+        if let Some(prefix) = &self.options.hidden_line_prefix {
+            if prefix.is_empty() || prefix.contains('\n') {
+                return Err(BuildError::InvalidMetadata(
+                    "hidden line prefix can't be empty or contain newlines".into(),
+                ));
+            }
+        }
 
         self.set_code_theme()?;
 
@@ -238,6 +373,8 @@ impl<'a, 'b> PresentationBuilder<'a, 'b> {
             self.slide_state.ignore_element_line_break = false;
             if self.options.render_speaker_notes_only {
                 self.process_element_for_speaker_notes_mode(element)?;
+            } else if self.slide_state.speaker_only && !matches!(element, MarkdownElement::Comment { .. }) {
+                self.slide_state.ignore_element_line_break = true;
             } else {
                 self.process_element_for_presentation_mode(element)?;
             }
@@ -254,40 +391,20 @@ impl<'a, 'b> PresentationBuilder<'a, 'b> {
         if self.slide_builders.is_empty() {
             self.terminate_slide();
         }
-
-        let mut bindings_modal_builder = KeyBindingsModalBuilder::default();
-        if self.options.print_modal_background {
-            let background = self.build_modal_background()?;
-            self.index_builder.set_background(background.clone());
-            bindings_modal_builder.set_background(background);
-        };
-
-        let mut slides = Vec::new();
-        let builders = mem::take(&mut self.slide_builders);
-        self.footer_vars.total_slides = builders.len();
-        for (index, mut builder) in builders.into_iter().enumerate() {
-            self.footer_vars.current_slide = index + 1;
-            if !self.slides_without_footer.contains(&index) {
-                builder = builder.footer(self.generate_footer()?);
-            }
-            slides.push(builder.build());
-        }
-
-        let bindings = bindings_modal_builder.build(&self.theme, &self.bindings_config);
-        let slide_index = self.index_builder.build(&self.theme, self.presentation_state.clone());
-        let modals = Modals { slide_index, bindings };
-        let presentation = Presentation::new(slides, modals, self.presentation_state);
-        Ok(presentation)
+        Ok(())
     }
 
     fn build_with_reader<F: PresentationReader>(self, path: &Path, reader: F) -> Result<Presentation, BuildError> {
         let _guard = self.sources.enter(path).map_err(BuildError::EnterRoot)?;
-        let input = reader.read(path).map_err(|e| BuildError::ReadPresentation(path.into(), e))?;
-        let elements =
-            self.markdown_parser.parse(&input).map_err(|error| BuildError::Parse { path: path.into(), error })?;
+        let elements = self.parse_input(path, reader)?;
         self.build_from_parsed(elements)
     }
 
+    fn parse_input<F: PresentationReader>(&self, path: &Path, reader: F) -> Result<Vec<MarkdownElement>, BuildError> {
+        let input = reader.read(path).map_err(|e| BuildError::ReadPresentation(path.into(), e))?;
+        self.markdown_parser.parse(&input).map_err(|error| BuildError::Parse { path: path.into(), error })
+    }
+
     fn build_modal_background(&self) -> Result<Image, RegisterImageError> {
         let color = self.theme.modals.style.colors.background.as_ref().and_then(Color::as_rgb);
         // If we don't have an rgb color (or we don't have a color at all), we default to a dark
@@ -326,18 +443,48 @@ impl<'a, 'b> PresentationBuilder<'a, 'b> {
         self.set_colors(style.colors);
 
         let footer_height = self.theme.footer.height();
-        self.chunk_operations.extend([
-            RenderOperation::ClearScreen,
-            RenderOperation::ApplyMargin(MarginProperties {
-                horizontal: self.theme.default_style.margin,
-                top: 0,
-                bottom: footer_height,
-            }),
-        ]);
+        self.chunk_operations.push(RenderOperation::ClearScreen);
+        let quiet_zone = self.theme.default_style.quiet_zone;
+        if !quiet_zone.is_empty() {
+            // This one is never popped so the footer is rendered within it as well.
+            self.chunk_operations.push(RenderQuietZone::new(quiet_zone).into());
+        }
+        self.chunk_operations.extend([RenderOperation::ApplyMargin(MarginProperties {
+            horizontal: self.theme.default_style.margin,
+            top: 0,
+            bottom: footer_height,
+        })]);
         self.push_line_break();
     }
 
     fn process_element_for_presentation_mode(&mut self, element: MarkdownElement) -> BuildResult {
+        if self.slide_state.image_row.is_some() {
+            match &element {
+                MarkdownElement::Image { .. } => (),
+                // Any whitespace in between images is ignored.
+                MarkdownElement::Paragraph(lines)
+                    if lines.iter().all(|line| line.0.iter().all(|text| text.content.trim().is_empty())) =>
+                {
+                    self.slide_state.ignore_element_line_break = true;
+                    return Ok(());
+                }
+                _ => self.push_image_row(),
+            };
+        }
+        if self.slide_state.floating_image.is_some() {
+            if let MarkdownElement::Paragraph(lines) = element {
+                let start = self.chunk_operations.len();
+                self.push_paragraph(lines)?;
+                let text = self.chunk_operations.split_off(start);
+                self.push_floating_image(text);
+                self.slide_state.last_element = LastElement::Other;
+                return Ok(());
+            }
+            self.flush_floating_image();
+        }
+        if let Some(content) = Self::slide_content(&element) {
+            self.slide_state.content.insert(content);
+        }
         let should_clear_last = !matches!(element, MarkdownElement::List(_) | MarkdownElement::Comment { .. });
         match element {
             // This one is processed before everything else as it affects how the rest of the
@@ -348,17 +495,24 @@ impl<'a, 'b> PresentationBuilder<'a, 'b> {
             MarkdownElement::Paragraph(elements) => self.push_paragraph(elements)?,
             MarkdownElement::List(elements) => self.push_list(elements)?,
             MarkdownElement::Snippet { info, code, source_position } => self.push_code(info, code, source_position)?,
+            MarkdownElement::DisplayMath { formula } => self.push_display_math(formula)?,
             MarkdownElement::Table(table) => self.push_table(table)?,
+            MarkdownElement::DefinitionList(definitions) => self.push_definition_list(definitions)?,
             MarkdownElement::ThematicBreak => self.process_thematic_break(),
             MarkdownElement::Comment { comment, source_position } => self.process_comment(comment, source_position)?,
             MarkdownElement::BlockQuote(lines) => self.push_block_quote(lines)?,
             MarkdownElement::Image { path, title, source_position } => {
-                self.push_image_from_path(path, title, source_position)?
+                if self.slide_state.image_row.is_some() {
+                    self.push_image_row_element(path, source_position)?;
+                } else {
+                    self.push_image_from_path(path, title, source_position)?;
+                }
             }
             MarkdownElement::Alert { alert_type, title, lines } => self.push_alert(alert_type, title, lines)?,
             MarkdownElement::Footnote(line) => {
                 let line = line.resolve(&self.theme.palette)?;
-                self.push_text(line, ElementType::Paragraph);
+                self.slide_state.footnotes.push(line);
+                self.slide_state.ignore_element_line_break = true;
             }
         };
         if should_clear_last {
@@ -368,14 +522,16 @@ impl<'a, 'b> PresentationBuilder<'a, 'b> {
     }
 
     fn process_element_for_speaker_notes_mode(&mut self, element: MarkdownElement) -> BuildResult {
+        // Speaker only content is rendered as-is in the speaker notes.
+        if self.slide_state.speaker_only && !matches!(element, MarkdownElement::Comment { .. }) {
+            return self.process_element_for_presentation_mode(element);
+        }
         match element {
             MarkdownElement::Comment { comment, source_position } => self.process_comment(comment, source_position)?,
             MarkdownElement::SetexHeading { text } => self.push_slide_title(text)?,
-            MarkdownElement::ThematicBreak => {
-                if self.options.end_slide_shorthand {
-                    self.terminate_slide();
-                    self.slide_state.ignore_element_line_break = true;
-                }
+            MarkdownElement::ThematicBreak if self.options.end_slide_shorthand => {
+                self.terminate_slide();
+                self.slide_state.ignore_element_line_break = true;
             }
             _ => {}
         }
@@ -387,8 +543,10 @@ impl<'a, 'b> PresentationBuilder<'a, 'b> {
 
     fn set_code_theme(&mut self) -> BuildResult {
         let theme = &self.theme.code.theme_name;
-        let highlighter =
-            self.themes.highlight.load_by_name(theme).ok_or_else(|| BuildError::InvalidCodeTheme(theme.clone()))?;
+        let highlighter = self.themes.highlight.load_by_name(theme).ok_or_else(|| {
+            let available = self.themes.highlight.theme_names().join(", ");
+            BuildError::InvalidCodeTheme(theme.clone(), available)
+        })?;
         self.highlighter = highlighter;
         Ok(())
     }
@@ -447,7 +605,11 @@ impl<'a, 'b> PresentationBuilder<'a, 'b> {
     fn push_paragraph(&mut self, lines: Vec<Line<RawColor>>) -> BuildResult {
         for line in lines {
             let line = line.resolve(&self.theme.palette)?;
-            self.push_text(line, ElementType::Paragraph);
+            if line.0.iter().any(|text| text.style.is_math()) {
+                self.push_inline_math(line)?;
+            } else {
+                self.push_text(line, ElementType::Paragraph);
+            }
             self.push_line_breaks(self.slide_font_size() as usize);
         }
         Ok(())
@@ -459,7 +621,9 @@ impl<'a, 'b> PresentationBuilder<'a, 'b> {
             self.slide_state.ignore_element_line_break = true;
         } else {
             self.chunk_operations.extend([
-                RenderSeparator::new(Line::default(), Default::default(), self.slide_font_size()).into(),
+                RenderSeparator::new(Line::default(), Default::default(), self.slide_font_size())
+                    .style(self.theme.thematic_break)
+                    .into(),
                 RenderOperation::RenderLineBreak,
             ]);
         }
@@ -467,10 +631,26 @@ impl<'a, 'b> PresentationBuilder<'a, 'b> {
 
     fn push_text(&mut self, line: Line, element_type: ElementType) {
         let alignment = self.slide_state.alignment.unwrap_or_else(|| self.theme.alignment(&element_type));
-        self.push_aligned_text(line, alignment);
+        match (element_type, self.options.max_prose_columns) {
+            (ElementType::Paragraph, Some(max_columns)) => {
+                let line = self.style_text(line);
+                if !line.0.is_empty() {
+                    let prose = RenderProse::new(line, alignment, max_columns);
+                    self.chunk_operations.push(RenderOperation::RenderDynamic(Rc::new(prose)));
+                }
+            }
+            _ => self.push_aligned_text(line, alignment),
+        };
     }
 
-    fn push_aligned_text(&mut self, mut block: Line, alignment: Alignment) {
+    fn push_aligned_text(&mut self, block: Line, alignment: Alignment) {
+        let block = self.style_text(block);
+        if !block.0.is_empty() {
+            self.chunk_operations.push(RenderOperation::RenderText { line: WeightedLine::from(block), alignment });
+        }
+    }
+
+    fn style_text(&self, mut block: Line) -> Line {
         let default_font_size = self.slide_font_size();
         for chunk in &mut block.0 {
             if chunk.style.is_code() {
@@ -480,9 +660,7 @@ impl<'a, 'b> PresentationBuilder<'a, 'b> {
                 chunk.style = chunk.style.size(default_font_size);
             }
         }
-        if !block.0.is_empty() {
-            self.chunk_operations.push(RenderOperation::RenderText { line: WeightedLine::from(block), alignment });
-        }
+        block
     }
 
     fn push_line_break(&mut self) {
@@ -494,6 +672,10 @@ impl<'a, 'b> PresentationBuilder<'a, 'b> {
     }
 
     fn terminate_slide(&mut self) {
+        if self.slide_state.image_row.is_some() {
+            self.push_image_row();
+        }
+        self.flush_floating_image();
         let operations = mem::take(&mut self.chunk_operations);
         let mutators = mem::take(&mut self.chunk_mutators);
         // Don't allow a last empty pause in slide since it adds nothing
@@ -502,14 +684,29 @@ impl<'a, 'b> PresentationBuilder<'a, 'b> {
         }
         let chunks = mem::take(&mut self.slide_chunks);
 
-        if !self.slide_state.skip_slide {
-            let builder = SlideBuilder::default().chunks(chunks);
+        self.slides_processed += 1;
+        let skip_slide = self.slide_state.skip_slide || self.skipped_slides.contains(&self.slides_processed);
+        if !skip_slide {
+            let title =
+                self.slide_state.title.as_ref().map(|title| title.0.iter().map(|text| text.content.as_str()).collect());
+            let summary = SlideSummary { title, content: mem::take(&mut self.slide_state.content) };
+            let builder = SlideBuilder::default().chunks(chunks).summary(summary);
             self.index_builder
                 .add_title(self.slide_state.title.take().unwrap_or_else(|| Text::from("<no title>").into()));
+            self.notes_builder.add_notes(mem::take(&mut self.slide_state.speaker_notes));
 
             if self.slide_state.ignore_footer {
                 self.slides_without_footer.insert(self.slide_builders.len());
             }
+            let chrome = mem::take(&mut self.slide_state.chrome);
+            if !chrome.is_empty() {
+                self.slide_chrome.insert(self.slide_builders.len(), chrome);
+            }
+            let footnotes = mem::take(&mut self.slide_state.footnotes);
+            if !footnotes.is_empty() {
+                let operations = self.footnote_operations(footnotes);
+                self.slide_footnotes.insert(self.slide_builders.len(), operations);
+            }
             self.slide_builders.push(builder);
         }
 
@@ -517,6 +714,26 @@ impl<'a, 'b> PresentationBuilder<'a, 'b> {
         self.slide_state = Default::default();
     }
 
+    fn slide_content(element: &MarkdownElement) -> Option<SlideContent> {
+        let content = match element {
+            MarkdownElement::Heading { .. } | MarkdownElement::Paragraph(_) | MarkdownElement::Footnote(_) => {
+                SlideContent::Text
+            }
+            MarkdownElement::List(_) | MarkdownElement::DefinitionList(_) => SlideContent::List,
+            MarkdownElement::Snippet { .. } => SlideContent::Code,
+            MarkdownElement::Table(_) => SlideContent::Table,
+            MarkdownElement::Image { .. } => SlideContent::Image,
+            MarkdownElement::BlockQuote(_) => SlideContent::Quote,
+            MarkdownElement::Alert { .. } => SlideContent::Alert,
+            MarkdownElement::DisplayMath { .. } => SlideContent::Math,
+            MarkdownElement::FrontMatter(_)
+            | MarkdownElement::SetexHeading { .. }
+            | MarkdownElement::ThematicBreak
+            | MarkdownElement::Comment { .. } => return None,
+        };
+        Some(content)
+    }
+
     fn is_chunk_empty(operations: &[RenderOperation]) -> bool {
         if operations.is_empty() {
             return true;
@@ -529,15 +746,31 @@ impl<'a, 'b> PresentationBuilder<'a, 'b> {
         true
     }
 
-    fn generate_footer(&self) -> Result<Vec<RenderOperation>, BuildError> {
+    fn generate_footer(&self) -> Result<RenderOperation, BuildError> {
         let generator = FooterGenerator::new(self.theme.footer.clone(), &self.footer_vars, &self.theme.palette)?;
-        Ok(vec![
+        Ok(RenderOperation::RenderDynamic(Rc::new(generator)))
+    }
+
+    fn footnote_operations(&self, footnotes: Vec<Line>) -> Vec<RenderOperation> {
+        let alignment = self.slide_state.alignment.unwrap_or_else(|| self.theme.alignment(&ElementType::Paragraph));
+        let mut operations = vec![
+            RenderOperation::ExitLayout,
+            RenderOperation::JumpToBottomRow { index: u16::try_from(footnotes.len() - 1).unwrap_or(u16::MAX) },
+        ];
+        for footnote in footnotes {
+            let line = WeightedLine::from(self.style_text(footnote));
+            operations.extend([RenderOperation::RenderText { line, alignment }, RenderOperation::RenderLineBreak]);
+        }
+        operations
+    }
+
+    fn footer_prelude() -> [RenderOperation; 2] {
+        [
             // Exit any layout we're in so this gets rendered on a default screen size.
             RenderOperation::ExitLayout,
             // Pop the slide margin so we're at the terminal rect.
             RenderOperation::PopMargin,
-            RenderOperation::RenderDynamic(Rc::new(generator)),
-        ])
+        ]
     }
 
     fn slide_font_size(&self) -> u8 {
@@ -558,6 +791,24 @@ impl PresentationReader for FilesystemPresentationReader {
     }
 }
 
+// The presentation can only be read once from stdin so it's buffered to allow reloading it.
+static STDIN_CONTENTS: Mutex<Option<String>> = Mutex::new(None);
+
+struct StdinPresentationReader;
+
+impl PresentationReader for StdinPresentationReader {
+    fn read(&self, _path: &Path) -> io::Result<String> {
+        let mut contents = STDIN_CONTENTS.lock().unwrap();
+        if let Some(contents) = contents.as_ref() {
+            return Ok(contents.clone());
+        }
+        let mut buffer = String::new();
+        io::stdin().read_to_string(&mut buffer)?;
+        *contents = Some(buffer.clone());
+        Ok(buffer)
+    }
+}
+
 #[derive(Debug, Default)]
 struct SlideState {
     ignore_element_line_break: bool,
@@ -572,6 +823,13 @@ struct SlideState {
     font_size: Option<u8>,
     alignment: Option<Alignment>,
     skip_slide: bool,
+    speaker_only: bool,
+    image_row: Option<Vec<Image>>,
+    floating_image: Option<(Image, ImageRenderProperties)>,
+    content: BTreeSet<SlideContent>,
+    chrome: Vec<RenderOperation>,
+    footnotes: Vec<Line>,
+    speaker_notes: Vec<String>,
 }
 
 #[derive(Debug, Default)]
@@ -601,7 +859,7 @@ enum LastElement {
 pub(crate) mod utils {
     use super::*;
     use crate::{
-        render::{engine::RenderEngine, properties::WindowSize},
+        render::{engine::RenderEngine, operation::ScrollDirection, properties::WindowSize},
         terminal::virt::VirtualTerminal,
     };
     use std::{path::PathBuf, thread::sleep, time::Duration};
@@ -644,6 +902,7 @@ pub(crate) mod utils {
         options: PresentationBuilderOptions,
         resources_path: PathBuf,
         theme: raw::PresentationTheme,
+        snippet_handles: Vec<SnippetHandle>,
     }
 
     impl Test {
@@ -654,7 +913,13 @@ pub(crate) mod utils {
                 theme_options: ThemeOptions { font_size_supported: true },
                 ..Default::default()
             };
-            Self { input: input.into(), options, resources_path: std::env::temp_dir(), theme: Default::default() }
+            Self {
+                input: input.into(),
+                options,
+                resources_path: std::env::temp_dir(),
+                theme: Default::default(),
+                snippet_handles: Vec::new(),
+            }
         }
 
         pub(crate) fn options(mut self, options: PresentationBuilderOptions) -> Self {
@@ -682,6 +947,16 @@ pub(crate) mod utils {
             self
         }
 
+        pub(crate) fn reuse_snippet_handles(mut self, handles: Vec<SnippetHandle>) -> Self {
+            self.snippet_handles = handles;
+            self
+        }
+
+        pub(crate) fn allow_trusted_snippets(mut self) -> Self {
+            self.options.allow_trusted_snippets = true;
+            self
+        }
+
         pub(crate) fn with_builder<T, F>(&self, callback: F) -> T
         where
             F: for<'a, 'b> Fn(PresentationBuilder<'a, 'b>) -> T,
@@ -705,7 +980,8 @@ pub(crate) mod utils {
                 &parser,
                 self.options.clone(),
             )
-            .expect("failed to create builder");
+            .expect("failed to create builder")
+            .reuse_snippet_handles(self.snippet_handles.clone());
             callback(builder)
         }
 
@@ -741,6 +1017,7 @@ pub(crate) mod utils {
         run_async_renders: bool,
         background_maps: Vec<(Color, char)>,
         advances: Option<usize>,
+        scrolls: usize,
     }
 
     impl PresentationRender {
@@ -752,6 +1029,7 @@ pub(crate) mod utils {
                 run_async_renders: true,
                 background_maps: Default::default(),
                 advances: None,
+                scrolls: 0,
             }
         }
 
@@ -770,6 +1048,11 @@ pub(crate) mod utils {
             self
         }
 
+        pub(crate) fn scrolls(mut self, number: usize) -> Self {
+            self.scrolls = number;
+            self
+        }
+
         pub(crate) fn run_async_renders(mut self, value: bool) -> Self {
             self.run_async_renders = value;
             self
@@ -785,7 +1068,7 @@ pub(crate) mod utils {
         }
 
         pub(crate) fn into_parts(self) -> (Vec<String>, Vec<String>) {
-            let Self { mut presentation, columns, rows, run_async_renders, background_maps, advances } = self;
+            let Self { mut presentation, columns, rows, run_async_renders, background_maps, advances, scrolls } = self;
             let columns = columns.expect("no columns");
             let rows = rows.expect("no rows");
             let dimensions = WindowSize { rows, columns, width: 0, height: 0 };
@@ -807,6 +1090,14 @@ pub(crate) mod utils {
                     }
                 }
             }
+            for _ in 0..scrolls {
+                let operations: Vec<_> = slide.iter_operations().collect();
+                let scrolled = operations.into_iter().rev().find_map(|operation| match operation {
+                    RenderOperation::RenderDynamic(operation) => operation.scroll(ScrollDirection::Down),
+                    _ => None,
+                });
+                scrolled.expect("nothing to scroll");
+            }
 
             let mut term = VirtualTerminal::new(dimensions, Default::default());
             let engine = RenderEngine::new(&mut term, dimensions, Default::default());
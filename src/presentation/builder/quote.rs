@@ -77,12 +77,15 @@ impl PresentationBuilder<'_, '_> {
             }
             self.chunk_operations.push(RenderOperation::RenderBlockLine(BlockLine {
                 prefix: prefix.clone().into(),
+                prefix_link: None,
                 right_padding_length: 0,
                 repeat_prefix_on_wrap: true,
                 text: line.into(),
                 block_length,
                 alignment,
                 block_color: base_colors.background,
+                border: None,
+                overflow: Default::default(),
             }));
             self.push_line_break();
         }
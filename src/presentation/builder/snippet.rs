@@ -1,48 +1,94 @@
 use super::{BuildError, BuildResult};
 use crate::{
     code::{
-        execute::LanguageSnippetExecutor,
+        execute::{LanguageSnippetExecutor, UnsupportedExecution},
+        highlighting::SnippetHighlighter,
         snippet::{
-            ExternalFile, Highlight, HighlightContext, HighlightGroup, HighlightMutator, HighlightedLine, Snippet,
+            CollapsedLine, ExternalFile, ExternalFileNumbering, ExternalFilePart, Highlight, HighlightContext,
+            HighlightGroup, HighlightMutator, HighlightedLine, Snippet, SnippetAttributes, SnippetChromePosition,
             SnippetExec, SnippetExecutorSpec, SnippetLanguage, SnippetLine, SnippetParser, SnippetRepr,
             SnippetSplitter,
         },
     },
-    markdown::elements::SourcePosition,
+    markdown::{
+        elements::{Line, SourcePosition, Text},
+        text::{WeightedLine, WeightedText},
+        text_style::TextStyle,
+    },
     presentation::builder::{PresentationBuilder, error::InvalidPresentation},
+    qr::QrCode,
     render::{
-        operation::{AsRenderOperations, RenderAsyncStartPolicy, RenderOperation},
+        operation::{
+            AsRenderOperations, BlockBorder, BorderEdge, ImageRenderProperties, ImageSize, LineOverflow,
+            RenderAsyncStartPolicy, RenderOperation,
+        },
         properties::WindowSize,
     },
-    theme::{Alignment, CodeBlockStyle},
+    terminal::image::printer::ImageSpec,
+    theme::{Alignment, CodeBlockBorder, CodeBlockOverflow, CodeBlockStyle, ElementType},
     third_party::ThirdPartyRenderRequest,
-    ui::execution::{
-        RunAcquireTerminalSnippet, RunImageSnippet, SnippetExecutionDisabledOperation, SnippetOutputOperation,
-        disabled::ExecutionType,
-        output::{ExecIndicator, ExecIndicatorStyle, RunSnippetTrigger, SnippetHandle},
-        validator::ValidateSnippetOperation,
+    ui::{
+        execution::{
+            RunAcquireTerminalSnippet, RunImageSnippet, SnippetChromeOperation, SnippetExecutionDisabledOperation,
+            SnippetOutputOperation,
+            disabled::ExecutionType,
+            output::{ExecIndicator, ExecIndicatorStyle, OutputHighlighter, RunSnippetTrigger, SnippetHandle},
+            validator::ValidateSnippetOperation,
+        },
+        scroll::{SCROLLBAR_WIDTH, ScrollWindow},
     },
 };
 use itertools::Itertools;
-use std::{cell::RefCell, rc::Rc};
+use std::{borrow::Cow, cell::RefCell, rc::Rc};
+use unicode_width::UnicodeWidthStr;
+
+// The number of pixels used to draw every module in a QR code. Images are scaled down to fit so
+// this only needs to be large enough so that they don't get blurry when scaled up.
+const QR_MODULE_PIXELS: u32 = 8;
 
 impl PresentationBuilder<'_, '_> {
     pub(crate) fn push_code(&mut self, info: String, code: String, source_position: SourcePosition) -> BuildResult {
         let mut snippet = SnippetParser::parse(info, code)
             .map_err(|e| self.invalid_presentation(source_position, InvalidPresentation::Snippet(e.to_string())))?;
+        if let Some(defaults) = &self.options.snippet_defaults {
+            let defaults = SnippetParser::parse_defaults(defaults).map_err(|e| {
+                self.invalid_presentation(source_position, InvalidPresentation::SnippetDefaults(e.to_string()))
+            })?;
+            snippet.attributes.merge_defaults(&defaults);
+        }
         if matches!(snippet.language, SnippetLanguage::File) {
             snippet = self.load_external_snippet(snippet, source_position)?;
         }
+        if let Some(name) = &snippet.attributes.theme {
+            if self.themes.highlight.load_by_name(name).is_none() {
+                let name = name.clone();
+                let available = self.themes.highlight.theme_names().join(", ");
+                return Err(
+                    self.invalid_presentation(source_position, InvalidPresentation::SnippetTheme { name, available })
+                );
+            }
+        }
+        let hidden_line_prefix = self.hidden_line_prefix(&snippet.language).map(ToString::to_string);
+        snippet.resolve_highlight_patterns(hidden_line_prefix.as_deref());
         if self.options.auto_render_languages.contains(&snippet.language) {
             snippet.attributes.representation = SnippetRepr::Render;
         }
-        // Ids can only be used in `+exec` snippets.
-        if snippet.attributes.id.is_some()
-            && (!matches!(snippet.attributes.execution, SnippetExec::Exec(_))
-                || !matches!(snippet.attributes.representation, SnippetRepr::Snippet))
-        {
+        self.record_snippet_commands(&snippet);
+        // Ids and pipes can only be used in `+exec` snippets.
+        let is_exec = matches!(snippet.attributes.execution, SnippetExec::Exec(_))
+            && matches!(snippet.attributes.representation, SnippetRepr::Snippet);
+        if snippet.attributes.id.is_some() && !is_exec {
             return Err(self.invalid_presentation(source_position, InvalidPresentation::SnippetIdNonExec));
         }
+        let pipe_source = match snippet.attributes.pipe_from.clone() {
+            Some(_) if !is_exec => {
+                return Err(self.invalid_presentation(source_position, InvalidPresentation::SnippetPipeNonExec));
+            }
+            Some(id) => Some(self.executable_snippets.get(&id).cloned().ok_or_else(|| {
+                self.invalid_presentation(source_position, InvalidPresentation::UndefinedSnippetId(id))
+            })?),
+            None => None,
+        };
 
         self.push_differ(snippet.contents.clone());
         // Redraw slide if attributes change
@@ -61,6 +107,11 @@ impl PresentationBuilder<'_, '_> {
                     return self.push_replace_code_execution(snippet.clone());
                 }
             }
+            SnippetRepr::Chrome(position) => {
+                if execution_allowed {
+                    return self.push_chrome_code_execution(snippet, position);
+                }
+            }
             SnippetRepr::Snippet => (),
         };
 
@@ -71,18 +122,37 @@ impl PresentationBuilder<'_, '_> {
                 let exec_type = match snippet.attributes.representation {
                     SnippetRepr::Image => ExecutionType::Image,
                     SnippetRepr::ExecReplace => ExecutionType::ExecReplace,
+                    SnippetRepr::Chrome(_) => ExecutionType::Chrome,
                     SnippetRepr::Render | SnippetRepr::Snippet => ExecutionType::Execute,
                 };
-                self.push_execution_disabled_operation(exec_type);
+                let alignment = self.code_style(&snippet).alignment;
+                self.push_execution_disabled_operation(exec_type, alignment);
+                Ok(())
+            }
+            // There's no point in running a process that does nothing, let the user know instead.
+            SnippetExec::Exec(_)
+                if snippet.attributes.id.is_none()
+                    && snippet.is_executable_empty(self.hidden_line_prefix(&snippet.language)) =>
+            {
+                let alignment = self.code_style(&snippet).alignment;
+                let operation = SnippetExecutionDisabledOperation::empty_snippet(
+                    self.theme.execution_output.status.not_started_style,
+                    alignment,
+                );
+                self.chunk_operations.push(RenderOperation::RenderAsync(Rc::new(operation)));
                 Ok(())
             }
             SnippetExec::Exec(spec) => {
-                let executor = self.snippet_executor.language_executor(&snippet.language, &spec)?;
+                let executor = self.language_executor(&snippet.language, &spec)?;
                 let alignment = self.code_style(&snippet).alignment;
-                let handle = SnippetHandle::new(snippet.clone(), executor, RenderAsyncStartPolicy::OnDemand);
+                let handle = self.snippet_handle(snippet.clone(), executor, RenderAsyncStartPolicy::OnDemand);
+                if let Some(source) = pipe_source {
+                    handle.pipe_from(source);
+                }
                 self.chunk_operations
                     .push(RenderOperation::RenderAsync(Rc::new(RunSnippetTrigger::new(handle.clone()))));
-                self.push_indicator(handle.clone(), block_length, alignment);
+                let compact = snippet.attributes.compact || self.options.compact_snippet_output;
+                self.push_indicator(handle.clone(), block_length, alignment, compact);
                 match snippet.attributes.id.clone() {
                     Some(id) => {
                         if self.executable_snippets.insert(id.clone(), handle).is_some() {
@@ -92,45 +162,105 @@ impl PresentationBuilder<'_, '_> {
                         Ok(())
                     }
                     None => {
-                        self.push_line_break();
+                        if !compact {
+                            self.push_line_break();
+                        }
                         self.push_code_execution(block_length, handle, alignment)
                     }
                 }
             }
             SnippetExec::AcquireTerminal(spec) => self.push_acquire_terminal_execution(snippet, block_length, &spec),
             SnippetExec::Validate(spec) => {
-                let executor = self.snippet_executor.language_executor(&snippet.language, &spec)?;
+                let executor = self.language_executor(&snippet.language, &spec)?;
                 self.push_validator(&snippet, &executor);
                 Ok(())
             }
         }
     }
 
+    fn record_snippet_commands(&mut self, snippet: &Snippet) {
+        let (attribute, spec) = match (&snippet.attributes.execution, &snippet.attributes.representation) {
+            (SnippetExec::None, _) => return,
+            (SnippetExec::Exec(spec), SnippetRepr::ExecReplace) => ("+exec_replace", spec),
+            (SnippetExec::Exec(spec), SnippetRepr::Chrome(_)) => ("+chrome", spec),
+            (SnippetExec::Exec(spec), SnippetRepr::Image) => ("+image", spec),
+            (SnippetExec::Exec(spec), _) => ("+exec", spec),
+            (SnippetExec::AcquireTerminal(spec), _) => ("+acquire_terminal", spec),
+            (SnippetExec::Validate(spec), _) => ("+validate", spec),
+        };
+        let (commands, contents, executor) = match self.language_executor(&snippet.language, spec) {
+            Ok(executor) => (Ok(executor.commands().to_vec()), executor.executable_contents(snippet), Some(executor)),
+            Err(e) => (Err(e.to_string()), snippet.contents.clone(), None),
+        };
+        self.snippet_commands.push(SnippetCommands {
+            slide: self.slide_builders.len() + 1,
+            language: snippet.language.clone(),
+            attribute,
+            commands,
+            contents,
+            executor,
+            snippet: snippet.clone(),
+        });
+    }
+
     pub(crate) fn push_detached_code_execution(&mut self, handle: SnippetHandle) -> BuildResult {
         let alignment = self.code_style(&handle.snippet()).alignment;
         self.push_code_execution(0, handle, alignment)
     }
 
+    fn language_executor(
+        &self,
+        language: &SnippetLanguage,
+        spec: &SnippetExecutorSpec,
+    ) -> Result<LanguageSnippetExecutor, UnsupportedExecution> {
+        let executor =
+            self.snippet_executor.language_executor(language, spec)?.with_hooks(self.execution_hooks.clone());
+        match &self.options.hidden_line_prefix {
+            Some(prefix) => Ok(executor.with_hidden_line_prefix(prefix.clone())),
+            None => Ok(executor),
+        }
+    }
+
+    // The presentation's prefix takes precedence over the language's one.
+    fn hidden_line_prefix(&self, language: &SnippetLanguage) -> Option<&str> {
+        self.options.hidden_line_prefix.as_deref().or_else(|| self.snippet_executor.hidden_line_prefix(language))
+    }
+
     fn is_execution_allowed(&self, snippet: &Snippet) -> bool {
+        if snippet.attributes.trusted && self.options.allow_trusted_snippets {
+            return true;
+        }
         match snippet.attributes.representation {
             SnippetRepr::Snippet => self.options.enable_snippet_execution,
-            SnippetRepr::Image | SnippetRepr::ExecReplace => self.options.enable_snippet_execution_replace,
+            SnippetRepr::Image | SnippetRepr::ExecReplace | SnippetRepr::Chrome(_) => {
+                self.options.enable_snippet_execution_replace
+            }
             SnippetRepr::Render => true,
         }
     }
 
     fn push_code_lines(&mut self, snippet: &Snippet) -> u16 {
-        let lines = SnippetSplitter::new(&self.theme.code, self.snippet_executor.hidden_line_prefix(&snippet.language))
-            .split(snippet);
-        let block_length = lines.iter().map(|line| line.width()).max().unwrap_or(0) * self.slide_font_size() as usize;
-        let block_length = block_length as u16;
-        let (lines, context) = self.highlight_lines(snippet, lines, block_length);
+        let mut snippet = Cow::Borrowed(snippet);
+        if self.options.force_line_numbers {
+            snippet.to_mut().attributes.line_numbers = true;
+        }
+        let snippet = snippet.as_ref();
+        let lines = SnippetSplitter::new(&self.theme.code, self.hidden_line_prefix(&snippet.language)).split(snippet);
+        let scrollable_lines = lines.iter().filter(|line| line.is_scrollable()).count();
+        let window =
+            snippet.attributes.max_rows.and_then(|rows| ScrollWindow::new(rows.get().into(), scrollable_lines));
+        let scrollbar_length = if window.is_some() { SCROLLBAR_WIDTH as usize } else { 0 };
+        let block_length = lines.iter().map(|line| line.width()).max().unwrap_or(0) + scrollbar_length;
+        let block_length = (block_length * self.slide_font_size() as usize) as u16;
+        self.push_copyable(snippet);
+        let (lines, context) = self.highlight_lines(snippet, lines, block_length, window);
         for line in lines {
             self.chunk_operations.push(RenderOperation::RenderDynamic(Rc::new(line)));
         }
         self.chunk_operations.push(RenderOperation::SetColors(self.theme.default_style.style.colors));
         if self.options.allow_mutations && context.borrow().groups.len() > 1 {
-            self.chunk_mutators.push(Box::new(HighlightMutator::new(context)));
+            let mutator = HighlightMutator::new(context).auto_step(snippet.attributes.auto_step);
+            self.chunk_mutators.push(Box::new(mutator));
         }
         block_length
     }
@@ -148,12 +278,43 @@ impl PresentationBuilder<'_, '_> {
             }
             other => other,
         };
-        let executor = self.snippet_executor.language_executor(&snippet.language, &spec)?;
-        let handle = SnippetHandle::new(snippet, executor, RenderAsyncStartPolicy::Automatic);
+        let executor = self.language_executor(&snippet.language, &spec)?;
+        let handle = self.snippet_handle(snippet, executor, RenderAsyncStartPolicy::Automatic);
         self.chunk_operations.push(RenderOperation::RenderAsync(Rc::new(RunSnippetTrigger::new(handle.clone()))));
         self.push_code_execution(0, handle, alignment)
     }
 
+    fn push_chrome_code_execution(&mut self, snippet: Snippet, position: SnippetChromePosition) -> BuildResult {
+        let SnippetExec::Exec(spec) = snippet.attributes.execution.clone() else {
+            panic!("not an exec snippet");
+        };
+        let executor = self.language_executor(&snippet.language, &spec)?;
+        let handle = self.snippet_handle(snippet, executor, RenderAsyncStartPolicy::Automatic);
+        let default_colors = self.theme.default_style.style.colors;
+        let operation = SnippetChromeOperation::new(handle.clone(), position, default_colors);
+        // The output is rendered along with the footer so it doesn't affect the slide's layout.
+        self.slide_state.chrome.extend([
+            RenderOperation::RenderAsync(Rc::new(RunSnippetTrigger::new(handle))),
+            RenderOperation::RenderDynamic(Rc::new(operation)),
+        ]);
+        Ok(())
+    }
+
+    fn snippet_handle(
+        &mut self,
+        snippet: Snippet,
+        executor: LanguageSnippetExecutor,
+        policy: RenderAsyncStartPolicy,
+    ) -> SnippetHandle {
+        let position = self.reusable_snippet_handles.iter().position(|handle| handle.matches(&snippet, policy));
+        let handle = match position {
+            Some(index) => self.reusable_snippet_handles.swap_remove(index),
+            None => SnippetHandle::new(snippet, executor, policy),
+        };
+        self.presentation_state.register_snippet_handle(handle.clone());
+        handle
+    }
+
     fn load_external_snippet(
         &mut self,
         mut code: Snippet,
@@ -161,16 +322,69 @@ impl PresentationBuilder<'_, '_> {
     ) -> Result<Snippet, BuildError> {
         let file: ExternalFile = serde_yaml::from_str(&code.contents)
             .map_err(|e| self.invalid_presentation(source_position, InvalidPresentation::Snippet(e.to_string())))?;
-        let path = file.path;
+        let parts = match (file.path, file.files.is_empty()) {
+            (Some(path), true) => vec![ExternalFilePart {
+                path,
+                start_line: file.start_line,
+                end_line: file.end_line,
+                line_url: file.line_url,
+            }],
+            (None, false) if file.start_line.is_none() && file.end_line.is_none() && file.line_url.is_none() => {
+                file.files
+            }
+            (None, false) => {
+                let error = "'start_line', 'end_line' and 'line_url' must be set on every file when using 'files'";
+                return Err(self.invalid_presentation(source_position, InvalidPresentation::Snippet(error.into())));
+            }
+            _ => {
+                let error = "exactly one of 'path' and 'files' must be set";
+                return Err(self.invalid_presentation(source_position, InvalidPresentation::Snippet(error.into())));
+            }
+        };
         let base_path = self.resource_base_path();
-        let contents = self.resources.external_text_file(&path, &base_path).map_err(|e| {
-            self.invalid_presentation(
-                source_position,
-                InvalidPresentation::Snippet(format!("failed to load snippet {path:?}: {e}")),
-            )
-        })?;
+        let hidden_line_prefix = self.hidden_line_prefix(&file.language).map(ToString::to_string);
+        let mut contents = String::new();
+        // The number of visible lines so far, used to refer to lines for line numbering purposes.
+        let mut visible_lines = 0;
+        for (index, part) in parts.into_iter().enumerate() {
+            let path = part.path;
+            let part_contents = self.resources.external_text_file(&path, &base_path).map_err(|e| {
+                self.invalid_presentation(
+                    source_position,
+                    InvalidPresentation::Snippet(format!("failed to load snippet {path:?}: {e}")),
+                )
+            })?;
+            let part_contents = Self::filter_lines(part_contents, part.start_line, part.end_line);
+            if index > 0 {
+                if !contents.ends_with('\n') {
+                    contents.push('\n');
+                }
+                if let Some(separator) = &file.separator {
+                    contents.push_str(separator);
+                    contents.push('\n');
+                    visible_lines += 1;
+                    code.attributes.skipped_line_numbers.insert(visible_lines);
+                }
+                if matches!(file.numbering, ExternalFileNumbering::PerFile) {
+                    code.attributes.line_number_restarts.insert(visible_lines + 1);
+                }
+            }
+            let prefix = hidden_line_prefix.as_deref();
+            let first_line = part.start_line.unwrap_or(1).max(1);
+            for (offset, line) in part_contents.lines().enumerate() {
+                if prefix.is_some_and(|p| line.starts_with(p)) {
+                    continue;
+                }
+                visible_lines += 1;
+                if let Some(url) = &part.line_url {
+                    let url = url.replace("{line}", &(first_line + offset).to_string());
+                    code.attributes.line_links.insert(visible_lines, url);
+                }
+            }
+            contents.push_str(&part_contents);
+        }
         code.language = file.language;
-        code.contents = Self::filter_lines(contents, file.start_line, file.end_line);
+        code.contents = contents;
         Ok(code)
     }
 
@@ -187,10 +401,14 @@ impl PresentationBuilder<'_, '_> {
     fn push_rendered_code(&mut self, code: Snippet, source_position: SourcePosition) -> BuildResult {
         let Snippet { contents, language, attributes } = code;
         let request = match language {
+            SnippetLanguage::Qr => return self.push_qr_code(contents, attributes, source_position),
             SnippetLanguage::Typst => ThirdPartyRenderRequest::Typst(contents, self.theme.typst.clone()),
             SnippetLanguage::Latex => ThirdPartyRenderRequest::Latex(contents, self.theme.typst.clone()),
             SnippetLanguage::Mermaid => ThirdPartyRenderRequest::Mermaid(contents, self.theme.mermaid.clone()),
             SnippetLanguage::D2 => ThirdPartyRenderRequest::D2(contents, self.theme.d2.clone()),
+            SnippetLanguage::Graphviz => {
+                ThirdPartyRenderRequest::Graphviz(contents, self.theme.default_style.style.colors)
+            }
             _ => {
                 return Err(self.invalid_presentation(
                     source_position,
@@ -203,44 +421,179 @@ impl PresentationBuilder<'_, '_> {
         Ok(())
     }
 
+    fn push_qr_code(
+        &mut self,
+        contents: String,
+        attributes: SnippetAttributes,
+        source_position: SourcePosition,
+    ) -> BuildResult {
+        let error_correction = attributes.error_correction.unwrap_or_default();
+        let code = QrCode::encode(contents.trim().as_bytes(), error_correction).map_err(|e| {
+            self.invalid_presentation(source_position, InvalidPresentation::Snippet(format!("invalid QR code: {e}")))
+        })?;
+        let image = self.image_registry.register(ImageSpec::Generated(code.to_image(QR_MODULE_PIXELS)))?;
+        let size = match attributes.width {
+            Some(percent) => ImageSize::WidthScaled { ratio: percent.as_ratio() },
+            None => Default::default(),
+        };
+        let properties = ImageRenderProperties {
+            size,
+            background_color: self.theme.default_style.style.colors.background,
+            ..Default::default()
+        };
+        self.chunk_operations.push(RenderOperation::RenderImage(image, properties));
+        Ok(())
+    }
+
+    pub(crate) fn push_display_math(&mut self, formula: String) -> BuildResult {
+        let fallback = Line::from(format!("$${formula}$$"));
+        let request = ThirdPartyRenderRequest::Latex(format!("\\[{formula}\\]"), self.theme.typst.clone());
+        let operation = self.third_party.render_with_fallback(request, &self.theme, fallback)?;
+        self.chunk_operations.push(operation);
+        Ok(())
+    }
+
+    pub(crate) fn push_inline_math(&mut self, line: Line) -> BuildResult {
+        let alignment = self.slide_state.alignment.unwrap_or_else(|| self.theme.alignment(&ElementType::Paragraph));
+        let line = self.style_text(line);
+        let max_columns = self.options.max_prose_columns;
+        let font_size = self.slide_font_size();
+        let operation = self.third_party.render_inline_math(line, &self.theme, alignment, max_columns, font_size)?;
+        self.chunk_operations.push(operation);
+        Ok(())
+    }
+
+    // Snippets can override the highlighter theme, which is validated when they're pushed.
+    fn snippet_highlighter(&self, snippet: &Snippet) -> SnippetHighlighter {
+        snippet
+            .attributes
+            .theme
+            .as_ref()
+            .and_then(|name| self.themes.highlight.load_by_name(name))
+            .unwrap_or_else(|| self.highlighter.clone())
+    }
+
     fn highlight_lines(
         &self,
         code: &Snippet,
         lines: Vec<SnippetLine>,
         block_length: u16,
+        window: Option<ScrollWindow>,
     ) -> (Vec<HighlightedLine>, Rc<RefCell<HighlightContext>>) {
-        let mut code_highlighter = self.highlighter.language_highlighter(&code.language);
+        let snippet_highlighter = self.snippet_highlighter(code);
+        let mut code_highlighter = snippet_highlighter.language_highlighter(&code.language);
         let style = self.code_style(code);
-        let block_length = self.theme.code.alignment.adjust_size(block_length);
+        let block_length = style.alignment.adjust_size(block_length);
         let font_size = self.slide_font_size();
         let dim_style = {
-            let mut highlighter = self.highlighter.language_highlighter(&SnippetLanguage::Rust);
+            let mut highlighter = snippet_highlighter.language_highlighter(&SnippetLanguage::Rust);
             highlighter.style_line("//", &style).0.first().expect("no styles").style.size(font_size)
         };
+        let marker_style = style.hidden_lines_marker.as_ref().map(|marker| marker.style.merged(&dim_style));
+        let plain_style = code.attributes.plain.then(|| {
+            let mut highlighter = snippet_highlighter.language_highlighter(&SnippetLanguage::File);
+            highlighter.style_line("_", &style).0.first().expect("no styles").style.size(font_size)
+        });
         let groups = match self.options.allow_mutations {
             true => code.attributes.highlight_groups.clone(),
             false => vec![HighlightGroup::new(vec![Highlight::All])],
         };
-        let context =
-            Rc::new(RefCell::new(HighlightContext { groups, current: 0, block_length, alignment: style.alignment }));
+        let row_line_numbers = lines.iter().filter(|line| line.is_scrollable()).map(|line| line.line_number).collect();
+        let context = Rc::new(RefCell::new(HighlightContext {
+            groups,
+            current: 0,
+            block_length,
+            alignment: style.alignment,
+            overflow: match style.overflow {
+                CodeBlockOverflow::Wrap => LineOverflow::WrapCharacters,
+                CodeBlockOverflow::Clip => LineOverflow::Clip,
+            },
+            context_lines: self.options.highlight_context_lines,
+            window,
+            scrollbar_style: dim_style,
+            fade: style.fade,
+            row_line_numbers,
+        }));
+
+        let border = |edge| {
+            let kind = style.border;
+            (kind != CodeBlockBorder::None).then_some(BlockBorder { kind, edge, style: dim_style })
+        };
+        let border_line = |edge| {
+            let empty: WeightedLine = Line::from(Text::new("", TextStyle::default().size(font_size))).into();
+            HighlightedLine {
+                prefix: WeightedText::from(""),
+                prefix_link: None,
+                right_padding_length: 0,
+                highlighted: empty.clone(),
+                not_highlighted: empty,
+                line_number: None,
+                context: context.clone(),
+                block_color: dim_style.colors.background,
+                border: border(edge),
+                collapsed: None,
+                row: None,
+                scrollbar_padding: 0,
+            }
+        };
+
+        let finish_line = |mut line: Line| {
+            if self.options.prevent_code_ligatures {
+                line.break_ligatures();
+            }
+            WeightedLine::from(line)
+        };
 
+        let content_length = lines.iter().map(|line| line.width()).max().unwrap_or(0);
         let mut output = Vec::new();
+        if style.border != CodeBlockBorder::None {
+            output.push(border_line(BorderEdge::Top));
+        }
+        let mut rows = 0;
         for line in lines.into_iter() {
+            let row = line.is_scrollable().then(|| {
+                rows += 1;
+                rows - 1
+            });
+            let scrollbar_padding = (content_length - line.width()) as u16 * font_size as u16;
             let prefix = line.dim_prefix(&dim_style);
-            let highlighted = line.highlight(&mut code_highlighter, &style, font_size);
-            let not_highlighted = line.dim(&dim_style);
+            let highlighted = match &plain_style {
+                Some(plain_style) => line.dim(plain_style),
+                None => line.highlight(&mut code_highlighter, &style, font_size),
+            };
+            let not_highlighted = match (&marker_style, line.marker) {
+                (Some(marker_style), true) => line.dim(marker_style),
+                _ => line.dim(&dim_style),
+            };
+            let highlighted = finish_line(highlighted);
+            let not_highlighted = finish_line(not_highlighted);
+            let collapsed = self.options.highlight_context_lines.map(|_| {
+                let symbol = style.hidden_lines_marker.as_ref().map(|marker| marker.symbol.as_str()).unwrap_or("⋮");
+                let marker = SnippetLine { code: format!("{symbol}\n"), ..SnippetLine::empty() };
+                let prefix = SnippetLine { prefix: " ".repeat(line.prefix.width()), ..SnippetLine::empty() };
+                let marker_style = marker_style.unwrap_or(dim_style);
+                CollapsedLine { prefix: prefix.dim_prefix(&dim_style), marker: marker.dim(&marker_style).into() }
+            });
             let line_number = line.line_number;
             let context = context.clone();
             output.push(HighlightedLine {
                 prefix,
+                prefix_link: line.link.clone(),
                 right_padding_length: line.right_padding_length * font_size as u16,
                 highlighted,
                 not_highlighted,
                 line_number,
                 context,
                 block_color: dim_style.colors.background,
+                border: border(BorderEdge::Sides),
+                collapsed,
+                row,
+                scrollbar_padding,
             });
         }
+        if style.border != CodeBlockBorder::None {
+            output.push(border_line(BorderEdge::Bottom));
+        }
         (output, context)
     }
 
@@ -249,17 +602,22 @@ impl PresentationBuilder<'_, '_> {
         if snippet.attributes.no_background {
             style.background = false;
         }
+        if let Some(alignment) = &snippet.attributes.alignment {
+            style.alignment = alignment.apply(style.alignment);
+        }
         style
     }
 
-    fn push_execution_disabled_operation(&mut self, exec_type: ExecutionType) {
+    fn push_execution_disabled_operation(&mut self, exec_type: ExecutionType, alignment: Alignment) {
         let policy = match exec_type {
-            ExecutionType::ExecReplace | ExecutionType::Image => RenderAsyncStartPolicy::Automatic,
+            ExecutionType::ExecReplace | ExecutionType::Image | ExecutionType::Chrome => {
+                RenderAsyncStartPolicy::Automatic
+            }
             ExecutionType::Execute => RenderAsyncStartPolicy::OnDemand,
         };
         let operation = SnippetExecutionDisabledOperation::new(
             self.theme.execution_output.status.failure_style,
-            self.theme.code.alignment,
+            alignment,
             policy,
             exec_type,
         );
@@ -267,11 +625,15 @@ impl PresentationBuilder<'_, '_> {
     }
 
     fn push_code_as_image(&mut self, snippet: Snippet) -> BuildResult {
-        let executor = self.snippet_executor.language_executor(&snippet.language, &Default::default())?;
+        let executor = self.language_executor(&snippet.language, &Default::default())?;
         self.push_validator(&snippet, &executor);
 
-        let operation =
-            RunImageSnippet::new(snippet, executor, self.image_registry.clone(), self.theme.execution_output.status);
+        let operation = RunImageSnippet::new(
+            snippet,
+            executor,
+            self.image_registry.clone(),
+            self.theme.execution_output.status.clone(),
+        );
         let operation = RenderOperation::RenderAsync(Rc::new(operation));
         self.chunk_operations.push(operation);
         Ok(())
@@ -283,12 +645,12 @@ impl PresentationBuilder<'_, '_> {
         block_length: u16,
         spec: &SnippetExecutorSpec,
     ) -> BuildResult {
-        let executor = self.snippet_executor.language_executor(&snippet.language, spec)?;
-        let block_length = self.theme.code.alignment.adjust_size(block_length);
+        let executor = self.language_executor(&snippet.language, spec)?;
+        let block_length = self.code_style(&snippet).alignment.adjust_size(block_length);
         let operation = RunAcquireTerminalSnippet::new(
             snippet,
             executor,
-            self.theme.execution_output.status,
+            self.theme.execution_output.status.clone(),
             block_length,
             self.slide_font_size(),
         );
@@ -297,12 +659,13 @@ impl PresentationBuilder<'_, '_> {
         Ok(())
     }
 
-    fn push_indicator(&mut self, handle: SnippetHandle, block_length: u16, alignment: Alignment) {
+    fn push_indicator(&mut self, handle: SnippetHandle, block_length: u16, alignment: Alignment, compact: bool) {
         let style = ExecIndicatorStyle {
-            theme: self.theme.execution_output.status,
+            theme: self.theme.execution_output.status.clone(),
             block_length,
             font_size: self.slide_font_size(),
             alignment,
+            compact,
         };
         let indicator = Rc::new(ExecIndicator::new(handle, style));
         self.chunk_operations.push(RenderOperation::RenderDynamic(indicator));
@@ -319,6 +682,11 @@ impl PresentationBuilder<'_, '_> {
             execution_output_style.style.colors.background = None;
             execution_output_style.padding = Default::default();
         }
+        let highlighter = snippet
+            .attributes
+            .output_language
+            .clone()
+            .map(|language| OutputHighlighter::new(self.highlighter.clone(), language, self.code_style(&snippet)));
         let operation = SnippetOutputOperation::new(
             handle,
             default_colors,
@@ -326,12 +694,29 @@ impl PresentationBuilder<'_, '_> {
             block_length,
             alignment,
             self.slide_font_size(),
-        );
+            self.options.max_snippet_output_rows,
+        )
+        .match_code_width(self.options.match_snippet_output_width)
+        .overflow(self.options.snippet_output_overflow)
+        .highlight_as(highlighter);
         let operation = RenderOperation::RenderDynamic(Rc::new(operation));
         self.chunk_operations.push(operation);
         Ok(())
     }
 
+    fn push_copyable(&mut self, snippet: &Snippet) {
+        let hidden_line_prefix = self.hidden_line_prefix(&snippet.language);
+        let contents = match self.options.copy_hidden_lines {
+            true => snippet.executable_contents(hidden_line_prefix),
+            false => snippet.visible_lines(hidden_line_prefix).fold(String::new(), |mut output, line| {
+                output.push_str(line);
+                output.push('\n');
+                output
+            }),
+        };
+        self.chunk_operations.push(RenderOperation::RenderDynamic(Rc::new(Copyable(contents))));
+    }
+
     fn push_differ(&mut self, text: String) {
         self.chunk_operations.push(RenderOperation::RenderDynamic(Rc::new(Differ(text))));
     }
@@ -348,6 +733,20 @@ impl PresentationBuilder<'_, '_> {
 #[derive(Debug)]
 struct Differ(String);
 
+/// The contents of a snippet that get copied into the clipboard.
+#[derive(Debug)]
+struct Copyable(String);
+
+impl AsRenderOperations for Copyable {
+    fn as_render_operations(&self, _: &WindowSize) -> Vec<RenderOperation> {
+        Vec::new()
+    }
+
+    fn copyable_content(&self) -> Option<&str> {
+        Some(&self.0)
+    }
+}
+
 impl AsRenderOperations for Differ {
     fn as_render_operations(&self, _: &WindowSize) -> Vec<RenderOperation> {
         Vec::new()
@@ -358,10 +757,33 @@ impl AsRenderOperations for Differ {
     }
 }
 
+/// A snippet that would be executed when presenting, along with the commands used to run it.
+#[derive(Debug)]
+pub(crate) struct SnippetCommands {
+    /// The slide the snippet is in, starting at 1.
+    pub(crate) slide: usize,
+    pub(crate) language: SnippetLanguage,
+
+    /// The attribute that causes this snippet to be executed.
+    pub(crate) attribute: &'static str,
+
+    /// The commands that would be run, or the reason why the snippet can't be executed.
+    pub(crate) commands: Result<Vec<Vec<String>>, String>,
+    pub(crate) contents: String,
+
+    /// The executor that runs this snippet, if it can be executed.
+    pub(crate) executor: Option<LanguageSnippetExecutor>,
+    pub(crate) snippet: Snippet,
+}
+
 #[cfg(all(test, target_os = "linux"))]
 mod tests {
     use super::*;
-    use crate::{markdown::text_style::Color, presentation::builder::utils::Test, theme::raw};
+    use crate::{
+        markdown::text_style::Color,
+        presentation::builder::{PresentationBuilderOptions, utils::Test},
+        theme::raw,
+    };
     use rstest::rstest;
     use std::fs;
 
@@ -395,6 +817,17 @@ echo hi
         assert_eq!(lines, expected);
     }
 
+    #[test]
+    fn plain_attribute() {
+        let input = "
+```bash +plain +line_numbers
+echo hi
+```";
+        let lines = Test::new(input).render().rows(3).columns(9).into_lines();
+        let expected = &["         ", "1 echo hi", "         "];
+        assert_eq!(lines, expected);
+    }
+
     #[test]
     fn external_snippet() {
         let temp = tempfile::NamedTempFile::new().expect("failed to create tempfile");
@@ -415,6 +848,151 @@ language: bash
         assert_eq!(lines, expected);
     }
 
+    #[rstest]
+    #[case::continuous("continuous", &["1 a", "  -", "2 b", "3 c"])]
+    #[case::per_file("per_file", &["1 a", "  -", "1 b", "2 c"])]
+    fn external_snippet_multiple_files(#[case] numbering: &str, #[case] expected: &[&str]) {
+        let first = tempfile::NamedTempFile::new().expect("failed to create tempfile");
+        let second = tempfile::NamedTempFile::new().expect("failed to create tempfile");
+        fs::write(first.path(), "a\n").unwrap();
+        fs::write(second.path(), "x\nb\nc\n").unwrap();
+
+        let first = first.path().to_string_lossy();
+        let second = second.path().to_string_lossy();
+        let input = format!(
+            "
+```file +line_numbers
+language: bash
+separator: \"-\"
+numbering: {numbering}
+files:
+  - path: {first}
+  - path: {second}
+    start_line: 2
+```
+"
+        );
+        let lines = Test::new(input).render().rows(6).columns(4).into_lines();
+        let lines: Vec<_> = lines[1..5].iter().map(|line| line.trim_end()).collect();
+        assert_eq!(lines, expected);
+    }
+
+    #[test]
+    fn external_snippet_line_links() {
+        let temp = tempfile::NamedTempFile::new().expect("failed to create tempfile");
+        fs::write(temp.path(), "a\nb\nc\n").unwrap();
+
+        let path = temp.path().to_string_lossy();
+        let input = format!(
+            "
+```file +line_numbers
+path: {path}
+language: bash
+start_line: 2
+line_url: \"https://example.com/file.sh#L{{line}}\"
+```
+"
+        );
+        let presentation = Test::new(input).build();
+        let dimensions = WindowSize { rows: 10, columns: 10, height: 0, width: 0 };
+        let links: Vec<_> = presentation.slides[0]
+            .iter_operations()
+            .filter_map(|operation| match operation {
+                RenderOperation::RenderDynamic(operation) => Some(operation.as_render_operations(&dimensions)),
+                _ => None,
+            })
+            .flatten()
+            .filter_map(|operation| match operation {
+                RenderOperation::RenderBlockLine(line) => line.prefix_link,
+                _ => None,
+            })
+            .collect();
+        assert_eq!(links, &["https://example.com/file.sh#L2", "https://example.com/file.sh#L3"]);
+    }
+
+    #[rstest]
+    #[case::none("language: bash")]
+    #[case::both("language: bash\npath: foo\nfiles:\n  - path: bar")]
+    #[case::top_level_range("language: bash\nstart_line: 2\nfiles:\n  - path: bar")]
+    #[case::top_level_url("language: bash\nline_url: foo\nfiles:\n  - path: bar")]
+    fn external_snippet_invalid(#[case] input: &str) {
+        let input = format!("```file\n{input}\n```");
+        Test::new(input).expect_invalid();
+    }
+
+    #[test]
+    fn presentation_hidden_line_prefix() {
+        let input = "---
+options:
+  hidden_line_prefix: \"/// \"
+---
+```bash
+/// echo hidden
+echo hi
+```";
+        let lines = Test::new(input).render().rows(3).columns(7).into_lines();
+        let expected = &["       ", "echo hi", "       "];
+        assert_eq!(lines, expected);
+    }
+
+    #[test]
+    fn qr_code() {
+        let input = "
+```qr +width:50%
+https://example.com
+```";
+        let presentation = Test::new(input).build();
+        let sizes: Vec<_> = presentation
+            .current_slide()
+            .iter_operations()
+            .filter_map(|operation| match operation {
+                RenderOperation::RenderImage(_, properties) => Some(properties.size.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(sizes, &[ImageSize::WidthScaled { ratio: 0.5 }]);
+    }
+
+    #[test]
+    fn qr_code_too_long() {
+        let input = format!("```qr +error_correction:high\n{}\n```", "a".repeat(120));
+        Test::new(input).expect_invalid();
+    }
+
+    #[rstest]
+    #[case::visible(false, "echo hi\n")]
+    #[case::hidden(true, "echo hidden\necho hi\n")]
+    fn copyable_content(#[case] copy_hidden_lines: bool, #[case] expected: &str) {
+        let input = "
+```bash
+/// echo hidden
+echo hi
+```";
+        let options = PresentationBuilderOptions {
+            hidden_line_prefix: Some("/// ".into()),
+            copy_hidden_lines,
+            ..Default::default()
+        };
+        let presentation = Test::new(input).options(options).build();
+        let contents: Vec<_> = presentation
+            .current_slide()
+            .iter_operations()
+            .filter_map(|operation| match operation {
+                RenderOperation::RenderDynamic(operation) => operation.copyable_content().map(ToString::to_string),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(contents, &[expected]);
+    }
+
+    #[rstest]
+    #[case::empty("\"\"")]
+    #[case::newline("\"a\\nb\"")]
+    fn invalid_presentation_hidden_line_prefix(#[case] prefix: &str) {
+        let input = format!("---\noptions:\n  hidden_line_prefix: {prefix}\n---\nhi");
+        Test::new(input).expect_invalid();
+    }
+
     #[test]
     fn line_numbers() {
         let input = "
@@ -427,6 +1005,47 @@ bye
         assert_eq!(lines, expected);
     }
 
+    #[test]
+    fn force_line_numbers() {
+        let input = "
+```bash
+hi
+bye
+```";
+        let options = PresentationBuilderOptions { force_line_numbers: true, ..Default::default() };
+        let lines = Test::new(input).options(options).render().rows(4).columns(5).into_lines();
+        let expected = &["     ", "1 hi ", "2 bye", "     "];
+        assert_eq!(lines, expected);
+    }
+
+    #[test]
+    fn snippet_defaults() {
+        let input = "---
+options:
+  snippet_defaults: +line_numbers
+---
+```bash
+hi
+bye
+```";
+        let lines = Test::new(input).render().rows(4).columns(5).into_lines();
+        let expected = &["     ", "1 hi ", "2 bye", "     "];
+        assert_eq!(lines, expected);
+    }
+
+    #[rstest]
+    #[case::invalid("+potato")]
+    #[case::id("+id:foo")]
+    #[case::pipe_from("+pipe_from:foo")]
+    fn invalid_snippet_defaults(#[case] defaults: &str) {
+        let input = "
+```bash
+hi
+```";
+        let options = PresentationBuilderOptions { snippet_defaults: Some(defaults.into()), ..Default::default() };
+        Test::new(input).options(options).expect_invalid();
+    }
+
     #[test]
     fn surroundings() {
         let input = "
@@ -440,6 +1059,60 @@ echo hi
         assert_eq!(lines, expected);
     }
 
+    #[rstest]
+    #[case::rounded(raw::CodeBlockBorder::Rounded, 10, &["          ", "╭────────╮", "│echo hi │", "╰────────╯"])]
+    #[case::square(raw::CodeBlockBorder::Square, 12, &["            ", "┌──────────┐", "│echo hi   │", "└──────────┘"])]
+    #[case::too_narrow(raw::CodeBlockBorder::Rounded, 9, &["         ", "         ", "echo hi  ", "         "])]
+    fn code_border(#[case] border: raw::CodeBlockBorder, #[case] columns: u16, #[case] expected: &[&str]) {
+        let input = "
+```bash
+echo hi
+```";
+        let theme = raw::PresentationTheme {
+            code: raw::CodeBlockStyle { border: Some(border), ..Default::default() },
+            ..Default::default()
+        };
+        let lines = Test::new(input).theme(theme).render().rows(4).columns(columns).into_lines();
+        assert_eq!(lines, expected);
+    }
+
+    #[rstest]
+    #[case::wrap(raw::CodeBlockOverflow::Wrap, &["echo https:/", "/example.com", "/a          "])]
+    #[case::clip(raw::CodeBlockOverflow::Clip, &["echo https:…", "            ", "            "])]
+    fn code_overflow(#[case] overflow: raw::CodeBlockOverflow, #[case] expected: &[&str]) {
+        let input = "
+```bash
+echo https://example.com/a
+```";
+        let theme = raw::PresentationTheme {
+            code: raw::CodeBlockStyle { overflow: Some(overflow), ..Default::default() },
+            ..Default::default()
+        };
+        let lines = Test::new(input).theme(theme).render().rows(4).columns(12).into_lines();
+        assert_eq!(&lines[1..], expected);
+    }
+
+    #[test]
+    fn highlight_context_lines() {
+        let input = "
+```bash {1|6}
+a
+b
+c
+d
+e
+f
+```";
+        let options = PresentationBuilderOptions { highlight_context_lines: Some(1), ..Default::default() };
+        let lines = Test::new(input).options(options.clone()).render().rows(5).columns(3).into_lines();
+        let expected = &["   ", "a  ", "b  ", "⋮  ", "   "];
+        assert_eq!(lines, expected);
+
+        let lines = Test::new(input).options(options).render().rows(5).columns(3).advances(1).into_lines();
+        let expected = &["   ", "⋮  ", "e  ", "f  ", "   "];
+        assert_eq!(lines, expected);
+    }
+
     #[test]
     fn padding() {
         let input = "
@@ -448,7 +1121,7 @@ echo hi
 ```";
         let theme = raw::PresentationTheme {
             code: raw::CodeBlockStyle {
-                padding: raw::PaddingRect { horizontal: Some(2), vertical: Some(1) },
+                padding: raw::PaddingRect { horizontal: Some(2), vertical: Some(1), ..Default::default() },
                 ..Default::default()
             },
             ..Default::default()
@@ -458,6 +1131,73 @@ echo hi
         assert_eq!(lines, expected);
     }
 
+    #[test]
+    fn asymmetric_padding() {
+        let input = "
+```bash
+echo hi
+```
+---";
+        let theme = raw::PresentationTheme {
+            code: raw::CodeBlockStyle {
+                padding: raw::PaddingRect {
+                    horizontal: Some(3),
+                    vertical: Some(1),
+                    top: Some(2),
+                    left: Some(1),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let lines = Test::new(input).theme(theme).render().rows(7).columns(13).into_lines();
+        // the separator shows where the bottom padding ends
+        let expected = &[
+            "             ",
+            "             ",
+            "             ",
+            " echo hi     ",
+            "             ",
+            "             ",
+            "—————————————",
+        ];
+        assert_eq!(lines, expected);
+    }
+
+    #[test]
+    fn collect_snippet_commands() {
+        let input = "
+```bash +exec
+echo hi
+```
+<!-- end_slide -->
+```bash
+echo nope
+```
+```bash +exec_replace
+echo bye
+```";
+        let dir = tempfile::tempdir().expect("no temp dir");
+        let path = dir.path().join("presentation.md");
+        fs::write(&path, input).expect("write failed");
+
+        let snippets = Test::new("")
+            .disable_exec()
+            .with_builder(|builder| builder.collect_snippet_commands(&path))
+            .expect("collect failed");
+        let snippets: Vec<_> = snippets
+            .into_iter()
+            .map(|snippet| (snippet.slide, snippet.attribute, snippet.commands.unwrap(), snippet.contents))
+            .collect();
+        let commands = vec![vec!["bash".to_string(), "$pwd/script.sh".to_string()]];
+        let expected = vec![
+            (1, "+exec", commands.clone(), "echo hi\n".to_string()),
+            (2, "+exec_replace", commands, "echo bye\n".to_string()),
+        ];
+        assert_eq!(snippets, expected);
+    }
+
     #[test]
     fn exec_no_run() {
         let input = "
@@ -487,6 +1227,68 @@ echo hi
         assert_eq!(lines, expected);
     }
 
+    #[rstest]
+    #[case::empty("bash", "")]
+    #[case::whitespace("bash", "  \n")]
+    #[case::hidden_only("rust", "# \n# \n")]
+    fn exec_empty(#[case] language: &str, #[case] code: &str) {
+        let input = format!("```{language} +exec\n{code}```");
+        let lines = Test::new(input).render().rows(4).columns(32).into_lines();
+        let note = lines.iter().filter(|line| line.trim() == "nothing to run: snippet is empty").count();
+        assert_eq!(note, 1, "{lines:#?}");
+        assert!(!lines.iter().any(|line| line.contains("finished")), "{lines:#?}");
+    }
+
+    #[test]
+    fn exec_status_labels() {
+        let input = "
+```bash +exec
+echo hi
+```";
+        let theme = raw::PresentationTheme {
+            execution_output: raw::ExecutionOutputBlockStyle {
+                status: raw::ExecutionStatusBlockStyle {
+                    labels: raw::ExecutionStatusLabels { not_started: Some("▶".into()), ..Default::default() },
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let lines = Test::new(input).theme(theme).render().rows(4).columns(11).run_async_renders(false).into_lines();
+        let expected = &["           ", "echo hi    ", "           ", "——— [▶] ———"];
+        assert_eq!(lines, expected);
+    }
+
+    #[test]
+    fn trusted_exec_disabled() {
+        let input = "
+```bash +exec +trusted
+echo hi
+```";
+        let lines = Test::new(input).disable_exec().render().rows(6).columns(25).into_lines();
+        let expected = &[
+            "                         ",
+            "echo hi                  ",
+            "                         ",
+            "snippet +exec is         ",
+            "disabled, run with -x to ",
+            "enable                   ",
+        ];
+        assert_eq!(lines, expected);
+    }
+
+    #[test]
+    fn trusted_exec_allowed() {
+        let input = "
+```bash +exec +trusted
+echo hi
+```";
+        let lines = Test::new(input).disable_exec().allow_trusted_snippets().render().rows(4).columns(16).into_lines();
+        let expected = &["                ", "echo hi         ", "                ", "—— [finished] ——"];
+        assert_eq!(lines, expected);
+    }
+
     #[test]
     fn exec_replace_disabled() {
         let input = "
@@ -517,7 +1319,7 @@ echo hi
                     background: Some(raw::RawColor::Color(Color::new(45, 45, 45))),
                     foreground: None,
                 },
-                padding: raw::PaddingRect { horizontal: Some(1), vertical: Some(1) },
+                padding: raw::PaddingRect { horizontal: Some(1), vertical: Some(1), ..Default::default() },
                 ..Default::default()
             },
             ..Default::default()
@@ -553,6 +1355,185 @@ echo hi
         assert_eq!(styles, expected_styles);
     }
 
+    #[rstest]
+    #[case::success("true", 'x')]
+    #[case::failure("false", 'f')]
+    fn exec_failure_colors(#[case] code: &str, #[case] expected: char) {
+        let input = format!(
+            "
+```bash +exec
+echo hi; {code}
+```"
+        );
+        let theme = raw::PresentationTheme {
+            execution_output: raw::ExecutionOutputBlockStyle {
+                colors: raw::RawColors {
+                    background: Some(raw::RawColor::Color(Color::new(45, 45, 45))),
+                    foreground: None,
+                },
+                failure: raw::RawColors {
+                    background: Some(raw::RawColor::Color(Color::new(100, 0, 0))),
+                    foreground: None,
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let (_, styles) = Test::new(input)
+            .theme(theme)
+            .render()
+            .map_background(Color::new(45, 45, 45), 'x')
+            .map_background(Color::new(100, 0, 0), 'f')
+            .rows(6)
+            .columns(16)
+            .into_parts();
+        assert_eq!(styles[5], expected.to_string().repeat(16), "{styles:#?}");
+    }
+
+    #[rstest]
+    #[case::attribute("+compact", false)]
+    #[case::config("", true)]
+    fn exec_compact(#[case] attribute: &str, #[case] compact_snippet_output: bool) {
+        let input = format!(
+            "
+```bash +exec {attribute}
+echo hi
+```"
+        );
+        let theme = raw::PresentationTheme {
+            execution_output: raw::ExecutionOutputBlockStyle {
+                colors: raw::RawColors {
+                    background: Some(raw::RawColor::Color(Color::new(45, 45, 45))),
+                    foreground: None,
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let options =
+            PresentationBuilderOptions { enable_snippet_execution: true, compact_snippet_output, ..Default::default() };
+        let (lines, styles) = Test::new(input)
+            .options(options)
+            .theme(theme)
+            .render()
+            .map_background(Color::new(45, 45, 45), 'x')
+            .rows(5)
+            .columns(16)
+            .into_parts();
+        let expected_lines =
+            &["                ", "echo hi         ", "—— [finished] ——", "hi              ", "                "];
+        let expected_styles =
+            &["                ", "xxxxxxxxxxxxxxxx", "                ", "xxxxxxxxxxxxxxxx", "                "];
+        assert_eq!(lines, expected_lines);
+        assert_eq!(styles, expected_styles);
+    }
+
+    #[rstest]
+    #[case::default(false, "       hi       ", "       xx       ")]
+    #[case::matching(true, "    hi          ", "    xxxxxxxx    ")]
+    fn exec_match_code_width(
+        #[case] match_snippet_output_width: bool,
+        #[case] output_line: &str,
+        #[case] output_style: &str,
+    ) {
+        let input = "
+```bash +exec
+echo hi
+```";
+        let background = Some(raw::RawColor::Color(Color::new(45, 45, 45)));
+        let theme = raw::PresentationTheme {
+            code: raw::CodeBlockStyle {
+                alignment: Some(raw::Alignment::Center { minimum_margin: raw::Margin::Fixed(0), minimum_size: 0 }),
+                ..Default::default()
+            },
+            execution_output: raw::ExecutionOutputBlockStyle {
+                colors: raw::RawColors { background, foreground: None },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let options = PresentationBuilderOptions {
+            enable_snippet_execution: true,
+            match_snippet_output_width,
+            ..Default::default()
+        };
+        let (lines, styles) = Test::new(input)
+            .options(options)
+            .theme(theme)
+            .render()
+            .map_background(Color::new(45, 45, 45), 'x')
+            .rows(6)
+            .columns(16)
+            .into_parts();
+        assert_eq!(lines[1], "    echo hi     ");
+        assert_eq!(styles[1], "    xxxxxxxx    ");
+        assert_eq!(lines[5], output_line);
+        assert_eq!(styles[5], output_style);
+    }
+
+    #[rstest]
+    #[case::top(0, &["1 a   █", "2 b   │"])]
+    #[case::middle(1, &["2 b   █", "3 ccc │"])]
+    #[case::bottom(5, &["3 ccc │", "4 d   █"])]
+    fn max_rows(#[case] scrolls: usize, #[case] expected: &[&str]) {
+        let input = "
+```bash +line_numbers +max_rows:2
+a
+b
+ccc
+d
+```";
+        let lines = Test::new(input).render().scrolls(scrolls).rows(4).columns(7).into_lines();
+        let expected: Vec<_> = ["       "].into_iter().chain(expected.iter().copied()).chain(["       "]).collect();
+        assert_eq!(lines, expected);
+    }
+
+    #[rstest]
+    #[case::first(0, &["1 a   █", "2 b   │"])]
+    #[case::scrolled(1, &["3 ccc │", "4 d   █"])]
+    #[case::all(2, &["3 ccc │", "4 d   █"])]
+    fn max_rows_highlight_groups(#[case] advances: usize, #[case] expected: &[&str]) {
+        let input = "
+```bash +line_numbers +max_rows:2 {1|4|all}
+a
+b
+ccc
+d
+```";
+        let lines = Test::new(input).render().advances(advances).rows(4).columns(7).into_lines();
+        let expected: Vec<_> = ["       "].into_iter().chain(expected.iter().copied()).chain(["       "]).collect();
+        assert_eq!(lines, expected);
+    }
+
+    #[test]
+    fn max_rows_fitting() {
+        let input = "
+```bash +max_rows:2
+a
+b
+```";
+        let lines = Test::new(input).render().rows(4).columns(3).into_lines();
+        assert_eq!(lines, &["   ", "a  ", "b  ", "   "]);
+    }
+
+    #[test]
+    fn exec_invalid_utf8() {
+        let input = r"
+```bash +exec
+printf 'a\377b'
+```";
+        let lines = Test::new(input).render().rows(6).columns(16).into_lines();
+        let expected = &[
+            "                ",
+            "printf 'a\\377b' ",
+            "                ",
+            "—— [finished] ——",
+            "                ",
+            "a\u{FFFD}b             ",
+        ];
+        assert_eq!(lines, expected);
+    }
+
     #[test]
     fn exec_font_size() {
         let input = "
@@ -591,7 +1572,7 @@ echo hi
                     background: Some(raw::RawColor::Color(Color::new(45, 45, 45))),
                     foreground: None,
                 },
-                padding: raw::PaddingRect { horizontal: Some(1), vertical: Some(1) },
+                padding: raw::PaddingRect { horizontal: Some(1), vertical: Some(1), ..Default::default() },
                 ..Default::default()
             },
             ..Default::default()
@@ -668,6 +1649,72 @@ bar
         assert_eq!(lines, expected);
     }
 
+    #[test]
+    fn exec_pipe_from() {
+        let input = "
+```bash +exec +id:foo
+echo hi
+echo bye >&2
+```
+
+```bash +exec +pipe_from:foo
+tr a-z A-Z
+```";
+        let lines = Test::new(input).render().rows(11).columns(16).into_lines();
+        let expected = &[
+            "                ",
+            "echo hi         ",
+            "echo bye >&2    ",
+            "                ",
+            "—— [finished] ——",
+            "                ",
+            "tr a-z A-Z      ",
+            "                ",
+            "—— [finished] ——",
+            "                ",
+            "HI              ",
+        ];
+        assert_eq!(lines, expected);
+    }
+
+    #[test]
+    fn exec_pipe_from_failed() {
+        let input = "
+```bash +exec +id:foo
+exit 1
+```
+
+```bash +exec +pipe_from:foo
+cat
+```";
+        let lines = Test::new(input).render().rows(10).columns(36).into_lines();
+        assert_eq!(lines[9].trim_end(), "snippet 'foo' failed");
+    }
+
+    #[test]
+    fn theme_override() {
+        let input = "
+```bash +theme:InspiredGitHub
+hi
+```";
+        let (_, styles) =
+            Test::new(input).render().map_background(Color::new(255, 255, 255), 'X').rows(3).columns(6).into_parts();
+        let expected = &["      ", "XXXXXX", "      "];
+        assert_eq!(styles, expected);
+    }
+
+    #[test]
+    fn unknown_theme() {
+        Test::new("```bash +theme:potato\nhi\n```").expect_invalid();
+    }
+
+    #[rstest]
+    #[case::undefined("```bash +exec +pipe_from:foo\ncat\n```")]
+    #[case::not_exec("```bash +exec +id:foo\necho hi\n```\n```bash +pipe_from:foo\ncat\n```")]
+    fn exec_pipe_from_invalid(#[case] input: &str) {
+        Test::new(input).expect_invalid();
+    }
+
     #[test]
     fn exec_replace() {
         let input = "
@@ -679,6 +1726,59 @@ echo hi
         assert_eq!(lines, expected);
     }
 
+    #[test]
+    fn exec_replace_as_language() {
+        let input = r#"
+```bash +exec_replace +as:rust
+printf '\033[1mlet\033[0m x = 1;\n'
+```"#;
+        let lines = Test::new(input).render().rows(3).columns(12).into_lines();
+        let expected = &["            ", "let x = 1;  ", "            "];
+        assert_eq!(lines, expected);
+    }
+
+    #[rstest]
+    #[case::top_left("top_left", &[" hi       ", "hello     ", "          "])]
+    #[case::top_right("top_right", &["       hi ", "hello     ", "          "])]
+    #[case::bottom("bottom", &["          ", "hello     ", "    hi    "])]
+    fn chrome(#[case] position: &str, #[case] expected: &[&str]) {
+        let input = format!(
+            "
+hello
+
+```bash +chrome:{position}
+echo hi
+```"
+        );
+        let lines = Test::new(input).render().rows(3).columns(10).into_lines();
+        assert_eq!(lines, expected);
+    }
+
+    #[test]
+    fn reuse_snippet_handles() {
+        let input = "
+```bash +exec_replace
+echo hi
+```";
+        let mut presentation = Test::new(input).build();
+        for operation in presentation.current_slide_mut().iter_operations_mut() {
+            if let RenderOperation::RenderAsync(operation) = operation {
+                let mut pollable = operation.pollable();
+                while !pollable.poll().is_completed() {
+                    std::thread::sleep(std::time::Duration::from_millis(1));
+                }
+            }
+        }
+        let handles = presentation.state.snippet_handles();
+        assert_eq!(handles.len(), 1);
+
+        // The snippet already ran so its output should be there without running it again.
+        let render = Test::new(input).reuse_snippet_handles(handles).render().run_async_renders(false);
+        let lines = render.rows(3).columns(7).into_lines();
+        let expected = &["       ", "hi     ", "       "];
+        assert_eq!(lines, expected);
+    }
+
     #[test]
     fn snippet_exec_replace_centered() {
         let input = "
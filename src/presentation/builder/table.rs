@@ -1,17 +1,23 @@
 use crate::{
-    markdown::elements::{Line, Table, TableRow, Text},
+    code::snippet::SnippetLanguage,
+    markdown::elements::{InlineImage, Line, Table, TableRow, Text},
     presentation::builder::{BuildResult, PresentationBuilder, error::BuildError},
+    render::operation::RenderOperation,
     theme::ElementType,
+    ui::inline_image::{EmbeddedImage, InlineImageLine},
 };
-use std::iter;
+use std::{iter, rc::Rc};
 
 impl PresentationBuilder<'_, '_> {
     pub(crate) fn push_table(&mut self, table: Table) -> BuildResult {
-        let widths: Vec<_> = (0..table.columns())
-            .map(|column| table.iter_column(column).map(|text| text.width()).max().unwrap_or(0))
+        let columns = table.columns();
+        let header = self.resolve_table_row(table.header)?;
+        let rows = table.rows.into_iter().map(|row| self.resolve_table_row(row)).collect::<Result<Vec<_>, _>>()?;
+        let widths: Vec<_> = (0..columns)
+            .map(|column| iter::once(&header).chain(&rows).map(|row| row.cells[column].width()).max().unwrap_or(0))
             .collect();
-        let flattened_header = self.prepare_table_row(table.header, &widths)?;
-        self.push_text(flattened_header, ElementType::Table);
+        let flattened_header = Self::prepare_table_row(header.cells, &widths);
+        self.push_table_row(flattened_header, header.images, &widths);
         self.push_line_break();
 
         let mut separator = Line(Vec::new());
@@ -32,23 +38,104 @@ impl PresentationBuilder<'_, '_> {
         self.push_text(separator, ElementType::Table);
         self.push_line_break();
 
-        for row in table.rows {
-            let flattened_row = self.prepare_table_row(row, &widths)?;
-            self.push_text(flattened_row, ElementType::Table);
+        for row in rows {
+            let flattened_row = Self::prepare_table_row(row.cells, &widths);
+            self.push_table_row(flattened_row, row.images, &widths);
             self.push_line_break();
         }
         Ok(())
     }
 
-    fn prepare_table_row(&self, row: TableRow, widths: &[usize]) -> Result<Line, BuildError> {
+    fn resolve_table_row(&self, row: TableRow) -> Result<ResolvedRow, BuildError> {
+        let mut cells = Vec::new();
+        let mut images = Vec::new();
+        for (index, cell) in row.0.into_iter().enumerate() {
+            let text = cell.contents.resolve(&self.theme.palette)?;
+            let mut image = cell.image;
+            let mut chunks = Vec::new();
+            for (position, text) in text.0.into_iter().enumerate() {
+                if let Some(image) = image.take_if(|image| image.position == position) {
+                    images.push((index, self.embed_table_cell_image(&mut chunks, image)?));
+                }
+                chunks.extend(self.highlight_inline_code(text));
+            }
+            // The image goes after every piece of text in the cell.
+            if let Some(image) = image {
+                images.push((index, self.embed_table_cell_image(&mut chunks, image)?));
+            }
+            cells.push(Line(chunks));
+        }
+        Ok(ResolvedRow { cells, images })
+    }
+
+    // Reserves space for an image at the end of the given cell contents.
+    fn embed_table_cell_image(&self, chunks: &mut Vec<Text>, image: InlineImage) -> Result<EmbeddedImage, BuildError> {
+        let label = image.path.display().to_string();
+        let (image, columns) = self.load_inline_image(image)?;
+        let column = chunks.iter().map(Text::width).sum::<usize>() as u16;
+        chunks.push(Text::from(" ".repeat(columns as usize)));
+        Ok(EmbeddedImage { image, column, columns, label })
+    }
+
+    fn push_table_row(&mut self, row: Line, images: Vec<(usize, EmbeddedImage)>, widths: &[usize]) {
+        if images.is_empty() {
+            self.push_text(row, ElementType::Table);
+            return;
+        }
+        let images: Vec<_> = images
+            .into_iter()
+            .map(|(cell, mut image)| {
+                // Every cell is preceded by the cells before it plus a " │ " separator.
+                let cell_start = widths[..cell].iter().sum::<usize>() + cell * 3;
+                image.column += cell_start as u16;
+                image
+            })
+            .collect();
+        let alignment = self.slide_state.alignment.unwrap_or_else(|| self.theme.alignment(&ElementType::Table));
+        let row = self.style_text(row);
+        let layout_length = row.width() as u16;
+        // Every image's label is shown in its place if they can't be displayed.
+        let fallback = RenderOperation::RenderText { line: EmbeddedImage::label_line(&row, &images).into(), alignment };
+        let operation = RenderOperation::RenderText { line: row.into(), alignment };
+        let background = self.theme.default_style.style.colors.background;
+        let font_size = self.slide_font_size();
+        let line = InlineImageLine::new(operation, fallback, images, alignment, layout_length, font_size, background);
+        self.chunk_operations.push(RenderOperation::RenderDynamic(Rc::new(line)));
+    }
+
+    // Inline code that ends in a language tag, like `let x = 1{:rust}`, is highlighted as that
+    // language. Anything else is left as is.
+    fn highlight_inline_code(&self, text: Text) -> Vec<Text> {
+        if !text.style.is_code() {
+            return vec![text];
+        }
+        let Some((code, language)) = text.content.strip_suffix('}').and_then(|content| content.rsplit_once("{:"))
+        else {
+            return vec![text];
+        };
+        let language: SnippetLanguage = language.parse().expect("language parsing");
+        if matches!(language, SnippetLanguage::Unknown(_)) {
+            return vec![text];
+        }
+        let background = self.theme.inline_code.style.colors.background;
+        let line = self.highlighter.language_highlighter(&language).highlight_line(code, &self.theme.code);
+        line.0
+            .into_iter()
+            .map(|mut chunk| {
+                chunk.style.colors.background = chunk.style.colors.background.or(background);
+                chunk
+            })
+            .collect()
+    }
+
+    fn prepare_table_row(row: Vec<Line>, widths: &[usize]) -> Line {
         let mut flattened_row = Line(Vec::new());
-        for (column, text) in row.0.into_iter().enumerate() {
-            let text = text.resolve(&self.theme.palette)?;
+        for (column, text) in row.into_iter().enumerate() {
             if column > 0 {
                 flattened_row.0.push(Text::from(" │ "));
             }
             let text_length = text.width();
-            flattened_row.0.extend(text.0.into_iter());
+            flattened_row.0.extend(text.0);
 
             let cell_width = widths[column];
             if text_length < cell_width {
@@ -56,13 +143,19 @@ impl PresentationBuilder<'_, '_> {
                 flattened_row.0.push(Text::from(padding));
             }
         }
-        Ok(flattened_row)
+        flattened_row
     }
 }
 
+struct ResolvedRow {
+    cells: Vec<Line>,
+    // The index of the cell every image is in, along with the image itself.
+    images: Vec<(usize, EmbeddedImage)>,
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::presentation::builder::utils::Test;
+    use crate::{markdown::text_style::Color, presentation::builder::utils::Test, theme::raw};
 
     #[test]
     fn table() {
@@ -83,4 +176,39 @@ mod tests {
         ];
         assert_eq!(lines, expected_lines);
     }
+
+    #[test]
+    fn highlighted_inline_code() {
+        let input = "
+| Code                 | Other        |
+| -------------------- | ------------ |
+| `let x = 1{:rust}`   | `a{:potato}` |
+";
+        let color = Color::new(1, 1, 1);
+        let theme = raw::PresentationTheme {
+            inline_code: raw::InlineCodeStyle {
+                colors: raw::RawColors { foreground: None, background: Some(raw::RawColor::Color(color)) },
+            },
+            code: raw::CodeBlockStyle { background: Some(false), ..Default::default() },
+            ..Default::default()
+        };
+        let (lines, styles) =
+            Test::new(input).theme(theme).render().map_background(color, 'X').rows(5).columns(24).into_parts();
+        let expected_lines = &[
+            "                        ",
+            "Code      │ Other       ",
+            "──────────┼───────────  ",
+            "let x = 1 │ a{:potato}  ",
+            "                        ",
+        ];
+        let expected_styles = &[
+            "                        ",
+            "                        ",
+            "                        ",
+            "XXXXXXXXX   XXXXXXXXXX  ",
+            "                        ",
+        ];
+        assert_eq!(lines, expected_lines);
+        assert_eq!(styles, expected_styles);
+    }
 }
@@ -1,5 +1,6 @@
 use super::*;
-use crate::presentation::builder::utils::Test;
+use crate::{presentation::builder::utils::Test, theme::raw};
+use rstest::rstest;
 
 #[test]
 fn prelude_appears_once() {
@@ -70,6 +71,23 @@ hi
     assert_eq!(lines, &["     ", "hi   "]);
 }
 
+#[rstest]
+#[case::thin(None, "—————")]
+#[case::thick(Some(raw::SeparatorStyle::Thick), "━━━━━")]
+#[case::double(Some(raw::SeparatorStyle::Double), "═════")]
+fn thematic_break(#[case] style: Option<raw::SeparatorStyle>, #[case] expected: &str) {
+    let input = "
+hola
+
+---
+
+hi
+";
+    let theme = raw::PresentationTheme { thematic_break: raw::ThematicBreakStyle { style }, ..Default::default() };
+    let lines = Test::new(input).theme(theme).render().rows(6).columns(5).into_lines();
+    assert_eq!(lines, &["     ", "hola ", "     ", expected, "     ", "hi   "]);
+}
+
 #[test]
 fn parse_front_matter_strict() {
     let options = PresentationBuilderOptions { strict_front_matter_parsing: false, ..Default::default() };
@@ -81,7 +99,120 @@ fn parse_front_matter_strict() {
 #[test]
 fn footnote() {
     let elements = vec![MarkdownElement::Footnote(Line::from("hi")), MarkdownElement::Footnote(Line::from("bye"))];
-    let lines = Test::new(elements).render().rows(3).columns(5).into_lines();
-    let expected = &["     ", "hi   ", "bye  "];
+    let lines = Test::new(elements).render().rows(7).columns(5).into_lines();
+    let expected = &["     ", "     ", "hi   ", "bye  ", "     ", "     ", "     "];
+    assert_eq!(lines, expected);
+}
+
+#[test]
+fn footnotes_in_referencing_slide() {
+    let input = "
+first[^a]
+
+<!-- end_slide -->
+
+second[^b]
+
+[^a]: one
+[^b]: two
+";
+    let lines = Test::new(input).render().rows(7).columns(10).into_lines();
+    let expected = &["          ", "firsta    ", "          ", "aone      ", "          ", "          ", "          "];
+    assert_eq!(lines, expected);
+
+    let lines = Test::new(input).render().rows(7).columns(10).advances(1).into_lines();
+    let expected = &["          ", "secondb   ", "          ", "btwo      ", "          ", "          ", "          "];
+    assert_eq!(lines, expected);
+}
+
+#[test]
+fn max_prose_columns() {
+    let input = "
+aaaa bbbb cccc dddd
+
+```
+eeee ffff gggg hhhh
+```
+";
+    let options = PresentationBuilderOptions { max_prose_columns: Some(12), ..Default::default() };
+    let lines = Test::new(input).options(options).render().rows(7).columns(32).into_lines();
+    let expected = &[
+        "                                ",
+        "          aaaa bbbb             ",
+        "          cccc dddd             ",
+        "                                ",
+        "eeee ffff gggg hhhh             ",
+        "                                ",
+        "                                ",
+    ];
     assert_eq!(lines, expected);
 }
+
+#[test]
+fn quiet_zone() {
+    let input = "
+hi
+
+some longer text
+";
+    let theme = "
+default:
+  quiet_zone:
+    horizontal:
+      fixed: 2
+    vertical:
+      fixed: 1
+footer:
+  style: template
+  left: foot
+  height: 1
+";
+    let theme = serde_yaml::from_str(theme).expect("invalid theme");
+    let lines = Test::new(input).theme(theme).render().rows(8).columns(16).into_lines();
+    let expected = &[
+        "                ",
+        "                ",
+        "  hi            ",
+        "                ",
+        "  some longer   ",
+        "  text          ",
+        "   foot         ",
+        "                ",
+    ];
+    assert_eq!(lines, expected);
+}
+
+#[test]
+fn slide_summary() {
+    let input = "
+Title
+===
+
+<!-- end_slide -->
+
+Intro
+---
+
+text
+
+```rust
+let a = 1;
+```
+
+* a list
+<!-- end_slide -->
+
+> quote
+";
+    let presentation = Test::new(input).build();
+    let summaries: Vec<_> = presentation.iter_slides().map(|slide| slide.summary().clone()).collect();
+    let expected = &[
+        SlideSummary { title: Some("Title".into()), content: Default::default() },
+        SlideSummary {
+            title: Some("Intro".into()),
+            content: [SlideContent::Text, SlideContent::List, SlideContent::Code].into(),
+        },
+        SlideSummary { title: None, content: [SlideContent::Quote].into() },
+    ];
+    assert_eq!(summaries, expected);
+}
@@ -154,12 +154,15 @@ mod test {
     #[case(RenderOperation::RenderBlockLine(
         BlockLine{
             prefix: "".into(),
+            prefix_link: None,
             right_padding_length: 0,
             repeat_prefix_on_wrap: false,
             text: WeightedLine::from("".to_string()),
             alignment: Default::default(),
             block_length: 42,
             block_color: None,
+            border: None,
+            overflow: Default::default(),
         }
     ))]
     #[case(RenderOperation::RenderDynamic(Rc::new(Dynamic)))]
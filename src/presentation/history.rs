@@ -0,0 +1,92 @@
+use std::collections::VecDeque;
+
+const MAX_HISTORY: usize = 100;
+
+/// The slides that have been visited, used to jump back and forth between them.
+///
+/// This works like a browser's history: visiting a slide after going back discards every slide
+/// that could have been jumped forward to.
+#[derive(Debug, Default)]
+pub(crate) struct SlideHistory {
+    back: VecDeque<usize>,
+    forward: Vec<usize>,
+    current: Option<usize>,
+}
+
+impl SlideHistory {
+    /// Record that a slide is being displayed.
+    pub(crate) fn visit(&mut self, slide: usize) {
+        if self.current == Some(slide) {
+            return;
+        }
+        if let Some(current) = self.current.replace(slide) {
+            if self.back.len() == MAX_HISTORY {
+                self.back.pop_front();
+            }
+            self.back.push_back(current);
+        }
+        self.forward.clear();
+    }
+
+    /// Go back to the previously visited slide.
+    pub(crate) fn back(&mut self) -> Option<usize> {
+        let slide = self.back.pop_back()?;
+        if let Some(current) = self.current.replace(slide) {
+            self.forward.push(current);
+        }
+        Some(slide)
+    }
+
+    /// Go forward to the slide we were in before going back.
+    pub(crate) fn forward(&mut self) -> Option<usize> {
+        let slide = self.forward.pop()?;
+        if let Some(current) = self.current.replace(slide) {
+            self.back.push_back(current);
+        }
+        Some(slide)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn back_and_forward() {
+        let mut history = SlideHistory::default();
+        for slide in [0, 1, 1, 5] {
+            history.visit(slide);
+        }
+        assert_eq!(history.back(), Some(1));
+        assert_eq!(history.back(), Some(0));
+        assert_eq!(history.back(), None);
+        assert_eq!(history.forward(), Some(1));
+        assert_eq!(history.forward(), Some(5));
+        assert_eq!(history.forward(), None);
+    }
+
+    #[test]
+    fn visit_discards_forward() {
+        let mut history = SlideHistory::default();
+        for slide in [0, 1, 2] {
+            history.visit(slide);
+        }
+        assert_eq!(history.back(), Some(1));
+        history.visit(7);
+        assert_eq!(history.forward(), None);
+        assert_eq!(history.back(), Some(1));
+    }
+
+    #[test]
+    fn bounded() {
+        let mut history = SlideHistory::default();
+        for slide in 0..MAX_HISTORY + 10 {
+            history.visit(slide);
+        }
+        let mut count = 0;
+        while history.back().is_some() {
+            count += 1;
+        }
+        assert_eq!(count, MAX_HISTORY);
+    }
+}
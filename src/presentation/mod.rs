@@ -1,21 +1,29 @@
-use crate::{config::OptionsConfig, render::operation::RenderOperation};
+use crate::{
+    config::OptionsConfig, render::operation::RenderOperation, theme::ModalStyle, ui::execution::output::SnippetHandle,
+};
 use serde::Deserialize;
 use std::{
     cell::RefCell,
-    fmt::Debug,
+    collections::{BTreeSet, HashMap},
+    fmt::{self, Debug},
     ops::Deref,
+    path::PathBuf,
     rc::Rc,
     sync::{Arc, Mutex},
+    time::Duration,
 };
 
 pub(crate) mod builder;
 pub(crate) mod diff;
+pub(crate) mod history;
 pub(crate) mod poller;
 
 #[derive(Debug)]
 pub(crate) struct Modals {
     pub(crate) slide_index: Vec<RenderOperation>,
     pub(crate) bindings: Vec<RenderOperation>,
+    pub(crate) notes: Vec<RenderOperation>,
+    pub(crate) style: ModalStyle,
 }
 
 /// A presentation.
@@ -52,6 +60,16 @@ impl Presentation {
         self.modals.bindings.iter()
     }
 
+    /// Iterate the operations that render the speaker notes modal.
+    pub(crate) fn iter_notes_operations(&self) -> impl Iterator<Item = &RenderOperation> {
+        self.modals.notes.iter()
+    }
+
+    /// Get the style used in modals.
+    pub(crate) fn modal_style(&self) -> &ModalStyle {
+        &self.modals.style
+    }
+
     /// Consume this presentation and return its slides.
     pub(crate) fn into_slides(self) -> Vec<Slide> {
         self.slides
@@ -67,6 +85,23 @@ impl Presentation {
         self.state.current_slide_index()
     }
 
+    /// The interval at which the current slide should be automatically advanced, if any.
+    pub(crate) fn auto_step_interval(&self) -> Option<Duration> {
+        self.current_slide().auto_step_interval()
+    }
+
+    /// Automatically advance the current slide.
+    pub(crate) fn auto_step(&mut self) -> bool {
+        self.current_slide().auto_step()
+    }
+
+    /// The current chunk's index along with how far its automatically advanced mutator is, if it has one.
+    pub(crate) fn auto_step_position(&self) -> Option<(usize, usize)> {
+        let slide = self.current_slide();
+        let chunk = slide.current_chunk_index();
+        slide.current_chunk().auto_step_mutations().map(|mutations| (chunk, mutations))
+    }
+
     /// Jump forwards.
     pub(crate) fn jump_next(&mut self) -> bool {
         let current_slide = self.current_slide_mut();
@@ -107,6 +142,24 @@ impl Presentation {
         }
     }
 
+    /// Jump forwards a number of slides, ignoring any pauses.
+    ///
+    /// This stops at the last slide if there aren't enough slides left.
+    pub(crate) fn jump_next_slides(&mut self, count: usize) -> bool {
+        let current_slide_index = self.current_slide_index();
+        let slide_index = current_slide_index.saturating_add(count).min(self.slides.len().saturating_sub(1));
+        slide_index != current_slide_index && self.go_to_slide(slide_index)
+    }
+
+    /// Jump backwards a number of slides, ignoring any pauses.
+    ///
+    /// This stops at the first slide if there aren't enough slides left.
+    pub(crate) fn jump_previous_slides(&mut self, count: usize) -> bool {
+        let current_slide_index = self.current_slide_index();
+        let slide_index = current_slide_index.saturating_sub(count);
+        slide_index != current_slide_index && self.go_to_slide(slide_index)
+    }
+
     /// Jump to the first slide.
     pub(crate) fn jump_first_slide(&mut self) -> bool {
         self.go_to_slide(0)
@@ -118,6 +171,14 @@ impl Presentation {
         self.go_to_slide(last_slide_index)
     }
 
+    /// Find the index of the first slide with the given title, ignoring case.
+    pub(crate) fn find_slide_by_title(&self, title: &str) -> Option<usize> {
+        let title = title.to_lowercase();
+        self.slides.iter().position(|slide| {
+            slide.summary.title.as_ref().is_some_and(|slide_title| slide_title.to_lowercase() == title)
+        })
+    }
+
     /// Jump to a specific slide.
     pub(crate) fn go_to_slide(&mut self, slide_index: usize) -> bool {
         if slide_index < self.slides.len() {
@@ -172,7 +233,7 @@ impl Presentation {
 
 impl From<Vec<Slide>> for Presentation {
     fn from(slides: Vec<Slide>) -> Self {
-        let modals = Modals { slide_index: vec![], bindings: vec![] };
+        let modals = Modals { slide_index: vec![], bindings: vec![], notes: vec![], style: Default::default() };
         Self::new(slides, modals, Default::default())
     }
 }
@@ -189,6 +250,8 @@ pub(crate) type AsyncPresentationErrorHolder = Arc<Mutex<Option<AsyncPresentatio
 pub(crate) struct PresentationStateInner {
     current_slide_index: usize,
     async_error_holder: AsyncPresentationErrorHolder,
+    snippet_handles: Vec<SnippetHandle>,
+    slide_index_filter: Option<String>,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -208,6 +271,24 @@ impl PresentationState {
     fn set_current_slide_index(&self, value: usize) {
         self.inner.deref().borrow_mut().current_slide_index = value;
     }
+
+    /// Get the filter being typed in the slide index, if any.
+    pub(crate) fn slide_index_filter(&self) -> Option<String> {
+        self.inner.deref().borrow().slide_index_filter.clone()
+    }
+
+    pub(crate) fn set_slide_index_filter(&self, filter: Option<String>) {
+        self.inner.deref().borrow_mut().slide_index_filter = filter;
+    }
+
+    /// Get the handles for all executable snippets in the presentation.
+    pub(crate) fn snippet_handles(&self) -> Vec<SnippetHandle> {
+        self.inner.deref().borrow().snippet_handles.clone()
+    }
+
+    pub(crate) fn register_snippet_handle(&self, handle: SnippetHandle) {
+        self.inner.deref().borrow_mut().snippet_handles.push(handle);
+    }
 }
 
 /// A slide builder.
@@ -215,6 +296,7 @@ impl PresentationState {
 pub(crate) struct SlideBuilder {
     chunks: Vec<SlideChunk>,
     footer: Vec<RenderOperation>,
+    summary: SlideSummary,
 }
 
 impl SlideBuilder {
@@ -228,8 +310,51 @@ impl SlideBuilder {
         self
     }
 
+    pub(crate) fn summary(mut self, summary: SlideSummary) -> Self {
+        self.summary = summary;
+        self
+    }
+
     pub(crate) fn build(self) -> Slide {
-        Slide::new(self.chunks, self.footer)
+        let mut slide = Slide::new(self.chunks, self.footer);
+        slide.summary = self.summary;
+        slide
+    }
+}
+
+/// A summary of what a slide contains.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct SlideSummary {
+    pub(crate) title: Option<String>,
+    pub(crate) content: BTreeSet<SlideContent>,
+}
+
+/// A type of content in a slide.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum SlideContent {
+    Text,
+    List,
+    Code,
+    Table,
+    Image,
+    Quote,
+    Alert,
+    Math,
+}
+
+impl fmt::Display for SlideContent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Text => "text",
+            Self::List => "list",
+            Self::Code => "code",
+            Self::Table => "table",
+            Self::Image => "image",
+            Self::Quote => "quote",
+            Self::Alert => "alert",
+            Self::Math => "math",
+        };
+        write!(f, "{name}")
     }
 }
 
@@ -242,11 +367,16 @@ pub(crate) struct Slide {
     chunks: Vec<SlideChunk>,
     footer: Vec<RenderOperation>,
     visible_chunks: usize,
+    summary: SlideSummary,
 }
 
 impl Slide {
     pub(crate) fn new(chunks: Vec<SlideChunk>, footer: Vec<RenderOperation>) -> Self {
-        Self { chunks, footer, visible_chunks: 1 }
+        Self { chunks, footer, visible_chunks: 1, summary: Default::default() }
+    }
+
+    pub(crate) fn summary(&self) -> &SlideSummary {
+        &self.summary
     }
 
     pub(crate) fn iter_operations(&self) -> impl Iterator<Item = &RenderOperation> + Clone {
@@ -314,6 +444,14 @@ impl Slide {
         }
     }
 
+    fn auto_step_interval(&self) -> Option<Duration> {
+        self.current_chunk().auto_step_mutator().map(|(_, interval)| interval)
+    }
+
+    fn auto_step(&self) -> bool {
+        self.current_chunk().auto_step_mutator().is_some_and(|(mutator, _)| mutator.mutate_next())
+    }
+
     fn move_previous(&mut self) -> bool {
         if self.chunks[self.current_chunk_index()].mutate_previous() {
             return true;
@@ -382,6 +520,21 @@ impl SlideChunk {
             mutator.apply_all_mutations();
         }
     }
+
+    /// How far the first mutator that's set to auto advance is, regardless of whether it can still be advanced.
+    fn auto_step_mutations(&self) -> Option<usize> {
+        let mutator = self.mutators.iter().find(|mutator| mutator.auto_step_interval().is_some())?;
+        Some(mutator.mutations().0)
+    }
+
+    /// The first mutator that's set to auto advance and can still be advanced.
+    fn auto_step_mutator(&self) -> Option<(&dyn ChunkMutator, Duration)> {
+        self.mutators.iter().find_map(|mutator| {
+            let interval = mutator.auto_step_interval()?;
+            let (current, total) = mutator.mutations();
+            (current + 1 < total).then_some((mutator.as_ref(), interval))
+        })
+    }
 }
 
 pub(crate) trait ChunkMutator: Debug {
@@ -389,8 +542,12 @@ pub(crate) trait ChunkMutator: Debug {
     fn mutate_previous(&self) -> bool;
     fn reset_mutations(&self);
     fn apply_all_mutations(&self);
-    #[allow(dead_code)]
     fn mutations(&self) -> (usize, usize);
+
+    /// The interval at which this mutator should be advanced without any user input.
+    fn auto_step_interval(&self) -> Option<Duration> {
+        None
+    }
 }
 
 /// The metadata for a presentation.
@@ -430,6 +587,22 @@ pub(crate) struct PresentationMetadata {
     /// The presentation's options.
     #[serde(default)]
     pub(crate) options: Option<OptionsConfig>,
+
+    /// Aliases for image paths, which can be referenced as `@<alias>` in images.
+    #[serde(default)]
+    pub(crate) images: HashMap<String, PathBuf>,
+
+    /// The numbers of the slides to exclude from the presentation, starting at 1.
+    #[serde(default)]
+    pub(crate) skip_slides: Vec<usize>,
+
+    /// A command to run before every snippet is executed.
+    #[serde(default)]
+    pub(crate) pre_exec: Vec<String>,
+
+    /// A command to run after every snippet is executed.
+    #[serde(default)]
+    pub(crate) post_exec: Vec<String>,
 }
 
 impl PresentationMetadata {
@@ -475,6 +648,8 @@ mod test {
         NextFast,
         Previous,
         PreviousFast,
+        NextSlides(usize),
+        PreviousSlides(usize),
         Specific(usize),
     }
 
@@ -488,6 +663,8 @@ mod test {
                 NextFast => presentation.jump_next_fast(),
                 Previous => presentation.jump_previous(),
                 PreviousFast => presentation.jump_previous_fast(),
+                NextSlides(count) => presentation.jump_next_slides(*count),
+                PreviousSlides(count) => presentation.jump_previous_slides(*count),
                 Specific(index) => presentation.go_to_slide(*index),
             };
         }
@@ -501,11 +678,16 @@ mod test {
     struct DummyMutator {
         current: RefCell<usize>,
         limit: usize,
+        auto_step: Option<Duration>,
     }
 
     impl DummyMutator {
         fn new(limit: usize) -> Self {
-            Self { current: 0.into(), limit }
+            Self { current: 0.into(), limit, auto_step: None }
+        }
+
+        fn auto_step(limit: usize, interval: Duration) -> Self {
+            Self { auto_step: Some(interval), ..Self::new(limit) }
         }
     }
 
@@ -539,7 +721,11 @@ mod test {
         }
 
         fn mutations(&self) -> (usize, usize) {
-            (*self.current.borrow(), self.limit)
+            (*self.current.borrow(), self.limit + 1)
+        }
+
+        fn auto_step_interval(&self) -> Option<Duration> {
+            self.auto_step
         }
     }
 
@@ -558,6 +744,10 @@ mod test {
     #[case::specific_first_from_second(1, &[Jump::Specific(0)], 0, 0)]
     #[case::specific_last_from_second(1, &[Jump::Specific(2)], 2, 0)]
     #[case::first_from_last(2, &[Jump::First], 0, 0)]
+    #[case::next_slides_from_first(0, &[Jump::Next, Jump::NextSlides(1)], 1, 0)]
+    #[case::next_slides_past_last(0, &[Jump::NextSlides(5)], 2, 0)]
+    #[case::previous_slides_from_last(2, &[Jump::Next, Jump::PreviousSlides(1)], 1, 0)]
+    #[case::previous_slides_past_first(1, &[Jump::PreviousSlides(5)], 0, 0)]
     fn jumping(
         #[case] from: usize,
         #[case] jumps: &[Jump],
@@ -629,4 +819,69 @@ mod test {
         assert_eq!(presentation.current_slide_index(), expected_slide, "slide differs");
         assert_eq!(presentation.current_slide().visible_chunks - 1, expected_chunk, "chunk differs");
     }
+
+    #[test]
+    fn auto_step() {
+        let interval = Duration::from_secs(1);
+        let mut presentation = Presentation::from(vec![
+            SlideBuilder::default()
+                .chunks(vec![SlideChunk::new(
+                    vec![],
+                    vec![Box::new(DummyMutator::new(1)), Box::new(DummyMutator::auto_step(2, interval))],
+                )])
+                .build(),
+        ]);
+        for _ in 0..2 {
+            assert_eq!(presentation.auto_step_interval(), Some(interval));
+            assert!(presentation.auto_step());
+        }
+        // The last step was reached so this stops.
+        assert_eq!(presentation.auto_step_interval(), None);
+        assert!(!presentation.auto_step());
+
+        // Only the auto stepping mutator was touched.
+        let mutations: Vec<_> =
+            presentation.current_slide().current_chunk().mutators.iter().map(|m| m.mutations().0).collect();
+        assert_eq!(mutations, &[0, 2]);
+    }
+
+    #[test]
+    fn auto_step_after_pause() {
+        let interval = Duration::from_secs(1);
+        let mut presentation = Presentation::from(vec![
+            SlideBuilder::default()
+                .chunks(vec![
+                    SlideChunk::default(),
+                    SlideChunk::new(vec![], vec![Box::new(DummyMutator::auto_step(1, interval))]),
+                ])
+                .build(),
+        ]);
+        assert_eq!(presentation.auto_step_interval(), None);
+        assert_eq!(presentation.auto_step_position(), None);
+
+        // Revealing the chunk starts advancing it automatically.
+        assert!(presentation.jump_next());
+        assert_eq!(presentation.auto_step_interval(), Some(interval));
+        assert_eq!(presentation.auto_step_position(), Some((1, 0)));
+
+        assert!(presentation.auto_step());
+        assert_eq!(presentation.auto_step_position(), Some((1, 1)));
+    }
+
+    #[rstest]
+    #[case::exact("Wrapping up", Some(1))]
+    #[case::case_insensitive("wrapping UP", Some(1))]
+    #[case::missing("Questions", None)]
+    fn find_slide_by_title(#[case] title: &str, #[case] expected: Option<usize>) {
+        let make_slide = |title: Option<&str>| {
+            let summary = SlideSummary { title: title.map(ToString::to_string), ..Default::default() };
+            SlideBuilder::default().summary(summary).build()
+        };
+        let presentation = Presentation::from(vec![
+            make_slide(None),
+            make_slide(Some("Wrapping up")),
+            make_slide(Some("Wrapping up")),
+        ]);
+        assert_eq!(presentation.find_slide_by_title(title), expected);
+    }
 }
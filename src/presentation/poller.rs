@@ -40,12 +40,17 @@ pub(crate) enum PollableEffect {
 
     /// Display an error for the given slide.
     DisplayError { slide: usize, error: String },
+
+    /// A pollable in the given slide that was polled with `notify` set is done.
+    Finished { slide: usize },
 }
 
 /// A poller command.
 pub(crate) enum PollerCommand {
     /// Start polling a pollable that's positioned in the given slide.
-    Poll { pollable: Box<dyn Pollable>, slide: usize },
+    ///
+    /// If `notify` is set, a [PollableEffect::Finished] is emitted once the pollable is done.
+    Poll { pollable: Box<dyn Pollable>, slide: usize, notify: bool },
 
     /// Reset all pollables.
     Reset,
@@ -54,7 +59,7 @@ pub(crate) enum PollerCommand {
 struct PollerWorker {
     receiver: Receiver<PollerCommand>,
     sender: Sender<PollableEffect>,
-    pollables: Vec<(Box<dyn Pollable>, usize)>,
+    pollables: Vec<WatchedPollable>,
 }
 
 impl PollerWorker {
@@ -75,17 +80,19 @@ impl PollerWorker {
 
     fn process_command(&mut self, command: PollerCommand) {
         match command {
-            PollerCommand::Poll { mut pollable, slide } => {
+            PollerCommand::Poll { mut pollable, slide, notify } => {
                 // Poll and only insert if it's still running.
                 match pollable.poll() {
                     PollableState::Unmodified | PollableState::Modified => {
-                        self.pollables.push((pollable, slide));
+                        self.pollables.push(WatchedPollable { pollable, slide, notify });
                     }
                     PollableState::Done => {
                         let _ = self.sender.send(PollableEffect::RefreshSlide(slide));
+                        self.notify_finished(slide, notify);
                     }
                     PollableState::Failed { error } => {
                         let _ = self.sender.send(PollableEffect::DisplayError { slide, error });
+                        self.notify_finished(slide, notify);
                     }
                 };
             }
@@ -95,7 +102,7 @@ impl PollerWorker {
 
     fn poll(&mut self) {
         let mut removables = Vec::new();
-        for (index, (pollable, slide)) in self.pollables.iter_mut().enumerate() {
+        for (index, WatchedPollable { pollable, slide, notify }) in self.pollables.iter_mut().enumerate() {
             let slide = *slide;
             let (effect, remove) = match pollable.poll() {
                 PollableState::Unmodified => (None, false),
@@ -107,6 +114,9 @@ impl PollerWorker {
                 let _ = self.sender.send(effect);
             }
             if remove {
+                if *notify {
+                    let _ = self.sender.send(PollableEffect::Finished { slide });
+                }
                 removables.push(index);
             }
         }
@@ -115,4 +125,51 @@ impl PollerWorker {
             self.pollables.swap_remove(*index);
         }
     }
+
+    fn notify_finished(&self, slide: usize, notify: bool) {
+        if notify {
+            let _ = self.sender.send(PollableEffect::Finished { slide });
+        }
+    }
+}
+
+struct WatchedPollable {
+    pollable: Box<dyn Pollable>,
+    slide: usize,
+    notify: bool,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstest::rstest;
+
+    struct Countdown(usize);
+
+    impl Pollable for Countdown {
+        fn poll(&mut self) -> PollableState {
+            match self.0.checked_sub(1) {
+                Some(remaining) => {
+                    self.0 = remaining;
+                    PollableState::Unmodified
+                }
+                None => PollableState::Done,
+            }
+        }
+    }
+
+    #[rstest]
+    #[case::immediately_done(0)]
+    #[case::running(1)]
+    fn finished_notification(#[case] polls: usize, #[values(true, false)] notify: bool) {
+        let (_, command_receiver) = channel();
+        let (effect_sender, effect_receiver) = channel();
+        let mut worker = PollerWorker::new(command_receiver, effect_sender);
+        worker.process_command(PollerCommand::Poll { pollable: Box::new(Countdown(polls)), slide: 2, notify });
+        worker.poll();
+
+        let finished =
+            effect_receiver.try_iter().filter(|e| matches!(e, PollableEffect::Finished { slide: 2 })).count();
+        assert_eq!(finished, notify as usize);
+    }
 }
@@ -1,27 +1,34 @@
 use crate::{
     code::execute::SnippetExecutor,
     commands::{
-        listener::{Command, CommandListener},
+        announcer::SlideAnnouncer,
+        listener::{Command, CommandListener, FilterInput},
         speaker_notes::{SpeakerNotesEvent, SpeakerNotesEventPublisher},
     },
-    config::{KeyBindingsConfig, SlideTransitionConfig, SlideTransitionStyleConfig},
+    config::{
+        ClockConfig, CompletionCueConfig, CountdownConfig, EndOfPresentationConfig, FastNavigationConfig,
+        KeyBindingsConfig, MinimumSizeConfig, SlideOutOfRangeConfig, SlideTransitionConfig, SlideTransitionStyleConfig,
+    },
+    export::{exporter::ExportError, snapshot::save_slide_snapshot},
     markdown::parse::MarkdownParser,
     presentation::{
         Presentation, Slide,
-        builder::{PresentationBuilder, PresentationBuilderOptions, Themes, error::BuildError},
+        builder::{PresentationBuilder, PresentationBuilderOptions, STDIN_PATH, Themes, error::BuildError},
         diff::PresentationDiffer,
+        history::SlideHistory,
         poller::{PollableEffect, Poller, PollerCommand},
     },
     render::{
         ErrorSource, RenderError, RenderResult, TerminalDrawer, TerminalDrawerOptions,
         ascii_scaler::AsciiScaler,
         engine::{MaxSize, RenderEngine, RenderEngineOptions},
-        operation::{Pollable, RenderAsyncStartPolicy, RenderOperation},
-        properties::WindowSize,
+        operation::{Pollable, RenderAsync, RenderAsyncStartPolicy, RenderOperation, ScrollDirection},
+        properties::{CellSizeFallback, WindowSize},
         validate::OverflowValidator,
     },
     resource::Resources,
     terminal::{
+        clipboard::copy_to_clipboard,
         image::printer::{ImagePrinter, ImageRegistry},
         printer::{TerminalCommand, TerminalIo},
         virt::{ImageBehavior, TerminalGrid, VirtualTerminal},
@@ -33,13 +40,18 @@ use crate::{
         collapse_horizontal::CollapseHorizontalAnimation, fade::FadeAnimation,
         slide_horizontal::SlideHorizontalAnimation,
     },
+    ui::{
+        clock::Clock, countdown::Countdown, execution::output::SnippetHandle, modals::title_matches,
+        presenter_view::PresenterView, status::StatusLine, thumbnails::SlideThumbnails, too_small::TerminalTooSmall,
+    },
 };
 use std::{
     fmt::Display,
-    io::{self},
-    mem,
+    io::{self, Write},
+    iter, mem,
     ops::Deref,
-    path::Path,
+    path::{Path, PathBuf},
+    rc::Rc,
     sync::Arc,
     time::{Duration, Instant},
 };
@@ -47,11 +59,22 @@ use std::{
 pub struct PresenterOptions {
     pub mode: PresentMode,
     pub builder_options: PresentationBuilderOptions,
-    pub font_size_fallback: u8,
+    pub cell_size_fallback: CellSizeFallback,
     pub bindings: KeyBindingsConfig,
     pub validate_overflows: bool,
     pub max_size: MaxSize,
     pub transition: Option<SlideTransitionConfig>,
+    pub fast_navigation: FastNavigationConfig,
+    pub slide_out_of_range: SlideOutOfRangeConfig,
+    pub end_of_presentation: EndOfPresentationConfig,
+    pub slide_index_thumbnails: bool,
+    pub presenter_view: bool,
+    pub completion_cue: CompletionCueConfig,
+    pub clock: ClockConfig,
+    pub countdown: CountdownConfig,
+    pub duration: Option<Duration>,
+    pub minimum_size: MinimumSizeConfig,
+    pub minimum_column_width: u16,
 }
 
 /// A slideshow presenter.
@@ -69,7 +92,17 @@ pub struct Presenter<'a> {
     themes: Themes,
     options: PresenterOptions,
     speaker_notes_event_publisher: Option<SpeakerNotesEventPublisher>,
+    slide_announcer: Option<SlideAnnouncer>,
+    auto_step: AutoStepTimer,
+    history: SlideHistory,
     poller: Poller,
+    status: Option<StatusLine>,
+    clock: Clock,
+    countdown: Option<Countdown>,
+    countdown_jump_pending: bool,
+    thumbnails: Option<SlideThumbnails>,
+    presenter_view: Option<PresenterView>,
+    preview: Option<Presentation>,
 }
 
 impl<'a> Presenter<'a> {
@@ -86,7 +119,20 @@ impl<'a> Presenter<'a> {
         image_printer: Arc<ImagePrinter>,
         options: PresenterOptions,
         speaker_notes_event_publisher: Option<SpeakerNotesEventPublisher>,
+        slide_announcer: Option<SlideAnnouncer>,
     ) -> Self {
+        let thumbnails = match image_printer.as_ref() {
+            ImagePrinter::Ascii(_) | ImagePrinter::Null => None,
+            _ if options.slide_index_thumbnails => {
+                let registry = ImageRegistry::new(image_printer.clone());
+                let options = RenderEngineOptions { max_size: options.max_size.clone(), ..Default::default() };
+                Some(SlideThumbnails::new(registry, options))
+            }
+            _ => None,
+        };
+        let presenter_view = options.presenter_view.then(PresenterView::new);
+        let clock = Clock::new(options.clock.position, options.clock.show);
+        let countdown = options.duration.map(|duration| Countdown::new(duration, &options.countdown));
         Self {
             default_theme,
             listener,
@@ -99,29 +145,57 @@ impl<'a> Presenter<'a> {
             themes,
             options,
             speaker_notes_event_publisher,
+            slide_announcer,
+            auto_step: AutoStepTimer::default(),
+            history: SlideHistory::default(),
             poller: Poller::launch(),
+            status: None,
+            clock,
+            countdown,
+            countdown_jump_pending: false,
+            thumbnails,
+            presenter_view,
+            preview: None,
         }
     }
 
     /// Run a presentation.
     pub fn present(mut self, path: &Path) -> Result<(), PresentationError> {
-        if matches!(self.options.mode, PresentMode::Development) {
+        // There's no file to watch if the presentation is read from stdin.
+        if matches!(self.options.mode, PresentMode::Development) && path != Path::new(STDIN_PATH) {
             self.resources.watch_presentation_file(path.to_path_buf());
         }
         self.state = PresenterState::Presenting(Presentation::from(vec![]));
         self.try_reload(path, true)?;
+        self.validate_countdown_expired_slide();
 
         let drawer_options = TerminalDrawerOptions {
-            font_size_fallback: self.options.font_size_fallback,
+            cell_size_fallback: self.options.cell_size_fallback,
             max_size: self.options.max_size.clone(),
+            minimum_column_width: self.options.minimum_column_width,
         };
         let mut drawer = TerminalDrawer::new(self.image_printer.clone(), drawer_options)?;
         loop {
             // Poll async renders once before we draw just in case.
             self.render(&mut drawer)?;
+            self.announce_slide()?;
+            self.history.visit(self.state.presentation().current_slide_index());
 
             loop {
-                if self.process_poller_effects()? {
+                let timer_modified = self.presenter_view.as_mut().is_some_and(PresenterView::tick);
+                let clock_modified = self.clock.tick();
+                let countdown_modified = self.countdown.as_mut().is_some_and(Countdown::tick);
+                let auto_stepped = self.poll_auto_step();
+                if self.poll_countdown_expired() {
+                    self.reset_entered_slide_async_renders();
+                    break;
+                }
+                if self.process_poller_effects()?
+                    || timer_modified
+                    || clock_modified
+                    || countdown_modified
+                    || auto_stepped
+                {
                     self.render(&mut drawer)?;
                 }
 
@@ -137,7 +211,15 @@ impl<'a> Presenter<'a> {
                         }
                     },
                 };
-                match self.apply_command(command) {
+                // Any status message is only displayed until the next command.
+                let had_status = self.status.take().is_some();
+                let current_slide = self.state.presentation().current_slide_index();
+                let side_effect = self.apply_command(command);
+                if self.state.presentation().current_slide_index() != current_slide {
+                    self.reset_entered_slide_async_renders();
+                }
+                self.sync_slide_index_filter();
+                match side_effect {
                     CommandSideEffect::Exit => {
                         self.publish_event(SpeakerNotesEvent::Exit)?;
                         return Ok(());
@@ -150,6 +232,14 @@ impl<'a> Presenter<'a> {
                         self.try_reload(path, false)?;
                         break;
                     }
+                    CommandSideEffect::ReloadTheme => {
+                        self.try_reload_theme(path)?;
+                        break;
+                    }
+                    CommandSideEffect::ToggleLineNumbers => {
+                        self.try_toggle_line_numbers(path)?;
+                        break;
+                    }
                     CommandSideEffect::Redraw => {
                         self.try_scale_transition_images()?;
                         break;
@@ -162,6 +252,7 @@ impl<'a> Presenter<'a> {
                         self.animate_previous_slide(&mut drawer)?;
                         break;
                     }
+                    CommandSideEffect::None if had_status => break,
                     CommandSideEffect::None => (),
                 };
             }
@@ -176,6 +267,7 @@ impl<'a> Presenter<'a> {
             PresenterState::Presenting(presentation)
             | PresenterState::SlideIndex(presentation)
             | PresenterState::KeyBindings(presentation)
+            | PresenterState::Notes(presentation)
             | PresenterState::Failure { presentation, .. } => presentation.current_slide_index(),
             PresenterState::Empty => usize::MAX,
         };
@@ -193,6 +285,9 @@ impl<'a> Presenter<'a> {
                         PresenterState::failure(error, presentation, ErrorSource::Slide(slide + 1), FailureMode::Other);
                     needs_render = true;
                 }
+                PollableEffect::Finished { slide } => {
+                    needs_render = self.give_completion_cue(slide)? || needs_render;
+                }
             }
         }
         if refreshed {
@@ -201,6 +296,22 @@ impl<'a> Presenter<'a> {
         Ok(needs_render)
     }
 
+    fn give_completion_cue(&mut self, slide: usize) -> io::Result<bool> {
+        match self.options.completion_cue {
+            CompletionCueConfig::Off => Ok(false),
+            CompletionCueConfig::Bell => {
+                let mut stdout = io::stdout();
+                stdout.write_all(b"\x07")?;
+                stdout.flush()?;
+                Ok(false)
+            }
+            CompletionCueConfig::Flash => {
+                self.status = Some(StatusLine::new(format!("snippet in slide {} finished running", slide + 1)));
+                Ok(true)
+            }
+        }
+    }
+
     fn publish_event(&self, event: SpeakerNotesEvent) -> io::Result<()> {
         if let Some(publisher) = &self.speaker_notes_event_publisher {
             publisher.send(event)?;
@@ -208,6 +319,62 @@ impl<'a> Presenter<'a> {
         Ok(())
     }
 
+    fn poll_auto_step(&mut self) -> bool {
+        let PresenterState::Presenting(presentation) = &mut self.state else {
+            return false;
+        };
+        let slide = presentation.current_slide_index();
+        let Some(interval) = self.auto_step.interval(slide, presentation.auto_step_interval()) else {
+            return false;
+        };
+        if self.auto_step.since.elapsed() < interval {
+            return false;
+        }
+        self.auto_step.since = Instant::now();
+        presentation.auto_step()
+    }
+
+    // Jumps to the configured slide when the countdown runs out, returning whether it did.
+    fn poll_countdown_expired(&mut self) -> bool {
+        if self.countdown.as_mut().is_some_and(Countdown::poll_expired) {
+            self.countdown_jump_pending = self.options.countdown.expired_slide.is_some();
+        }
+        // If a modal or the slide index is open the jump waits until they're closed.
+        let (true, Some(title), PresenterState::Presenting(presentation)) =
+            (self.countdown_jump_pending, &self.options.countdown.expired_slide, &mut self.state)
+        else {
+            return false;
+        };
+        self.countdown_jump_pending = false;
+        match presentation.find_slide_by_title(title) {
+            Some(index) => presentation.go_to_slide(index),
+            None => {
+                // The next redraw of the countdown picks this up.
+                self.status = Some(StatusLine::new(format!("no slide titled '{title}' to jump to")));
+                false
+            }
+        }
+    }
+
+    fn validate_countdown_expired_slide(&mut self) {
+        let (Some(_), Some(title), PresenterState::Presenting(presentation)) =
+            (&self.countdown, &self.options.countdown.expired_slide, &self.state)
+        else {
+            return;
+        };
+        if presentation.find_slide_by_title(title).is_none() {
+            let message = format!("countdown.expired_slide: no slide titled '{title}'");
+            self.status = Some(StatusLine::new(message));
+        }
+    }
+
+    fn announce_slide(&mut self) -> io::Result<()> {
+        if let (Some(announcer), PresenterState::Presenting(presentation)) = (&mut self.slide_announcer, &self.state) {
+            announcer.announce(presentation)?;
+        }
+        Ok(())
+    }
+
     fn check_async_error(&mut self) -> bool {
         let error_holder = self.state.presentation().state.async_error_holder();
         let error_holder = error_holder.lock().unwrap();
@@ -227,9 +394,38 @@ impl<'a> Presenter<'a> {
     }
 
     fn render(&mut self, drawer: &mut TerminalDrawer) -> RenderResult {
+        let mut status: Vec<_> =
+            self.status.iter().map(|status| RenderOperation::RenderDynamic(Rc::new(status.clone()))).collect();
+        if let Some(clock) = self.clock.visible() {
+            status.push(RenderOperation::RenderDynamic(Rc::new(clock.clone())));
+        }
+        if let Some(countdown) = &self.countdown {
+            status.push(RenderOperation::RenderDynamic(Rc::new(countdown.clone())));
+        }
+        let dimensions = WindowSize::current(self.options.cell_size_fallback)?;
+        if let Some(too_small) = TerminalTooSmall::check(&self.options.minimum_size, &dimensions) {
+            let operation = RenderOperation::RenderDynamic(Rc::new(too_small));
+            // The message itself may not fit either, there's nothing else we can do about that.
+            return match drawer.render_operations(iter::once(&operation)) {
+                Err(RenderError::TerminalTooSmall) => Ok(()),
+                result => result,
+            };
+        }
         let result = match &self.state {
+            // The filter is only displayed in the regular slide index.
+            PresenterState::SlideIndex(presentation)
+                if self.thumbnails.is_some() && presentation.state.slide_index_filter().is_none() =>
+            {
+                let dimensions = self.thumbnail_dimensions()?;
+                let thumbnails = self.thumbnails.as_mut().expect("no thumbnails");
+                let operations = thumbnails.build(presentation, presentation.modal_style(), dimensions)?;
+                drawer.render_operations(operations.iter())
+            }
+            PresenterState::Presenting(presentation) if self.presenter_view.is_some() => {
+                self.render_presenter_view(drawer, presentation, &status)
+            }
             PresenterState::Presenting(presentation) => {
-                drawer.render_operations(presentation.current_slide().iter_visible_operations())
+                drawer.render_operations(presentation.current_slide().iter_visible_operations().chain(status.iter()))
             }
             PresenterState::SlideIndex(presentation) => {
                 drawer.render_operations(presentation.current_slide().iter_visible_operations())?;
@@ -239,6 +435,10 @@ impl<'a> Presenter<'a> {
                 drawer.render_operations(presentation.current_slide().iter_visible_operations())?;
                 drawer.render_operations(presentation.iter_bindings_operations())
             }
+            PresenterState::Notes(presentation) => {
+                drawer.render_operations(presentation.current_slide().iter_visible_operations())?;
+                drawer.render_operations(presentation.iter_notes_operations())
+            }
             PresenterState::Failure { error, source, .. } => drawer.render_error(error, source),
             PresenterState::Empty => panic!("cannot render without state"),
         };
@@ -247,6 +447,24 @@ impl<'a> Presenter<'a> {
         if matches!(result, Err(RenderError::TerminalTooSmall)) { Ok(()) } else { result }
     }
 
+    fn render_presenter_view(
+        &self,
+        drawer: &mut TerminalDrawer,
+        presentation: &Presentation,
+        status: &[RenderOperation],
+    ) -> RenderResult {
+        let presenter_view = self.presenter_view.as_ref().expect("no presenter view");
+        let dimensions = WindowSize::current(self.options.cell_size_fallback)?;
+        let margin = RenderOperation::ApplyMargin(PresenterView::notes_margin(&dimensions));
+        let notes = iter::once(&margin).chain(presentation.current_slide().iter_visible_operations());
+        drawer.render_operations(notes.chain(status.iter()))?;
+
+        let next_slide =
+            self.preview.as_ref().and_then(|preview| preview.iter_slides().nth(presentation.current_slide_index() + 1));
+        let operations = presenter_view.build(next_slide, presentation.modal_style(), &dimensions)?;
+        drawer.render_operations(operations.iter())
+    }
+
     fn apply_command(&mut self, command: Command) -> CommandSideEffect {
         // These ones always happens no matter our state.
         match command {
@@ -259,6 +477,12 @@ impl<'a> Presenter<'a> {
                 }
                 return CommandSideEffect::Reload;
             }
+            Command::ReloadTheme => return CommandSideEffect::ReloadTheme,
+            Command::ToggleLineNumbers => return CommandSideEffect::ToggleLineNumbers,
+            Command::ToggleClock => {
+                self.clock.toggle();
+                return CommandSideEffect::Redraw;
+            }
             Command::Exit => return CommandSideEffect::Exit,
             Command::Suspend => return CommandSideEffect::Suspend,
             _ => (),
@@ -272,66 +496,195 @@ impl<'a> Presenter<'a> {
         }
 
         // Now apply the commands that require a presentation.
+        let in_slide_index = matches!(self.state, PresenterState::SlideIndex(_));
         let presentation = match &mut self.state {
             PresenterState::Presenting(presentation)
             | PresenterState::SlideIndex(presentation)
-            | PresenterState::KeyBindings(presentation) => presentation,
+            | PresenterState::KeyBindings(presentation)
+            | PresenterState::Notes(presentation) => presentation,
             _ => {
                 return CommandSideEffect::None;
             }
         };
+        if in_slide_index && matches!(command, Command::Next | Command::Previous) {
+            // While filtering the slide index only the matching slides can be selected.
+            if let Some(filter) = presentation.state.slide_index_filter() {
+                let forward = matches!(command, Command::Next);
+                let moved = Self::jump_matching_slide(presentation, &filter, forward);
+                return if moved { CommandSideEffect::Redraw } else { CommandSideEffect::None };
+            }
+        }
         let needs_redraw = match command {
             Command::Next => {
                 let current_slide = presentation.current_slide_index();
-                if !presentation.jump_next() {
-                    false
+                let auto_step_position = presentation.auto_step_position();
+                let moved = presentation.jump_next();
+                self.auto_step.observe_step(auto_step_position, presentation.auto_step_position());
+                if !moved {
+                    match self.options.end_of_presentation {
+                        EndOfPresentationConfig::Stay => false,
+                        EndOfPresentationConfig::Wrap => presentation.jump_first_slide(),
+                        EndOfPresentationConfig::Indicator => {
+                            self.status = Some(StatusLine::new("end of presentation"));
+                            true
+                        }
+                    }
                 } else if presentation.current_slide_index() != current_slide {
                     return CommandSideEffect::AnimateNextSlide;
                 } else {
                     true
                 }
             }
-            Command::NextFast => presentation.jump_next_fast(),
+            Command::NextFast => match self.options.fast_navigation {
+                FastNavigationConfig::Pauses => presentation.jump_next_fast(),
+                FastNavigationConfig::Slides { count } => presentation.jump_next_slides(count.max(1) as usize),
+            },
             Command::Previous => {
                 let current_slide = presentation.current_slide_index();
-                if !presentation.jump_previous() {
-                    false
+                let auto_step_position = presentation.auto_step_position();
+                let moved = presentation.jump_previous();
+                self.auto_step.observe_step(auto_step_position, presentation.auto_step_position());
+                if !moved {
+                    match self.options.end_of_presentation {
+                        EndOfPresentationConfig::Stay => false,
+                        EndOfPresentationConfig::Wrap => presentation.jump_last_slide(),
+                        EndOfPresentationConfig::Indicator => {
+                            self.status = Some(StatusLine::new("start of presentation"));
+                            true
+                        }
+                    }
                 } else if presentation.current_slide_index() != current_slide {
                     return CommandSideEffect::AnimatePreviousSlide;
                 } else {
                     true
                 }
             }
-            Command::PreviousFast => presentation.jump_previous_fast(),
+            Command::PreviousFast => match self.options.fast_navigation {
+                FastNavigationConfig::Pauses => presentation.jump_previous_fast(),
+                FastNavigationConfig::Slides { count } => presentation.jump_previous_slides(count.max(1) as usize),
+            },
+            Command::JumpBack => self.history.back().is_some_and(|slide| presentation.go_to_slide(slide)),
+            Command::JumpForward => self.history.forward().is_some_and(|slide| presentation.go_to_slide(slide)),
             Command::FirstSlide => presentation.jump_first_slide(),
             Command::LastSlide => presentation.jump_last_slide(),
-            Command::GoToSlide(number) => presentation.go_to_slide(number.saturating_sub(1) as usize),
+            Command::GoToSlide(number) => {
+                let slide_count = presentation.iter_slides().count();
+                let slide_index = number.saturating_sub(1) as usize;
+                match self.options.slide_out_of_range {
+                    _ if slide_index < slide_count => presentation.go_to_slide(slide_index),
+                    SlideOutOfRangeConfig::Clamp => presentation.jump_last_slide(),
+                    SlideOutOfRangeConfig::Error => {
+                        let message = format!("no such slide: {number}, presentation has {slide_count} slides");
+                        self.status = Some(StatusLine::new(message));
+                        true
+                    }
+                }
+            }
             Command::RenderAsyncOperations => {
                 let pollables = Self::trigger_slide_async_renders(presentation);
                 if !pollables.is_empty() {
+                    let slide = presentation.current_slide_index();
                     for pollable in pollables {
-                        self.poller.send(PollerCommand::Poll { pollable, slide: presentation.current_slide_index() });
+                        self.poller.send(PollerCommand::Poll { pollable, slide, notify: true });
                     }
                     return CommandSideEffect::Redraw;
                 } else {
                     return CommandSideEffect::None;
                 }
             }
+            Command::ResetAsyncOperations => {
+                let pollables = Self::reset_slide_async_renders(presentation, |_| true);
+                let slide = presentation.current_slide_index();
+                for pollable in pollables {
+                    self.poller.send(PollerCommand::Poll { pollable, slide, notify: false });
+                }
+                true
+            }
             Command::ToggleSlideIndex => {
                 self.toggle_slide_index();
                 true
             }
+            Command::FilterSlideIndex => {
+                if !in_slide_index {
+                    return CommandSideEffect::None;
+                }
+                presentation.state.set_slide_index_filter(Some(String::new()));
+                true
+            }
+            Command::FilterInput(input) => match input {
+                FilterInput::Char(c) => Self::update_slide_index_filter(presentation, |filter| filter.push(c)),
+                FilterInput::Backspace => Self::update_slide_index_filter(presentation, |filter| {
+                    filter.pop();
+                }),
+                FilterInput::Accept => {
+                    presentation.state.set_slide_index_filter(None);
+                    self.toggle_slide_index();
+                    true
+                }
+                FilterInput::Cancel => {
+                    presentation.state.set_slide_index_filter(None);
+                    true
+                }
+            },
             Command::ToggleKeyBindingsConfig => {
                 self.toggle_key_bindings();
                 true
             }
+            Command::ToggleNotes => {
+                self.toggle_notes();
+                true
+            }
             Command::CloseModal => {
                 let presentation = mem::take(&mut self.state).into_presentation();
                 self.state = PresenterState::Presenting(presentation);
                 true
             }
+            Command::CopySnippet => {
+                let message = match Self::copy_slide_snippet(presentation) {
+                    Ok(true) => "snippet copied to clipboard".to_string(),
+                    Ok(false) => "no snippet to copy in this slide".to_string(),
+                    Err(e) => format!("failed to copy snippet: {e}"),
+                };
+                self.status = Some(StatusLine::new(message));
+                true
+            }
+            Command::ScrollOutputUp => Self::scroll_slide_output(presentation, ScrollDirection::Up),
+            Command::ScrollOutputDown => Self::scroll_slide_output(presentation, ScrollDirection::Down),
+            Command::SnapshotSlide => {
+                let message = match self.snapshot_slide() {
+                    Ok(path) => format!("slide snapshot saved to {}", path.display()),
+                    Err(e) => format!("failed to save slide snapshot: {e}"),
+                };
+                self.status = Some(StatusLine::new(message));
+                true
+            }
+            Command::ListPersistentProcesses => {
+                let processes = self.code_executor.persistent_processes().list();
+                let message = match processes.is_empty() {
+                    true => "no persistent processes are running".to_string(),
+                    false => {
+                        let processes: Vec<_> =
+                            processes.into_iter().map(|(pid, command)| format!("{command} ({pid})")).collect();
+                        format!("persistent processes: {}", processes.join(", "))
+                    }
+                };
+                self.status = Some(StatusLine::new(message));
+                true
+            }
+            Command::KillPersistentProcesses => {
+                let killed = self.code_executor.persistent_processes().kill_all();
+                self.status = Some(StatusLine::new(format!("killed {killed} persistent processes")));
+                true
+            }
             // These are handled above as they don't require the presentation
-            Command::Reload | Command::HardReload | Command::Exit | Command::Suspend | Command::Redraw => {
+            Command::Reload
+            | Command::HardReload
+            | Command::ReloadTheme
+            | Command::ToggleLineNumbers
+            | Command::ToggleClock
+            | Command::Exit
+            | Command::Suspend
+            | Command::Redraw => {
                 panic!("unreachable commands")
             }
         };
@@ -344,7 +697,7 @@ impl<'a> Presenter<'a> {
         }
         self.poller.send(PollerCommand::Reset);
         self.resources.clear_watches();
-        match self.load_presentation(path) {
+        match self.load_presentation(path, Vec::new()) {
             Ok(mut presentation) => {
                 let current = self.state.presentation();
                 if let Some(modification) = PresentationDiffer::find_first_modification(current, &presentation) {
@@ -355,6 +708,11 @@ impl<'a> Presenter<'a> {
                     presentation.jump_chunk(current.current_chunk());
                 }
                 self.start_automatic_async_renders(&mut presentation);
+                self.auto_step = AutoStepTimer::default();
+                if let Some(thumbnails) = &mut self.thumbnails {
+                    thumbnails.clear();
+                }
+                self.reload_preview(path);
                 self.state = self.validate_overflows(presentation);
                 self.try_scale_transition_images()?;
             }
@@ -366,13 +724,76 @@ impl<'a> Presenter<'a> {
         Ok(())
     }
 
+    fn try_reload_theme(&mut self, path: &Path) -> RenderResult {
+        // A presentation that failed to load may have done so because of its theme file.
+        let failed = matches!(self.state, PresenterState::Failure { .. });
+        if !failed && !self.resources.has_themes() {
+            self.status = Some(StatusLine::new("the presentation doesn't use a theme file"));
+            return Ok(());
+        }
+        self.resources.clear_themes();
+        self.rebuild_presentation(path, "failed to reload theme")?;
+        if self.status.is_none() {
+            self.status = Some(StatusLine::new("theme reloaded"));
+        }
+        Ok(())
+    }
+
+    fn try_toggle_line_numbers(&mut self, path: &Path) -> RenderResult {
+        let options = &mut self.options.builder_options;
+        options.force_line_numbers = !options.force_line_numbers;
+        // The new setting will be picked up the next time the presentation is reloaded.
+        if matches!(self.state, PresenterState::Failure { .. }) {
+            return Ok(());
+        }
+        self.rebuild_presentation(path, "failed to toggle line numbers")
+    }
+
+    /// Build the presentation again while staying in the current slide.
+    fn rebuild_presentation(&mut self, path: &Path, error_context: &str) -> RenderResult {
+        let current = self.state.presentation();
+        let (slide_index, chunk) = (current.current_slide_index(), current.current_chunk());
+        // Reusing the snippet handles keeps the output of any snippets that already ran.
+        let snippet_handles = current.state.snippet_handles();
+        match self.load_presentation(path, snippet_handles) {
+            Ok(mut presentation) => {
+                presentation.go_to_slide(slide_index);
+                presentation.jump_chunk(chunk);
+                self.start_automatic_async_renders(&mut presentation);
+                self.auto_step = AutoStepTimer::default();
+                if let Some(thumbnails) = &mut self.thumbnails {
+                    thumbnails.clear();
+                }
+                self.reload_preview(path);
+                self.state = self.validate_overflows(presentation);
+                self.try_scale_transition_images()?;
+            }
+            Err(e) => {
+                self.status = Some(StatusLine::new(format!("{error_context}: {e}")));
+            }
+        };
+        Ok(())
+    }
+
+    fn thumbnail_dimensions(&self) -> Result<WindowSize, RenderError> {
+        let dimensions = WindowSize::current(self.options.cell_size_fallback)?;
+        let max_size = &self.options.max_size;
+        let dimensions = dimensions.shrink_columns(dimensions.columns.saturating_sub(max_size.max_columns));
+        let dimensions = dimensions.shrink_rows(dimensions.rows.saturating_sub(max_size.max_rows));
+        Ok(dimensions)
+    }
+
     fn try_scale_transition_images(&self) -> RenderResult {
         if self.options.transition.is_none() {
             return Ok(());
         }
-        let options = RenderEngineOptions { max_size: self.options.max_size.clone(), ..Default::default() };
+        let options = RenderEngineOptions {
+            max_size: self.options.max_size.clone(),
+            minimum_column_width: self.options.minimum_column_width,
+            ..Default::default()
+        };
         let scaler = AsciiScaler::new(options);
-        let dimensions = WindowSize::current(self.options.font_size_fallback)?;
+        let dimensions = WindowSize::current(self.options.cell_size_fallback)?;
         scaler.process(self.state.presentation(), &dimensions)?;
         Ok(())
     }
@@ -390,13 +811,76 @@ impl<'a> Presenter<'a> {
         pollables
     }
 
+    fn reset_entered_slide_async_renders(&mut self) {
+        let presentation = self.state.presentation_mut();
+        // Resetting kills anything that's still running so it can't compete with the next run.
+        let pollables = Self::reset_slide_async_renders(presentation, |operation| operation.reset_on_enter());
+        let slide = presentation.current_slide_index();
+        for pollable in pollables {
+            self.poller.send(PollerCommand::Poll { pollable, slide, notify: false });
+        }
+    }
+
+    fn reset_slide_async_renders<F>(presentation: &mut Presentation, filter: F) -> Vec<Box<dyn Pollable>>
+    where
+        F: Fn(&dyn RenderAsync) -> bool,
+    {
+        let slide = presentation.current_slide_mut();
+        let mut pollables = Vec::new();
+        for operation in slide.iter_operations_mut() {
+            if let RenderOperation::RenderAsync(operation) = operation {
+                if !filter(operation.as_ref()) {
+                    continue;
+                }
+                // Automatic operations need to be started again as nobody will trigger them.
+                if operation.reset() && matches!(operation.start_policy(), RenderAsyncStartPolicy::Automatic) {
+                    pollables.push(operation.pollable());
+                }
+            }
+        }
+        pollables
+    }
+
+    fn snapshot_slide(&self) -> Result<PathBuf, ExportError> {
+        let dimensions = WindowSize::current(self.options.cell_size_fallback)?;
+        let slide = self.state.presentation().current_slide();
+        save_slide_snapshot(slide, dimensions, Path::new("."))
+    }
+
+    fn scroll_slide_output(presentation: &Presentation, direction: ScrollDirection) -> bool {
+        // Only the last scrollable operation in the visible part of the slide gets scrolled.
+        let operations: Vec<_> = presentation.current_slide().iter_visible_operations().collect();
+        for operation in operations.into_iter().rev() {
+            if let RenderOperation::RenderDynamic(operation) = operation {
+                if let Some(modified) = operation.scroll(direction) {
+                    return modified;
+                }
+            }
+        }
+        false
+    }
+
+    fn copy_slide_snippet(presentation: &Presentation) -> io::Result<bool> {
+        // Like scrolling, only the last snippet in the visible part of the slide is copied.
+        let operations: Vec<_> = presentation.current_slide().iter_visible_operations().collect();
+        for operation in operations.into_iter().rev() {
+            if let RenderOperation::RenderDynamic(operation) = operation {
+                if let Some(contents) = operation.copyable_content() {
+                    copy_to_clipboard(&mut io::stdout(), contents)?;
+                    return Ok(true);
+                }
+            }
+        }
+        Ok(false)
+    }
+
     fn is_displaying_other_error(&self) -> bool {
         matches!(self.state, PresenterState::Failure { mode: FailureMode::Other, .. })
     }
 
     fn validate_overflows(&self, presentation: Presentation) -> PresenterState {
         if self.options.validate_overflows {
-            let dimensions = match WindowSize::current(self.options.font_size_fallback) {
+            let dimensions = match WindowSize::current(self.options.cell_size_fallback) {
                 Ok(dimensions) => dimensions,
                 Err(e) => {
                     return PresenterState::failure(e, presentation, ErrorSource::Presentation, FailureMode::Other);
@@ -411,7 +895,37 @@ impl<'a> Presenter<'a> {
         }
     }
 
-    fn load_presentation(&mut self, path: &Path) -> Result<Presentation, LoadPresentationError> {
+    fn load_presentation(
+        &mut self,
+        path: &Path,
+        snippet_handles: Vec<SnippetHandle>,
+    ) -> Result<Presentation, LoadPresentationError> {
+        let options = self.options.builder_options.clone();
+        self.build_presentation(path, snippet_handles, options)
+    }
+
+    fn reload_preview(&mut self, path: &Path) {
+        if self.presenter_view.is_none() {
+            return;
+        }
+        // The preview contains the actual slides rather than the speaker notes, and it's only
+        // displayed so don't run anything in it.
+        let options = PresentationBuilderOptions {
+            render_speaker_notes_only: false,
+            enable_snippet_execution: false,
+            enable_snippet_execution_replace: false,
+            validate_snippets: false,
+            ..self.options.builder_options.clone()
+        };
+        self.preview = self.build_presentation(path, Vec::new(), options).ok();
+    }
+
+    fn build_presentation(
+        &mut self,
+        path: &Path,
+        snippet_handles: Vec<SnippetHandle>,
+        options: PresentationBuilderOptions,
+    ) -> Result<Presentation, LoadPresentationError> {
         let presentation = PresentationBuilder::new(
             self.default_theme,
             self.resources.clone(),
@@ -421,8 +935,9 @@ impl<'a> Presenter<'a> {
             ImageRegistry::new(self.image_printer.clone()),
             self.options.bindings.clone(),
             &self.parser,
-            self.options.builder_options.clone(),
+            options,
         )?
+        .reuse_snippet_handles(snippet_handles)
         .build(path)?;
         Ok(presentation)
     }
@@ -430,25 +945,87 @@ impl<'a> Presenter<'a> {
     fn toggle_slide_index(&mut self) {
         let state = mem::take(&mut self.state);
         match state {
-            PresenterState::Presenting(presentation) | PresenterState::KeyBindings(presentation) => {
-                self.state = PresenterState::SlideIndex(presentation)
-            }
+            PresenterState::Presenting(presentation)
+            | PresenterState::KeyBindings(presentation)
+            | PresenterState::Notes(presentation) => self.state = PresenterState::SlideIndex(presentation),
             PresenterState::SlideIndex(presentation) => self.state = PresenterState::Presenting(presentation),
             other => self.state = other,
         }
     }
 
+    fn slide_matches(slide: &Slide, filter: &str) -> bool {
+        title_matches(slide.summary().title.as_deref().unwrap_or("<no title>"), filter)
+    }
+
+    fn update_slide_index_filter<F>(presentation: &mut Presentation, update: F) -> bool
+    where
+        F: FnOnce(&mut String),
+    {
+        let Some(mut filter) = presentation.state.slide_index_filter() else {
+            return false;
+        };
+        update(&mut filter);
+        let current_slide = presentation.current_slide_index();
+        let current_matches =
+            presentation.iter_slides().nth(current_slide).is_some_and(|slide| Self::slide_matches(slide, &filter));
+        let first_match = presentation.iter_slides().position(|slide| Self::slide_matches(slide, &filter));
+        if let (false, Some(index)) = (current_matches, first_match) {
+            presentation.go_to_slide(index);
+        }
+        presentation.state.set_slide_index_filter(Some(filter));
+        true
+    }
+
+    fn jump_matching_slide(presentation: &mut Presentation, filter: &str, forward: bool) -> bool {
+        let current_slide = presentation.current_slide_index();
+        let matching: Vec<_> = presentation
+            .iter_slides()
+            .enumerate()
+            .filter(|(_, slide)| Self::slide_matches(slide, filter))
+            .map(|(index, _)| index)
+            .collect();
+        let target = match forward {
+            true => matching.into_iter().find(|index| *index > current_slide),
+            false => matching.into_iter().rev().find(|index| *index < current_slide),
+        };
+        target.is_some_and(|index| presentation.go_to_slide(index))
+    }
+
+    fn sync_slide_index_filter(&mut self) {
+        let filtering = match &self.state {
+            PresenterState::SlideIndex(presentation) => presentation.state.slide_index_filter().is_some(),
+            PresenterState::Empty => false,
+            other => {
+                // Leaving the slide index discards whatever was being typed.
+                other.presentation().state.set_slide_index_filter(None);
+                false
+            }
+        };
+        self.listener.set_text_input(filtering);
+    }
+
     fn toggle_key_bindings(&mut self) {
         let state = mem::take(&mut self.state);
         match state {
-            PresenterState::Presenting(presentation) | PresenterState::SlideIndex(presentation) => {
-                self.state = PresenterState::KeyBindings(presentation)
-            }
+            PresenterState::Presenting(presentation)
+            | PresenterState::SlideIndex(presentation)
+            | PresenterState::Notes(presentation) => self.state = PresenterState::KeyBindings(presentation),
             PresenterState::KeyBindings(presentation) => self.state = PresenterState::Presenting(presentation),
             other => self.state = other,
         }
     }
 
+    fn toggle_notes(&mut self) {
+        let state = mem::take(&mut self.state);
+        match state {
+            PresenterState::Presenting(presentation)
+            | PresenterState::SlideIndex(presentation)
+            | PresenterState::KeyBindings(presentation) => self.state = PresenterState::Notes(presentation),
+            PresenterState::Notes(presentation) => self.state = PresenterState::Presenting(presentation),
+            other => self.state = other,
+        }
+    }
+
     fn suspend(&self, drawer: &mut TerminalDrawer) {
         #[cfg(unix)]
         unsafe {
@@ -465,7 +1042,7 @@ impl<'a> Presenter<'a> {
 
         let options = drawer.render_engine_options();
         let presentation = self.state.presentation_mut();
-        let dimensions = WindowSize::current(self.options.font_size_fallback)?;
+        let dimensions = WindowSize::current(self.options.cell_size_fallback)?;
         presentation.jump_previous();
         let left = Self::virtual_render(presentation.current_slide(), dimensions, &options)?;
         presentation.jump_next();
@@ -481,7 +1058,7 @@ impl<'a> Presenter<'a> {
 
         let options = drawer.render_engine_options();
         let presentation = self.state.presentation_mut();
-        let dimensions = WindowSize::current(self.options.font_size_fallback)?;
+        let dimensions = WindowSize::current(self.options.cell_size_fallback)?;
         presentation.jump_next();
 
         // Re-borrow to avoid calling fns above while mutably borrowing
@@ -585,7 +1162,7 @@ impl<'a> Presenter<'a> {
                 if let RenderOperation::RenderAsync(operation) = operation {
                     if let RenderAsyncStartPolicy::Automatic = operation.start_policy() {
                         let pollable = operation.pollable();
-                        self.poller.send(PollerCommand::Poll { pollable, slide: index });
+                        self.poller.send(PollerCommand::Poll { pollable, slide: index, notify: false });
                     }
                 }
             }
@@ -593,11 +1170,50 @@ impl<'a> Presenter<'a> {
     }
 }
 
+/// Keeps track of when the current slide was last advanced automatically.
+struct AutoStepTimer {
+    slide: Option<usize>,
+    since: Instant,
+    cancelled: bool,
+}
+
+impl AutoStepTimer {
+    /// Get the interval to use for the given slide, restarting the timer if the slide changed.
+    fn interval(&mut self, slide: usize, interval: Option<Duration>) -> Option<Duration> {
+        if self.slide != Some(slide) {
+            self.slide = Some(slide);
+            self.since = Instant::now();
+            self.cancelled = false;
+        }
+        if self.cancelled { None } else { interval }
+    }
+
+    /// Stop advancing automatically if a manual step moved the automatically advanced chunk itself.
+    ///
+    /// The positions are the ones before and after the step. Revealing another chunk doesn't count
+    /// so chunks behind a pause still get to advance on their own.
+    fn observe_step(&mut self, before: Option<(usize, usize)>, after: Option<(usize, usize)>) {
+        if let (Some((chunk, step)), Some((next_chunk, next_step))) = (before, after) {
+            if chunk == next_chunk && step != next_step {
+                self.cancelled = true;
+            }
+        }
+    }
+}
+
+impl Default for AutoStepTimer {
+    fn default() -> Self {
+        Self { slide: None, since: Instant::now(), cancelled: false }
+    }
+}
+
 enum CommandSideEffect {
     Exit,
     Suspend,
     Redraw,
     Reload,
+    ReloadTheme,
+    ToggleLineNumbers,
     AnimateNextSlide,
     AnimatePreviousSlide,
     None,
@@ -610,6 +1226,7 @@ enum PresenterState {
     Presenting(Presentation),
     SlideIndex(Presentation),
     KeyBindings(Presentation),
+    Notes(Presentation),
     Failure {
         error: String,
         presentation: Presentation,
@@ -633,6 +1250,7 @@ impl PresenterState {
             Self::Presenting(presentation)
             | Self::SlideIndex(presentation)
             | Self::KeyBindings(presentation)
+            | Self::Notes(presentation)
             | Self::Failure { presentation, .. } => presentation,
             Self::Empty => panic!("state is empty"),
         }
@@ -643,6 +1261,7 @@ impl PresenterState {
             Self::Presenting(presentation)
             | Self::SlideIndex(presentation)
             | Self::KeyBindings(presentation)
+            | Self::Notes(presentation)
             | Self::Failure { presentation, .. } => presentation,
             Self::Empty => panic!("state is empty"),
         }
@@ -653,6 +1272,7 @@ impl PresenterState {
             Self::Presenting(presentation)
             | Self::SlideIndex(presentation)
             | Self::KeyBindings(presentation)
+            | Self::Notes(presentation)
             | Self::Failure { presentation, .. } => presentation,
             Self::Empty => panic!("state is empty"),
         }
@@ -692,3 +1312,31 @@ pub enum PresentationError {
     #[error("io: {0}")]
     Io(#[from] io::Error),
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn auto_step_cancellation() {
+        let interval = Some(Duration::from_secs(1));
+        let mut timer = AutoStepTimer::default();
+        assert_eq!(timer.interval(0, None), None);
+
+        // Revealing a chunk behind a pause doesn't stop it from advancing on its own.
+        timer.observe_step(None, Some((1, 0)));
+        assert_eq!(timer.interval(0, interval), interval);
+
+        // Moving to another chunk doesn't either.
+        timer.observe_step(Some((1, 2)), None);
+        assert_eq!(timer.interval(0, interval), interval);
+
+        // Stepping through it by hand does.
+        timer.observe_step(Some((1, 0)), Some((1, 1)));
+        assert_eq!(timer.interval(0, interval), None);
+
+        // Until the slide is entered again.
+        timer.interval(1, None);
+        assert_eq!(timer.interval(0, interval), interval);
+    }
+}
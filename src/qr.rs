@@ -0,0 +1,477 @@
+use image::{DynamicImage, GrayImage, Luma};
+
+// The largest version supported. Version 10 fits 119 bytes using high error correction and 271
+// using low, which is plenty for links.
+const MAX_VERSION: usize = 10;
+
+// The number of modules that surround the code, as required by the spec.
+const QUIET_ZONE: u32 = 4;
+
+// The error correction codewords per block, then the number of blocks and data codewords per
+// block for each of the two block groups.
+type BlockLayout = (usize, usize, usize, usize, usize);
+
+// The block layout for every version and level, in the order low, medium, quartile, high.
+const BLOCK_LAYOUTS: [[BlockLayout; 4]; MAX_VERSION] = [
+    [(7, 1, 19, 0, 0), (10, 1, 16, 0, 0), (13, 1, 13, 0, 0), (17, 1, 9, 0, 0)],
+    [(10, 1, 34, 0, 0), (16, 1, 28, 0, 0), (22, 1, 22, 0, 0), (28, 1, 16, 0, 0)],
+    [(15, 1, 55, 0, 0), (26, 1, 44, 0, 0), (18, 2, 17, 0, 0), (22, 2, 13, 0, 0)],
+    [(20, 1, 80, 0, 0), (18, 2, 32, 0, 0), (26, 2, 24, 0, 0), (16, 4, 9, 0, 0)],
+    [(26, 1, 108, 0, 0), (24, 2, 43, 0, 0), (18, 2, 15, 2, 16), (22, 2, 11, 2, 12)],
+    [(18, 2, 68, 0, 0), (16, 4, 27, 0, 0), (24, 4, 19, 0, 0), (28, 4, 15, 0, 0)],
+    [(20, 2, 78, 0, 0), (18, 4, 31, 0, 0), (18, 2, 14, 4, 15), (26, 4, 13, 1, 14)],
+    [(24, 2, 97, 0, 0), (22, 2, 38, 2, 39), (22, 4, 18, 2, 19), (26, 4, 14, 2, 15)],
+    [(30, 2, 116, 0, 0), (22, 3, 36, 2, 37), (20, 4, 16, 4, 17), (24, 4, 12, 4, 13)],
+    [(18, 2, 68, 2, 69), (26, 4, 43, 1, 44), (24, 6, 19, 2, 20), (28, 6, 15, 2, 16)],
+];
+
+// The center coordinates of the alignment patterns for every version.
+const ALIGNMENT_POSITIONS: [&[usize]; MAX_VERSION] =
+    [&[], &[6, 18], &[6, 22], &[6, 26], &[6, 30], &[6, 34], &[6, 22, 38], &[6, 24, 42], &[6, 26, 46], &[6, 28, 50]];
+
+/// The amount of error correction to use in a QR code.
+///
+/// Higher levels let a code be read even if part of it is damaged at the cost of making it larger.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum QrErrorCorrection {
+    Low,
+    #[default]
+    Medium,
+    Quartile,
+    High,
+}
+
+impl QrErrorCorrection {
+    fn index(&self) -> usize {
+        match self {
+            Self::Low => 0,
+            Self::Medium => 1,
+            Self::Quartile => 2,
+            Self::High => 3,
+        }
+    }
+
+    fn format_bits(&self) -> u32 {
+        match self {
+            Self::Low => 1,
+            Self::Medium => 0,
+            Self::Quartile => 3,
+            Self::High => 2,
+        }
+    }
+}
+
+/// A QR code that encodes some bytes.
+#[derive(Debug)]
+pub(crate) struct QrCode {
+    size: usize,
+    modules: Vec<bool>,
+}
+
+impl QrCode {
+    /// Encode the given data using the smallest version that fits it.
+    pub(crate) fn encode(data: &[u8], error_correction: QrErrorCorrection) -> Result<Self, QrError> {
+        let version = (1..=MAX_VERSION)
+            .find(|version| Self::data_capacity(*version, error_correction) >= data.len())
+            .ok_or(QrError::TooLong(data.len()))?;
+        let codewords = Self::build_codewords(data, version, error_correction);
+        let mut canvas = Canvas::new(version);
+        canvas.draw_function_patterns(version);
+        canvas.draw_codewords(&codewords);
+
+        // Use the mask that makes the code the easiest to read.
+        let mut best: Option<(u32, Canvas)> = None;
+        for mask in 0..8 {
+            let mut candidate = canvas.clone();
+            candidate.apply_mask(mask);
+            candidate.draw_format_bits(error_correction, mask);
+            let penalty = candidate.penalty();
+            if best.as_ref().is_none_or(|(best, _)| penalty < *best) {
+                best = Some((penalty, candidate));
+            }
+        }
+        let (_, canvas) = best.expect("no masks");
+        Ok(Self { size: canvas.size, modules: canvas.modules })
+    }
+
+    /// Draw this code into an image, using the given number of pixels for every module.
+    pub(crate) fn to_image(&self, module_pixels: u32) -> DynamicImage {
+        let width = (self.size as u32 + QUIET_ZONE * 2) * module_pixels;
+        let image = GrayImage::from_fn(width, width, |x, y| {
+            let x = (x / module_pixels).checked_sub(QUIET_ZONE).map(|x| x as usize);
+            let y = (y / module_pixels).checked_sub(QUIET_ZONE).map(|y| y as usize);
+            let dark = match (x, y) {
+                (Some(x), Some(y)) if x < self.size && y < self.size => self.modules[y * self.size + x],
+                _ => false,
+            };
+            if dark { Luma([0]) } else { Luma([255]) }
+        });
+        DynamicImage::ImageLuma8(image)
+    }
+
+    fn data_capacity(version: usize, error_correction: QrErrorCorrection) -> usize {
+        let count_bits = if version < 10 { 8 } else { 16 };
+        let available_bits = Self::data_codewords(version, error_correction) * 8;
+        (available_bits - 4 - count_bits) / 8
+    }
+
+    fn data_codewords(version: usize, error_correction: QrErrorCorrection) -> usize {
+        let (_, blocks1, data1, blocks2, data2) = BLOCK_LAYOUTS[version - 1][error_correction.index()];
+        blocks1 * data1 + blocks2 * data2
+    }
+
+    fn build_codewords(data: &[u8], version: usize, error_correction: QrErrorCorrection) -> Vec<u8> {
+        // Everything is encoded in byte mode.
+        let mut bits = BitBuffer::default();
+        bits.push(0b0100, 4);
+        bits.push(data.len() as u32, if version < 10 { 8 } else { 16 });
+        for byte in data {
+            bits.push(*byte as u32, 8);
+        }
+        let capacity = Self::data_codewords(version, error_correction) * 8;
+        bits.push(0, (capacity - bits.len()).min(4));
+        bits.push(0, (8 - bits.len() % 8) % 8);
+        let mut codewords = bits.into_bytes();
+        for padding in [0xec, 0x11].into_iter().cycle() {
+            if codewords.len() * 8 >= capacity {
+                break;
+            }
+            codewords.push(padding);
+        }
+
+        let (ecc_length, blocks1, data1, blocks2, data2) = BLOCK_LAYOUTS[version - 1][error_correction.index()];
+        let mut blocks = Vec::new();
+        let mut remaining = codewords.as_slice();
+        for length in std::iter::repeat_n(data1, blocks1).chain(std::iter::repeat_n(data2, blocks2)) {
+            let (block, rest) = remaining.split_at(length);
+            blocks.push((block, reed_solomon(block, ecc_length)));
+            remaining = rest;
+        }
+
+        // Interleave the data codewords and then the error correction ones.
+        let mut output = Vec::new();
+        for index in 0..data1.max(data2) {
+            output.extend(blocks.iter().filter_map(|(data, _)| data.get(index)));
+        }
+        for index in 0..ecc_length {
+            output.extend(blocks.iter().map(|(_, ecc)| ecc[index]));
+        }
+        output
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum QrError {
+    #[error("{0} bytes are too many to fit in a QR code")]
+    TooLong(usize),
+}
+
+#[derive(Default)]
+struct BitBuffer {
+    bits: Vec<bool>,
+}
+
+impl BitBuffer {
+    fn push(&mut self, value: u32, length: usize) {
+        self.bits.extend((0..length).rev().map(|bit| (value >> bit) & 1 == 1));
+    }
+
+    fn len(&self) -> usize {
+        self.bits.len()
+    }
+
+    fn into_bytes(self) -> Vec<u8> {
+        self.bits.chunks(8).map(|byte| byte.iter().fold(0, |output, bit| (output << 1) | *bit as u8)).collect()
+    }
+}
+
+#[derive(Clone)]
+struct Canvas {
+    size: usize,
+    modules: Vec<bool>,
+    // Whether every module is part of a function pattern, which data and masks don't touch.
+    functions: Vec<bool>,
+}
+
+impl Canvas {
+    fn new(version: usize) -> Self {
+        let size = version * 4 + 17;
+        Self { size, modules: vec![false; size * size], functions: vec![false; size * size] }
+    }
+
+    fn set_function(&mut self, x: usize, y: usize, dark: bool) {
+        self.modules[y * self.size + x] = dark;
+        self.functions[y * self.size + x] = true;
+    }
+
+    fn draw_function_patterns(&mut self, version: usize) {
+        for index in 0..self.size {
+            self.set_function(6, index, index % 2 == 0);
+            self.set_function(index, 6, index % 2 == 0);
+        }
+        let far = self.size - 4;
+        for (x, y) in [(3, 3), (far, 3), (3, far)] {
+            self.draw_finder(x, y);
+        }
+        let positions = ALIGNMENT_POSITIONS[version - 1];
+        let last = positions.len().saturating_sub(1);
+        for (i, x) in positions.iter().enumerate() {
+            for (j, y) in positions.iter().enumerate() {
+                // Skip the ones that would overlap with finders.
+                if (i, j) == (0, 0) || (i, j) == (0, last) || (i, j) == (last, 0) {
+                    continue;
+                }
+                self.draw_alignment(*x, *y);
+            }
+        }
+        // Reserve the format areas, they're drawn once a mask is chosen.
+        self.draw_format_bits(QrErrorCorrection::Low, 0);
+        if version >= 7 {
+            self.draw_version(version);
+        }
+    }
+
+    fn draw_finder(&mut self, x: usize, y: usize) {
+        for dy in -4..=4_isize {
+            for dx in -4..=4_isize {
+                let (Some(x), Some(y)) = (x.checked_add_signed(dx), y.checked_add_signed(dy)) else {
+                    continue;
+                };
+                if x < self.size && y < self.size {
+                    let distance = dx.abs().max(dy.abs());
+                    self.set_function(x, y, distance != 2 && distance != 4);
+                }
+            }
+        }
+    }
+
+    fn draw_alignment(&mut self, x: usize, y: usize) {
+        for dy in -2..=2_isize {
+            for dx in -2..=2_isize {
+                self.set_function(x.wrapping_add_signed(dx), y.wrapping_add_signed(dy), dx.abs().max(dy.abs()) != 1);
+            }
+        }
+    }
+
+    fn draw_format_bits(&mut self, error_correction: QrErrorCorrection, mask: u8) {
+        let data = (error_correction.format_bits() << 3) | mask as u32;
+        let bits = ((data << 10) | bch_remainder(data, 10, 0x537)) ^ 0x5412;
+        let bit = |index: usize| (bits >> index) & 1 == 1;
+        let size = self.size;
+        for index in 0..=5 {
+            self.set_function(8, index, bit(index));
+        }
+        self.set_function(8, 7, bit(6));
+        self.set_function(8, 8, bit(7));
+        self.set_function(7, 8, bit(8));
+        for index in 9..15 {
+            self.set_function(14 - index, 8, bit(index));
+        }
+        for index in 0..8 {
+            self.set_function(size - 1 - index, 8, bit(index));
+        }
+        for index in 8..15 {
+            self.set_function(8, size - 15 + index, bit(index));
+        }
+        self.set_function(8, size - 8, true);
+    }
+
+    fn draw_version(&mut self, version: usize) {
+        let version = version as u32;
+        let bits = (version << 12) | bch_remainder(version, 12, 0x1f25);
+        for index in 0..18 {
+            let dark = (bits >> index) & 1 == 1;
+            let (a, b) = (self.size - 11 + index % 3, index / 3);
+            self.set_function(a, b, dark);
+            self.set_function(b, a, dark);
+        }
+    }
+
+    fn draw_codewords(&mut self, codewords: &[u8]) {
+        let total_bits = codewords.len() * 8;
+        let mut index = 0;
+        // Go through 2 column wide strips from right to left, zigzagging up and down.
+        let mut right = self.size - 1;
+        loop {
+            // The vertical timing pattern is skipped entirely.
+            if right == 6 {
+                right = 5;
+            }
+            let upward = (right + 1) & 2 == 0;
+            for vertical in 0..self.size {
+                let y = if upward { self.size - 1 - vertical } else { vertical };
+                for x in [right, right - 1] {
+                    let position = y * self.size + x;
+                    if !self.functions[position] && index < total_bits {
+                        self.modules[position] = (codewords[index / 8] >> (7 - index % 8)) & 1 == 1;
+                        index += 1;
+                    }
+                }
+            }
+            if right < 3 {
+                break;
+            }
+            right -= 2;
+        }
+    }
+
+    fn apply_mask(&mut self, mask: u8) {
+        for y in 0..self.size {
+            for x in 0..self.size {
+                let invert = match mask {
+                    0 => (x + y) % 2 == 0,
+                    1 => y % 2 == 0,
+                    2 => x % 3 == 0,
+                    3 => (x + y) % 3 == 0,
+                    4 => (x / 3 + y / 2) % 2 == 0,
+                    5 => x * y % 2 + x * y % 3 == 0,
+                    6 => (x * y % 2 + x * y % 3) % 2 == 0,
+                    _ => ((x + y) % 2 + x * y % 3) % 2 == 0,
+                };
+                let position = y * self.size + x;
+                if invert && !self.functions[position] {
+                    self.modules[position] = !self.modules[position];
+                }
+            }
+        }
+    }
+
+    fn get(&self, x: usize, y: usize) -> bool {
+        self.modules[y * self.size + x]
+    }
+
+    fn penalty(&self) -> u32 {
+        const FINDER_LIKE: [bool; 11] = [true, false, true, true, true, false, true, false, false, false, false];
+        let size = self.size;
+        let mut penalty = 0;
+        let rows = (0..size).map(|y| (0..size).map(|x| self.get(x, y)).collect::<Vec<_>>());
+        let columns = (0..size).map(|x| (0..size).map(|y| self.get(x, y)).collect::<Vec<_>>());
+        for line in rows.chain(columns) {
+            // Long runs of modules with the same color.
+            for run in line.chunk_by(|a, b| a == b) {
+                if run.len() >= 5 {
+                    penalty += run.len() as u32 - 2;
+                }
+            }
+            // Patterns that look like finders.
+            for window in line.windows(FINDER_LIKE.len()) {
+                if window == FINDER_LIKE || window.iter().rev().eq(FINDER_LIKE.iter()) {
+                    penalty += 40;
+                }
+            }
+        }
+        // Blocks of modules with the same color.
+        for y in 0..size - 1 {
+            for x in 0..size - 1 {
+                let color = self.get(x, y);
+                if color == self.get(x + 1, y) && color == self.get(x, y + 1) && color == self.get(x + 1, y + 1) {
+                    penalty += 3;
+                }
+            }
+        }
+        // Unbalanced amounts of dark and light modules.
+        let total = self.modules.len() as i64;
+        let dark = self.modules.iter().filter(|dark| **dark).count() as i64;
+        let k = ((dark * 20 - total * 10).abs() + total - 1) / total - 1;
+        penalty + k as u32 * 10
+    }
+}
+
+fn bch_remainder(data: u32, length: u32, generator: u32) -> u32 {
+    let mut remainder = data;
+    for _ in 0..length {
+        remainder = (remainder << 1) ^ ((remainder >> (length - 1)) * generator);
+    }
+    remainder & ((1 << length) - 1)
+}
+
+// Multiply two numbers in GF(2^8) modulo the polynomial used by QR codes.
+fn gf_multiply(a: u8, b: u8) -> u8 {
+    let mut output: u8 = 0;
+    for bit in (0..8).rev() {
+        output = (output << 1) ^ if output & 0x80 != 0 { 0x1d } else { 0 };
+        if (b >> bit) & 1 == 1 {
+            output ^= a;
+        }
+    }
+    output
+}
+
+fn reed_solomon(data: &[u8], length: usize) -> Vec<u8> {
+    // The generator polynomial is the product of (x - 2^i) for every i < length, skipping the
+    // leading coefficient which is always 1.
+    let mut generator = vec![0; length];
+    generator[length - 1] = 1;
+    let mut root = 1;
+    for _ in 0..length {
+        for index in 0..length {
+            generator[index] = gf_multiply(generator[index], root);
+            if index + 1 < length {
+                generator[index] ^= generator[index + 1];
+            }
+        }
+        root = gf_multiply(root, 2);
+    }
+
+    let mut remainder = vec![0; length];
+    for byte in data {
+        let factor = byte ^ remainder.remove(0);
+        remainder.push(0);
+        for (remainder, coefficient) in remainder.iter_mut().zip(&generator) {
+            *remainder ^= gf_multiply(*coefficient, factor);
+        }
+    }
+    remainder
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstest::rstest;
+
+    #[test]
+    fn error_correction_codewords() {
+        let data = [32, 91, 11, 120, 209, 114, 220, 77, 67, 64, 236, 17, 236, 17, 236, 17];
+        let expected = [196, 35, 39, 119, 235, 215, 231, 226, 93, 23];
+        assert_eq!(reed_solomon(&data, 10), expected);
+    }
+
+    #[rstest]
+    #[case::low_mask_0(QrErrorCorrection::Low, 0, 0b111011111000100)]
+    #[case::medium_mask_5(QrErrorCorrection::Medium, 5, 0b100000011001110)]
+    #[case::high_mask_7(QrErrorCorrection::High, 7, 0b000100000111011)]
+    fn format_bits(#[case] error_correction: QrErrorCorrection, #[case] mask: u32, #[case] expected: u32) {
+        let data = (error_correction.format_bits() << 3) | mask;
+        assert_eq!(((data << 10) | bch_remainder(data, 10, 0x537)) ^ 0x5412, expected);
+    }
+
+    #[test]
+    fn version_bits() {
+        assert_eq!((7 << 12) | bch_remainder(7, 12, 0x1f25), 0b000111110010010100);
+    }
+
+    #[rstest]
+    #[case::short(17, QrErrorCorrection::Low, 21)]
+    #[case::needs_bigger(18, QrErrorCorrection::Low, 25)]
+    #[case::more_correction(17, QrErrorCorrection::High, 29)]
+    #[case::largest(271, QrErrorCorrection::Low, 57)]
+    fn size(#[case] length: usize, #[case] error_correction: QrErrorCorrection, #[case] expected: usize) {
+        let code = QrCode::encode(&vec![b'a'; length], error_correction).expect("encoding failed");
+        assert_eq!(code.size, expected);
+    }
+
+    #[test]
+    fn too_long() {
+        QrCode::encode(&[b'a'; 272], QrErrorCorrection::Low).expect_err("encoding succeeded");
+    }
+
+    #[test]
+    fn image() {
+        let code = QrCode::encode(b"hi", QrErrorCorrection::Medium).expect("encoding failed");
+        let image = code.to_image(2).into_luma8();
+        assert_eq!(image.width(), (21 + 8) * 2);
+        // The quiet zone is light and the top left finder is dark.
+        assert_eq!(image.get_pixel(7, 7).0, [255]);
+        assert_eq!(image.get_pixel(8, 8).0, [0]);
+    }
+}
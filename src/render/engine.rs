@@ -1,12 +1,19 @@
 use super::{
-    RenderError, RenderResult, layout::Layout, operation::ImagePosition, properties::CursorPosition, text::TextDrawer,
+    RenderError, RenderResult,
+    layout::{Layout, Positioning},
+    operation::ImagePosition,
+    properties::CursorPosition,
+    text::TextDrawer,
 };
 use crate::{
     config::{MaxColumnsAlignment, MaxRowsAlignment},
-    markdown::{text::WeightedLine, text_style::Colors},
+    markdown::{
+        text::WeightedLine,
+        text_style::{Colors, TextStyle},
+    },
     render::{
         operation::{
-            AsRenderOperations, BlockLine, ImageRenderProperties, ImageSize, MarginProperties, RenderAsync,
+            AsRenderOperations, BlockLine, BorderEdge, ImageRenderProperties, ImageSize, MarginProperties, RenderAsync,
             RenderOperation,
         },
         properties::WindowSize,
@@ -19,11 +26,13 @@ use crate::{
         },
         printer::{TerminalCommand, TerminalIo},
     },
-    theme::Alignment,
+    theme::{Alignment, ImageShadowStyle, Margin},
 };
 use std::mem;
 
 const MINIMUM_LINE_LENGTH: u16 = 10;
+const BORDER_HORIZONTAL: &str = "─";
+const BORDER_VERTICAL: &str = "│";
 
 #[derive(Clone, Debug)]
 pub(crate) struct MaxSize {
@@ -49,11 +58,17 @@ pub(crate) struct RenderEngineOptions {
     pub(crate) validate_overflows: bool,
     pub(crate) max_size: MaxSize,
     pub(crate) column_layout_margin: u16,
+    pub(crate) minimum_column_width: u16,
 }
 
 impl Default for RenderEngineOptions {
     fn default() -> Self {
-        Self { validate_overflows: false, max_size: Default::default(), column_layout_margin: 4 }
+        Self {
+            validate_overflows: false,
+            max_size: Default::default(),
+            column_layout_margin: 4,
+            minimum_column_width: 0,
+        }
     }
 }
 
@@ -271,7 +286,9 @@ where
             }
             ImageSize::Specific(columns, rows) => (columns, rows),
             ImageSize::WidthScaled { ratio } => {
-                let extra_columns = (rect.dimensions.columns as f64 * (1.0 - ratio)).ceil() as u16;
+                // Round to the nearest column so fractional percentages add up when laid out together.
+                let columns = (rect.dimensions.columns as f64 * ratio).round() as u16;
+                let extra_columns = rect.dimensions.columns.saturating_sub(columns);
                 let dimensions = rect.dimensions.shrink_columns(extra_columns);
                 let image_scale =
                     self.image_scaler.scale_image(&dimensions, &rect.dimensions, width, height, &starting_cursor);
@@ -282,7 +299,20 @@ where
             ImagePosition::Cursor => starting_cursor.clone(),
             ImagePosition::Center => Self::center_cursor(columns, &rect.dimensions, &starting_cursor),
             ImagePosition::Right => Self::align_cursor_right(columns, &rect.dimensions, &starting_cursor),
+            ImagePosition::Column(column) => {
+                CursorPosition { row: starting_cursor.row, column: starting_cursor.column + column }
+            }
         };
+        // Skip the shadow if it would go past the area we're drawing on.
+        let shadow = properties.shadow.filter(|shadow| {
+            let last_column = cursor.column.saturating_add(columns).saturating_add(shadow.offset);
+            let last_row = starting_row.saturating_add(rows).saturating_add(shadow.offset);
+            last_column <= rect.start_column.saturating_add(rect.dimensions.columns)
+                && last_row <= rect.start_row.saturating_add(rect.dimensions.rows)
+        });
+        if let Some(shadow) = &shadow {
+            self.render_image_shadow(shadow, &cursor, starting_row, columns, rows)?;
+        }
         self.terminal.execute(&TerminalCommand::MoveToColumn(cursor.column))?;
 
         let options = PrintOptions {
@@ -297,8 +327,63 @@ where
         if properties.restore_cursor {
             self.terminal.execute(&TerminalCommand::MoveTo { column: starting_cursor.column, row: starting_row })?;
         } else {
-            self.terminal.execute(&TerminalCommand::MoveToRow(starting_row + rows))?;
+            let shadow_rows = shadow.map(|shadow| shadow.offset).unwrap_or_default();
+            self.terminal.execute(&TerminalCommand::MoveToRow(starting_row + rows + shadow_rows))?;
+            if let Some(caption) = &properties.caption {
+                self.render_image_caption(caption, &rect, cursor.column, columns)?;
+            }
+        }
+        self.apply_colors()
+    }
+
+    fn render_image_caption(
+        &mut self,
+        caption: &WeightedLine,
+        rect: &WindowRect,
+        start_column: u16,
+        columns: u16,
+    ) -> RenderResult {
+        // Don't bother if the image is too narrow to fit any reasonable caption.
+        if columns <= MINIMUM_LINE_LENGTH && caption.width() > columns as usize {
+            return Ok(());
         }
+        // The caption is centered within the columns the image takes up.
+        let dimensions = rect.dimensions.shrink_columns(rect.dimensions.columns.saturating_sub(columns));
+        self.window_rects.push(WindowRect { dimensions, start_column, start_row: rect.start_row });
+        let alignment = Alignment::Center { minimum_margin: Margin::Fixed(0), minimum_size: 0 };
+        let result = self.render_text(caption, alignment);
+        self.window_rects.pop();
+        result?;
+        self.terminal.execute(&TerminalCommand::MoveToNextLine)?;
+        Ok(())
+    }
+
+    fn render_image_shadow(
+        &mut self,
+        shadow: &ImageShadowStyle,
+        cursor: &CursorPosition,
+        starting_row: u16,
+        columns: u16,
+        rows: u16,
+    ) -> RenderResult {
+        // Only the parts of the shadow that stick out from under the image are drawn: a vertical
+        // strip on its right and a horizontal one below it.
+        let offset = shadow.offset;
+        self.terminal.execute(&TerminalCommand::SetBackgroundColor(shadow.color))?;
+        let style = TextStyle::default();
+        let right_strip = " ".repeat(offset as usize);
+        for row in offset..rows + offset {
+            let column = cursor.column + columns;
+            self.terminal.execute(&TerminalCommand::MoveTo { column, row: starting_row + row })?;
+            self.terminal.execute(&TerminalCommand::PrintText { content: &right_strip, style })?;
+        }
+        let bottom_strip = " ".repeat(columns as usize);
+        for row in rows..rows + offset {
+            let column = cursor.column + offset;
+            self.terminal.execute(&TerminalCommand::MoveTo { column, row: starting_row + row })?;
+            self.terminal.execute(&TerminalCommand::PrintText { content: &bottom_strip, style })?;
+        }
+        self.terminal.execute(&TerminalCommand::MoveToRow(starting_row))?;
         self.apply_colors()
     }
 
@@ -314,29 +399,74 @@ where
     }
 
     fn render_block_line(&mut self, operation: &BlockLine) -> RenderResult {
+        let BlockLine { text, block_length, alignment, border, .. } = operation;
+        let font_size = text.font_size();
+        let layout = self.build_layout(*alignment).with_font_size(font_size);
+
+        let dimensions = self.current_dimensions();
+
+        // Only draw the border if the entire block fits with it, otherwise don't draw it at all.
+        let bordered_length = block_length.saturating_add(2);
+        let border = border.as_ref().and_then(|border| {
+            let corners = border.kind.corners()?;
+            let positioning = layout.compute(dimensions, bordered_length);
+            let fits = positioning.max_line_length >= bordered_length.saturating_mul(font_size as u16);
+            fits.then_some((border, corners, positioning))
+        });
+        let Some((border, corners, positioning)) = border else {
+            let positioning = layout.compute(dimensions, *block_length);
+            if self.options.validate_overflows && text.width() as u16 > positioning.max_line_length {
+                return Err(RenderError::HorizontalOverflow);
+            }
+            return self.draw_block_text(operation, positioning);
+        };
+
+        let border_width = font_size as u16;
+        let style = border.style.size(font_size);
+        self.terminal.execute(&TerminalCommand::MoveToColumn(positioning.start_column))?;
+        let (left, right) = match border.edge {
+            BorderEdge::Top => (corners[0], corners[1]),
+            BorderEdge::Bottom => (corners[2], corners[3]),
+            BorderEdge::Sides => {
+                self.terminal.execute(&TerminalCommand::PrintText { content: BORDER_VERTICAL, style })?;
+                let inner_positioning = Positioning {
+                    max_line_length: positioning.max_line_length.saturating_sub(border_width * 2),
+                    start_column: positioning.start_column + border_width,
+                };
+                if self.options.validate_overflows && text.width() as u16 > inner_positioning.max_line_length {
+                    return Err(RenderError::HorizontalOverflow);
+                }
+                self.draw_block_text(operation, inner_positioning)?;
+                let right_column = positioning.start_column + positioning.max_line_length - border_width;
+                self.terminal.execute(&TerminalCommand::MoveToColumn(right_column))?;
+                self.terminal.execute(&TerminalCommand::PrintText { content: BORDER_VERTICAL, style })?;
+                return self.apply_colors();
+            }
+        };
+        let horizontal_length = (positioning.max_line_length / border_width).saturating_sub(2) as usize;
+        let content = format!("{left}{}{right}", BORDER_HORIZONTAL.repeat(horizontal_length));
+        self.terminal.execute(&TerminalCommand::PrintText { content: &content, style })?;
+        self.apply_colors()
+    }
+
+    fn draw_block_text(&mut self, operation: &BlockLine, positioning: Positioning) -> RenderResult {
         let BlockLine {
-            text,
-            block_length,
-            alignment,
-            block_color,
             prefix,
+            prefix_link,
             right_padding_length,
+            text,
+            block_color,
             repeat_prefix_on_wrap,
+            overflow,
+            ..
         } = operation;
-        let layout = self.build_layout(*alignment).with_font_size(text.font_size());
-
-        let dimensions = self.current_dimensions();
-
-        let positioning = layout.compute(dimensions, *block_length);
-        if self.options.validate_overflows && text.width() as u16 > positioning.max_line_length {
-            return Err(RenderError::HorizontalOverflow);
-        }
-
         self.terminal.execute(&TerminalCommand::MoveToColumn(positioning.start_column))?;
         let text_drawer =
             TextDrawer::new(prefix, *right_padding_length, text, positioning, &self.colors, MINIMUM_LINE_LENGTH)?
                 .with_surrounding_block(*block_color)
-                .repeat_prefix_on_wrap(*repeat_prefix_on_wrap);
+                .repeat_prefix_on_wrap(*repeat_prefix_on_wrap)
+                .overflow(*overflow)
+                .prefix_link(prefix_link.as_deref());
         text_drawer.draw(self.terminal)?;
 
         // Restore colors
@@ -364,6 +494,17 @@ where
         if !matches!(self.layout, LayoutState::Default) {
             self.exit_layout()?;
         }
+        // If any of the columns would be too narrow, render them one after the other instead.
+        let units: Vec<u16> = columns.iter().map(|width| *width as u16).collect();
+        let available_columns = self.current_dimensions().columns;
+        let too_narrow = (0..units.len()).any(|index| {
+            Self::column_width(available_columns, &units, index, self.options.column_layout_margin)
+                < self.options.minimum_column_width
+        });
+        if too_narrow {
+            self.layout = LayoutState::Stacked { columns: columns.len(), column: None };
+            return Ok(());
+        }
         let columns = columns
             .iter()
             .map(|width| Column { width: *width as u16, current_row: self.terminal.cursor_row() })
@@ -372,9 +513,39 @@ where
         Ok(())
     }
 
+    // The width the column at the given index will have once entered, matching what `enter_column` does.
+    fn column_width(available_columns: u16, units: &[u16], index: usize, margin: u16) -> u16 {
+        let total_column_units: u16 = units.iter().sum();
+        if total_column_units == 0 {
+            return 0;
+        }
+        let unit_width = available_columns as f64 / total_column_units as f64;
+        let other_columns = (total_column_units - units[index]) * unit_width as u16;
+        let mut width = available_columns.saturating_sub(other_columns);
+        // Edge columns only have a margin on their inner side.
+        if index < units.len() - 1 {
+            width = width.saturating_sub(margin);
+        }
+        if index > 0 {
+            width = width.saturating_sub(margin);
+        }
+        width
+    }
+
     fn enter_column(&mut self, column_index: usize) -> RenderResult {
         let columns = match mem::take(&mut self.layout) {
             LayoutState::Default => return Err(RenderError::InvalidLayoutEnter),
+            LayoutState::Stacked { columns, .. } if column_index >= columns => {
+                return Err(RenderError::InvalidLayoutEnter);
+            }
+            LayoutState::Stacked { columns, column } => {
+                // Leave some space between the contents of different columns.
+                if column.is_some_and(|column| column != column_index) {
+                    self.render_line_break()?;
+                }
+                self.layout = LayoutState::Stacked { columns, column: Some(column_index) };
+                return Ok(());
+            }
             LayoutState::InitializedColumn { columns, .. } | LayoutState::EnteredColumn { columns, .. }
                 if column_index >= columns.len() =>
             {
@@ -417,6 +588,10 @@ where
     fn exit_layout(&mut self) -> RenderResult {
         match &self.layout {
             LayoutState::Default | LayoutState::InitializedColumn { .. } => Ok(()),
+            LayoutState::Stacked { .. } => {
+                self.layout = LayoutState::Default;
+                Ok(())
+            }
             LayoutState::EnteredColumn { .. } => {
                 self.terminal.execute(&TerminalCommand::MoveTo { column: 0, row: self.max_modified_row })?;
                 self.layout = LayoutState::Default;
@@ -442,6 +617,10 @@ enum LayoutState {
         column: usize,
         columns: Vec<Column>,
     },
+    Stacked {
+        columns: usize,
+        column: Option<usize>,
+    },
 }
 
 struct Column {
@@ -609,6 +788,7 @@ mod tests {
                 MoveLeft(amount) => self.move_left(*amount)?,
                 MoveToNextLine => self.move_to_next_line()?,
                 PrintText { content, style } => self.print_text(content, style)?,
+                BeginHyperlink { .. } | EndHyperlink => (),
                 ClearScreen => self.clear_screen()?,
                 SetColors(colors) => self.set_colors(*colors)?,
                 SetBackgroundColor(color) => self.set_background_color(*color)?,
@@ -652,7 +832,7 @@ mod tests {
     fn do_render(max_size: MaxSize, operations: &[RenderOperation]) -> Vec<Instruction> {
         let mut buf = TerminalBuf::default();
         let dimensions = WindowSize { rows: 100, columns: 100, height: 200, width: 200 };
-        let options = RenderEngineOptions { validate_overflows: false, max_size, column_layout_margin: 0 };
+        let options = RenderEngineOptions { max_size, column_layout_margin: 0, ..Default::default() };
         let mut engine = RenderEngine::new(&mut buf, dimensions, options);
         engine.image_scaler = Box::new(DummyImageScaler);
         engine.render(operations.iter()).expect("render failed");
@@ -702,6 +882,55 @@ mod tests {
         assert_eq!(ops, expected);
     }
 
+    #[test]
+    fn narrow_columns_stacked() {
+        let mut buf = TerminalBuf::default();
+        let dimensions = WindowSize { rows: 100, columns: 100, height: 200, width: 200 };
+        let options = RenderEngineOptions { column_layout_margin: 0, minimum_column_width: 60, ..Default::default() };
+        let engine = RenderEngine::new(&mut buf, dimensions, options);
+        let operations = [
+            RenderOperation::InitColumnLayout { columns: vec![1, 1] },
+            RenderOperation::EnterColumn { column: 0 },
+            RenderOperation::RenderText { line: "A".into(), alignment: Alignment::Left { margin: Margin::Fixed(0) } },
+            RenderOperation::EnterColumn { column: 1 },
+            RenderOperation::RenderText { line: "B".into(), alignment: Alignment::Left { margin: Margin::Fixed(0) } },
+            RenderOperation::ExitLayout,
+        ];
+        engine.render(operations.iter()).expect("render failed");
+        // Both columns are rendered using the entire width, with a line break between them.
+        let expected = [
+            Instruction::MoveToColumn(0),
+            Instruction::PrintText("A".into()),
+            Instruction::MoveToNextLine,
+            Instruction::MoveToColumn(0),
+            Instruction::PrintText("B".into()),
+        ];
+        assert_eq!(buf.instructions, expected);
+    }
+
+    #[rstest]
+    #[case::fits(24, 57)]
+    #[case::too_narrow(25, 0)]
+    fn narrow_columns_boundary(#[case] minimum_column_width: u16, #[case] expected_column: u16) {
+        let mut buf = TerminalBuf::default();
+        let dimensions = WindowSize { rows: 100, columns: 80, height: 200, width: 160 };
+        let options = RenderEngineOptions { column_layout_margin: 4, minimum_column_width, ..Default::default() };
+        let engine = RenderEngine::new(&mut buf, dimensions, options);
+        let operations = [
+            RenderOperation::InitColumnLayout { columns: vec![2, 1] },
+            RenderOperation::EnterColumn { column: 1 },
+            RenderOperation::RenderText { line: "B".into(), alignment: Alignment::Left { margin: Margin::Fixed(0) } },
+            RenderOperation::ExitLayout,
+        ];
+        engine.render(operations.iter()).expect("render failed");
+        // The second column starts at 53 and loses the 4 columns of margin on its left, leaving it 24 columns wide.
+        let column = buf.instructions.iter().find_map(|instruction| match instruction {
+            Instruction::MoveToColumn(column) => Some(*column),
+            _ => None,
+        });
+        assert_eq!(column, Some(expected_column));
+    }
+
     #[test]
     fn bottom_margin() {
         let ops = render(&[
@@ -822,6 +1051,8 @@ mod tests {
             restore_cursor: false,
             background_color: None,
             position: ImagePosition::Cursor,
+            shadow: None,
+            caption: None,
         };
         let ops = render_with_max_size(&[RenderOperation::RenderImage(image, properties)]);
         let expected = [
@@ -856,6 +1087,8 @@ mod tests {
             restore_cursor: false,
             background_color: None,
             position: ImagePosition::Center,
+            shadow: None,
+            caption: None,
         };
         let ops = render_with_max_size(&[RenderOperation::RenderImage(image, properties)]);
         let expected = [
@@ -890,6 +1123,8 @@ mod tests {
             restore_cursor: false,
             background_color: None,
             position: ImagePosition::Right,
+            shadow: None,
+            caption: None,
         };
         let ops = render_with_max_size(&[RenderOperation::RenderImage(image, properties)]);
         let expected = [
@@ -910,6 +1145,131 @@ mod tests {
         assert_eq!(ops, expected);
     }
 
+    #[test]
+    fn image_shadow() {
+        let image = DynamicImage::new(2, 2, ColorType::Rgba8);
+        let image = Image::new(TerminalImage::Ascii(image.into()), ImageSource::Generated);
+        let properties = ImageRenderProperties {
+            z_index: 0,
+            size: ImageSize::Specific(2, 2),
+            restore_cursor: false,
+            background_color: None,
+            position: ImagePosition::Column(3),
+            shadow: Some(ImageShadowStyle { offset: 1, color: Color::Red }),
+            caption: None,
+        };
+        let ops = render_with_max_size(&[RenderOperation::RenderImage(image, properties)]);
+        let expected = [
+            Instruction::MoveTo(40, 45),
+            Instruction::SetBackgroundColor(Color::Red),
+            // right strip, shifted down by the offset
+            Instruction::MoveTo(45, 46),
+            Instruction::PrintText(" ".into()),
+            Instruction::MoveTo(45, 47),
+            Instruction::PrintText(" ".into()),
+            // bottom strip, shifted right by the offset
+            Instruction::MoveTo(44, 47),
+            Instruction::PrintText("  ".into()),
+            Instruction::MoveToRow(45),
+            Instruction::MoveToColumn(43),
+            Instruction::PrintImage(PrintOptions {
+                columns: 2,
+                rows: 2,
+                z_index: 0,
+                background_color: None,
+                column_width: 2,
+                row_height: 2,
+            }),
+            // the shadow takes up an extra row
+            Instruction::MoveToRow(48),
+        ];
+        assert_eq!(ops, expected);
+    }
+
+    #[test]
+    fn image_shadow_overflow() {
+        let image = DynamicImage::new(2, 2, ColorType::Rgba8);
+        let image = Image::new(TerminalImage::Ascii(image.into()), ImageSource::Generated);
+        let properties = ImageRenderProperties {
+            z_index: 0,
+            size: ImageSize::Specific(2, 2),
+            restore_cursor: false,
+            background_color: None,
+            position: ImagePosition::Right,
+            shadow: Some(ImageShadowStyle { offset: 1, color: Color::Red }),
+            caption: None,
+        };
+        let ops = render_with_max_size(&[RenderOperation::RenderImage(image, properties)]);
+        // the image is right aligned so there's no room for the shadow
+        assert!(!ops.contains(&Instruction::SetBackgroundColor(Color::Red)));
+        assert_eq!(ops.last(), Some(&Instruction::MoveToRow(47)));
+    }
+
+    #[test]
+    fn image_caption() {
+        let image = DynamicImage::new(2, 2, ColorType::Rgba8);
+        let image = Image::new(TerminalImage::Ascii(image.into()), ImageSource::Generated);
+        let properties = ImageRenderProperties {
+            z_index: 0,
+            size: ImageSize::Specific(12, 2),
+            restore_cursor: false,
+            background_color: None,
+            position: ImagePosition::Column(3),
+            shadow: None,
+            caption: Some(WeightedLine::from("hi")),
+        };
+        let ops = render_with_max_size(&[RenderOperation::RenderImage(image, properties)]);
+        let expected = [
+            Instruction::MoveTo(40, 45),
+            Instruction::MoveToColumn(43),
+            Instruction::PrintImage(PrintOptions {
+                columns: 12,
+                rows: 2,
+                z_index: 0,
+                background_color: None,
+                column_width: 2,
+                row_height: 2,
+            }),
+            Instruction::MoveToRow(47),
+            // centered within the 12 columns the image takes up
+            Instruction::MoveToColumn(48),
+            Instruction::PrintText("hi".into()),
+            Instruction::MoveToNextLine,
+        ];
+        assert_eq!(ops, expected);
+    }
+
+    #[test]
+    fn image_at_column() {
+        let image = DynamicImage::new(2, 2, ColorType::Rgba8);
+        let image = Image::new(TerminalImage::Ascii(image.into()), ImageSource::Generated);
+        let properties = ImageRenderProperties {
+            z_index: 0,
+            size: ImageSize::Specific(2, 2),
+            restore_cursor: false,
+            background_color: None,
+            position: ImagePosition::Column(3),
+            shadow: None,
+            caption: None,
+        };
+        let ops = render_with_max_size(&[RenderOperation::RenderImage(image, properties)]);
+        let expected = [
+            Instruction::MoveTo(40, 45),
+            // 3 columns away from the start of the layout
+            Instruction::MoveToColumn(43),
+            Instruction::PrintImage(PrintOptions {
+                columns: 2,
+                rows: 2,
+                z_index: 0,
+                background_color: None,
+                column_width: 2,
+                row_height: 2,
+            }),
+            Instruction::MoveToRow(47),
+        ];
+        assert_eq!(ops, expected);
+    }
+
     // same as the above but center it
     #[rstest]
     fn restore_cursor_after_image() {
@@ -921,6 +1281,8 @@ mod tests {
             restore_cursor: true,
             background_color: None,
             position: ImagePosition::Center,
+            shadow: None,
+            caption: None,
         };
         let ops = render_with_max_size(&[RenderOperation::RenderImage(image, properties)]);
         let expected = [
@@ -12,7 +12,10 @@ use crate::{
         text::WeightedLine,
         text_style::{Color, Colors, PaletteColorError, TextStyle},
     },
-    render::{operation::RenderOperation, properties::WindowSize},
+    render::{
+        operation::RenderOperation,
+        properties::{CellSizeFallback, WindowSize},
+    },
     terminal::{
         Terminal,
         ansi::AnsiParser,
@@ -33,15 +36,11 @@ use std::{
 /// The result of a render operation.
 pub(crate) type RenderResult = Result<(), RenderError>;
 
+#[derive(Default)]
 pub(crate) struct TerminalDrawerOptions {
-    pub(crate) font_size_fallback: u8,
+    pub(crate) cell_size_fallback: CellSizeFallback,
     pub(crate) max_size: MaxSize,
-}
-
-impl Default for TerminalDrawerOptions {
-    fn default() -> Self {
-        Self { font_size_fallback: 1, max_size: Default::default() }
-    }
+    pub(crate) minimum_column_width: u16,
 }
 
 /// Allows drawing on the terminal.
@@ -60,7 +59,7 @@ impl TerminalDrawer {
         &mut self,
         operations: impl Iterator<Item = &'a RenderOperation>,
     ) -> RenderResult {
-        let dimensions = WindowSize::current(self.options.font_size_fallback)?;
+        let dimensions = WindowSize::current(self.options.cell_size_fallback)?;
         let engine = self.create_engine(dimensions);
         engine.render(operations)?;
         Ok(())
@@ -71,17 +70,21 @@ impl TerminalDrawer {
         let lines = lines.into_iter().map(Into::into).collect();
         let operation = RenderErrorOperation { lines, source: source.clone() };
         let operation = RenderOperation::RenderDynamic(Rc::new(operation));
-        let dimensions = WindowSize::current(self.options.font_size_fallback)?;
+        let dimensions = WindowSize::current(self.options.cell_size_fallback)?;
         let engine = self.create_engine(dimensions);
         engine.render(iter::once(&operation))?;
         Ok(())
     }
 
     pub(crate) fn render_engine_options(&self) -> RenderEngineOptions {
-        RenderEngineOptions { max_size: self.options.max_size.clone(), ..Default::default() }
+        RenderEngineOptions {
+            max_size: self.options.max_size.clone(),
+            minimum_column_width: self.options.minimum_column_width,
+            ..Default::default()
+        }
     }
 
-    fn create_engine(&mut self, dimensions: WindowSize) -> RenderEngine<Terminal<Stdout>> {
+    fn create_engine(&mut self, dimensions: WindowSize) -> RenderEngine<'_, Terminal<Stdout>> {
         let options = self.render_engine_options();
         RenderEngine::new(&mut self.terminal, dimensions, options)
     }
@@ -2,10 +2,10 @@ use super::properties::WindowSize;
 use crate::{
     markdown::{
         text::{WeightedLine, WeightedText},
-        text_style::{Color, Colors},
+        text_style::{Color, Colors, TextStyle},
     },
     terminal::image::Image,
-    theme::{Alignment, Margin},
+    theme::{Alignment, CodeBlockBorder, ImageShadowStyle, Margin},
 };
 use std::{
     fmt::Debug,
@@ -19,12 +19,50 @@ const DEFAULT_IMAGE_Z_INDEX: i32 = -2;
 #[derive(Clone, Debug, PartialEq)]
 pub(crate) struct BlockLine {
     pub(crate) prefix: WeightedText,
+    pub(crate) prefix_link: Option<String>,
     pub(crate) right_padding_length: u16,
     pub(crate) repeat_prefix_on_wrap: bool,
     pub(crate) text: WeightedLine,
     pub(crate) block_length: u16,
     pub(crate) block_color: Option<Color>,
     pub(crate) alignment: Alignment,
+    pub(crate) border: Option<BlockBorder>,
+    pub(crate) overflow: LineOverflow,
+}
+
+/// What to do with a line that doesn't fit in the available width.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) enum LineOverflow {
+    /// Wrap the line at word boundaries.
+    #[default]
+    WrapWords,
+
+    /// Wrap the line at the last character that fits.
+    WrapCharacters,
+
+    /// Cut the line and display an ellipsis at the end.
+    Clip,
+}
+
+/// The part of a border around a block that a [BlockLine] draws.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct BlockBorder {
+    pub(crate) kind: CodeBlockBorder,
+    pub(crate) edge: BorderEdge,
+    pub(crate) style: TextStyle,
+}
+
+/// An edge in a block's border.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum BorderEdge {
+    /// The top edge, which is drawn instead of the line's text.
+    Top,
+
+    /// The left and right edges, drawn around the line's text.
+    Sides,
+
+    /// The bottom edge, which is drawn instead of the line's text.
+    Bottom,
 }
 
 /// A render operation.
@@ -106,6 +144,8 @@ pub(crate) struct ImageRenderProperties {
     pub(crate) restore_cursor: bool,
     pub(crate) background_color: Option<Color>,
     pub(crate) position: ImagePosition,
+    pub(crate) shadow: Option<ImageShadowStyle>,
+    pub(crate) caption: Option<WeightedLine>,
 }
 
 impl Default for ImageRenderProperties {
@@ -116,6 +156,8 @@ impl Default for ImageRenderProperties {
             restore_cursor: false,
             background_color: None,
             position: ImagePosition::Center,
+            shadow: None,
+            caption: None,
         }
     }
 }
@@ -125,6 +167,7 @@ pub(crate) enum ImagePosition {
     Cursor,
     Center,
     Right,
+    Column(u16),
 }
 
 /// The size used when printing an image.
@@ -160,6 +203,25 @@ pub(crate) trait AsRenderOperations: Debug + 'static {
     fn diffable_content(&self) -> Option<&str> {
         None
     }
+
+    /// Get the content in this type that can be copied into the clipboard.
+    fn copyable_content(&self) -> Option<&str> {
+        None
+    }
+
+    /// Scroll the contents of this type one row in the given direction.
+    ///
+    /// Returns `None` if this type can't be scrolled, otherwise whether the contents moved.
+    fn scroll(&self, _direction: ScrollDirection) -> Option<bool> {
+        None
+    }
+}
+
+/// The direction in which to scroll.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ScrollDirection {
+    Up,
+    Down,
 }
 
 /// An operation that can be rendered asynchronously.
@@ -175,10 +237,22 @@ pub(crate) trait RenderAsync: AsRenderOperations {
     fn start_policy(&self) -> RenderAsyncStartPolicy {
         RenderAsyncStartPolicy::OnDemand
     }
+
+    /// Reset this operation to its initial state so it can be started again.
+    ///
+    /// Returns `true` if the operation was modified.
+    fn reset(&self) -> bool {
+        false
+    }
+
+    /// Whether this operation should be reset every time the slide it belongs to is entered.
+    fn reset_on_enter(&self) -> bool {
+        false
+    }
 }
 
 /// The start policy for an async render.
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub(crate) enum RenderAsyncStartPolicy {
     /// Start automatically.
     Automatic,
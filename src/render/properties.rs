@@ -1,6 +1,26 @@
 use crossterm::terminal;
 use std::io::{self, ErrorKind};
 
+/// The default height/width ratio of a terminal cell.
+pub(crate) const DEFAULT_CELL_ASPECT_RATIO: f64 = 2.0;
+
+/// The values used to compute the size of a terminal cell in pixels when the terminal doesn't
+/// report it.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct CellSizeFallback {
+    /// The width of a cell, in pixels.
+    pub(crate) font_size: u8,
+
+    /// The height/width ratio of a cell.
+    pub(crate) aspect_ratio: f64,
+}
+
+impl Default for CellSizeFallback {
+    fn default() -> Self {
+        Self { font_size: 1, aspect_ratio: DEFAULT_CELL_ASPECT_RATIO }
+    }
+}
+
 /// The size of the terminal window.
 ///
 /// This is the same as [crossterm::terminal::window_size] except with some added functionality,
@@ -15,7 +35,10 @@ pub(crate) struct WindowSize {
 
 impl WindowSize {
     /// Get the current window size.
-    pub(crate) fn current(font_size_fallback: u8) -> io::Result<Self> {
+    ///
+    /// The pixel size is taken from what the terminal reports, using the given fallback for any
+    /// dimension the terminal doesn't report.
+    pub(crate) fn current(fallback: CellSizeFallback) -> io::Result<Self> {
         let mut size: Self = match terminal::window_size() {
             Ok(size) => size.into(),
             Err(e) if e.kind() == ErrorKind::Unsupported => {
@@ -25,14 +48,21 @@ impl WindowSize {
             }
             Err(e) => return Err(e),
         };
-        let font_size_fallback = font_size_fallback as u16;
-        if size.width == 0 {
-            size.width = size.columns * font_size_fallback.max(1);
+        size.fill_missing_pixels(fallback);
+        Ok(size)
+    }
+
+    fn fill_missing_pixels(&mut self, fallback: CellSizeFallback) {
+        if self.width == 0 {
+            self.width = self.columns.saturating_mul(fallback.font_size.max(1) as u16);
         }
-        if size.height == 0 {
-            size.height = size.rows * font_size_fallback.max(1) * 2;
+        if self.height == 0 {
+            // Use the real cell width if we know it so the cell aspect ratio is preserved.
+            let aspect_ratio =
+                if fallback.aspect_ratio > 0.0 { fallback.aspect_ratio } else { DEFAULT_CELL_ASPECT_RATIO };
+            let height = self.rows as f64 * self.pixels_per_column() * aspect_ratio;
+            self.height = height.round().min(u16::MAX as f64) as u16;
         }
-        Ok(size)
     }
 
     /// Shrink a window by the given number of rows.
@@ -101,6 +131,7 @@ pub(crate) struct CursorPosition {
 #[cfg(test)]
 mod test {
     use super::*;
+    use rstest::rstest;
 
     #[test]
     fn shrink() {
@@ -116,4 +147,15 @@ mod test {
         assert_eq!(new_dimensions.columns, 7);
         assert_eq!(new_dimensions.width, 140);
     }
+
+    #[rstest]
+    #[case::nothing_reported((0, 0), (80, 400))]
+    #[case::width_reported((100, 0), (100, 500))]
+    #[case::everything_reported((100, 300), (100, 300))]
+    fn missing_pixels(#[case] reported: (u16, u16), #[case] expected: (u16, u16)) {
+        let (width, height) = reported;
+        let mut dimensions = WindowSize { rows: 10, columns: 10, width, height };
+        dimensions.fill_missing_pixels(CellSizeFallback { font_size: 8, aspect_ratio: 5.0 });
+        assert_eq!((dimensions.width, dimensions.height), expected);
+    }
 }
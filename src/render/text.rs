@@ -4,7 +4,7 @@ use crate::{
         text::{WeightedLine, WeightedText},
         text_style::{Color, Colors, TextStyle},
     },
-    render::{RenderError, RenderResult, layout::Positioning},
+    render::{RenderError, RenderResult, layout::Positioning, operation::LineOverflow},
     terminal::printer::{TerminalCommand, TerminalIo},
 };
 
@@ -13,6 +13,7 @@ use crate::{
 /// This deals with splitting words and doing word wrapping based on the given positioning.
 pub(crate) struct TextDrawer<'a> {
     prefix: &'a WeightedText,
+    prefix_link: Option<&'a str>,
     right_padding_length: u16,
     line: &'a WeightedLine,
     positioning: Positioning,
@@ -22,8 +23,11 @@ pub(crate) struct TextDrawer<'a> {
     block_color: Option<Color>,
     repeat_prefix: bool,
     center_newlines: bool,
+    overflow: LineOverflow,
 }
 
+const ELLIPSIS: &str = "…";
+
 impl<'a> TextDrawer<'a> {
     pub(crate) fn new(
         prefix: &'a WeightedText,
@@ -48,6 +52,7 @@ impl<'a> TextDrawer<'a> {
         };
         Ok(Self {
             prefix,
+            prefix_link: None,
             right_padding_length,
             line,
             positioning,
@@ -57,6 +62,7 @@ impl<'a> TextDrawer<'a> {
             block_color: None,
             repeat_prefix: false,
             center_newlines: false,
+            overflow: Default::default(),
         })
     }
 
@@ -76,6 +82,17 @@ impl<'a> TextDrawer<'a> {
         self
     }
 
+    pub(crate) fn overflow(mut self, overflow: LineOverflow) -> Self {
+        self.overflow = overflow;
+        self
+    }
+
+    /// Make the prefix a hyperlink to the given url.
+    pub(crate) fn prefix_link(mut self, url: Option<&'a str>) -> Self {
+        self.prefix_link = url;
+        self
+    }
+
     /// Draw text on the given handle.
     ///
     /// This performs word splitting and word wrapping.
@@ -89,10 +106,19 @@ impl<'a> TextDrawer<'a> {
 
         // Print the prefix at the beginning of the line.
         if self.prefix_width > 0 {
-            let Text { content, style } = self.prefix.text();
-            terminal.execute(&TerminalCommand::PrintText { content, style: *style })?;
+            self.print_prefix(terminal)?;
         }
-        for (line_index, line) in self.line.split(self.positioning.max_line_length as usize).enumerate() {
+        let max_line_length = self.positioning.max_line_length as usize;
+        let clip = self.overflow == LineOverflow::Clip && self.line.width() > max_line_length;
+        let lines = match self.overflow {
+            LineOverflow::WrapWords => self.line.split(max_line_length),
+            // Leave space for the ellipsis if we're clipping.
+            LineOverflow::Clip if clip => self.line.split_characters(max_line_length.saturating_sub(font_size.into())),
+            LineOverflow::WrapCharacters | LineOverflow::Clip => self.line.split_characters(max_line_length),
+        };
+        let lines = lines.take(if clip { 1 } else { usize::MAX });
+        let mut last_style = TextStyle::default().size(font_size);
+        for (line_index, line) in lines.enumerate() {
             if line_index > 0 {
                 // Complete the current line's block to the right before moving down.
                 self.print_block_background(line_length, terminal)?;
@@ -111,8 +137,7 @@ impl<'a> TextDrawer<'a> {
                 // Complete the new line in this block to the left where the prefix would be.
                 if self.prefix_width > 0 {
                     if self.repeat_prefix {
-                        let Text { content, style } = self.prefix.text();
-                        terminal.execute(&TerminalCommand::PrintText { content, style: *style })?;
+                        self.print_prefix(terminal)?;
                     } else {
                         if let Some(color) = self.block_color {
                             terminal.execute(&TerminalCommand::SetBackgroundColor(color))?;
@@ -128,6 +153,7 @@ impl<'a> TextDrawer<'a> {
 
                 let (text, style) = chunk.into_parts();
                 terminal.execute(&TerminalCommand::PrintText { content: text, style })?;
+                last_style = style;
 
                 // Crossterm resets colors if any attributes are set so let's just re-apply colors
                 // if the format has anything on it at all.
@@ -136,10 +162,32 @@ impl<'a> TextDrawer<'a> {
                 }
             }
         }
+        if clip {
+            terminal.execute(&TerminalCommand::PrintText { content: ELLIPSIS, style: last_style })?;
+            if last_style != Default::default() {
+                terminal.execute(&TerminalCommand::SetColors(*self.default_colors))?;
+            }
+            line_length = line_length.saturating_add(font_size.into());
+        }
         self.print_block_background(line_length, terminal)?;
         Ok(())
     }
 
+    fn print_prefix<T>(&self, terminal: &mut T) -> RenderResult
+    where
+        T: TerminalIo,
+    {
+        let Text { content, style } = self.prefix.text();
+        if let Some(url) = self.prefix_link {
+            terminal.execute(&TerminalCommand::BeginHyperlink { url })?;
+        }
+        terminal.execute(&TerminalCommand::PrintText { content, style: *style })?;
+        if self.prefix_link.is_some() {
+            terminal.execute(&TerminalCommand::EndHyperlink)?;
+        }
+        Ok(())
+    }
+
     fn print_block_background<T>(&self, line_length: u16, terminal: &mut T) -> RenderResult
     where
         T: TerminalIo,
@@ -173,6 +221,8 @@ mod tests {
         MoveDown(u16),
         MoveToColumn(u16),
         PrintText { content: String, font_size: u8 },
+        BeginHyperlink(String),
+        EndHyperlink,
     }
 
     #[derive(Default)]
@@ -240,6 +290,8 @@ mod tests {
                 MoveToColumn(column) => self.move_to_column(*column)?,
                 MoveDown(amount) => self.move_down(*amount)?,
                 PrintText { content, style } => self.print_text(content, style)?,
+                BeginHyperlink { url } => self.push(Instruction::BeginHyperlink(url.to_string()))?,
+                EndHyperlink => self.push(Instruction::EndHyperlink)?,
                 ClearScreen => self.clear_screen()?,
                 SetColors(colors) => self.set_colors(*colors)?,
                 SetBackgroundColor(color) => self.set_background_color(*color)?,
@@ -255,6 +307,7 @@ mod tests {
 
     struct TestDrawer {
         prefix: WeightedText,
+        prefix_link: Option<String>,
         positioning: Positioning,
         right_padding_length: u16,
         repeat_prefix_on_wrap: bool,
@@ -267,6 +320,11 @@ mod tests {
             self
         }
 
+        fn prefix_link(mut self, url: &str) -> Self {
+            self.prefix_link = Some(url.into());
+            self
+        }
+
         fn start_column(mut self, column: u16) -> Self {
             self.positioning.start_column = column;
             self
@@ -293,7 +351,8 @@ mod tests {
             let drawer = TextDrawer::new(&self.prefix, self.right_padding_length, &line, self.positioning, &colors, 0)
                 .expect("failed to create drawer")
                 .repeat_prefix_on_wrap(self.repeat_prefix_on_wrap)
-                .center_newlines(self.center_newlines);
+                .center_newlines(self.center_newlines)
+                .prefix_link(self.prefix_link.as_deref());
             let mut buf = TerminalBuf::default();
             drawer.draw(&mut buf).expect("drawing failed");
             buf.instructions
@@ -304,6 +363,7 @@ mod tests {
         fn default() -> Self {
             Self {
                 prefix: WeightedText::from(""),
+                prefix_link: None,
                 positioning: Positioning { max_line_length: 100, start_column: 0 },
                 right_padding_length: 0,
                 repeat_prefix_on_wrap: false,
@@ -362,6 +422,30 @@ mod tests {
         assert_eq!(instructions, expected);
     }
 
+    #[test]
+    fn linked_prefix() {
+        let instructions = TestDrawer::default()
+            .prefix("1 ")
+            .prefix_link("https://example.com#L1")
+            .max_line_length(4)
+            .repeat_prefix_on_wrap()
+            .draw("AAAA");
+        let expected = &[
+            Instruction::MoveToColumn(0),
+            Instruction::BeginHyperlink("https://example.com#L1".into()),
+            Instruction::PrintText { content: "1 ".into(), font_size: 1 },
+            Instruction::EndHyperlink,
+            Instruction::PrintText { content: "AA".into(), font_size: 1 },
+            Instruction::MoveDown(1),
+            Instruction::MoveToColumn(0),
+            Instruction::BeginHyperlink("https://example.com#L1".into()),
+            Instruction::PrintText { content: "1 ".into(), font_size: 1 },
+            Instruction::EndHyperlink,
+            Instruction::PrintText { content: "AA".into(), font_size: 1 },
+        ];
+        assert_eq!(instructions, expected);
+    }
+
     #[test]
     fn center_newlines() {
         let text = WeightedLine::from(vec![Text::from("hello world foo")]);
@@ -142,6 +142,18 @@ impl Resources {
         inner.themes.clear();
     }
 
+    /// Whether any theme was loaded from a file.
+    pub(crate) fn has_themes(&self) -> bool {
+        let inner = self.inner.borrow();
+        !inner.themes.is_empty()
+    }
+
+    /// Clears all cached themes.
+    pub(crate) fn clear_themes(&self) {
+        let mut inner = self.inner.borrow_mut();
+        inner.themes.clear();
+    }
+
     pub(crate) fn resolve_path<P: AsRef<Path>>(&self, path: P, base_path: &ResourceBasePath) -> PathBuf {
         match base_path {
             ResourceBasePath::Presentation => {
@@ -2,7 +2,7 @@ use crate::markdown::{
     elements::{Line, Text},
     text_style::{Color, TextStyle},
 };
-use std::mem;
+use std::collections::BTreeSet;
 use vte::{ParamsIter, Parser, Perform};
 
 pub(crate) struct AnsiParser {
@@ -14,46 +14,142 @@ impl AnsiParser {
         Self { starting_style: current_style }
     }
 
+    /// Parse the given lines, applying any styles and cursor movements in them.
+    ///
+    /// Cursor movements, line/screen clearing, and absolute positioning are applied to the
+    /// buffer built from all lines parsed so far, so programs that redraw their output in place
+    /// end up producing only their last frame.
     pub(crate) fn parse_lines<I, S>(self, lines: I) -> (Vec<Line>, TextStyle)
     where
         I: IntoIterator<Item = S>,
         S: AsRef<str>,
     {
-        let mut output_lines = Vec::new();
-        let mut style = self.starting_style;
-        for line in lines {
-            let mut handler = Handler::new(style);
-            let mut parser = Parser::new();
-            parser.advance(&mut handler, line.as_ref().as_bytes());
+        let (lines, style, _) = self.parse_tagged_lines(lines, &Default::default());
+        (lines, style)
+    }
 
-            let (line, ending_style) = handler.into_parts();
-            output_lines.push(line);
-            style = ending_style;
+    /// Parse the given lines like [`AnsiParser::parse_lines`], also returning which of the
+    /// resulting lines were written by the input lines whose indexes are in `tagged`.
+    ///
+    /// A resulting line is only considered tagged if every character in it was printed by a
+    /// tagged input line.
+    pub(crate) fn parse_tagged_lines<I, S>(
+        self,
+        lines: I,
+        tagged: &BTreeSet<usize>,
+    ) -> (Vec<Line>, TextStyle, BTreeSet<usize>)
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut handler = Handler::new(self.starting_style);
+        let mut parser = Parser::new();
+        for (index, line) in lines.into_iter().enumerate() {
+            if index > 0 {
+                handler.next_line();
+            }
+            handler.ensure_row();
+            handler.tagged = tagged.contains(&index);
+            parser.advance(&mut handler, line.as_ref().as_bytes());
         }
-        (output_lines, style)
+        handler.into_parts()
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Cell {
+    character: char,
+    style: TextStyle,
+    tagged: bool,
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self { character: ' ', style: Default::default(), tagged: false }
     }
 }
 
 struct Handler {
-    line: Line,
-    pending_text: Text,
+    rows: Vec<Vec<Cell>>,
+    row: usize,
+    column: usize,
     style: TextStyle,
+    tagged: bool,
 }
 
 impl Handler {
     fn new(style: TextStyle) -> Self {
-        Self { line: Default::default(), pending_text: Default::default(), style }
+        Self { rows: Default::default(), row: 0, column: 0, style, tagged: false }
     }
 
-    fn into_parts(mut self) -> (Line, TextStyle) {
-        self.save_pending_text();
-        (self.line, self.style)
+    fn into_parts(mut self) -> (Vec<Line>, TextStyle, BTreeSet<usize>) {
+        // Moving the cursor up leaves behind the empty rows that were started below it.
+        while self.rows.len() > self.row + 1 && self.rows.last().is_some_and(Vec::is_empty) {
+            self.rows.pop();
+        }
+        let mut lines = Vec::new();
+        let mut tagged = BTreeSet::new();
+        for (index, cells) in self.rows.into_iter().enumerate() {
+            let mut printed = cells.iter().filter(|cell| !cell.character.is_whitespace()).peekable();
+            if printed.peek().is_some() && printed.all(|cell| cell.tagged) {
+                tagged.insert(index);
+            }
+            let mut line = Line::default();
+            for cell in cells {
+                match line.0.last_mut() {
+                    Some(text) if text.style == cell.style => text.content.push(cell.character),
+                    _ => line.0.push(Text::new(cell.character.to_string(), cell.style)),
+                }
+            }
+            lines.push(line);
+        }
+        (lines, self.style, tagged)
     }
 
-    fn save_pending_text(&mut self) {
-        if !self.pending_text.content.is_empty() {
-            self.line.0.push(mem::take(&mut self.pending_text));
+    fn ensure_row(&mut self) -> &mut Vec<Cell> {
+        if self.rows.len() <= self.row {
+            self.rows.resize_with(self.row + 1, Default::default);
         }
+        &mut self.rows[self.row]
+    }
+
+    fn next_line(&mut self) {
+        self.row += 1;
+        self.column = 0;
+        self.ensure_row();
+    }
+
+    fn erase_line(&mut self, mode: u16) {
+        let column = self.column;
+        let Some(row) = self.rows.get_mut(self.row) else {
+            return;
+        };
+        match mode {
+            0 => row.truncate(column),
+            1 => row.iter_mut().take(column + 1).for_each(|cell| *cell = Cell::default()),
+            2 => row.clear(),
+            _ => (),
+        };
+    }
+
+    fn erase_display(&mut self, mode: u16) {
+        match mode {
+            0 => {
+                self.erase_line(0);
+                self.rows.truncate(self.row + 1);
+            }
+            1 => {
+                self.rows.iter_mut().take(self.row).for_each(Vec::clear);
+                self.erase_line(1);
+            }
+            2 | 3 => self.rows.clear(),
+            _ => (),
+        };
+    }
+
+    // Movements default to 1 when the parameter is missing or 0.
+    fn amount(value: Option<u16>) -> usize {
+        value.filter(|value| *value > 0).unwrap_or(1) as usize
     }
 
     fn parse_standard_color(value: u16) -> Option<Color> {
@@ -109,10 +205,7 @@ impl Handler {
 
     fn update_style(&self, mut codes: ParamsIter) -> TextStyle {
         let mut style = self.style;
-        loop {
-            let Some(&[next]) = codes.next() else {
-                break;
-            };
+        while let Some(&[next]) = codes.next() {
             match next {
                 0 => style = Default::default(),
                 1 => style = style.bold(),
@@ -153,16 +246,54 @@ impl Handler {
 }
 
 impl Perform for Handler {
-    fn print(&mut self, c: char) {
-        self.pending_text.content.push(c);
+    fn print(&mut self, character: char) {
+        let column = self.column;
+        let style = self.style;
+        let tagged = self.tagged;
+        let row = self.ensure_row();
+        if row.len() <= column {
+            row.resize_with(column + 1, Default::default);
+        }
+        row[column] = Cell { character, style, tagged };
+        self.column += 1;
+    }
+
+    fn execute(&mut self, byte: u8) {
+        match byte {
+            b'\n' => self.next_line(),
+            b'\r' => self.column = 0,
+            0x08 => self.column = self.column.saturating_sub(1),
+            _ => (),
+        };
     }
 
     fn csi_dispatch(&mut self, params: &vte::Params, _intermediates: &[u8], _ignore: bool, action: char) {
-        if action == 'm' {
-            self.save_pending_text();
-            self.style = self.update_style(params.iter());
-            self.pending_text.style = self.style;
-        }
+        let mut values = params.iter().map(|param| param.first().copied().unwrap_or_default());
+        let first = values.next();
+        match action {
+            'm' => self.style = self.update_style(params.iter()),
+            'A' => self.row = self.row.saturating_sub(Self::amount(first)),
+            'B' => self.row += Self::amount(first),
+            'C' => self.column += Self::amount(first),
+            'D' => self.column = self.column.saturating_sub(Self::amount(first)),
+            'E' => {
+                self.row += Self::amount(first);
+                self.column = 0;
+            }
+            'F' => {
+                self.row = self.row.saturating_sub(Self::amount(first));
+                self.column = 0;
+            }
+            'G' => self.column = Self::amount(first) - 1,
+            'd' => self.row = Self::amount(first) - 1,
+            'H' | 'f' => {
+                self.row = Self::amount(first) - 1;
+                self.column = Self::amount(values.next()) - 1;
+            }
+            'K' => self.erase_line(first.unwrap_or_default()),
+            'J' => self.erase_display(first.unwrap_or_default()),
+            _ => (),
+        };
     }
 }
 
@@ -270,4 +401,37 @@ mod tests {
         let (lines, _) = splitter.parse_lines([input]);
         assert_eq!(lines, vec![expected]);
     }
+
+    #[rstest]
+    #[case::plain(&["a", "b"], &["a", "b"])]
+    #[case::carriage_return(&["hello\rj"], &["jello"])]
+    #[case::backspace(&["ab\x08c"], &["ac"])]
+    #[case::erase_to_end(&["hello\x1b[3D\x1b[K"], &["he"])]
+    #[case::erase_whole_line(&["hello\x1b[2Kbye"], &["     bye"])]
+    #[case::cursor_up(&["frame 1", "status", "\x1b[2Aframe 2"], &["frame 2", "status"])]
+    #[case::cursor_up_and_clear(&["a", "b", "\x1b[1A\x1b[Kc"], &["a", "c"])]
+    #[case::absolute_position(&["abc", "def", "\x1b[1;2HX"], &["aXc", "def"])]
+    #[case::column(&["abc\x1b[1GX"], &["Xbc"])]
+    #[case::forward(&["a\x1b[2Cb"], &["a  b"])]
+    #[case::clear_screen(&["a", "b", "\x1b[2J\x1b[Hc"], &["c"])]
+    fn cursor_movement(#[case] input: &[&str], #[case] expected: &[&str]) {
+        let splitter = AnsiParser::new(Default::default());
+        let (lines, _) = splitter.parse_lines(input);
+        let lines: Vec<_> =
+            lines.into_iter().map(|line| line.0.into_iter().map(|text| text.content).collect::<String>()).collect();
+        assert_eq!(lines, expected);
+    }
+
+    #[rstest]
+    #[case::plain(&["a", "b", "c"], &[1], &[1])]
+    #[case::overwritten_by_untagged(&["a", "b", "\x1b[1Ac"], &[1], &[])]
+    #[case::overwrites_untagged(&["a", "b", "\x1b[2Ac"], &[2], &[0])]
+    #[case::partially_overwritten(&["abc", "\x1b[1AX"], &[1], &[])]
+    #[case::erased(&["a", "b\x1b[2K", "c"], &[1], &[])]
+    fn tagged_lines(#[case] input: &[&str], #[case] tagged: &[usize], #[case] expected: &[usize]) {
+        let tagged = tagged.iter().copied().collect();
+        let (_, _, tagged) = AnsiParser::new(Default::default()).parse_tagged_lines(input, &tagged);
+        let tagged: Vec<_> = tagged.into_iter().collect();
+        assert_eq!(tagged, expected);
+    }
 }
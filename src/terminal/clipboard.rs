@@ -0,0 +1,26 @@
+use base64::{Engine, engine::general_purpose::STANDARD};
+use std::io::{self, Write};
+
+/// Copy the given text into the system clipboard using the OSC 52 escape sequence.
+///
+/// This goes through the terminal so it works over ssh as well, as long as the terminal supports
+/// it.
+pub(crate) fn copy_to_clipboard<W: Write>(writer: &mut W, text: &str) -> io::Result<()> {
+    write!(writer, "{}", osc52(text))?;
+    writer.flush()
+}
+
+fn osc52(text: &str) -> String {
+    let contents = STANDARD.encode(text);
+    format!("\x1b]52;c;{contents}\x07")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn sequence() {
+        assert_eq!(osc52("hi\n"), "\x1b]52;c;aGkK\x07");
+    }
+}
@@ -1,4 +1,8 @@
-use super::{GraphicsMode, capabilities::TerminalCapabilities, image::protocols::kitty::KittyMode};
+use super::{
+    GraphicsMode,
+    capabilities::TerminalCapabilities,
+    image::protocols::kitty::{DEFAULT_MAX_TRANSFERS, KittyMode},
+};
 use std::{env, sync::OnceLock};
 use strum::IntoEnumIterator;
 
@@ -41,7 +45,6 @@ impl TerminalEmulator {
     }
 
     pub fn preferred_protocol(&self) -> GraphicsMode {
-        let capabilities = Self::capabilities();
         // Note: the order here is very important. In particular:
         //
         // * We prioritize checking for iterm2 support as the default for terminals that support
@@ -53,18 +56,30 @@ impl TerminalEmulator {
         let modes = [
             GraphicsMode::Iterm2,
             GraphicsMode::Iterm2Multipart,
-            GraphicsMode::Kitty { mode: KittyMode::Local },
-            GraphicsMode::Kitty { mode: KittyMode::Remote },
+            GraphicsMode::Kitty {
+                mode: KittyMode::Local,
+                unicode_placeholders: false,
+                max_transfers: DEFAULT_MAX_TRANSFERS,
+            },
+            GraphicsMode::Kitty {
+                mode: KittyMode::Remote,
+                unicode_placeholders: false,
+                max_transfers: DEFAULT_MAX_TRANSFERS,
+            },
             #[cfg(feature = "sixel")]
             GraphicsMode::Sixel,
             GraphicsMode::AsciiBlocks,
         ];
-        for mode in modes {
-            if self.supports_graphics_mode(&mode, &capabilities) {
-                return mode;
-            }
-        }
-        unreachable!("ascii blocks is always supported")
+        self.first_supported_protocol(modes).expect("ascii blocks is always supported")
+    }
+
+    /// Get the first graphics mode out of the given ones that this terminal supports.
+    pub fn first_supported_protocol<I>(&self, modes: I) -> Option<GraphicsMode>
+    where
+        I: IntoIterator<Item = GraphicsMode>,
+    {
+        let capabilities = Self::capabilities();
+        modes.into_iter().find(|mode| self.supports_graphics_mode(mode, &capabilities))
     }
 
     fn is_detected(&self, term: &str, term_program: &str) -> bool {
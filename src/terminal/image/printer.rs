@@ -95,7 +95,9 @@ impl ImagePrinter {
     pub fn new(mode: GraphicsMode) -> Result<Self, CreatePrinterError> {
         let capabilities = TerminalEmulator::capabilities();
         let printer = match mode {
-            GraphicsMode::Kitty { mode } => Self::Kitty(KittyPrinter::new(mode, capabilities.tmux)?),
+            GraphicsMode::Kitty { mode, unicode_placeholders, max_transfers } => {
+                Self::Kitty(KittyPrinter::new(mode, capabilities.tmux, unicode_placeholders, max_transfers)?)
+            }
             GraphicsMode::Iterm2 => Self::Iterm(ItermPrinter::new(ItermMode::Single, capabilities.tmux)),
             GraphicsMode::Iterm2Multipart => Self::Iterm(ItermPrinter::new(ItermMode::Multipart, capabilities.tmux)),
             GraphicsMode::AsciiBlocks => Self::Ascii(AsciiPrinter),
@@ -8,15 +8,26 @@ use crate::{
 use base64::{Engine, engine::general_purpose::STANDARD};
 use image::{AnimationDecoder, Delay, EncodableLayout, ImageReader, RgbaImage, codecs::gif::GifDecoder};
 use std::{
+    collections::VecDeque,
     fmt,
     fs::{self, File},
     io::{self, BufReader},
+    num::NonZeroU16,
     path::{Path, PathBuf},
-    sync::atomic::{AtomicU32, Ordering},
+    sync::{
+        Mutex,
+        atomic::{AtomicU32, Ordering},
+    },
+    thread,
+    time::{Duration, Instant},
 };
 use tempfile::{TempDir, tempdir};
 
 const IMAGE_PLACEHOLDER: &str = "\u{10EEEE}";
+const TRANSFER_WINDOW: Duration = Duration::from_millis(50);
+
+/// The default maximum number of image transfers in flight at any given time.
+pub(crate) const DEFAULT_MAX_TRANSFERS: NonZeroU16 = NonZeroU16::new(16).unwrap();
 const DIACRITICS: &[u32] = &[
     0x305, 0x30d, 0x30e, 0x310, 0x312, 0x33d, 0x33e, 0x33f, 0x346, 0x34a, 0x34b, 0x34c, 0x350, 0x351, 0x352, 0x357,
     0x35b, 0x363, 0x364, 0x365, 0x366, 0x367, 0x368, 0x369, 0x36a, 0x36b, 0x36c, 0x36d, 0x36e, 0x36f, 0x483, 0x484,
@@ -116,17 +127,70 @@ struct GifFrame<T> {
     buffer: T,
 }
 
+/// Limits the number of image transfers that are in flight at any given time.
+///
+/// Terminals don't report when they're done processing a transfer so every transfer is assumed to
+/// be in flight for a short window after it's sent.
+struct TransferThrottle {
+    max_transfers: usize,
+    transfers: Mutex<VecDeque<Instant>>,
+}
+
+impl TransferThrottle {
+    fn new(max_transfers: NonZeroU16) -> Self {
+        Self { max_transfers: max_transfers.get() as usize, transfers: Default::default() }
+    }
+
+    /// Reserve a slot for a transfer, returning how long to wait before starting it.
+    fn reserve(&self, now: Instant) -> Option<Duration> {
+        let mut transfers = self.transfers.lock().unwrap();
+        while transfers.front().is_some_and(|sent| now.saturating_duration_since(*sent) >= TRANSFER_WINDOW) {
+            transfers.pop_front();
+        }
+        if transfers.len() < self.max_transfers {
+            transfers.push_back(now);
+            return None;
+        }
+        let oldest = transfers.pop_front().expect("no transfers");
+        let delay = TRANSFER_WINDOW.saturating_sub(now.saturating_duration_since(oldest));
+        transfers.push_back(now + delay);
+        Some(delay)
+    }
+}
+
 pub struct KittyPrinter {
     mode: KittyMode,
     tmux: bool,
+    unicode_placeholders: bool,
     base_directory: TempDir,
     next: AtomicU32,
+    throttle: TransferThrottle,
 }
 
 impl KittyPrinter {
-    pub(crate) fn new(mode: KittyMode, tmux: bool) -> io::Result<Self> {
+    pub(crate) fn new(
+        mode: KittyMode,
+        tmux: bool,
+        unicode_placeholders: bool,
+        max_transfers: NonZeroU16,
+    ) -> io::Result<Self> {
         let base_directory = tempdir()?;
-        Ok(Self { mode, tmux, base_directory, next: Default::default() })
+        // tmux only supports images via unicode placeholders.
+        let unicode_placeholders = unicode_placeholders || tmux;
+        let throttle = TransferThrottle::new(max_transfers);
+        Ok(Self { mode, tmux, unicode_placeholders, base_directory, next: Default::default(), throttle })
+    }
+
+    fn wait_for_transfer_slot<T>(&self, terminal: &mut T) -> Result<(), PrintImageError>
+    where
+        T: TerminalIo,
+    {
+        if let Some(delay) = self.throttle.reserve(Instant::now()) {
+            // Make sure everything sent so far reaches the terminal before waiting on it.
+            terminal.execute(&TerminalCommand::Flush)?;
+            thread::sleep(delay);
+        }
+        Ok(())
     }
 
     fn allocate_tempfile(&self) -> PathBuf {
@@ -189,7 +253,7 @@ impl KittyPrinter {
             ControlOption::Quiet(2),
         ];
         let mut image_id = 0;
-        if self.tmux {
+        if self.unicode_placeholders {
             image_id = Self::generate_image_id();
             options.extend([ControlOption::UnicodePlaceholder, ControlOption::ImageId(image_id)]);
         }
@@ -198,7 +262,7 @@ impl KittyPrinter {
             KittyBuffer::Filesystem(path) => self.print_local(options, path, terminal)?,
             KittyBuffer::Memory(buffer) => self.print_remote(options, buffer, terminal, false)?,
         };
-        if self.tmux {
+        if self.unicode_placeholders {
             self.print_unicode_placeholders(terminal, print_options, image_id)?;
         }
 
@@ -234,7 +298,7 @@ impl KittyPrinter {
                     ControlOption::Columns(print_options.columns),
                     ControlOption::Rows(print_options.rows),
                 ]);
-                if self.tmux {
+                if self.unicode_placeholders {
                     options.push(ControlOption::UnicodePlaceholder);
                 }
             } else {
@@ -267,7 +331,7 @@ impl KittyPrinter {
                 terminal.execute(&TerminalCommand::PrintText { content: &command, style: Default::default() })?;
             }
         }
-        if self.tmux {
+        if self.unicode_placeholders {
             self.print_unicode_placeholders(terminal, print_options, image_id)?;
         }
         let options = &[
@@ -354,7 +418,7 @@ impl KittyPrinter {
         let color = Color::new((image_id >> 16) as u8, (image_id >> 8) as u8, image_id as u8);
         let style = TextStyle::default().fg_color(color);
         if options.rows.max(options.columns) >= DIACRITICS.len() as u16 {
-            return Err(PrintImageError::other("image is too large to fit in tmux"));
+            return Err(PrintImageError::other("image is too large to be displayed using unicode placeholders"));
         }
 
         let last_byte = char::from_u32(DIACRITICS[(image_id >> 24) as usize]).unwrap();
@@ -411,6 +475,7 @@ impl PrintImage for KittyPrinter {
     where
         T: TerminalIo,
     {
+        self.wait_for_transfer_slot(terminal)?;
         match &image.resource {
             GenericResource::Image(resource) => self.print_image(image.dimensions, resource, terminal, options)?,
             GenericResource::Gif(frames) => self.print_gif(image.dimensions, frames, terminal, options)?,
@@ -550,3 +615,20 @@ impl fmt::Display for Action {
         write!(f, "{value}")
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn throttle() {
+        let throttle = TransferThrottle::new(NonZeroU16::new(2).unwrap());
+        let now = Instant::now();
+        assert_eq!(throttle.reserve(now), None);
+        assert_eq!(throttle.reserve(now + Duration::from_millis(10)), None);
+        assert_eq!(throttle.reserve(now + Duration::from_millis(20)), Some(Duration::from_millis(30)));
+
+        // Once the window passes the slots are free again.
+        assert_eq!(throttle.reserve(now + Duration::from_millis(200)), None);
+    }
+}
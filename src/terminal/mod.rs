@@ -1,5 +1,6 @@
 pub(crate) mod ansi;
 pub(crate) mod capabilities;
+pub(crate) mod clipboard;
 pub(crate) mod emulator;
 pub(crate) mod image;
 pub(crate) mod printer;
@@ -13,6 +14,8 @@ pub enum GraphicsMode {
     Iterm2Multipart,
     Kitty {
         mode: image::protocols::kitty::KittyMode,
+        unicode_placeholders: bool,
+        max_transfers: std::num::NonZeroU16,
     },
     AsciiBlocks,
     Raw,
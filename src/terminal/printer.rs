@@ -27,6 +27,8 @@ pub(crate) enum TerminalCommand<'a> {
     MoveLeft(u16),
     MoveToNextLine,
     PrintText { content: &'a str, style: TextStyle },
+    BeginHyperlink { url: &'a str },
+    EndHyperlink,
     ClearScreen,
     SetColors(Colors),
     SetBackgroundColor(Color),
@@ -137,6 +139,17 @@ impl<I: TerminalWrite> Terminal<I> {
         Ok(())
     }
 
+    fn begin_hyperlink(&mut self, url: &str) -> io::Result<()> {
+        // OSC 8: everything printed until the link is closed points to this url.
+        write!(self.writer, "\x1b]8;;{url}\x1b\\")?;
+        Ok(())
+    }
+
+    fn end_hyperlink(&mut self) -> io::Result<()> {
+        write!(self.writer, "\x1b]8;;\x1b\\")?;
+        Ok(())
+    }
+
     fn clear_screen(&mut self) -> io::Result<()> {
         match (self.last_cleared_background_color, self.background_color) {
             (_, Some(Color::Rgb { r, g, b })) => {
@@ -211,6 +224,8 @@ impl<I: TerminalWrite> TerminalIo for Terminal<I> {
             MoveLeft(amount) => self.move_left(*amount)?,
             MoveToNextLine => self.move_to_next_line()?,
             PrintText { content, style } => self.print_text(content, style)?,
+            BeginHyperlink { url } => self.begin_hyperlink(url)?,
+            EndHyperlink => self.end_hyperlink()?,
             ClearScreen => self.clear_screen()?,
             SetColors(colors) => self.set_colors(*colors)?,
             SetBackgroundColor(color) => self.set_background_color(*color)?,
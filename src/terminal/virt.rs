@@ -201,7 +201,7 @@ impl TerminalIo for VirtualTerminal {
     fn execute(&mut self, command: &TerminalCommand<'_>) -> Result<(), TerminalError> {
         use TerminalCommand::*;
         match command {
-            BeginUpdate | EndUpdate => (),
+            BeginUpdate | EndUpdate | BeginHyperlink { .. } | EndHyperlink => (),
             MoveTo { column, row } => self.move_to(*column, *row)?,
             MoveToRow(row) => self.move_to_row(*row)?,
             MoveToColumn(column) => self.move_to_column(*column)?,
@@ -1,16 +1,20 @@
 use super::{
-    AuthorPositioning, FooterTemplate, Margin,
-    raw::{self, RawColor},
+    AuthorPositioning, CodeBlockBorder, CodeBlockOverflow, FooterTemplate, Margin, SeparatorStyle,
+    raw::{self, ListNumbering, RawColor},
 };
 use crate::{
+    code::snippet::SnippetLanguage,
     markdown::text_style::{Color, Colors, TextStyle, UndefinedPaletteColorError},
     resource::Resources,
     terminal::image::{Image, printer::RegisterImageError},
 };
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 const DEFAULT_CODE_HIGHLIGHT_THEME: &str = "base16-eighties.dark";
 const DEFAULT_BLOCK_QUOTE_PREFIX: &str = "▍ ";
+const DEFAULT_LIST_BULLETS: &[&str] = &["•", "◦", "▪"];
+const DEFAULT_TASK_CHECKED_GLYPH: &str = "☑";
+const DEFAULT_TASK_UNCHECKED_GLYPH: &str = "☐";
 const DEFAULT_PROGRESS_BAR_CHAR: char = '█';
 const DEFAULT_FOOTER_HEIGHT: u16 = 3;
 const DEFAULT_TYPST_HORIZONTAL_MARGIN: u16 = 5;
@@ -38,6 +42,9 @@ pub(crate) struct PresentationTheme {
     pub(crate) inline_code: InlineCodeStyle,
     pub(crate) table: Alignment,
     pub(crate) block_quote: BlockQuoteStyle,
+    pub(crate) thematic_break: SeparatorStyle,
+    pub(crate) list: ListStyle,
+    pub(crate) task_list: TaskListStyle,
     pub(crate) alert: AlertStyle,
     pub(crate) default_style: DefaultStyle,
     pub(crate) headings: HeadingStyles,
@@ -47,6 +54,7 @@ pub(crate) struct PresentationTheme {
     pub(crate) mermaid: MermaidStyle,
     pub(crate) d2: D2Style,
     pub(crate) modals: ModalStyle,
+    pub(crate) image: ImageStyle,
     pub(crate) palette: ColorPalette,
 }
 
@@ -63,6 +71,9 @@ impl PresentationTheme {
             inline_code,
             table,
             block_quote,
+            thematic_break,
+            list,
+            task_list,
             alert,
             default_style,
             headings,
@@ -72,6 +83,7 @@ impl PresentationTheme {
             mermaid,
             d2,
             modals,
+            image,
             palette,
             extends: _,
         } = raw;
@@ -80,11 +92,14 @@ impl PresentationTheme {
         let default_style = DefaultStyle::new(default_style, &palette)?;
         Ok(Self {
             slide_title: SlideTitleStyle::new(slide_title, &palette, options)?,
-            code: CodeBlockStyle::new(code),
+            code: CodeBlockStyle::new(code, &palette)?,
             execution_output: ExecutionOutputBlockStyle::new(execution_output, &palette)?,
             inline_code: InlineCodeStyle::new(inline_code, &palette)?,
             table: table.clone().unwrap_or_default().into(),
             block_quote: BlockQuoteStyle::new(block_quote, &palette)?,
+            thematic_break: thematic_break.style.unwrap_or_default(),
+            list: ListStyle::new(list),
+            task_list: TaskListStyle::new(task_list, &palette)?,
             alert: AlertStyle::new(alert, &palette)?,
             default_style: default_style.clone(),
             headings: HeadingStyles::new(headings, &palette, options)?,
@@ -94,6 +109,7 @@ impl PresentationTheme {
             mermaid: MermaidStyle::new(mermaid),
             d2: D2Style::new(d2),
             modals: ModalStyle::new(modals, &default_style, &palette)?,
+            image: ImageStyle::new(image, &palette)?,
             palette,
         })
     }
@@ -137,6 +153,7 @@ pub(crate) enum ProcessingThemeError {
 pub(crate) struct SlideTitleStyle {
     pub(crate) alignment: Alignment,
     pub(crate) separator: bool,
+    pub(crate) separator_style: SeparatorStyle,
     pub(crate) padding_top: u8,
     pub(crate) padding_bottom: u8,
     pub(crate) style: TextStyle,
@@ -151,6 +168,7 @@ impl SlideTitleStyle {
         let raw::SlideTitleStyle {
             alignment,
             separator,
+            separator_style,
             padding_top,
             padding_bottom,
             colors,
@@ -173,6 +191,7 @@ impl SlideTitleStyle {
         Ok(Self {
             alignment: alignment.clone().unwrap_or_default().into(),
             separator: *separator,
+            separator_style: separator_style.unwrap_or_default(),
             padding_top: padding_top.unwrap_or_default(),
             padding_bottom: padding_bottom.unwrap_or_default(),
             style,
@@ -250,6 +269,72 @@ impl BlockQuoteStyle {
     }
 }
 
+#[derive(Clone, Debug)]
+pub(crate) struct ListStyle {
+    bullets: Vec<String>,
+    numbering: Vec<ListNumbering>,
+}
+
+impl ListStyle {
+    fn new(raw: &raw::ListStyle) -> Self {
+        let raw::ListStyle { bullets, numbering } = raw;
+        Self { bullets: bullets.clone(), numbering: numbering.clone() }
+    }
+
+    /// The bullet to use for items in an unordered list at the given depth.
+    pub(crate) fn bullet(&self, depth: u8) -> &str {
+        let depth = depth as usize;
+        if self.bullets.is_empty() {
+            // By default the deepest bullet is used for any levels past it.
+            DEFAULT_LIST_BULLETS[depth.min(DEFAULT_LIST_BULLETS.len() - 1)]
+        } else {
+            &self.bullets[depth % self.bullets.len()]
+        }
+    }
+
+    /// The numbering to use for items in an ordered list at the given depth.
+    pub(crate) fn numbering(&self, depth: u8) -> ListNumbering {
+        match self.numbering.is_empty() {
+            true => ListNumbering::Decimal,
+            false => self.numbering[depth as usize % self.numbering.len()],
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct TaskListStyle {
+    pub(crate) checked: TaskItemStyle,
+    pub(crate) unchecked: TaskItemStyle,
+}
+
+impl TaskListStyle {
+    fn new(raw: &raw::TaskListStyle, palette: &ColorPalette) -> Result<Self, ProcessingThemeError> {
+        let raw::TaskListStyle { checked, unchecked } = raw;
+        let checked = TaskItemStyle::new(checked, DEFAULT_TASK_CHECKED_GLYPH, palette)?;
+        let unchecked = TaskItemStyle::new(unchecked, DEFAULT_TASK_UNCHECKED_GLYPH, palette)?;
+        Ok(Self { checked, unchecked })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct TaskItemStyle {
+    pub(crate) glyph: String,
+    pub(crate) style: TextStyle,
+}
+
+impl TaskItemStyle {
+    fn new(
+        raw: &raw::TaskItemStyle,
+        default_glyph: &str,
+        palette: &ColorPalette,
+    ) -> Result<Self, ProcessingThemeError> {
+        let raw::TaskItemStyle { glyph, colors } = raw;
+        let glyph = glyph.as_deref().unwrap_or(default_glyph).to_string();
+        let style = TextStyle::colored(colors.resolve(palette)?);
+        Ok(Self { glyph, style })
+    }
+}
+
 #[derive(Clone, Debug)]
 pub(crate) struct AlertStyle {
     pub(crate) alignment: Alignment,
@@ -430,14 +515,32 @@ impl AuthorStyle {
 pub(crate) struct DefaultStyle {
     pub(crate) margin: Margin,
     pub(crate) style: TextStyle,
+    pub(crate) quiet_zone: QuietZone,
 }
 
 impl DefaultStyle {
     fn new(raw: &raw::DefaultStyle, palette: &ColorPalette) -> Result<Self, ProcessingThemeError> {
-        let raw::DefaultStyle { margin, colors } = raw;
+        let raw::DefaultStyle { margin, colors, quiet_zone } = raw;
         let margin = margin.unwrap_or_default();
         let style = TextStyle::colored(colors.resolve(palette)?);
-        Ok(Self { margin, style })
+        let quiet_zone = QuietZone {
+            horizontal: quiet_zone.horizontal.unwrap_or_default(),
+            vertical: quiet_zone.vertical.unwrap_or_default(),
+        };
+        Ok(Self { margin, style, quiet_zone })
+    }
+}
+
+/// The blank area left around the entire slide.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) struct QuietZone {
+    pub(crate) horizontal: Margin,
+    pub(crate) vertical: Margin,
+}
+
+impl QuietZone {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.horizontal.is_empty() && self.vertical.is_empty()
     }
 }
 
@@ -546,70 +649,239 @@ pub(crate) struct CodeBlockStyle {
     pub(crate) padding: PaddingRect,
     pub(crate) theme_name: String,
     pub(crate) background: bool,
+    pub(crate) hidden_lines_marker: Option<HiddenLinesMarkerStyle>,
+    pub(crate) border: CodeBlockBorder,
+    pub(crate) overrides: Vec<CodeHighlightOverride>,
+    pub(crate) overflow: CodeBlockOverflow,
+    pub(crate) fade: CodeBlockFade,
+    pub(crate) comment_keywords: Option<CommentKeywordsStyle>,
 }
 
 impl CodeBlockStyle {
-    fn new(raw: &raw::CodeBlockStyle) -> Self {
-        let raw::CodeBlockStyle { alignment, padding, theme_name, background } = raw;
-        let padding = PaddingRect {
-            horizontal: padding.horizontal.unwrap_or_default(),
-            vertical: padding.vertical.unwrap_or_default(),
+    fn new(raw: &raw::CodeBlockStyle, palette: &ColorPalette) -> Result<Self, ProcessingThemeError> {
+        let raw::CodeBlockStyle {
+            alignment,
+            padding,
+            theme_name,
+            background,
+            hidden_lines_marker,
+            border,
+            overrides,
+            overflow,
+            fade,
+            comment_keywords,
+        } = raw;
+        let padding = PaddingRect::from(padding);
+        let hidden_lines_marker = match hidden_lines_marker.enabled {
+            Some(true) => Some(HiddenLinesMarkerStyle::new(hidden_lines_marker, palette)?),
+            _ => None,
         };
-        Self {
+        Ok(Self {
             alignment: alignment.clone().unwrap_or_default().into(),
             padding,
             theme_name: theme_name.as_deref().unwrap_or(DEFAULT_CODE_HIGHLIGHT_THEME).to_string(),
             background: background.unwrap_or(true),
+            hidden_lines_marker,
+            border: border.unwrap_or_default(),
+            overrides: overrides
+                .iter()
+                .map(|o| CodeHighlightOverride::new(o, palette))
+                .collect::<Result<_, ProcessingThemeError>>()?,
+            overflow: overflow.unwrap_or_default(),
+            fade: CodeBlockFade::from(fade),
+            comment_keywords: match comment_keywords.enabled {
+                Some(false) => None,
+                _ => Some(CommentKeywordsStyle::new(comment_keywords, palette)?),
+            },
+        })
+    }
+}
+
+/// The style for keywords like `TODO` within comments in snippets.
+#[derive(Clone, Debug)]
+pub(crate) struct CommentKeywordsStyle {
+    pub(crate) words: BTreeSet<String>,
+    pub(crate) style: TextStyle,
+}
+
+impl CommentKeywordsStyle {
+    fn new(raw: &raw::CommentKeywordsStyle, palette: &ColorPalette) -> Result<Self, ProcessingThemeError> {
+        let raw::CommentKeywordsStyle { enabled: _, words, colors } = raw;
+        let words = match words {
+            Some(words) => words.iter().cloned().collect(),
+            None => ["TODO", "FIXME", "NOTE", "XXX"].into_iter().map(String::from).collect(),
+        };
+        let style = TextStyle::colored(colors.resolve(palette)?).bold();
+        Ok(Self { words, style })
+    }
+}
+
+/// How the edges of a code block that doesn't fit in its `+max_rows` window fade out.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct CodeBlockFade {
+    pub(crate) rows: u8,
+    pub(crate) intensity: f32,
+}
+
+impl Default for CodeBlockFade {
+    fn default() -> Self {
+        Self { rows: 0, intensity: 0.7 }
+    }
+}
+
+impl From<&raw::CodeBlockFade> for CodeBlockFade {
+    fn from(raw: &raw::CodeBlockFade) -> Self {
+        let default = Self::default();
+        Self {
+            rows: raw.rows.unwrap_or(default.rows),
+            intensity: raw.intensity.unwrap_or(default.intensity).clamp(0.0, 1.0),
         }
     }
 }
 
-/// Vertical/horizontal padding.
-#[derive(Clone, Copy, Debug, Default)]
+/// A list of words to be colored in a specific way in snippets written in a language.
+#[derive(Clone, Debug)]
+pub(crate) struct CodeHighlightOverride {
+    pub(crate) language: SnippetLanguage,
+    pub(crate) words: BTreeSet<String>,
+    pub(crate) colors: Colors,
+}
+
+impl CodeHighlightOverride {
+    fn new(raw: &raw::CodeHighlightOverride, palette: &ColorPalette) -> Result<Self, ProcessingThemeError> {
+        let raw::CodeHighlightOverride { language, words, colors } = raw;
+        let language = language.parse().expect("language parsing");
+        let words = words.iter().cloned().collect();
+        let colors = colors.resolve(palette)?;
+        Ok(Self { language, words, colors })
+    }
+}
+
+/// The style for the marker displayed in place of a group of hidden lines in a snippet.
+#[derive(Clone, Debug)]
+pub(crate) struct HiddenLinesMarkerStyle {
+    pub(crate) symbol: String,
+    pub(crate) style: TextStyle,
+}
+
+impl HiddenLinesMarkerStyle {
+    fn new(raw: &raw::HiddenLinesMarkerStyle, palette: &ColorPalette) -> Result<Self, ProcessingThemeError> {
+        let raw::HiddenLinesMarkerStyle { enabled: _, symbol, colors } = raw;
+        let symbol = symbol.clone().unwrap_or_else(|| "⋮".into());
+        let style = TextStyle::colored(colors.resolve(palette)?);
+        Ok(Self { symbol, style })
+    }
+}
+
+/// Padding around a block.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub(crate) struct PaddingRect {
-    /// The number of columns to use as horizontal padding.
-    pub(crate) horizontal: u8,
+    /// The number of rows to use as padding above the block.
+    pub(crate) top: u8,
+
+    /// The number of rows to use as padding below the block.
+    pub(crate) bottom: u8,
+
+    /// The number of columns to use as padding to the left of the block.
+    pub(crate) left: u8,
 
-    /// The number of rows to use as vertical padding.
-    pub(crate) vertical: u8,
+    /// The number of columns to use as padding to the right of the block.
+    pub(crate) right: u8,
+}
+
+impl From<&raw::PaddingRect> for PaddingRect {
+    fn from(raw: &raw::PaddingRect) -> Self {
+        let raw::PaddingRect { horizontal, vertical, top, bottom, left, right } = raw;
+        Self {
+            top: top.or(*vertical).unwrap_or_default(),
+            bottom: bottom.or(*vertical).unwrap_or_default(),
+            left: left.or(*horizontal).unwrap_or_default(),
+            right: right.or(*horizontal).unwrap_or_default(),
+        }
+    }
 }
 
 #[derive(Clone, Debug, Default)]
 pub(crate) struct ExecutionOutputBlockStyle {
     pub(crate) style: TextStyle,
+    pub(crate) failure_style: TextStyle,
     pub(crate) status: ExecutionStatusBlockStyle,
+    pub(crate) stderr_style: TextStyle,
     pub(crate) padding: PaddingRect,
 }
 
 impl ExecutionOutputBlockStyle {
     fn new(raw: &raw::ExecutionOutputBlockStyle, palette: &ColorPalette) -> Result<Self, ProcessingThemeError> {
-        let raw::ExecutionOutputBlockStyle { colors, status, padding } = raw;
+        let raw::ExecutionOutputBlockStyle { colors, failure, status, stderr, padding } = raw;
         let colors = colors.resolve(palette)?;
         let style = TextStyle::colored(colors);
-        let padding = PaddingRect {
-            horizontal: padding.horizontal.unwrap_or_default(),
-            vertical: padding.vertical.unwrap_or_default(),
-        };
-        Ok(Self { style, status: ExecutionStatusBlockStyle::new(status, palette)?, padding })
+        let failure = failure.resolve(palette)?;
+        let failure_style = TextStyle::colored(Colors {
+            background: failure.background.or(colors.background),
+            foreground: failure.foreground.or(colors.foreground),
+        });
+        let status = ExecutionStatusBlockStyle::new(status, palette)?;
+        let mut stderr_colors = stderr.resolve(palette)?;
+        // Default to the same color used when a snippet fails.
+        if stderr_colors.foreground.is_none() {
+            stderr_colors.foreground = status.failure_style.colors.foreground;
+        }
+        let stderr_style = TextStyle::colored(stderr_colors);
+        let padding = PaddingRect::from(padding);
+        Ok(Self { style, failure_style, status, stderr_style, padding })
     }
 }
 
-#[derive(Copy, Clone, Debug, Default)]
+#[derive(Clone, Debug, Default)]
 pub(crate) struct ExecutionStatusBlockStyle {
     pub(crate) running_style: TextStyle,
     pub(crate) success_style: TextStyle,
     pub(crate) failure_style: TextStyle,
     pub(crate) not_started_style: TextStyle,
+    pub(crate) labels: ExecutionStatusLabels,
 }
 
 impl ExecutionStatusBlockStyle {
     fn new(raw: &raw::ExecutionStatusBlockStyle, palette: &ColorPalette) -> Result<Self, ProcessingThemeError> {
-        let raw::ExecutionStatusBlockStyle { running, success, failure, not_started } = raw;
+        let raw::ExecutionStatusBlockStyle { running, success, failure, not_started, labels } = raw;
         let running_style = TextStyle::colored(running.resolve(palette)?);
         let success_style = TextStyle::colored(success.resolve(palette)?);
         let failure_style = TextStyle::colored(failure.resolve(palette)?);
         let not_started_style = TextStyle::colored(not_started.resolve(palette)?);
-        Ok(Self { running_style, success_style, failure_style, not_started_style })
+        let labels = ExecutionStatusLabels::new(labels);
+        Ok(Self { running_style, success_style, failure_style, not_started_style, labels })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct ExecutionStatusLabels {
+    pub(crate) running: String,
+    pub(crate) success: String,
+    pub(crate) failure: String,
+    pub(crate) not_started: String,
+}
+
+impl ExecutionStatusLabels {
+    fn new(raw: &raw::ExecutionStatusLabels) -> Self {
+        let defaults = Self::default();
+        let raw::ExecutionStatusLabels { running, success, failure, not_started } = raw;
+        Self {
+            running: running.clone().unwrap_or(defaults.running),
+            success: success.clone().unwrap_or(defaults.success),
+            failure: failure.clone().unwrap_or(defaults.failure),
+            not_started: not_started.clone().unwrap_or(defaults.not_started),
+        }
+    }
+}
+
+impl Default for ExecutionStatusLabels {
+    fn default() -> Self {
+        Self {
+            running: "running".into(),
+            success: "finished".into(),
+            failure: "finished with error".into(),
+            not_started: "not started".into(),
+        }
     }
 }
 
@@ -650,15 +922,18 @@ pub(crate) struct TypstStyle {
     pub(crate) horizontal_margin: u16,
     pub(crate) vertical_margin: u16,
     pub(crate) style: TextStyle,
+    pub(crate) ppi: Option<u32>,
+    pub(crate) font: Option<String>,
+    pub(crate) font_size: Option<u16>,
 }
 
 impl TypstStyle {
     fn new(raw: &raw::TypstStyle, palette: &ColorPalette) -> Result<Self, ProcessingThemeError> {
-        let raw::TypstStyle { horizontal_margin, vertical_margin, colors } = raw;
+        let raw::TypstStyle { horizontal_margin, vertical_margin, colors, ppi, font, font_size } = raw;
         let horizontal_margin = horizontal_margin.unwrap_or(DEFAULT_TYPST_HORIZONTAL_MARGIN);
         let vertical_margin = vertical_margin.unwrap_or(DEFAULT_TYPST_VERTICAL_MARGIN);
         let style = TextStyle::colored(colors.resolve(palette)?);
-        Ok(Self { horizontal_margin, vertical_margin, style })
+        Ok(Self { horizontal_margin, vertical_margin, style, ppi: *ppi, font: font.clone(), font_size: *font_size })
     }
 }
 
@@ -666,14 +941,15 @@ impl TypstStyle {
 pub(crate) struct MermaidStyle {
     pub(crate) theme: String,
     pub(crate) background: String,
+    pub(crate) scale: Option<u32>,
 }
 
 impl MermaidStyle {
     fn new(raw: &raw::MermaidStyle) -> Self {
-        let raw::MermaidStyle { theme, background } = raw;
+        let raw::MermaidStyle { theme, background, scale } = raw;
         let theme = theme.as_deref().unwrap_or(DEFAULT_MERMAID_THEME).to_string();
         let background = background.as_deref().unwrap_or(DEFAULT_MERMAID_BACKGROUND).to_string();
-        Self { theme, background }
+        Self { theme, background, scale: *scale }
     }
 }
 
@@ -690,7 +966,7 @@ impl D2Style {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 pub(crate) struct ModalStyle {
     pub(crate) style: TextStyle,
     pub(crate) selection_style: TextStyle,
@@ -712,6 +988,81 @@ impl ModalStyle {
     }
 }
 
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ImageStyle {
+    pub(crate) shadow: Option<ImageShadowStyle>,
+    pub(crate) caption: Option<ImageCaptionStyle>,
+    pub(crate) spacing: ImageSpacing,
+}
+
+impl ImageStyle {
+    fn new(raw: &raw::ImageStyle, palette: &ColorPalette) -> Result<Self, ProcessingThemeError> {
+        let raw::ImageStyle { shadow, caption, spacing } = raw;
+        let shadow = match shadow.enabled {
+            Some(true) => Some(ImageShadowStyle::new(shadow, palette)?),
+            _ => None,
+        };
+        let caption = match caption.enabled {
+            Some(true) => Some(ImageCaptionStyle::new(caption, palette)?),
+            _ => None,
+        };
+        Ok(Self { shadow, caption, spacing: ImageSpacing::from(spacing) })
+    }
+}
+
+/// The vertical spacing around images.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct ImageSpacing {
+    pub(crate) above: u8,
+    pub(crate) below: u8,
+}
+
+impl Default for ImageSpacing {
+    fn default() -> Self {
+        // Images are followed by an empty line like any other element.
+        Self { above: 0, below: 1 }
+    }
+}
+
+impl From<&raw::ImageSpacing> for ImageSpacing {
+    fn from(raw: &raw::ImageSpacing) -> Self {
+        let default = Self::default();
+        Self { above: raw.above.unwrap_or(default.above), below: raw.below.unwrap_or(default.below) }
+    }
+}
+
+/// The style for image captions.
+#[derive(Clone, Debug)]
+pub(crate) struct ImageCaptionStyle {
+    pub(crate) style: TextStyle,
+}
+
+impl ImageCaptionStyle {
+    fn new(raw: &raw::ImageCaptionStyle, palette: &ColorPalette) -> Result<Self, ProcessingThemeError> {
+        let raw::ImageCaptionStyle { enabled: _, colors } = raw;
+        let style = TextStyle::colored(colors.resolve(palette)?);
+        Ok(Self { style })
+    }
+}
+
+/// The style for the shadow drawn behind images.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct ImageShadowStyle {
+    pub(crate) offset: u16,
+    pub(crate) color: Color,
+}
+
+impl ImageShadowStyle {
+    fn new(raw: &raw::ImageShadowStyle, palette: &ColorPalette) -> Result<Self, ProcessingThemeError> {
+        let raw::ImageShadowStyle { enabled: _, offset, color } = raw;
+        let color = match color {
+            Some(color) => color.resolve(palette)?,
+            None => None,
+        };
+        Ok(Self { offset: offset.unwrap_or(1).max(1), color: color.unwrap_or(Color::Black) })
+    }
+}
+
 /// The color palette.
 #[derive(Clone, Debug, Default)]
 pub(crate) struct ColorPalette {
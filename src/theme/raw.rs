@@ -43,6 +43,18 @@ pub struct PresentationTheme {
     #[serde(default)]
     pub(crate) block_quote: BlockQuoteStyle,
 
+    /// The style for thematic breaks.
+    #[serde(default)]
+    pub(crate) thematic_break: ThematicBreakStyle,
+
+    /// The style for lists.
+    #[serde(default)]
+    pub(crate) list: ListStyle,
+
+    /// The style for task lists.
+    #[serde(default)]
+    pub(crate) task_list: TaskListStyle,
+
     /// The style for an alert.
     #[serde(default)]
     pub(crate) alert: AlertStyle,
@@ -79,6 +91,10 @@ pub struct PresentationTheme {
     #[serde(default)]
     pub(crate) modals: ModalStyle,
 
+    /// The style for images.
+    #[serde(default)]
+    pub(crate) image: ImageStyle,
+
     /// The color palette.
     #[serde(default)]
     pub(crate) palette: ColorPalette,
@@ -105,6 +121,10 @@ pub(crate) struct SlideTitleStyle {
     #[serde(default)]
     pub(crate) separator: bool,
 
+    /// The style of the separator line.
+    #[serde(default)]
+    pub(crate) separator_style: Option<SeparatorStyle>,
+
     /// The padding that should be added before the text.
     #[serde(default)]
     pub(crate) padding_top: Option<u8>,
@@ -318,6 +338,23 @@ pub(crate) struct DefaultStyle {
     /// The colors to be used.
     #[serde(default)]
     pub(crate) colors: RawColors,
+
+    /// The blank area left around the entire slide, including the footer.
+    #[serde(default)]
+    pub(crate) quiet_zone: QuietZone,
+}
+
+/// The blank area left around the entire slide.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct QuietZone {
+    /// The margin on the left/right of the screen.
+    #[serde(default, with = "serde_yaml::with::singleton_map")]
+    pub(crate) horizontal: Option<Margin>,
+
+    /// The margin on the top/bottom of the screen.
+    #[serde(default, with = "serde_yaml::with::singleton_map")]
+    pub(crate) vertical: Option<Margin>,
 }
 
 /// A simple style.
@@ -633,11 +670,283 @@ pub(crate) struct CodeBlockStyle {
     pub(crate) padding: PaddingRect,
 
     /// The syntect theme name to use.
-    #[serde(default)]
+    #[serde(default, alias = "theme")]
     pub(crate) theme_name: Option<String>,
 
     /// Whether to use the theme's background color.
     pub(crate) background: Option<bool>,
+
+    /// The marker to display in place of hidden lines.
+    #[serde(default)]
+    pub(crate) hidden_lines_marker: HiddenLinesMarkerStyle,
+
+    /// The border to draw around the code block.
+    #[serde(default)]
+    pub(crate) border: Option<CodeBlockBorder>,
+
+    /// Words that should be colored in a specific way, applied on top of syntax highlighting.
+    #[serde(default)]
+    pub(crate) overrides: Vec<CodeHighlightOverride>,
+
+    /// What to do with lines that are too long to fit in the code block.
+    #[serde(default)]
+    pub(crate) overflow: Option<CodeBlockOverflow>,
+
+    /// How the edges of a code block that doesn't fit in its `+max_rows` window fade out.
+    #[serde(default)]
+    pub(crate) fade: CodeBlockFade,
+
+    /// The style for keywords like `TODO` within comments.
+    #[serde(default)]
+    pub(crate) comment_keywords: CommentKeywordsStyle,
+}
+
+/// The style for keywords like `TODO` within comments in snippets.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub(crate) struct CommentKeywordsStyle {
+    /// Whether to style these keywords.
+    #[serde(default)]
+    pub(crate) enabled: Option<bool>,
+
+    /// The keywords to style, which default to `TODO`, `FIXME`, `NOTE`, and `XXX`.
+    #[serde(default)]
+    pub(crate) words: Option<Vec<String>>,
+
+    /// The colors to use.
+    #[serde(default)]
+    pub(crate) colors: RawColors,
+}
+
+/// How the edges of a code block that doesn't fit in its `+max_rows` window fade out.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub(crate) struct CodeBlockFade {
+    /// The number of rows at each edge that fade out.
+    #[serde(default)]
+    pub(crate) rows: Option<u8>,
+
+    /// How much the outermost row fades into the background, from 0 to 1.
+    #[serde(default)]
+    pub(crate) intensity: Option<f32>,
+}
+
+/// A list of words to be colored in a specific way in snippets written in a language.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub(crate) struct CodeHighlightOverride {
+    /// The language the words apply to.
+    pub(crate) language: String,
+
+    /// The words to be colored.
+    #[serde(default)]
+    pub(crate) words: Vec<String>,
+
+    /// The colors to use.
+    #[serde(default)]
+    pub(crate) colors: RawColors,
+}
+
+/// The style for thematic breaks.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub(crate) struct ThematicBreakStyle {
+    /// The style of the separator line.
+    #[serde(default)]
+    pub(crate) style: Option<SeparatorStyle>,
+}
+
+/// The style for lists.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub(crate) struct ListStyle {
+    /// The glyphs used as bullets in unordered lists, one per nesting level.
+    ///
+    /// These are cycled through for levels deeper than the number of glyphs.
+    #[serde(default)]
+    pub(crate) bullets: Vec<String>,
+
+    /// The numbering used in ordered lists, one per nesting level.
+    ///
+    /// These are cycled through for levels deeper than the number of styles.
+    #[serde(default)]
+    pub(crate) numbering: Vec<ListNumbering>,
+}
+
+/// The numbering used in an ordered list.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ListNumbering {
+    /// Decimal numbers: 1, 2, 3.
+    #[default]
+    Decimal,
+
+    /// Lowercase letters: a, b, c.
+    LowerAlpha,
+
+    /// Uppercase letters: A, B, C.
+    UpperAlpha,
+
+    /// Lowercase roman numerals: i, ii, iii.
+    LowerRoman,
+
+    /// Uppercase roman numerals: I, II, III.
+    UpperRoman,
+}
+
+impl ListNumbering {
+    /// Format a list item's number.
+    pub(crate) fn format(&self, value: usize) -> String {
+        match self {
+            Self::Decimal => value.to_string(),
+            Self::LowerAlpha => Self::alpha(value),
+            Self::UpperAlpha => Self::alpha(value).to_uppercase(),
+            Self::LowerRoman => Self::roman(value).to_lowercase(),
+            Self::UpperRoman => Self::roman(value),
+        }
+    }
+
+    fn alpha(mut value: usize) -> String {
+        // There's no letter for 0 so there's nothing better than using the number itself.
+        if value == 0 {
+            return value.to_string();
+        }
+        let mut letters = Vec::new();
+        while value > 0 {
+            value -= 1;
+            letters.push(char::from(b'a' + (value % 26) as u8));
+            value /= 26;
+        }
+        letters.into_iter().rev().collect()
+    }
+
+    fn roman(mut value: usize) -> String {
+        const NUMERALS: &[(usize, &str)] = &[
+            (1000, "M"),
+            (900, "CM"),
+            (500, "D"),
+            (400, "CD"),
+            (100, "C"),
+            (90, "XC"),
+            (50, "L"),
+            (40, "XL"),
+            (10, "X"),
+            (9, "IX"),
+            (5, "V"),
+            (4, "IV"),
+            (1, "I"),
+        ];
+        if value == 0 {
+            return value.to_string();
+        }
+        let mut output = String::new();
+        for (numeral_value, numeral) in NUMERALS {
+            while value >= *numeral_value {
+                output.push_str(numeral);
+                value -= numeral_value;
+            }
+        }
+        output
+    }
+}
+
+/// The style for task lists.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub(crate) struct TaskListStyle {
+    /// The style for items that are done.
+    #[serde(default)]
+    pub(crate) checked: TaskItemStyle,
+
+    /// The style for items that aren't done.
+    #[serde(default)]
+    pub(crate) unchecked: TaskItemStyle,
+}
+
+/// The style for a task list item.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub(crate) struct TaskItemStyle {
+    /// The glyph that marks the state of the item.
+    #[serde(default)]
+    pub(crate) glyph: Option<String>,
+
+    /// The colors to be used for the glyph.
+    #[serde(default)]
+    pub(crate) colors: RawColors,
+}
+
+/// The style of a separator line.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum SeparatorStyle {
+    /// A thin line.
+    #[default]
+    Thin,
+
+    /// A thick line.
+    Thick,
+
+    /// A double line.
+    Double,
+}
+
+impl SeparatorStyle {
+    /// The character the separator line is made of.
+    pub(crate) fn character(&self) -> &'static str {
+        match self {
+            Self::Thin => "—",
+            Self::Thick => "━",
+            Self::Double => "═",
+        }
+    }
+}
+
+/// The border drawn around a code block.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum CodeBlockBorder {
+    /// No border.
+    #[default]
+    None,
+
+    /// A border with rounded corners.
+    Rounded,
+
+    /// A border with square corners.
+    Square,
+}
+
+impl CodeBlockBorder {
+    /// The top left, top right, bottom left, and bottom right corners of this border.
+    pub(crate) fn corners(&self) -> Option<[char; 4]> {
+        match self {
+            Self::None => None,
+            Self::Rounded => Some(['╭', '╮', '╰', '╯']),
+            Self::Square => Some(['┌', '┐', '└', '┘']),
+        }
+    }
+}
+
+/// What to do with lines that are too long to fit in a code block.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum CodeBlockOverflow {
+    /// Wrap lines at the last character that fits.
+    #[default]
+    Wrap,
+
+    /// Cut lines at the last character that fits and display an ellipsis.
+    Clip,
+}
+
+/// The style for the marker displayed in place of a group of hidden lines in a snippet.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub(crate) struct HiddenLinesMarkerStyle {
+    /// Whether to display the marker.
+    #[serde(default)]
+    pub(crate) enabled: Option<bool>,
+
+    /// The symbol to use.
+    #[serde(default)]
+    pub(crate) symbol: Option<String>,
+
+    /// The colors to use.
+    #[serde(default)]
+    pub(crate) colors: RawColors,
 }
 
 /// The style for the output of a code execution block.
@@ -647,10 +956,20 @@ pub(crate) struct ExecutionOutputBlockStyle {
     #[serde(default)]
     pub(crate) colors: RawColors,
 
+    /// The colors to be used for the output pane when the execution fails.
+    ///
+    /// Any color that isn't set falls back to the one in `colors`.
+    #[serde(default)]
+    pub(crate) failure: RawColors,
+
     /// The colors to be used for the text that represents the status of the execution block.
     #[serde(default)]
     pub(crate) status: ExecutionStatusBlockStyle,
 
+    /// The colors to be used for output lines written to stderr when splitting it from stdout.
+    #[serde(default)]
+    pub(crate) stderr: RawColors,
+
     /// The padding.
     #[serde(default)]
     pub(crate) padding: PaddingRect,
@@ -674,6 +993,30 @@ pub(crate) struct ExecutionStatusBlockStyle {
     /// The colors for the "not started" status.
     #[serde(default)]
     pub(crate) not_started: RawColors,
+
+    /// The text to use for each status.
+    #[serde(default)]
+    pub(crate) labels: ExecutionStatusLabels,
+}
+
+/// The text used to describe each status of a code execution block.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub(crate) struct ExecutionStatusLabels {
+    /// The text for the "running" status.
+    #[serde(default)]
+    pub(crate) running: Option<String>,
+
+    /// The text for the "finished" status.
+    #[serde(default)]
+    pub(crate) success: Option<String>,
+
+    /// The text for the "finished with error" status.
+    #[serde(default)]
+    pub(crate) failure: Option<String>,
+
+    /// The text for the "not started" status.
+    #[serde(default)]
+    pub(crate) not_started: Option<String>,
 }
 
 /// The style for inline code.
@@ -684,7 +1027,10 @@ pub(crate) struct InlineCodeStyle {
     pub(crate) colors: RawColors,
 }
 
-/// Vertical/horizontal padding.
+/// Padding around a block.
+///
+/// `horizontal` and `vertical` are shorthands for padding on both sides, which are overridden by
+/// any of the individual sides.
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub(crate) struct PaddingRect {
     /// The number of columns to use as horizontal padding.
@@ -694,6 +1040,22 @@ pub(crate) struct PaddingRect {
     /// The number of rows to use as vertical padding.
     #[serde(default)]
     pub(crate) vertical: Option<u8>,
+
+    /// The number of rows to use as padding above the block.
+    #[serde(default)]
+    pub(crate) top: Option<u8>,
+
+    /// The number of rows to use as padding below the block.
+    #[serde(default)]
+    pub(crate) bottom: Option<u8>,
+
+    /// The number of columns to use as padding to the left of the block.
+    #[serde(default)]
+    pub(crate) left: Option<u8>,
+
+    /// The number of columns to use as padding to the right of the block.
+    #[serde(default)]
+    pub(crate) right: Option<u8>,
 }
 
 /// A margin.
@@ -729,30 +1091,6 @@ impl Default for Margin {
     }
 }
 
-/// An element type.
-#[derive(Clone, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
-#[serde(rename_all = "snake_case")]
-pub(crate) enum ElementType {
-    SlideTitle,
-    Heading1,
-    Heading2,
-    Heading3,
-    Heading4,
-    Heading5,
-    Heading6,
-    Paragraph,
-    List,
-    Code,
-    PresentationTitle,
-    PresentationSubTitle,
-    PresentationEvent,
-    PresentationLocation,
-    PresentationDate,
-    PresentationAuthor,
-    Table,
-    BlockQuote,
-}
-
 /// Where to position the author's name in the intro slide.
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 #[serde(rename_all = "snake_case")]
@@ -777,6 +1115,15 @@ pub(crate) struct TypstStyle {
     /// The colors to be used.
     #[serde(default)]
     pub(crate) colors: RawColors,
+
+    /// The pixels per inch to use, overriding the one in the config file.
+    pub(crate) ppi: Option<u32>,
+
+    /// The font to use.
+    pub(crate) font: Option<String>,
+
+    /// The font size to use, in points.
+    pub(crate) font_size: Option<u16>,
 }
 
 /// Mermaid styles.
@@ -787,6 +1134,9 @@ pub(crate) struct MermaidStyle {
 
     /// The background color to use.
     pub(crate) background: Option<String>,
+
+    /// The scale to use, overriding the one in the config file.
+    pub(crate) scale: Option<u32>,
 }
 
 /// D2 styles.
@@ -808,6 +1158,62 @@ pub(crate) struct ModalStyle {
     pub(crate) selection_colors: RawColors,
 }
 
+/// Images style.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub(crate) struct ImageStyle {
+    /// The shadow drawn behind images.
+    #[serde(default)]
+    pub(crate) shadow: ImageShadowStyle,
+
+    /// The caption displayed below images, taken from their alt text.
+    #[serde(default)]
+    pub(crate) caption: ImageCaptionStyle,
+
+    /// The vertical spacing around images.
+    #[serde(default)]
+    pub(crate) spacing: ImageSpacing,
+}
+
+/// The vertical spacing around images.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub(crate) struct ImageSpacing {
+    /// The number of empty lines above an image.
+    #[serde(default)]
+    pub(crate) above: Option<u8>,
+
+    /// The number of empty lines below an image.
+    #[serde(default)]
+    pub(crate) below: Option<u8>,
+}
+
+/// The style for image captions.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub(crate) struct ImageCaptionStyle {
+    /// Whether to display captions.
+    #[serde(default)]
+    pub(crate) enabled: Option<bool>,
+
+    /// The colors to use.
+    #[serde(default)]
+    pub(crate) colors: RawColors,
+}
+
+/// The style for the shadow drawn behind images.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub(crate) struct ImageShadowStyle {
+    /// Whether to draw the shadow.
+    #[serde(default)]
+    pub(crate) enabled: Option<bool>,
+
+    /// The number of cells the shadow is offset by, both down and to the right.
+    #[serde(default)]
+    pub(crate) offset: Option<u16>,
+
+    /// The color of the shadow.
+    #[serde(default)]
+    pub(crate) color: Option<RawColor>,
+}
+
 /// The color palette.
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub(crate) struct ColorPalette {
@@ -1018,4 +1424,25 @@ mod test {
         let RawColor::Palette(name) = color else { panic!("not a palette color") };
         assert_eq!(name, expected);
     }
+
+    #[rstest]
+    #[case::theme_name("theme_name")]
+    #[case::theme("theme")]
+    fn code_theme_name(#[case] key: &str) {
+        let input = format!("{key}: InspiredGitHub");
+        let style: CodeBlockStyle = serde_yaml::from_str(&input).expect("invalid style");
+        assert_eq!(style.theme_name.as_deref(), Some("InspiredGitHub"));
+    }
+
+    #[rstest]
+    #[case::decimal(ListNumbering::Decimal, 12, "12")]
+    #[case::lower_alpha(ListNumbering::LowerAlpha, 2, "b")]
+    #[case::lower_alpha_wrapped(ListNumbering::LowerAlpha, 28, "ab")]
+    #[case::upper_alpha(ListNumbering::UpperAlpha, 26, "Z")]
+    #[case::lower_roman(ListNumbering::LowerRoman, 14, "xiv")]
+    #[case::upper_roman(ListNumbering::UpperRoman, 1994, "MCMXCIV")]
+    #[case::zero(ListNumbering::LowerRoman, 0, "0")]
+    fn list_numbering(#[case] numbering: ListNumbering, #[case] value: usize, #[case] expected: &str) {
+        assert_eq!(numbering.format(value), expected);
+    }
 }
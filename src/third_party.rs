@@ -3,7 +3,7 @@ use crate::{
     config::{default_mermaid_scale, default_snippet_render_threads, default_typst_ppi},
     markdown::{
         elements::{Line, Percent, Text},
-        text_style::{Color, TextStyle},
+        text_style::{Color, Colors, TextStyle},
     },
     render::{
         operation::{
@@ -14,10 +14,14 @@ use crate::{
     },
     terminal::image::{
         Image,
-        printer::{ImageSpec, RegisterImageError},
+        printer::{ImageProperties, ImageSpec, RegisterImageError},
     },
     theme::{Alignment, D2Style, MermaidStyle, PresentationTheme, TypstStyle, raw::RawColor},
     tools::{ExecutionError, ThirdPartyTools},
+    ui::{
+        inline_image::{EmbeddedImage, InlineImageLine},
+        prose::RenderProse,
+    },
 };
 use std::{
     collections::{HashMap, VecDeque},
@@ -57,7 +61,45 @@ impl ThirdPartyRender {
         width: Option<Percent>,
     ) -> Result<RenderOperation, ThirdPartyRenderError> {
         let result = self.render_pool.render(request);
-        let operation = Rc::new(RenderThirdParty::new(result, theme.default_style.style, width));
+        let operation = Rc::new(RenderThirdParty::new(result, theme.default_style.style, width, None));
+        Ok(RenderOperation::RenderAsync(operation))
+    }
+
+    /// Render a request, displaying the given text instead if rendering fails.
+    pub(crate) fn render_with_fallback(
+        &self,
+        request: ThirdPartyRenderRequest,
+        theme: &PresentationTheme,
+        fallback: Line,
+    ) -> Result<RenderOperation, ThirdPartyRenderError> {
+        let result = self.render_pool.render(request);
+        let operation = Rc::new(RenderThirdParty::new(result, theme.default_style.style, None, Some(fallback)));
+        Ok(RenderOperation::RenderAsync(operation))
+    }
+
+    /// Render the inline math formulas in a line, placing each of them within the line's text.
+    ///
+    /// If a maximum number of columns is given, the line is laid out like any other line of prose.
+    pub(crate) fn render_inline_math(
+        &self,
+        line: Line,
+        theme: &PresentationTheme,
+        alignment: Alignment,
+        max_columns: Option<u16>,
+        font_size: u8,
+    ) -> Result<RenderOperation, ThirdPartyRenderError> {
+        let mut formulas = Vec::new();
+        for (index, text) in line.0.iter().enumerate() {
+            if !text.style.is_math() {
+                continue;
+            }
+            let formula = text.content.strip_prefix('$').and_then(|content| content.strip_suffix('$'));
+            let formula = formula.unwrap_or(&text.content);
+            let request = ThirdPartyRenderRequest::Latex(format!("\\({formula}\\)"), theme.typst.clone());
+            formulas.push((index, self.render_pool.render(request)));
+        }
+        let background = theme.default_style.style.colors.background;
+        let operation = Rc::new(RenderInlineMath::new(line, formulas, alignment, max_columns, font_size, background));
         Ok(RenderOperation::RenderAsync(operation))
     }
 }
@@ -80,6 +122,7 @@ pub(crate) enum ThirdPartyRenderRequest {
     Latex(String, TypstStyle),
     Mermaid(String, MermaidStyle),
     D2(String, D2Style),
+    Graphviz(String, Colors),
 }
 
 #[derive(Debug, Default)]
@@ -158,6 +201,7 @@ impl Worker {
             ThirdPartyRenderRequest::Latex(input, style) => self.render_latex(input, &style),
             ThirdPartyRenderRequest::Mermaid(input, style) => self.render_mermaid(input, &style),
             ThirdPartyRenderRequest::D2(input, style) => self.render_d2(input, &style),
+            ThirdPartyRenderRequest::Graphviz(input, colors) => self.render_graphviz(input, &colors),
         };
         let mut result = result.lock().unwrap();
         match output {
@@ -197,13 +241,14 @@ impl Worker {
         let input_path = workdir.path().join("input.mmd");
         fs::write(&input_path, input)?;
 
+        let scale = style.scale.map(|scale| scale.to_string());
         ThirdPartyTools::mermaid(&[
             "-i",
             &input_path.to_string_lossy(),
             "-o",
             &output_path.to_string_lossy(),
             "-s",
-            &self.shared.config.mermaid_scale,
+            scale.as_deref().unwrap_or(&self.shared.config.mermaid_scale),
             "-t",
             &style.theme,
             "-b",
@@ -235,6 +280,36 @@ impl Worker {
         self.load_image(snippet, &output_path)
     }
 
+    pub(crate) fn render_graphviz(&self, input: String, colors: &Colors) -> Result<Image, ThirdPartyRenderError> {
+        let snippet = ImageSnippet { snippet: input.clone(), source: SnippetSource::Graphviz };
+        if let Some(image) = self.state.lock().unwrap().cache.get(&snippet).cloned() {
+            return Ok(image);
+        }
+        let workdir = tempfile::Builder::default().prefix(".presenterm").tempdir()?;
+        let output_path = workdir.path().join("output.png");
+        let input_path = workdir.path().join("input.dot");
+        fs::write(&input_path, input)?;
+
+        // Use the presentation's colors unless the graph itself overrides them.
+        let background = match &colors.background {
+            Some(color) => Self::as_hex_color(color)?,
+            None => "transparent".into(),
+        };
+        let mut args = vec![format!("-Gbgcolor={background}")];
+        if let Some(color) = &colors.foreground {
+            let color = Self::as_hex_color(color)?;
+            for attribute in ["Gcolor", "Gfontcolor", "Ncolor", "Nfontcolor", "Ecolor", "Efontcolor"] {
+                args.push(format!("-{attribute}={color}"));
+            }
+        }
+        args.extend(["-Tpng".into(), "-o".into(), output_path.to_string_lossy().into()]);
+        args.push(input_path.to_string_lossy().into());
+        let args: Vec<_> = args.iter().map(String::as_str).collect();
+        ThirdPartyTools::graphviz(&args).run()?;
+
+        self.load_image(snippet, &output_path)
+    }
+
     fn do_render_typst(
         &self,
         snippet: ImageSnippet,
@@ -249,6 +324,7 @@ impl Worker {
         fs::write(&input_path, &typst_input)?;
 
         let output_path = workdir.path().join("output.png");
+        let ppi = style.ppi.map(|ppi| ppi.to_string());
         ThirdPartyTools::typst(&[
             "compile",
             "--format",
@@ -256,7 +332,7 @@ impl Worker {
             "--root",
             &self.shared.root_dir,
             "--ppi",
-            &self.shared.config.typst_ppi,
+            ppi.as_deref().unwrap_or(&self.shared.config.typst_ppi),
             &input_path.to_string_lossy(),
             &output_path.to_string_lossy(),
         ])
@@ -282,12 +358,24 @@ impl Worker {
             let color = Self::as_typst_color(color)?;
             header.push_str(&format!("#set text(fill: {color})\n"));
         }
+        if let Some(font) = &style.font {
+            // Escape it so it can be used as a typst string.
+            let font = font.replace('\\', "\\\\").replace('"', "\\\"");
+            header.push_str(&format!("#set text(font: \"{font}\")\n"));
+        }
+        if let Some(size) = style.font_size {
+            header.push_str(&format!("#set text(size: {size}pt)\n"));
+        }
         Ok(header)
     }
 
     fn as_typst_color(color: &Color) -> Result<String, ThirdPartyRenderError> {
+        Ok(format!("rgb(\"{}\")", Self::as_hex_color(color)?))
+    }
+
+    fn as_hex_color(color: &Color) -> Result<String, ThirdPartyRenderError> {
         match color.as_rgb() {
-            Some((r, g, b)) => Ok(format!("rgb(\"#{r:02x}{g:02x}{b:02x}\")")),
+            Some((r, g, b)) => Ok(format!("#{r:02x}{g:02x}{b:02x}")),
             None => Err(ThirdPartyRenderError::UnsupportedColor(RawColor::from(*color).to_string())),
         }
     }
@@ -325,6 +413,7 @@ enum SnippetSource {
     Latex,
     Mermaid,
     D2,
+    Graphviz,
 }
 
 #[derive(Hash, PartialEq, Eq)]
@@ -339,17 +428,27 @@ pub(crate) struct RenderThirdParty {
     pending_result: Arc<Mutex<RenderResult>>,
     default_style: TextStyle,
     width: Option<Percent>,
+    fallback: Option<Line>,
 }
 
 impl RenderThirdParty {
-    fn new(pending_result: Arc<Mutex<RenderResult>>, default_style: TextStyle, width: Option<Percent>) -> Self {
-        Self { contents: Default::default(), pending_result, default_style, width }
+    fn new(
+        pending_result: Arc<Mutex<RenderResult>>,
+        default_style: TextStyle,
+        width: Option<Percent>,
+        fallback: Option<Line>,
+    ) -> Self {
+        Self { contents: Default::default(), pending_result, default_style, width, fallback }
     }
 }
 
 impl RenderAsync for RenderThirdParty {
     fn pollable(&self) -> Box<dyn Pollable> {
-        Box::new(OperationPollable { contents: self.contents.clone(), pending_result: self.pending_result.clone() })
+        Box::new(OperationPollable {
+            contents: self.contents.clone(),
+            pending_result: self.pending_result.clone(),
+            has_fallback: self.fallback.is_some(),
+        })
     }
 
     fn start_policy(&self) -> RenderAsyncStartPolicy {
@@ -373,7 +472,13 @@ impl AsRenderOperations for RenderThirdParty {
 
                 vec![RenderOperation::RenderImage(image.clone(), properties)]
             }
-            Some(Output::Error) => Vec::new(),
+            Some(Output::Error) => match &self.fallback {
+                Some(line) => vec![RenderOperation::RenderText {
+                    line: line.clone().into(),
+                    alignment: Alignment::Center { minimum_margin: Default::default(), minimum_size: 0 },
+                }],
+                None => Vec::new(),
+            },
             None => {
                 let text = Line::from(Text::new("Loading...", TextStyle::default().bold()));
                 vec![RenderOperation::RenderText {
@@ -385,6 +490,120 @@ impl AsRenderOperations for RenderThirdParty {
     }
 }
 
+/// A line of text containing inline math formulas.
+///
+/// The line is displayed as is until every formula is rendered, at which point they're replaced
+/// with images. Formulas that fail to render, as well as all of them if the line doesn't fit in
+/// a single row, are kept as text.
+#[derive(Debug)]
+pub(crate) struct RenderInlineMath {
+    line: Line,
+    formulas: Vec<(usize, Arc<Mutex<RenderResult>>)>,
+    images: Arc<Mutex<Option<Vec<Option<Image>>>>>,
+    alignment: Alignment,
+    max_columns: Option<u16>,
+    font_size: u8,
+    background_color: Option<Color>,
+}
+
+impl RenderInlineMath {
+    fn new(
+        line: Line,
+        formulas: Vec<(usize, Arc<Mutex<RenderResult>>)>,
+        alignment: Alignment,
+        max_columns: Option<u16>,
+        font_size: u8,
+        background_color: Option<Color>,
+    ) -> Self {
+        Self { line, formulas, images: Default::default(), alignment, max_columns, font_size, background_color }
+    }
+
+    fn alignment(&self, dimensions: &WindowSize) -> Alignment {
+        match self.max_columns {
+            Some(max_columns) => RenderProse::capped_alignment(self.alignment, max_columns, dimensions),
+            None => self.alignment,
+        }
+    }
+}
+
+impl RenderAsync for RenderInlineMath {
+    fn pollable(&self) -> Box<dyn Pollable> {
+        Box::new(InlineMathPollable {
+            images: self.images.clone(),
+            pending_results: self.formulas.iter().map(|(_, result)| result.clone()).collect(),
+        })
+    }
+
+    fn start_policy(&self) -> RenderAsyncStartPolicy {
+        RenderAsyncStartPolicy::Automatic
+    }
+}
+
+impl AsRenderOperations for RenderInlineMath {
+    fn as_render_operations(&self, dimensions: &WindowSize) -> Vec<RenderOperation> {
+        let alignment = self.alignment(dimensions);
+        // The formulas are displayed as text until they're rendered, as well as if they can't be placed.
+        let fallback = RenderOperation::RenderText { line: self.line.clone().into(), alignment };
+        let Some(images) = &*self.images.lock().unwrap() else {
+            return vec![fallback];
+        };
+        let mut line = self.line.clone();
+        let mut embedded = Vec::new();
+        for ((index, _), image) in self.formulas.iter().zip(images) {
+            let Some(image) = image else {
+                continue;
+            };
+            // Images are one row tall and terminal cells are roughly twice as tall as they are wide.
+            let (width, height) = image.image().dimensions();
+            let columns = (width as f64 / height.max(1) as f64 * 2.0).round().max(1.0) as u16;
+            let column = line.0[..*index].iter().map(Text::width).sum::<usize>() as u16;
+            let text = &mut line.0[*index];
+            let label = mem::replace(&mut text.content, " ".repeat(columns as usize));
+            embedded.push(EmbeddedImage { image: image.clone(), column, columns, label });
+        }
+        let layout_length = line.width() as u16;
+        let operation = RenderOperation::RenderText { line: line.into(), alignment };
+        InlineImageLine::new(
+            operation,
+            fallback,
+            embedded,
+            alignment,
+            layout_length,
+            self.font_size,
+            self.background_color,
+        )
+        .as_render_operations(dimensions)
+    }
+}
+
+struct InlineMathPollable {
+    images: Arc<Mutex<Option<Vec<Option<Image>>>>>,
+    pending_results: Vec<Arc<Mutex<RenderResult>>>,
+}
+
+impl Pollable for InlineMathPollable {
+    fn poll(&mut self) -> PollableState {
+        let mut images = self.images.lock().unwrap();
+        if images.is_some() {
+            return PollableState::Done;
+        }
+        if self.pending_results.iter().any(|result| matches!(*result.lock().unwrap(), RenderResult::Pending)) {
+            return PollableState::Unmodified;
+        }
+        // Formulas that can't be rendered are displayed as text so failures aren't errors.
+        let rendered = self
+            .pending_results
+            .iter()
+            .map(|result| match mem::take(&mut *result.lock().unwrap()) {
+                RenderResult::Success(image) => Some(image),
+                RenderResult::Failure(_) | RenderResult::Pending => None,
+            })
+            .collect();
+        *images = Some(rendered);
+        PollableState::Done
+    }
+}
+
 #[derive(Debug)]
 enum Output {
     Image(Image),
@@ -395,6 +614,7 @@ enum Output {
 struct OperationPollable {
     contents: Arc<Mutex<Option<Output>>>,
     pending_result: Arc<Mutex<RenderResult>>,
+    has_fallback: bool,
 }
 
 impl Pollable for OperationPollable {
@@ -410,9 +630,88 @@ impl Pollable for OperationPollable {
             }
             RenderResult::Failure(error) => {
                 *contents = Some(Output::Error);
-                PollableState::Failed { error }
+                // If there's a fallback we display it rather than treating this as an error.
+                match self.has_fallback {
+                    true => PollableState::Done,
+                    false => PollableState::Failed { error },
+                }
             }
             RenderResult::Pending => PollableState::Unmodified,
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        markdown::text_style::TextStyle,
+        render::operation::ImagePosition,
+        terminal::image::{ImageSource, printer::TerminalImage},
+    };
+    use image::{ColorType, DynamicImage};
+    use rstest::rstest;
+
+    #[test]
+    fn typst_page_header_font() {
+        let style = TypstStyle {
+            horizontal_margin: 1,
+            vertical_margin: 2,
+            style: TextStyle::default(),
+            ppi: None,
+            font: Some("Fira \"Sans\"".into()),
+            font_size: Some(14),
+        };
+        let header = Worker::generate_page_header(&style).expect("header failed");
+        let expected = "#set page(width: auto, height: auto, margin: (x: 1pt, y: 2pt), fill: none)
+#set text(font: \"Fira \\\"Sans\\\"\")
+#set text(size: 14pt)
+";
+        assert_eq!(header, expected);
+    }
+
+    #[rstest]
+    #[case::rendered(true, 20, "    ", &[2])]
+    #[case::failed(false, 20, "$x^2$", &[])]
+    #[case::wrapped(true, 6, "$x^2$", &[])]
+    fn inline_math(
+        #[case] success: bool,
+        #[case] window_columns: u16,
+        #[case] expected_formula: &str,
+        #[case] expected_columns: &[u16],
+    ) {
+        let make_line = |formula: &str| {
+            Line(vec![Text::from("a "), Text::new(formula, TextStyle::default().math()), Text::from(" b")])
+        };
+        let line = make_line("$x^2$");
+        let result = match success {
+            true => {
+                let image = DynamicImage::new(8, 4, ColorType::Rgba8);
+                RenderResult::Success(Image::new(TerminalImage::Ascii(image.into()), ImageSource::Generated))
+            }
+            false => RenderResult::Failure("no latex".into()),
+        };
+        let formulas = vec![(1, Arc::new(Mutex::new(result)))];
+        let alignment = Alignment::Left { margin: Default::default() };
+        let operation = RenderInlineMath::new(line, formulas, alignment, None, 1, None);
+        assert_eq!(operation.pollable().poll(), PollableState::Done);
+
+        let dimensions = WindowSize { rows: 10, columns: window_columns, width: window_columns, height: 10 };
+        let operations = operation.as_render_operations(&dimensions);
+        // The image is twice as wide as it's tall so it takes up 4 columns.
+        let RenderOperation::RenderText { line, .. } = &operations[0] else { panic!("not text: {operations:?}") };
+        assert_eq!(line, &make_line(expected_formula).into());
+
+        let columns: Vec<_> = operations
+            .iter()
+            .filter_map(|operation| match operation {
+                RenderOperation::RenderImage(_, properties) => match properties.position {
+                    ImagePosition::Column(column) => Some(column),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect();
+        assert_eq!(columns, expected_columns);
+    }
+}
@@ -26,6 +26,10 @@ impl ThirdPartyTools {
         Tool::new("d2", args)
     }
 
+    pub(crate) fn graphviz(args: &[&str]) -> Tool {
+        Tool::new("dot", args)
+    }
+
     pub(crate) fn weasyprint(args: &[&str]) -> Tool {
         Tool::new("weasyprint", args).inherit_stdout().max_error_lines(100)
     }
@@ -0,0 +1,250 @@
+use crate::{
+    markdown::{
+        elements::{Line, Text},
+        text_style::TextStyle,
+    },
+    render::{
+        operation::{AsRenderOperations, RenderOperation},
+        properties::WindowSize,
+    },
+    theme::{Alignment, Margin},
+};
+use serde::Deserialize;
+use std::rc::Rc;
+
+pub(crate) const GLYPH_ROWS: usize = 5;
+const GLYPH_SPACING: usize = 1;
+const UNKNOWN_GLYPH: char = '?';
+
+// The characters that represent every combination of filled cells in a 2x2 grid, indexed by the
+// bits top left, top right, bottom left, bottom right.
+const QUADRANTS: [char; 16] = [' ', '▗', '▖', '▄', '▝', '▐', '▞', '▟', '▘', '▚', '▌', '▙', '▀', '▜', '▛', '█'];
+
+// Every glyph is drawn in a 5 rows grid where `#` means the cell is filled.
+const GLYPHS: &[(char, [&str; GLYPH_ROWS])] = &[
+    ('A', [" ### ", "#   #", "#####", "#   #", "#   #"]),
+    ('B', ["#### ", "#   #", "#### ", "#   #", "#### "]),
+    ('C', [" ####", "#    ", "#    ", "#    ", " ####"]),
+    ('D', ["#### ", "#   #", "#   #", "#   #", "#### "]),
+    ('E', ["#####", "#    ", "#### ", "#    ", "#####"]),
+    ('F', ["#####", "#    ", "#### ", "#    ", "#    "]),
+    ('G', [" ####", "#    ", "#  ##", "#   #", " ####"]),
+    ('H', ["#   #", "#   #", "#####", "#   #", "#   #"]),
+    ('I', ["###", " # ", " # ", " # ", "###"]),
+    ('J', ["    #", "    #", "    #", "#   #", " ### "]),
+    ('K', ["#   #", "#  # ", "###  ", "#  # ", "#   #"]),
+    ('L', ["#    ", "#    ", "#    ", "#    ", "#####"]),
+    ('M', ["#   #", "## ##", "# # #", "#   #", "#   #"]),
+    ('N', ["#   #", "##  #", "# # #", "#  ##", "#   #"]),
+    ('O', [" ### ", "#   #", "#   #", "#   #", " ### "]),
+    ('P', ["#### ", "#   #", "#### ", "#    ", "#    "]),
+    ('Q', [" ### ", "#   #", "# # #", "#  # ", " ## #"]),
+    ('R', ["#### ", "#   #", "#### ", "#  # ", "#   #"]),
+    ('S', [" ####", "#    ", " ### ", "    #", "#### "]),
+    ('T', ["#####", "  #  ", "  #  ", "  #  ", "  #  "]),
+    ('U', ["#   #", "#   #", "#   #", "#   #", " ### "]),
+    ('V', ["#   #", "#   #", "#   #", " # # ", "  #  "]),
+    ('W', ["#   #", "#   #", "# # #", "## ##", "#   #"]),
+    ('X', ["#   #", " # # ", "  #  ", " # # ", "#   #"]),
+    ('Y', ["#   #", " # # ", "  #  ", "  #  ", "  #  "]),
+    ('Z', ["#####", "   # ", "  #  ", " #   ", "#####"]),
+    ('0', [" ### ", "#  ##", "# # #", "##  #", " ### "]),
+    ('1', [" # ", "## ", " # ", " # ", "###"]),
+    ('2', ["#### ", "    #", " ### ", "#    ", "#####"]),
+    ('3', ["#### ", "    #", " ### ", "    #", "#### "]),
+    ('4', ["#   #", "#   #", "#####", "    #", "    #"]),
+    ('5', ["#####", "#    ", "#### ", "    #", "#### "]),
+    ('6', [" ### ", "#    ", "#### ", "#   #", " ### "]),
+    ('7', ["#####", "    #", "   # ", "  #  ", "  #  "]),
+    ('8', [" ### ", "#   #", " ### ", "#   #", " ### "]),
+    ('9', [" ### ", "#   #", " ####", "    #", " ### "]),
+    (' ', ["   ", "   ", "   ", "   ", "   "]),
+    ('!', ["#", "#", "#", " ", "#"]),
+    ('?', ["### ", "   #", " ## ", "    ", " #  "]),
+    ('.', [" ", " ", " ", " ", "#"]),
+    (',', ["  ", "  ", "  ", " #", "# "]),
+    ('-', ["    ", "    ", "####", "    ", "    "]),
+    (':', [" ", "#", " ", "#", " "]),
+    ('\'', ["#", "#", " ", " ", " "]),
+];
+
+/// The font used to draw big text.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum BigTextFont {
+    /// A font that uses a full block per cell, 5 rows tall.
+    #[default]
+    Block,
+
+    /// A font that uses quadrant blocks to pack 2x2 cells in one, 3 rows tall and half as wide.
+    Compact,
+}
+
+impl BigTextFont {
+    /// The fonts to try, in order, when drawing text using this font.
+    fn candidates(&self) -> &'static [BigTextFont] {
+        match self {
+            Self::Block => &[Self::Block, Self::Compact],
+            Self::Compact => &[Self::Compact],
+        }
+    }
+
+    fn width(&self, text: &str) -> usize {
+        let glyphs: Vec<_> = text.chars().map(glyph).collect();
+        let spacing = glyphs.len().saturating_sub(1) * GLYPH_SPACING;
+        let width = glyphs.iter().map(|glyph| glyph[0].len()).sum::<usize>() + spacing;
+        match self {
+            Self::Block => width,
+            Self::Compact => width.div_ceil(2),
+        }
+    }
+
+    fn render(&self, text: &str) -> Vec<String> {
+        let glyphs: Vec<_> = text.chars().map(glyph).collect();
+        let spacing = " ".repeat(GLYPH_SPACING);
+        let rows = (0..GLYPH_ROWS).map(|row| glyphs.iter().map(|glyph| glyph[row]).collect::<Vec<_>>().join(&spacing));
+        let rows: Vec<String> = rows.collect();
+        match self {
+            Self::Block => rows.iter().map(|row| row.replace('#', "█")).collect(),
+            Self::Compact => {
+                let width = rows[0].len();
+                let filled =
+                    |row: Option<&String>, column| row.and_then(|row| row.as_bytes().get(column)) == Some(&b'#');
+                rows.chunks(2)
+                    .map(|pair| {
+                        let (top, bottom) = (pair.first(), pair.get(1));
+                        (0..width)
+                            .step_by(2)
+                            .map(|column| {
+                                let cells = [
+                                    filled(top, column),
+                                    filled(top, column + 1),
+                                    filled(bottom, column),
+                                    filled(bottom, column + 1),
+                                ];
+                                QUADRANTS[cells.iter().fold(0, |index, cell| index << 1 | *cell as usize)]
+                            })
+                            .collect()
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Get the rows for the glyph that represents a character, using `#` for filled cells.
+pub(crate) fn glyph(c: char) -> &'static [&'static str; GLYPH_ROWS] {
+    let c = c.to_ascii_uppercase();
+    let find = |c| GLYPHS.iter().find(|(glyph, _)| *glyph == c).map(|(_, rows)| rows);
+    find(c).or_else(|| find(UNKNOWN_GLYPH)).expect("no unknown glyph")
+}
+
+/// A line of text drawn using a large font.
+///
+/// If the text doesn't fit in the available width it's wrapped at word boundaries, and if a single
+/// word doesn't fit then a smaller font is used. If nothing works, the text is displayed as is.
+#[derive(Debug)]
+pub(crate) struct BigText {
+    text: String,
+    font: BigTextFont,
+    style: TextStyle,
+}
+
+impl BigText {
+    pub(crate) fn new(text: String, font: BigTextFont, style: TextStyle) -> Self {
+        Self { text, font, style }
+    }
+
+    fn wrap(&self, font: BigTextFont, max_width: usize) -> Option<Vec<String>> {
+        let mut lines = Vec::new();
+        let mut current = String::new();
+        for word in self.text.split_whitespace() {
+            if font.width(word) > max_width {
+                return None;
+            }
+            if current.is_empty() {
+                current.push_str(word);
+                continue;
+            }
+            let candidate = format!("{current} {word}");
+            if font.width(&candidate) > max_width {
+                lines.push(current);
+                current = word.to_string();
+            } else {
+                current = candidate;
+            }
+        }
+        lines.push(current);
+        Some(lines)
+    }
+
+    fn push_line(&self, operations: &mut Vec<RenderOperation>, line: String) {
+        let alignment = Alignment::Center { minimum_margin: Margin::Fixed(0), minimum_size: 0 };
+        operations.extend([
+            RenderOperation::RenderText { line: Line::from(Text::new(line, self.style)).into(), alignment },
+            RenderOperation::RenderLineBreak,
+        ]);
+    }
+}
+
+impl From<BigText> for RenderOperation {
+    fn from(text: BigText) -> Self {
+        Self::RenderDynamic(Rc::new(text))
+    }
+}
+
+impl AsRenderOperations for BigText {
+    fn as_render_operations(&self, dimensions: &WindowSize) -> Vec<RenderOperation> {
+        let max_width = dimensions.columns as usize;
+        let mut operations = Vec::new();
+        for font in self.font.candidates() {
+            let Some(lines) = self.wrap(*font, max_width) else {
+                continue;
+            };
+            for (index, line) in lines.iter().enumerate() {
+                if index > 0 {
+                    operations.push(RenderOperation::RenderLineBreak);
+                }
+                for row in font.render(line) {
+                    self.push_line(&mut operations, row);
+                }
+            }
+            return operations;
+        }
+        // Nothing fits so just display the text as is.
+        self.push_line(&mut operations, self.text.clone());
+        operations
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstest::rstest;
+
+    #[test]
+    fn glyphs_are_consistent() {
+        for (c, rows) in GLYPHS {
+            let width = rows[0].len();
+            assert!(rows.iter().all(|row| row.len() == width), "glyph {c} has uneven rows");
+        }
+    }
+
+    #[rstest]
+    #[case::block(BigTextFont::Block, &["█   █ ███", "█   █  █ ", "█████  █ ", "█   █  █ ", "█   █ ███"])]
+    #[case::compact(BigTextFont::Compact, &["▌ ▌▜▘", "▛▀▌▐ ", "▘ ▘▀▘"])]
+    fn render(#[case] font: BigTextFont, #[case] expected: &[&str]) {
+        assert_eq!(font.render("hi"), expected);
+    }
+
+    #[rstest]
+    #[case::fits(43, Some(vec!["hi there"]))]
+    #[case::wrapped(30, Some(vec!["hi", "there"]))]
+    #[case::word_too_long(20, None)]
+    fn wrap(#[case] max_width: usize, #[case] expected: Option<Vec<&str>>) {
+        let text = BigText::new("hi there".into(), BigTextFont::Block, Default::default());
+        let lines = text.wrap(BigTextFont::Block, max_width);
+        let lines: Option<Vec<_>> = lines.as_ref().map(|lines| lines.iter().map(String::as_str).collect());
+        assert_eq!(lines, expected);
+    }
+}
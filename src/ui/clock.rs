@@ -0,0 +1,105 @@
+use crate::{
+    config::ClockPosition,
+    markdown::{elements::Text, text_style::TextStyle},
+    render::{
+        operation::{AsRenderOperations, RenderOperation},
+        properties::WindowSize,
+    },
+    theme::{Alignment, Margin},
+};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A wall clock displayed in a corner of the screen.
+#[derive(Clone, Debug)]
+pub(crate) struct Clock {
+    position: ClockPosition,
+    visible: bool,
+    time: String,
+}
+
+impl Clock {
+    pub(crate) fn new(position: ClockPosition, visible: bool) -> Self {
+        let (hour, minute) = local_time();
+        Self { position, visible, time: format_time(hour, minute) }
+    }
+
+    pub(crate) fn toggle(&mut self) {
+        self.visible = !self.visible;
+    }
+
+    /// Get this clock if it should be displayed.
+    pub(crate) fn visible(&self) -> Option<&Self> {
+        self.visible.then_some(self)
+    }
+
+    /// Check whether the clock needs to be redrawn.
+    pub(crate) fn tick(&mut self) -> bool {
+        let (hour, minute) = local_time();
+        let time = format_time(hour, minute);
+        let modified = time != self.time;
+        self.time = time;
+        modified && self.visible
+    }
+}
+
+impl AsRenderOperations for Clock {
+    fn as_render_operations(&self, _: &WindowSize) -> Vec<RenderOperation> {
+        let jump = match self.position {
+            ClockPosition::TopLeft | ClockPosition::TopRight => RenderOperation::JumpToRow { index: 0 },
+            ClockPosition::BottomLeft | ClockPosition::BottomRight => RenderOperation::JumpToBottomRow { index: 0 },
+        };
+        let margin = Margin::Fixed(1);
+        let alignment = match self.position {
+            ClockPosition::TopLeft | ClockPosition::BottomLeft => Alignment::Left { margin },
+            ClockPosition::TopRight | ClockPosition::BottomRight => Alignment::Right { margin },
+        };
+        let text = Text::new(self.time.clone(), TextStyle::default().bold());
+        vec![jump, RenderOperation::RenderText { line: vec![text].into(), alignment }]
+    }
+}
+
+fn format_time(hour: u8, minute: u8) -> String {
+    format!("{hour:02}:{minute:02}")
+}
+
+/// Get the current hour and minute in the local timezone.
+fn local_time() -> (u8, u8) {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    #[cfg(unix)]
+    {
+        let time = now as libc::time_t;
+        // `tm` is plain data that's fully written by `localtime_r` when it succeeds.
+        let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+        if !unsafe { libc::localtime_r(&time, &mut tm) }.is_null() {
+            return (tm.tm_hour as u8, tm.tm_min as u8);
+        }
+    }
+    // Fall back to UTC if we can't tell the timezone.
+    (((now / 3600) % 24) as u8, ((now / 60) % 60) as u8)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::padded(9, 5, "09:05")]
+    #[case::afternoon(17, 45, "17:45")]
+    fn format(#[case] hour: u8, #[case] minute: u8, #[case] expected: &str) {
+        assert_eq!(format_time(hour, minute), expected);
+    }
+
+    #[test]
+    fn hidden_never_needs_redraw() {
+        let mut clock = Clock::new(ClockPosition::TopRight, false);
+        clock.time.clear();
+        assert!(!clock.tick());
+        assert!(clock.visible().is_none());
+
+        clock.toggle();
+        clock.time.clear();
+        assert!(clock.tick());
+        assert!(clock.visible().is_some());
+    }
+}
@@ -0,0 +1,180 @@
+use crate::{
+    config::{ClockPosition, CountdownConfig},
+    markdown::{
+        elements::Text,
+        text_style::{Color, TextStyle},
+    },
+    render::{
+        operation::{AsRenderOperations, RenderOperation},
+        properties::WindowSize,
+    },
+    theme::{Alignment, Margin},
+};
+use std::time::{Duration, Instant};
+
+/// A countdown that tracks the time left in a talk, displayed in a corner of the screen.
+///
+/// The countdown turns red once the configured warning period starts and flashes once time runs
+/// out.
+#[derive(Clone, Debug)]
+pub(crate) struct Countdown {
+    started: Instant,
+    duration: u64,
+    warning: u64,
+    position: ClockPosition,
+    elapsed: u64,
+    expired: bool,
+}
+
+impl Countdown {
+    pub(crate) fn new(duration: Duration, config: &CountdownConfig) -> Self {
+        Self {
+            started: Instant::now(),
+            duration: duration.as_secs(),
+            warning: config.warning_minutes * 60,
+            position: config.position,
+            elapsed: 0,
+            expired: false,
+        }
+    }
+
+    /// Check whether the countdown needs to be redrawn.
+    pub(crate) fn tick(&mut self) -> bool {
+        let elapsed = self.started.elapsed().as_secs();
+        let modified = elapsed != self.elapsed;
+        self.elapsed = elapsed;
+        modified
+    }
+
+    /// Check whether time just ran out.
+    ///
+    /// This only returns true the first time it's called after the countdown reaches zero.
+    pub(crate) fn poll_expired(&mut self) -> bool {
+        if self.expired || self.elapsed < self.duration {
+            return false;
+        }
+        self.expired = true;
+        true
+    }
+
+    fn text(&self) -> Option<Text> {
+        let remaining = self.duration.saturating_sub(self.elapsed);
+        // Flash once time runs out by hiding the countdown every other second.
+        if remaining == 0 && (self.elapsed - self.duration) % 2 == 1 {
+            return None;
+        }
+        let mut style = TextStyle::default().bold();
+        if remaining <= self.warning {
+            style = style.fg_color(Color::Red);
+        }
+        Some(Text::new(format_remaining(remaining), style))
+    }
+}
+
+impl AsRenderOperations for Countdown {
+    fn as_render_operations(&self, _: &WindowSize) -> Vec<RenderOperation> {
+        let Some(text) = self.text() else {
+            return Vec::new();
+        };
+        let jump = match self.position {
+            ClockPosition::TopLeft | ClockPosition::TopRight => RenderOperation::JumpToRow { index: 0 },
+            ClockPosition::BottomLeft | ClockPosition::BottomRight => RenderOperation::JumpToBottomRow { index: 0 },
+        };
+        let margin = Margin::Fixed(1);
+        let alignment = match self.position {
+            ClockPosition::TopLeft | ClockPosition::BottomLeft => Alignment::Left { margin },
+            ClockPosition::TopRight | ClockPosition::BottomRight => Alignment::Right { margin },
+        };
+        vec![jump, RenderOperation::RenderText { line: vec![text].into(), alignment }]
+    }
+}
+
+fn format_remaining(seconds: u64) -> String {
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    match hours {
+        0 => format!("{minutes:02}:{seconds:02}"),
+        _ => format!("{hours}:{minutes:02}:{seconds:02}"),
+    }
+}
+
+/// Parse a talk duration like `30m`, `90s` or `1h15m`.
+pub(crate) fn parse_duration(input: &str) -> Result<Duration, String> {
+    let error = || format!("invalid duration '{input}', expected something like '30m' or '1h15m'");
+    let mut seconds = 0;
+    let mut rest = input;
+    while !rest.is_empty() {
+        let unit_start = rest.find(|c: char| !c.is_ascii_digit()).ok_or_else(error)?;
+        let value: u64 = rest[..unit_start].parse().map_err(|_| error())?;
+        let multiplier = match rest[unit_start..].chars().next() {
+            Some('h') => 3600,
+            Some('m') => 60,
+            Some('s') => 1,
+            _ => return Err(error()),
+        };
+        seconds += value * multiplier;
+        rest = &rest[unit_start + 1..];
+    }
+    match seconds {
+        0 => Err(error()),
+        _ => Ok(Duration::from_secs(seconds)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstest::rstest;
+
+    fn make_countdown(duration: u64, elapsed: u64) -> Countdown {
+        let config = CountdownConfig::default();
+        let mut countdown = Countdown::new(Duration::from_secs(duration), &config);
+        countdown.elapsed = elapsed;
+        countdown
+    }
+
+    #[rstest]
+    #[case::minutes("30m", 1800)]
+    #[case::seconds("90s", 90)]
+    #[case::combined("1h15m", 4500)]
+    fn parse(#[case] input: &str, #[case] expected: u64) {
+        assert_eq!(parse_duration(input), Ok(Duration::from_secs(expected)));
+    }
+
+    #[rstest]
+    #[case::empty("")]
+    #[case::no_unit("30")]
+    #[case::unknown_unit("30d")]
+    #[case::no_value("m")]
+    #[case::zero("0m")]
+    fn parse_invalid(#[case] input: &str) {
+        assert!(parse_duration(input).is_err());
+    }
+
+    #[rstest]
+    #[case::minutes(125, "02:05")]
+    #[case::hours(3725, "1:02:05")]
+    fn format(#[case] seconds: u64, #[case] expected: &str) {
+        assert_eq!(format_remaining(seconds), expected);
+    }
+
+    #[rstest]
+    #[case::normal(1800, 0, Some(("30:00", None)))]
+    #[case::warning(1800, 1500, Some(("05:00", Some(Color::Red))))]
+    #[case::expired(1800, 1800, Some(("00:00", Some(Color::Red))))]
+    #[case::flashing(1800, 1801, None)]
+    fn text(#[case] duration: u64, #[case] elapsed: u64, #[case] expected: Option<(&str, Option<Color>)>) {
+        let text = make_countdown(duration, elapsed).text();
+        let text = text.as_ref().map(|text| (text.content.as_str(), text.style.colors.foreground));
+        assert_eq!(text, expected);
+    }
+
+    #[test]
+    fn expires_once() {
+        let mut countdown = make_countdown(60, 59);
+        assert!(!countdown.poll_expired());
+
+        countdown.elapsed = 60;
+        assert!(countdown.poll_expired());
+        assert!(!countdown.poll_expired());
+    }
+}
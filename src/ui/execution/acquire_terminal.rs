@@ -68,9 +68,9 @@ impl AsRenderOperations for RunAcquireTerminalSnippet {
     fn as_render_operations(&self, _dimensions: &WindowSize) -> Vec<RenderOperation> {
         let state = self.state.lock().unwrap();
         let separator_text = match state.deref() {
-            State::NotStarted => Text::new("not started", self.colors.not_started_style),
-            State::Success => Text::new("finished", self.colors.success_style),
-            State::Failure(_) => Text::new("finished with error", self.colors.failure_style),
+            State::NotStarted => Text::new(&self.colors.labels.not_started, self.colors.not_started_style),
+            State::Success => Text::new(&self.colors.labels.success, self.colors.success_style),
+            State::Failure(_) => Text::new(&self.colors.labels.failure, self.colors.failure_style),
         };
 
         let heading = Line(vec![" [".into(), separator_text, "] ".into()]);
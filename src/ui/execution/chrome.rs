@@ -0,0 +1,55 @@
+use super::output::SnippetHandle;
+use crate::{
+    code::snippet::SnippetChromePosition,
+    markdown::text_style::Colors,
+    render::{
+        operation::{AsRenderOperations, RenderOperation},
+        properties::WindowSize,
+    },
+    theme::{Alignment, Margin},
+};
+
+/// Displays a snippet's output as decoration on a fixed position of the screen.
+#[derive(Debug)]
+pub(crate) struct SnippetChromeOperation {
+    handle: SnippetHandle,
+    position: SnippetChromePosition,
+    default_colors: Colors,
+}
+
+impl SnippetChromeOperation {
+    pub(crate) fn new(handle: SnippetHandle, position: SnippetChromePosition, default_colors: Colors) -> Self {
+        Self { handle, position, default_colors }
+    }
+}
+
+impl AsRenderOperations for SnippetChromeOperation {
+    fn as_render_operations(&self, _: &WindowSize) -> Vec<RenderOperation> {
+        use SnippetChromePosition::*;
+        let lines = self.handle.output_lines();
+        if lines.is_empty() {
+            return Vec::new();
+        }
+        let jump = match self.position {
+            TopLeft | Top | TopRight => RenderOperation::JumpToRow { index: 0 },
+            BottomLeft | Bottom | BottomRight => {
+                RenderOperation::JumpToBottomRow { index: u16::try_from(lines.len() - 1).unwrap_or(u16::MAX) }
+            }
+        };
+        let margin = Margin::Fixed(1);
+        let alignment = match self.position {
+            TopLeft | BottomLeft => Alignment::Left { margin },
+            Top | Bottom => Alignment::Center { minimum_margin: margin, minimum_size: 0 },
+            TopRight | BottomRight => Alignment::Right { margin },
+        };
+        let mut operations = vec![jump];
+        for line in lines {
+            operations.extend([
+                RenderOperation::RenderText { line: line.into(), alignment },
+                RenderOperation::RenderLineBreak,
+            ]);
+        }
+        operations.push(RenderOperation::SetColors(self.default_colors));
+        operations
+    }
+}
@@ -27,10 +27,17 @@ impl SnippetExecutionDisabledOperation {
             ExecutionType::Execute => ("+exec", "-x"),
             ExecutionType::ExecReplace => ("+exec_replace", "-X"),
             ExecutionType::Image => ("+image", "-X"),
+            ExecutionType::Chrome => ("+chrome", "-X"),
         };
         let text = Text::new(format!("snippet {attribute} is disabled, run with {cli_parameter} to enable"), style);
         Self { text, alignment, policy, toggled: Default::default() }
     }
+
+    /// Create an operation that notes an executable snippet has nothing to run.
+    pub(crate) fn empty_snippet(style: TextStyle, alignment: Alignment) -> Self {
+        let text = Text::new("nothing to run: snippet is empty", style);
+        Self { text, alignment, policy: RenderAsyncStartPolicy::OnDemand, toggled: Default::default() }
+    }
 }
 
 impl AsRenderOperations for SnippetExecutionDisabledOperation {
@@ -61,4 +68,5 @@ pub(crate) enum ExecutionType {
     Execute,
     ExecReplace,
     Image,
+    Chrome,
 }
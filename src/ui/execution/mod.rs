@@ -1,10 +1,12 @@
 pub(crate) mod acquire_terminal;
+pub(crate) mod chrome;
 pub(crate) mod disabled;
 pub(crate) mod image;
 pub(crate) mod output;
 pub(crate) mod validator;
 
 pub(crate) use acquire_terminal::RunAcquireTerminalSnippet;
+pub(crate) use chrome::SnippetChromeOperation;
 pub(crate) use disabled::SnippetExecutionDisabledOperation;
 pub(crate) use image::RunImageSnippet;
 pub(crate) use output::SnippetOutputOperation;
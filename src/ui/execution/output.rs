@@ -1,8 +1,10 @@
 use crate::{
     code::{
         execute::{ExecutionHandle, ExecutionState, LanguageSnippetExecutor, ProcessStatus},
-        snippet::Snippet,
+        highlighting::SnippetHighlighter,
+        snippet::{Snippet, SnippetLanguage, SnippetOutputPolicy},
     },
+    config::SnippetOutputOverflow,
     markdown::{
         elements::{Line, Text},
         text_style::{Colors, TextStyle},
@@ -10,16 +12,19 @@ use crate::{
     render::{
         operation::{
             AsRenderOperations, BlockLine, Pollable, PollableState, RenderAsync, RenderAsyncStartPolicy,
-            RenderOperation,
+            RenderOperation, ScrollDirection,
         },
         properties::WindowSize,
     },
     terminal::ansi::AnsiParser,
-    theme::{Alignment, ExecutionOutputBlockStyle, ExecutionStatusBlockStyle},
-    ui::separator::{RenderSeparator, SeparatorWidth},
+    theme::{Alignment, CodeBlockStyle, ExecutionOutputBlockStyle, ExecutionStatusBlockStyle},
+    ui::{
+        scroll::{SCROLLBAR_THUMB, SCROLLBAR_TRACK, SCROLLBAR_WIDTH, scrollbar_thumb},
+        separator::{RenderSeparator, SeparatorWidth},
+    },
 };
 use std::{
-    io::BufRead,
+    collections::BTreeSet,
     iter,
     rc::Rc,
     sync::{Arc, Mutex},
@@ -40,10 +45,15 @@ struct Inner {
     snippet: Snippet,
     executor: LanguageSnippetExecutor,
     output_lines: Vec<Line>,
+    stderr_lines: BTreeSet<usize>,
     max_line_length: u16,
     process_status: Option<ProcessStatus>,
     state: State,
     policy: RenderAsyncStartPolicy,
+    scroll_offset: usize,
+    generation: usize,
+    pipe_source: Option<SnippetHandle>,
+    stdout: Vec<u8>,
 }
 
 #[derive(Debug)]
@@ -54,6 +64,10 @@ pub(crate) struct SnippetOutputOperation {
     alignment: Alignment,
     handle: SnippetHandle,
     font_size: u8,
+    max_rows: Option<u16>,
+    match_code_width: bool,
+    overflow: SnippetOutputOverflow,
+    highlighter: Option<OutputHighlighter>,
 }
 
 impl SnippetOutputOperation {
@@ -65,9 +79,125 @@ impl SnippetOutputOperation {
         block_length: u16,
         alignment: Alignment,
         font_size: u8,
+        max_rows: Option<u16>,
     ) -> Self {
         let block_length = alignment.adjust_size(block_length);
-        Self { default_colors, style, block_length, alignment, handle, font_size }
+        Self {
+            default_colors,
+            style,
+            block_length,
+            alignment,
+            handle,
+            font_size,
+            max_rows,
+            match_code_width: false,
+            overflow: Default::default(),
+            highlighter: None,
+        }
+    }
+
+    /// Make the output block at least as wide as the code block it belongs to, regardless of alignment.
+    pub(crate) fn match_code_width(mut self, enabled: bool) -> Self {
+        self.match_code_width = enabled;
+        self
+    }
+
+    /// Set how to deal with output that doesn't fit in the maximum number of rows.
+    pub(crate) fn overflow(mut self, overflow: SnippetOutputOverflow) -> Self {
+        self.overflow = overflow;
+        self
+    }
+
+    /// Highlight the output as if it was code rather than interpreting any ANSI escape codes in it.
+    pub(crate) fn highlight_as(mut self, highlighter: Option<OutputHighlighter>) -> Self {
+        self.highlighter = highlighter;
+        self
+    }
+
+    fn visible_rows(&self, total_lines: usize) -> Option<usize> {
+        let max_rows = usize::from(self.max_rows?).max(1);
+        (total_lines > max_rows).then_some(max_rows)
+    }
+
+    fn styled_lines(&self, inner: &Inner) -> Vec<Line> {
+        let mut lines = match &self.highlighter {
+            Some(highlighter) => highlighter.highlight(&inner.output_lines),
+            None => inner.output_lines.clone(),
+        };
+        for (index, line) in lines.iter_mut().enumerate() {
+            if inner.stderr_lines.contains(&index) {
+                line.apply_style(&self.style.stderr_style);
+            }
+        }
+        lines
+    }
+
+    fn scrolled_lines(&self, inner: &Inner) -> Vec<Line> {
+        let lines = self.styled_lines(inner);
+        let Some(rows) = self.visible_rows(lines.len()) else {
+            return lines;
+        };
+        let total_lines = lines.len();
+        let offset = inner.scroll_offset.min(total_lines - rows);
+        let thumb = scrollbar_thumb(rows, total_lines, offset);
+        let line_length = usize::from(inner.max_line_length);
+        let mut output = Vec::new();
+        for (index, mut line) in lines.into_iter().skip(offset).take(rows).enumerate() {
+            let padding = line_length.saturating_sub(line.width());
+            let indicator = if thumb.contains(&index) { SCROLLBAR_THUMB } else { SCROLLBAR_TRACK };
+            line.0.extend([Text::from(" ".repeat(padding)), Text::from(indicator)]);
+            output.push(line);
+        }
+        output
+    }
+
+    fn clipped_lines(&self, inner: &Inner, rows: usize) -> Vec<Line> {
+        let mut lines = self.styled_lines(inner);
+        let total_lines = lines.len();
+        match self.overflow {
+            SnippetOutputOverflow::Scroll => (),
+            SnippetOutputOverflow::Head => lines.truncate(rows),
+            SnippetOutputOverflow::Tail => {
+                lines.drain(..total_lines - rows);
+            }
+            SnippetOutputOverflow::HeadTail => {
+                // One of the rows is taken by the marker.
+                let head = (rows - 1).div_ceil(2);
+                let tail = rows - 1 - head;
+                let omitted = total_lines - head - tail;
+                let marker = Line::from(format!("… ({omitted} lines omitted) …"));
+                lines.splice(head..total_lines - tail, [marker]);
+            }
+        };
+        lines
+    }
+}
+
+/// Highlights a snippet's output using the syntax of some language.
+#[derive(Debug)]
+pub(crate) struct OutputHighlighter {
+    highlighter: SnippetHighlighter,
+    language: SnippetLanguage,
+    style: CodeBlockStyle,
+}
+
+impl OutputHighlighter {
+    pub(crate) fn new(highlighter: SnippetHighlighter, language: SnippetLanguage, mut style: CodeBlockStyle) -> Self {
+        // The output block has its own background so don't let the highlighting theme override it.
+        style.background = false;
+        Self { highlighter, language, style }
+    }
+
+    fn highlight(&self, lines: &[Line]) -> Vec<Line> {
+        let mut highlighter = self.highlighter.language_highlighter(&self.language);
+        lines
+            .iter()
+            .map(|line| {
+                let mut text: String = line.0.iter().map(|text| text.content.as_str()).collect();
+                text.push('\n');
+                highlighter.highlight_line(&text, &self.style)
+            })
+            .collect()
     }
 }
 
@@ -79,7 +209,10 @@ impl AsRenderOperations for SnippetOutputOperation {
         }
 
         let mut operations = vec![];
-        let block_colors = self.style.style.colors;
+        let block_colors = match inner.process_status {
+            Some(ProcessStatus::Failure) => self.style.failure_style.colors,
+            _ => self.style.style.colors,
+        };
         if block_colors.background.is_some() {
             operations.push(RenderOperation::SetColors(block_colors));
         }
@@ -91,22 +224,41 @@ impl AsRenderOperations for SnippetOutputOperation {
                 Alignment::Center { minimum_margin, minimum_size } => !minimum_margin.is_empty() || minimum_size != &0,
             };
             let padding = self.style.padding;
-            let block_length =
-                if has_margin { self.block_length.max(inner.max_line_length) } else { inner.max_line_length };
-            let vertical_padding = iter::repeat_n(" ", padding.vertical as usize).map(Line::from);
-            let lines = vertical_padding.clone().chain(inner.output_lines.iter().cloned()).chain(vertical_padding);
+            let (output_lines, max_line_length) = match self.visible_rows(inner.output_lines.len()) {
+                Some(_) if self.overflow == SnippetOutputOverflow::Scroll => {
+                    (self.scrolled_lines(&inner), inner.max_line_length.saturating_add(SCROLLBAR_WIDTH))
+                }
+                Some(rows) => {
+                    // Only the lines being kept are taken into account when sizing the block.
+                    let lines = self.clipped_lines(&inner, rows);
+                    let max_line_length = lines.iter().map(Line::width).max().unwrap_or_default();
+                    (lines, u16::try_from(max_line_length).unwrap_or(u16::MAX))
+                }
+                None => (self.styled_lines(&inner), inner.max_line_length),
+            };
+            let block_length = if has_margin || self.match_code_width {
+                self.block_length.max(max_line_length)
+            } else {
+                max_line_length
+            };
+            let top_padding = iter::repeat_n(" ", padding.top as usize).map(Line::from);
+            let bottom_padding = iter::repeat_n(" ", padding.bottom as usize).map(Line::from);
+            let lines = top_padding.chain(output_lines).chain(bottom_padding);
             let style = TextStyle::default().size(self.font_size);
             for mut line in lines {
                 line.apply_style(&style);
-                let prefix = Text::new(" ".repeat(padding.horizontal as usize), style).into();
+                let prefix = Text::new(" ".repeat(padding.left as usize), style).into();
                 operations.push(RenderOperation::RenderBlockLine(BlockLine {
                     prefix,
-                    right_padding_length: padding.horizontal as u16,
+                    prefix_link: None,
+                    right_padding_length: padding.right as u16,
                     repeat_prefix_on_wrap: false,
                     text: line.into(),
                     block_length,
                     alignment: self.alignment,
                     block_color: block_colors.background,
+                    border: None,
+                    overflow: Default::default(),
                 }));
                 operations.push(RenderOperation::RenderLineBreak);
             }
@@ -114,11 +266,33 @@ impl AsRenderOperations for SnippetOutputOperation {
         operations.extend([RenderOperation::SetColors(self.default_colors)]);
         operations
     }
+
+    fn scroll(&self, direction: ScrollDirection) -> Option<bool> {
+        let mut inner = self.handle.0.lock().unwrap();
+        if let State::Initial = inner.state {
+            return None;
+        }
+        let Some(rows) = self.visible_rows(inner.output_lines.len()) else {
+            return Some(false);
+        };
+        if self.overflow != SnippetOutputOverflow::Scroll {
+            return Some(false);
+        }
+        let max_offset = inner.output_lines.len() - rows;
+        let offset = inner.scroll_offset.min(max_offset);
+        let new_offset = match direction {
+            ScrollDirection::Up => offset.saturating_sub(1),
+            ScrollDirection::Down => (offset + 1).min(max_offset),
+        };
+        inner.scroll_offset = new_offset;
+        Some(new_offset != offset)
+    }
 }
 
 struct OperationPollable {
     inner: Arc<Mutex<Inner>>,
     last_length: usize,
+    generation: usize,
 }
 
 impl OperationPollable {
@@ -127,7 +301,16 @@ impl OperationPollable {
         if !matches!(inner.state, State::Initial) {
             return;
         }
-        inner.state = match inner.executor.execute_async(&inner.snippet) {
+        let stdin = match inner.pipe_source.as_ref().map(SnippetHandle::piped_output).transpose() {
+            Ok(stdin) => stdin,
+            Err(e) => {
+                inner.output_lines = vec![e.to_string().into()];
+                inner.process_status = Some(ProcessStatus::Failure);
+                inner.state = State::Done;
+                return;
+            }
+        };
+        inner.state = match inner.executor.execute_async_with_stdin(&inner.snippet, stdin) {
             Ok(handle) => State::Running(handle),
             Err(e) => {
                 inner.output_lines = vec![e.to_string().into()];
@@ -140,6 +323,10 @@ impl OperationPollable {
 impl Pollable for OperationPollable {
     fn poll(&mut self) -> PollableState {
         let mut inner = self.inner.lock().unwrap();
+        // The operation was reset after this pollable was created.
+        if inner.generation != self.generation {
+            return PollableState::Done;
+        }
         self.try_start(&mut inner);
 
         // At this point if we don't have a handle it's because we're done.
@@ -149,13 +336,19 @@ impl Pollable for OperationPollable {
 
         // Pull data out of the process' output and drop the handle state.
         let mut state = handle.state.lock().unwrap();
-        let ExecutionState { output, status } = &mut *state;
+        let ExecutionState { output, status, stderr_lines, .. } = &mut *state;
         let status = status.clone();
+        let stderr_lines = stderr_lines.clone();
 
         let modified = output.len() != self.last_length;
         let mut lines = Vec::new();
-        for line in output.lines() {
-            let mut line = line.expect("invalid utf8");
+        let mut stdout = Vec::new();
+        for (index, line) in String::from_utf8_lossy(output).lines().enumerate() {
+            if !stderr_lines.contains(&index) {
+                stdout.extend(line.as_bytes());
+                stdout.push(b'\n');
+            }
+            let mut line = line.to_string();
             if line.contains('\t') {
                 line = line.replace('\t', "    ");
             }
@@ -164,7 +357,8 @@ impl Pollable for OperationPollable {
         drop(state);
 
         let mut max_line_length = 0;
-        let (lines, _) = AnsiParser::new(Default::default()).parse_lines(&lines);
+        // Cursor movements can move stderr lines around so use the rows they end up in.
+        let (lines, _, stderr_lines) = AnsiParser::new(Default::default()).parse_tagged_lines(&lines, &stderr_lines);
         for line in &lines {
             let width = u16::try_from(line.width()).unwrap_or(u16::MAX);
             max_line_length = max_line_length.max(width);
@@ -173,7 +367,9 @@ impl Pollable for OperationPollable {
         let is_finished = status.is_finished();
         inner.process_status = Some(status);
         inner.output_lines = lines;
+        inner.stderr_lines = stderr_lines;
         inner.max_line_length = inner.max_line_length.max(max_line_length);
+        inner.stdout = stdout;
         if is_finished {
             inner.state = State::Done;
             PollableState::Done
@@ -196,9 +392,14 @@ impl SnippetHandle {
             executor,
             process_status: Default::default(),
             output_lines: Default::default(),
+            stderr_lines: Default::default(),
             max_line_length: Default::default(),
             state: Default::default(),
             policy,
+            scroll_offset: 0,
+            generation: 0,
+            pipe_source: None,
+            stdout: Vec::new(),
         };
         Self(Arc::new(Mutex::new(inner)))
     }
@@ -210,6 +411,33 @@ impl SnippetHandle {
     pub(crate) fn snippet(&self) -> Snippet {
         self.0.lock().unwrap().snippet.clone()
     }
+
+    /// Get the lines this snippet has written so far.
+    pub(crate) fn output_lines(&self) -> Vec<Line> {
+        self.0.lock().unwrap().output_lines.clone()
+    }
+
+    /// Feed the output of the given snippet into this one's standard input when it runs.
+    pub(crate) fn pipe_from(&self, source: SnippetHandle) {
+        self.0.lock().unwrap().pipe_source = Some(source);
+    }
+
+    /// Get the standard output of this snippet, as long as it ran successfully.
+    fn piped_output(&self) -> Result<Vec<u8>, PipeSourceError> {
+        let inner = self.0.lock().unwrap();
+        let id = inner.snippet.attributes.id.clone().unwrap_or_default();
+        match (&inner.state, &inner.process_status) {
+            (State::Done, Some(ProcessStatus::Success)) => Ok(inner.stdout.clone()),
+            (State::Done, Some(ProcessStatus::Failure)) => Err(PipeSourceError::Failed(id)),
+            _ => Err(PipeSourceError::NotRun(id)),
+        }
+    }
+
+    /// Whether this handle runs the given snippet using the given start policy.
+    pub(crate) fn matches(&self, snippet: &Snippet, policy: RenderAsyncStartPolicy) -> bool {
+        let inner = self.0.lock().unwrap();
+        &inner.snippet == snippet && inner.policy == policy
+    }
 }
 
 #[derive(Debug)]
@@ -229,12 +457,48 @@ impl AsRenderOperations for RunSnippetTrigger {
 
 impl RenderAsync for RunSnippetTrigger {
     fn pollable(&self) -> Box<dyn Pollable> {
-        Box::new(OperationPollable { inner: self.0.clone(), last_length: 0 })
+        let generation = self.0.lock().unwrap().generation;
+        Box::new(OperationPollable { inner: self.0.clone(), last_length: 0, generation })
     }
 
     fn start_policy(&self) -> RenderAsyncStartPolicy {
         self.0.lock().unwrap().policy
     }
+
+    fn reset(&self) -> bool {
+        let mut inner = self.0.lock().unwrap();
+        if matches!(inner.state, State::Initial) {
+            return false;
+        }
+        // Don't leave the previous run behind if it's still going.
+        if let State::Running(handle) = &inner.state {
+            handle.kill();
+        }
+        // Any pollable created before this point belongs to the previous run.
+        inner.generation += 1;
+        inner.state = State::Initial;
+        inner.output_lines.clear();
+        inner.stderr_lines.clear();
+        inner.max_line_length = 0;
+        inner.process_status = None;
+        inner.scroll_offset = 0;
+        inner.stdout.clear();
+        true
+    }
+
+    fn reset_on_enter(&self) -> bool {
+        self.0.lock().unwrap().snippet.attributes.output_policy == Some(SnippetOutputPolicy::Clear)
+    }
+}
+
+/// An error when feeding the output of a snippet into another one.
+#[derive(Debug, thiserror::Error)]
+enum PipeSourceError {
+    #[error("snippet '{0}' needs to be run first")]
+    NotRun(String),
+
+    #[error("snippet '{0}' failed")]
+    Failed(String),
 }
 
 #[derive(Debug)]
@@ -243,6 +507,7 @@ pub(crate) struct ExecIndicatorStyle {
     pub(crate) block_length: u16,
     pub(crate) font_size: u8,
     pub(crate) alignment: Alignment,
+    pub(crate) compact: bool,
 }
 
 #[derive(Debug)]
@@ -251,11 +516,12 @@ pub(crate) struct ExecIndicator {
     separator_width: SeparatorWidth,
     theme: ExecutionStatusBlockStyle,
     font_size: u8,
+    compact: bool,
 }
 
 impl ExecIndicator {
     pub(crate) fn new(handle: SnippetHandle, style: ExecIndicatorStyle) -> Self {
-        let ExecIndicatorStyle { theme, block_length, font_size, alignment } = style;
+        let ExecIndicatorStyle { theme, block_length, font_size, alignment, compact } = style;
         let block_length = alignment.adjust_size(block_length);
         let separator_width = match &alignment {
             Alignment::Left { .. } | Alignment::Right { .. } => SeparatorWidth::FitToWindow,
@@ -265,7 +531,7 @@ impl ExecIndicator {
                 SeparatorWidth::Fixed(block_length.max(MINIMUM_SEPARATOR_WIDTH * font_size as u16))
             }
         };
-        Self { handle, separator_width, theme, font_size }
+        Self { handle, separator_width, theme, font_size, compact }
     }
 }
 
@@ -274,19 +540,19 @@ impl AsRenderOperations for ExecIndicator {
         let inner = self.handle.0.lock().unwrap();
         let status = &inner.process_status;
         let description = match status {
-            Some(ProcessStatus::Running) => Text::new("running", self.theme.running_style),
-            Some(ProcessStatus::Success) => Text::new("finished", self.theme.success_style),
-            Some(ProcessStatus::Failure) => Text::new("finished with error", self.theme.failure_style),
-            None => Text::new("not started", self.theme.not_started_style),
+            Some(ProcessStatus::Running) => Text::new(&self.theme.labels.running, self.theme.running_style),
+            Some(ProcessStatus::Success) => Text::new(&self.theme.labels.success, self.theme.success_style),
+            Some(ProcessStatus::Failure) => Text::new(&self.theme.labels.failure, self.theme.failure_style),
+            None => Text::new(&self.theme.labels.not_started, self.theme.not_started_style),
         };
 
         let heading = Line(vec![" [".into(), description.clone(), "] ".into()]);
         let separator = RenderSeparator::new(heading, self.separator_width, self.font_size);
-        vec![
-            RenderOperation::RenderLineBreak,
-            RenderOperation::RenderDynamic(Rc::new(separator)),
-            RenderOperation::RenderLineBreak,
-        ]
+        let separator = [RenderOperation::RenderDynamic(Rc::new(separator)), RenderOperation::RenderLineBreak];
+        match self.compact {
+            true => separator.into(),
+            false => iter::once(RenderOperation::RenderLineBreak).chain(separator).collect(),
+        }
     }
 }
 
@@ -303,6 +569,7 @@ mod tests {
             text_style::Color,
         },
     };
+    use rstest::rstest;
 
     fn make_run_shell(code: &str) -> RunSnippetTrigger {
         let snippet = Snippet {
@@ -329,6 +596,19 @@ mod tests {
         assert_eq!(inner.output_lines, vec![line]);
     }
 
+    #[test]
+    fn stderr_with_cursor_movement() {
+        let trigger = make_run_shell("echo out; sleep 0.2; printf '\\033[1A\\033[2Kerr\\n' >&2; sleep 0.2; echo last");
+        trigger.0.lock().unwrap().snippet.attributes.split_stderr = true;
+        let mut pollable = trigger.pollable();
+        while let PollableState::Modified | PollableState::Unmodified = pollable.poll() {}
+
+        // The stderr line moved up and replaced the first line so that's the one that's tagged.
+        let inner = trigger.0.lock().unwrap();
+        assert_eq!(inner.output_lines, vec![Line::from("err"), Line::from("last")]);
+        assert_eq!(inner.stderr_lines, BTreeSet::from([0]));
+    }
+
     #[test]
     fn multiple_pollables() {
         let handle = make_run_shell("echo -e '\\033[1;31mhi mom'");
@@ -344,4 +624,176 @@ mod tests {
         let mut pollable3 = handle.pollable();
         assert_eq!(pollable3.poll(), PollableState::Done);
     }
+
+    #[test]
+    fn pipe_from_not_run() {
+        let source = make_run_shell("echo hi");
+        source.0.lock().unwrap().snippet.attributes.id = Some("foo".into());
+        let trigger = make_run_shell("cat");
+        SnippetHandle(trigger.0.clone()).pipe_from(SnippetHandle(source.0.clone()));
+
+        let mut pollable = trigger.pollable();
+        assert_eq!(pollable.poll(), PollableState::Done);
+        let inner = trigger.0.lock().unwrap();
+        assert_eq!(inner.output_lines, vec![Line::from("snippet 'foo' needs to be run first")]);
+    }
+
+    #[test]
+    fn scroll_output() {
+        let trigger = make_run_shell("seq 1 5");
+        let operation = SnippetOutputOperation::new(
+            SnippetHandle(trigger.0.clone()),
+            Default::default(),
+            Default::default(),
+            0,
+            Default::default(),
+            1,
+            Some(2),
+        );
+        // Nothing to scroll until the snippet runs.
+        assert_eq!(operation.scroll(ScrollDirection::Down), None);
+
+        let mut pollable = trigger.pollable();
+        while let PollableState::Modified | PollableState::Unmodified = pollable.poll() {}
+
+        let visible_lines = || -> Vec<String> {
+            let inner = trigger.0.lock().unwrap();
+            operation
+                .scrolled_lines(&inner)
+                .into_iter()
+                .map(|line| line.0.into_iter().map(|text| text.content).collect())
+                .collect()
+        };
+        assert_eq!(visible_lines(), &["1 █", "2 │"]);
+
+        for _ in 0..3 {
+            assert_eq!(operation.scroll(ScrollDirection::Down), Some(true));
+        }
+        assert_eq!(operation.scroll(ScrollDirection::Down), Some(false));
+        assert_eq!(visible_lines(), &["4 │", "5 █"]);
+
+        assert_eq!(operation.scroll(ScrollDirection::Up), Some(true));
+        assert_eq!(visible_lines(), &["3 █", "4 │"]);
+    }
+
+    #[test]
+    fn output_fits() {
+        let trigger = make_run_shell("seq 1 2");
+        let operation = SnippetOutputOperation::new(
+            SnippetHandle(trigger.0.clone()),
+            Default::default(),
+            Default::default(),
+            0,
+            Default::default(),
+            1,
+            Some(2),
+        );
+        let mut pollable = trigger.pollable();
+        while let PollableState::Modified | PollableState::Unmodified = pollable.poll() {}
+
+        assert_eq!(operation.scroll(ScrollDirection::Down), Some(false));
+        let inner = trigger.0.lock().unwrap();
+        assert_eq!(operation.scrolled_lines(&inner), vec![Line::from("1"), Line::from("2")]);
+    }
+
+    #[rstest]
+    #[case::tail(SnippetOutputOverflow::Tail, 3, &["4", "5", "6"])]
+    #[case::head(SnippetOutputOverflow::Head, 3, &["1", "2", "3"])]
+    #[case::head_tail(SnippetOutputOverflow::HeadTail, 3, &["1", "… (4 lines omitted) …", "6"])]
+    #[case::head_tail_uneven(SnippetOutputOverflow::HeadTail, 4, &["1", "2", "… (3 lines omitted) …", "6"])]
+    fn clip_output(#[case] overflow: SnippetOutputOverflow, #[case] rows: u16, #[case] expected: &[&str]) {
+        let trigger = make_run_shell("seq 1 6");
+        let operation = SnippetOutputOperation::new(
+            SnippetHandle(trigger.0.clone()),
+            Default::default(),
+            Default::default(),
+            0,
+            Default::default(),
+            1,
+            Some(rows),
+        )
+        .overflow(overflow);
+        let mut pollable = trigger.pollable();
+        while let PollableState::Modified | PollableState::Unmodified = pollable.poll() {}
+
+        assert_eq!(operation.scroll(ScrollDirection::Down), Some(false));
+        let inner = trigger.0.lock().unwrap();
+        let lines: Vec<String> = operation
+            .clipped_lines(&inner, rows.into())
+            .into_iter()
+            .map(|line| line.0.into_iter().map(|text| text.content).collect())
+            .collect();
+        assert_eq!(lines, expected);
+    }
+
+    fn run(pollable: &mut Box<dyn Pollable>) {
+        while !matches!(pollable.poll(), PollableState::Done) {}
+    }
+
+    #[test]
+    fn reset() {
+        let trigger = make_run_shell("echo hi");
+        assert!(!trigger.reset());
+
+        let mut pollable = trigger.pollable();
+        run(&mut pollable);
+        assert_eq!(trigger.0.lock().unwrap().output_lines, &[Line::from("hi")]);
+
+        assert!(trigger.reset());
+        let inner = trigger.0.lock().unwrap();
+        assert!(matches!(inner.state, State::Initial));
+        assert!(inner.output_lines.is_empty());
+        assert!(inner.process_status.is_none());
+    }
+
+    #[test]
+    fn reset_kills_running() {
+        let trigger = make_run_shell("sleep 30 &\necho $!\nwait");
+        let mut pollable = trigger.pollable();
+        while trigger.0.lock().unwrap().output_lines.is_empty() {
+            pollable.poll();
+        }
+        let pid = trigger.0.lock().unwrap().output_lines[0].0[0].content.clone();
+        assert!(trigger.reset());
+
+        // The process may take a moment to die and be left as a zombie if nothing reaps it.
+        let is_dead = || {
+            let stat = std::fs::read_to_string(format!("/proc/{pid}/stat")).unwrap_or_default();
+            let state = stat.rsplit(')').next().unwrap_or_default().split_whitespace().next();
+            matches!(state, None | Some("Z"))
+        };
+        let dead = (0..100).any(|_| {
+            if is_dead() {
+                return true;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            false
+        });
+        assert!(dead, "process still running");
+    }
+
+    #[test]
+    fn reset_on_enter() {
+        let trigger = make_run_shell("echo hi");
+        assert!(!trigger.reset_on_enter());
+
+        trigger.0.lock().unwrap().snippet.attributes.output_policy = Some(SnippetOutputPolicy::Clear);
+        assert!(trigger.reset_on_enter());
+    }
+
+    #[test]
+    fn stale_pollable() {
+        let trigger = make_run_shell("echo hi");
+        let mut stale = trigger.pollable();
+        run(&mut stale);
+        trigger.reset();
+
+        // the old pollable must not start the snippet again
+        assert!(matches!(stale.poll(), PollableState::Done));
+        assert!(matches!(trigger.0.lock().unwrap().state, State::Initial));
+
+        let mut pollable = trigger.pollable();
+        run(&mut pollable);
+        assert_eq!(trigger.0.lock().unwrap().output_lines, &[Line::from("hi")]);
+    }
 }
@@ -0,0 +1,76 @@
+use crate::{
+    render::{
+        operation::{AsRenderOperations, ImagePosition, ImageRenderProperties, RenderOperation},
+        properties::WindowSize,
+    },
+    terminal::image::Image,
+};
+
+/// The minimum number of columns needed to display the image next to the text.
+const MINIMUM_COLUMNS: u16 = 60;
+
+/// The width of the text and the image, in column units.
+const COLUMN_WIDTHS: [u8; 2] = [2, 1];
+
+/// An image displayed to the right of a block of text.
+///
+/// If the screen is too narrow to fit both next to each other, the image is displayed above the
+/// text instead.
+#[derive(Debug)]
+pub(crate) struct FloatingImage {
+    image: Image,
+    properties: ImageRenderProperties,
+    text: Vec<RenderOperation>,
+}
+
+impl FloatingImage {
+    pub(crate) fn new(image: Image, properties: ImageRenderProperties, text: Vec<RenderOperation>) -> Self {
+        Self { image, properties, text }
+    }
+}
+
+impl AsRenderOperations for FloatingImage {
+    fn as_render_operations(&self, dimensions: &WindowSize) -> Vec<RenderOperation> {
+        if dimensions.columns < MINIMUM_COLUMNS {
+            let mut operations = vec![
+                RenderOperation::RenderImage(self.image.clone(), self.properties.clone()),
+                RenderOperation::RenderLineBreak,
+            ];
+            operations.extend(self.text.iter().cloned());
+            return operations;
+        }
+        let properties = ImageRenderProperties { position: ImagePosition::Right, ..self.properties.clone() };
+        let mut operations = vec![
+            RenderOperation::InitColumnLayout { columns: COLUMN_WIDTHS.to_vec() },
+            RenderOperation::EnterColumn { column: 1 },
+            RenderOperation::RenderImage(self.image.clone(), properties),
+            RenderOperation::EnterColumn { column: 0 },
+        ];
+        operations.extend(self.text.iter().cloned());
+        operations.push(RenderOperation::ExitLayout);
+        operations
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::terminal::image::{ImageSource, printer::TerminalImage};
+    use image::{ColorType, DynamicImage};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::side_by_side(MINIMUM_COLUMNS, true)]
+    #[case::stacked(MINIMUM_COLUMNS - 1, false)]
+    fn layout(#[case] columns: u16, #[case] side_by_side: bool) {
+        let image = DynamicImage::new(1, 1, ColorType::Rgba8);
+        let image = Image::new(TerminalImage::Ascii(image.into()), ImageSource::Generated);
+        let text = vec![RenderOperation::RenderLineBreak];
+        let floating = FloatingImage::new(image, Default::default(), text);
+        let dimensions = WindowSize { rows: 10, columns, width: 0, height: 0 };
+        let operations = floating.as_render_operations(&dimensions);
+        let has_layout =
+            operations.iter().any(|operation| matches!(operation, RenderOperation::InitColumnLayout { .. }));
+        assert_eq!(has_layout, side_by_side);
+    }
+}
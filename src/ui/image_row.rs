@@ -0,0 +1,124 @@
+use crate::{
+    markdown::text_style::Color,
+    render::{
+        operation::{AsRenderOperations, ImagePosition, ImageRenderProperties, ImageSize, RenderOperation},
+        properties::WindowSize,
+    },
+    terminal::image::{Image, printer::ImageProperties, scale::TerminalRect},
+};
+
+/// The number of columns between two consecutive images in a row.
+const IMAGE_SPACING: u16 = 2;
+
+/// A row of images displayed next to each other.
+///
+/// Images are top aligned and they're all scaled down by the same ratio if they don't fit in the
+/// available space.
+#[derive(Debug)]
+pub(crate) struct ImageRow {
+    images: Vec<Image>,
+    background_color: Option<Color>,
+}
+
+impl ImageRow {
+    pub(crate) fn new(images: Vec<Image>, background_color: Option<Color>) -> Self {
+        Self { images, background_color }
+    }
+
+    fn layout(&self, dimensions: &WindowSize) -> Vec<(u16, TerminalRect)> {
+        let spacing = IMAGE_SPACING * self.images.len().saturating_sub(1) as u16;
+        let available_columns = dimensions.columns.saturating_sub(spacing) as f64;
+
+        // Compute how large every image would naturally be and scale them all by the same ratio so
+        // they fit both horizontally and vertically.
+        let mut natural_sizes = Vec::new();
+        for image in &self.images {
+            let (width, height) = image.image().dimensions();
+            let columns = width as f64 / dimensions.pixels_per_column();
+            let rows = columns * (height as f64 / width as f64) * dimensions.aspect_ratio();
+            natural_sizes.push((columns, rows));
+        }
+        let total_columns: f64 = natural_sizes.iter().map(|(columns, _)| columns).sum();
+        let max_rows = natural_sizes.iter().map(|(_, rows)| *rows).fold(0.0, f64::max);
+        let ratio = (available_columns / total_columns).min(dimensions.rows as f64 / max_rows).min(1.0);
+
+        let rects: Vec<_> = natural_sizes
+            .into_iter()
+            .map(|(columns, rows)| TerminalRect {
+                columns: ((columns * ratio).floor() as u16).max(1),
+                rows: ((rows * ratio).round() as u16).max(1),
+            })
+            .collect();
+        let total_width = rects.iter().map(|rect| rect.columns).sum::<u16>() + spacing;
+        let mut column = dimensions.columns.saturating_sub(total_width) / 2;
+        let mut output = Vec::new();
+        for rect in rects {
+            let columns = rect.columns;
+            output.push((column, rect));
+            column += columns + IMAGE_SPACING;
+        }
+        output
+    }
+}
+
+impl AsRenderOperations for ImageRow {
+    fn as_render_operations(&self, dimensions: &WindowSize) -> Vec<RenderOperation> {
+        let mut operations = Vec::new();
+        let mut max_rows = 0;
+        for (image, (column, rect)) in self.images.iter().zip(self.layout(dimensions)) {
+            max_rows = max_rows.max(rect.rows);
+            let properties = ImageRenderProperties {
+                size: ImageSize::Specific(rect.columns, rect.rows),
+                restore_cursor: true,
+                background_color: self.background_color,
+                position: ImagePosition::Column(column),
+                ..Default::default()
+            };
+            operations.push(RenderOperation::RenderImage(image.clone(), properties));
+        }
+        operations.extend((0..max_rows).map(|_| RenderOperation::RenderLineBreak));
+        operations
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::terminal::image::{ImageSource, printer::TerminalImage};
+    use image::{ColorType, DynamicImage};
+
+    fn make_image(width: u32, height: u32) -> Image {
+        let image = DynamicImage::new(width, height, ColorType::Rgba8);
+        Image::new(TerminalImage::Ascii(image.into()), ImageSource::Generated)
+    }
+
+    fn layout(images: Vec<Image>, dimensions: WindowSize) -> Vec<(u16, u16, u16)> {
+        let row = ImageRow::new(images, None);
+        row.layout(&dimensions).into_iter().map(|(column, rect)| (column, rect.columns, rect.rows)).collect()
+    }
+
+    #[test]
+    fn fits() {
+        // 1 pixel per column/row
+        let dimensions = WindowSize { rows: 20, columns: 40, width: 40, height: 20 };
+        let sizes = layout(vec![make_image(10, 4), make_image(6, 6)], dimensions);
+        // 10 + 2 + 6 = 18 columns, centered
+        assert_eq!(sizes, &[(11, 10, 4), (23, 6, 6)]);
+    }
+
+    #[test]
+    fn shrink_to_width() {
+        let dimensions = WindowSize { rows: 20, columns: 22, width: 22, height: 20 };
+        let sizes = layout(vec![make_image(20, 10), make_image(20, 4)], dimensions);
+        // 20 columns available, so both are scaled by half
+        assert_eq!(sizes, &[(0, 10, 5), (12, 10, 2)]);
+    }
+
+    #[test]
+    fn shrink_to_height() {
+        let dimensions = WindowSize { rows: 5, columns: 40, width: 40, height: 5 };
+        let sizes = layout(vec![make_image(10, 10), make_image(4, 2)], dimensions);
+        // the tallest one is 10 rows so both are scaled by half
+        assert_eq!(sizes, &[(15, 5, 5), (22, 2, 1)]);
+    }
+}
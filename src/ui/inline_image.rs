@@ -0,0 +1,187 @@
+use crate::{
+    markdown::{elements::Line, text_style::Color},
+    render::{
+        layout::Layout,
+        operation::{AsRenderOperations, ImagePosition, ImageRenderProperties, ImageSize, RenderOperation},
+        properties::WindowSize,
+    },
+    terminal::image::Image,
+    theme::Alignment,
+};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// An image embedded within a line of text.
+#[derive(Clone, Debug)]
+pub(crate) struct EmbeddedImage {
+    pub(crate) image: Image,
+
+    /// The column within the line where the image starts.
+    pub(crate) column: u16,
+
+    /// The number of columns reserved for the image.
+    pub(crate) columns: u16,
+
+    /// The text displayed in place of the image when it can't be displayed, e.g. its path.
+    pub(crate) label: String,
+}
+
+impl EmbeddedImage {
+    /// Replace the space reserved for every image in a line with their labels.
+    ///
+    /// Labels are cut short or padded so they take up exactly the space reserved for the image.
+    pub(crate) fn label_line(line: &Line, images: &[EmbeddedImage]) -> Line {
+        let mut line = line.clone();
+        let mut column = 0;
+        for text in &mut line.0 {
+            let width = text.width();
+            let image = images.iter().find(|image| image.column as usize == column && image.columns as usize == width);
+            if let Some(image) = image {
+                if text.content.trim().is_empty() {
+                    text.content = Self::fit_label(&image.label, width);
+                }
+            }
+            column += width;
+        }
+        line
+    }
+
+    fn fit_label(label: &str, width: usize) -> String {
+        let mut output = String::new();
+        if label.width() <= width {
+            output.push_str(label);
+        } else {
+            // Leave room for the ellipsis.
+            for c in label.chars() {
+                if output.width() + c.width().unwrap_or(0) >= width {
+                    break;
+                }
+                output.push(c);
+            }
+            output.push('…');
+        }
+        let padding = width.saturating_sub(output.width());
+        output.extend(std::iter::repeat_n(' ', padding));
+        output
+    }
+}
+
+/// A line of text that has images embedded in it.
+///
+/// The line contains blank space where every image goes and images are drawn on top of it, one
+/// row tall. If the line doesn't fit in a single row, the fallback line is displayed instead.
+#[derive(Debug)]
+pub(crate) struct InlineImageLine {
+    line: RenderOperation,
+    fallback: RenderOperation,
+    images: Vec<EmbeddedImage>,
+    alignment: Alignment,
+    layout_length: u16,
+    font_size: u8,
+    background_color: Option<Color>,
+}
+
+impl InlineImageLine {
+    pub(crate) fn new(
+        line: RenderOperation,
+        fallback: RenderOperation,
+        images: Vec<EmbeddedImage>,
+        alignment: Alignment,
+        layout_length: u16,
+        font_size: u8,
+        background_color: Option<Color>,
+    ) -> Self {
+        Self { line, fallback, images, alignment, layout_length, font_size, background_color }
+    }
+}
+
+impl AsRenderOperations for InlineImageLine {
+    fn as_render_operations(&self, dimensions: &WindowSize) -> Vec<RenderOperation> {
+        let font_size = self.font_size as u16;
+        let positioning =
+            Layout::new(self.alignment).with_font_size(self.font_size).compute(dimensions, self.layout_length);
+        // If the line gets wrapped there's no telling where the images should go so skip them.
+        if self.layout_length * font_size > positioning.max_line_length {
+            return vec![self.fallback.clone()];
+        }
+        let mut operations = vec![self.line.clone()];
+        for image in &self.images {
+            let properties = ImageRenderProperties {
+                size: ImageSize::Specific(image.columns * font_size, font_size),
+                restore_cursor: true,
+                background_color: self.background_color,
+                position: ImagePosition::Column(positioning.start_column + image.column * font_size),
+                ..Default::default()
+            };
+            operations.push(RenderOperation::RenderImage(image.image.clone(), properties));
+        }
+        operations
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::markdown::elements::Text;
+    use crate::{
+        markdown::text::WeightedLine,
+        terminal::image::{ImageSource, printer::TerminalImage},
+    };
+    use image::{ColorType, DynamicImage};
+    use rstest::rstest;
+
+    fn make_line(alignment: Alignment, layout_length: u16) -> InlineImageLine {
+        let image = DynamicImage::new(2, 1, ColorType::Rgba8);
+        let image = Image::new(TerminalImage::Ascii(image.into()), ImageSource::Generated);
+        let line = RenderOperation::RenderText { line: WeightedLine::from("a    b"), alignment };
+        let fallback = RenderOperation::RenderText { line: WeightedLine::from("a a.png b"), alignment };
+        let images = vec![EmbeddedImage { image, column: 2, columns: 4, label: "a.png".into() }];
+        InlineImageLine::new(line, fallback, images, alignment, layout_length, 1, None)
+    }
+
+    fn image_columns(line: &InlineImageLine, dimensions: &WindowSize) -> Vec<u16> {
+        line.as_render_operations(dimensions)
+            .into_iter()
+            .filter_map(|operation| match operation {
+                RenderOperation::RenderImage(_, properties) => match properties.position {
+                    ImagePosition::Column(column) => Some(column),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn positioned_within_line() {
+        let dimensions = WindowSize { rows: 10, columns: 20, width: 20, height: 10 };
+        let alignment = Alignment::Center { minimum_margin: Default::default(), minimum_size: 0 };
+        let line = make_line(alignment, 6);
+        // the line starts at column 7 so the image starts 2 columns after that
+        assert_eq!(image_columns(&line, &dimensions), &[9]);
+    }
+
+    #[test]
+    fn skipped_when_wrapped() {
+        let dimensions = WindowSize { rows: 10, columns: 4, width: 4, height: 10 };
+        let alignment = Alignment::Left { margin: Default::default() };
+        let line = make_line(alignment, 6);
+        let operations = line.as_render_operations(&dimensions);
+        let [RenderOperation::RenderText { line, .. }] = operations.as_slice() else {
+            panic!("unexpected operations: {operations:?}")
+        };
+        assert_eq!(line, &WeightedLine::from("a a.png b"));
+    }
+
+    #[rstest]
+    #[case::fits("a.png", "a.png ")]
+    #[case::exact("ab.png", "ab.png")]
+    #[case::too_long("logo.png", "logo.…")]
+    fn label_line(#[case] label: &str, #[case] expected: &str) {
+        let image = DynamicImage::new(1, 1, ColorType::Rgba8);
+        let image = Image::new(TerminalImage::Ascii(image.into()), ImageSource::Generated);
+        let images = vec![EmbeddedImage { image, column: 2, columns: 6, label: label.into() }];
+        let line = Line(vec![Text::from("a "), Text::from("      "), Text::from(" b")]);
+        let line = EmbeddedImage::label_line(&line, &images);
+        assert_eq!(line, Line(vec![Text::from("a "), Text::from(expected), Text::from(" b")]));
+    }
+}
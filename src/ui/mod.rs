@@ -1,4 +1,17 @@
+pub(crate) mod big_text;
+pub(crate) mod clock;
+pub(crate) mod countdown;
 pub(crate) mod execution;
+pub(crate) mod floating_image;
 pub(crate) mod footer;
+pub(crate) mod image_row;
+pub(crate) mod inline_image;
 pub(crate) mod modals;
+pub(crate) mod presenter_view;
+pub(crate) mod prose;
+pub(crate) mod quiet_zone;
+pub(crate) mod scroll;
 pub(crate) mod separator;
+pub(crate) mod status;
+pub(crate) mod thumbnails;
+pub(crate) mod too_small;
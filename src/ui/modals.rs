@@ -40,6 +40,8 @@ impl IndexBuilder {
     pub(crate) fn build(self, theme: &PresentationTheme, state: PresentationState) -> Vec<RenderOperation> {
         let mut builder = ModalBuilder::new("Slides");
         let padder = NumberPadder::new(self.titles.len());
+        let titles =
+            self.titles.iter().map(|title| title.0.iter().map(|text| text.content.as_str()).collect()).collect();
         for (index, mut title) in self.titles.into_iter().enumerate() {
             let index = padder.pad_right(index + 1);
             title.0.insert(0, format!("{index}: ").into());
@@ -51,6 +53,7 @@ impl IndexBuilder {
         let drawer = IndexDrawer {
             prefix,
             rows: content,
+            titles,
             suffix,
             state,
             content_width,
@@ -61,10 +64,16 @@ impl IndexBuilder {
     }
 }
 
+/// Check whether a slide title matches a filter typed in the slide index.
+pub(crate) fn title_matches(title: &str, filter: &str) -> bool {
+    title.to_lowercase().contains(&filter.to_lowercase())
+}
+
 #[derive(Debug)]
 struct IndexDrawer {
     prefix: Vec<RenderOperation>,
     rows: Vec<ContentRow>,
+    titles: Vec<String>,
     suffix: Vec<RenderOperation>,
     content_width: u16,
     state: PresentationState,
@@ -75,17 +84,28 @@ struct IndexDrawer {
 impl AsRenderOperations for IndexDrawer {
     fn as_render_operations(&self, dimensions: &WindowSize) -> Vec<RenderOperation> {
         let current_slide_index = self.state.current_slide_index();
-        let max_rows = (dimensions.rows as f64 * 0.8) as u16;
-        let (skip, take) = match self.rows.len() as u16 > max_rows {
+        let filter = self.state.slide_index_filter();
+        let rows: Vec<_> = self
+            .rows
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| filter.as_ref().is_none_or(|filter| title_matches(&self.titles[*index], filter)))
+            .collect();
+        // The line the filter is displayed in takes up one row.
+        let max_rows = ((dimensions.rows as f64 * 0.8) as u16).saturating_sub(filter.is_some() as u16).max(1);
+        let (skip, take) = match rows.len() as u16 > max_rows {
             true => {
-                let start = (current_slide_index as u16).saturating_sub(max_rows / 2);
-                let start = start.min(self.rows.len() as u16 - max_rows);
+                let position = rows.iter().position(|(index, _)| *index == current_slide_index).unwrap_or(0);
+                let start = (position as u16).saturating_sub(max_rows / 2);
+                let start = start.min(rows.len() as u16 - max_rows);
                 (start as usize, max_rows as usize)
             }
-            false => (0, self.rows.len()),
+            false => (0, rows.len()),
         };
-        let visible_rows = self.rows.iter().enumerate().skip(skip).take(take);
-        let mut operations = vec![CenterModalContent::new(self.content_width, take, self.background.clone()).into()];
+        let visible_rows = rows.into_iter().skip(skip).take(take);
+        let total_rows = take + filter.is_some() as usize;
+        let mut operations =
+            vec![CenterModalContent::new(self.content_width, total_rows, self.background.clone()).into()];
         operations.extend(self.prefix.iter().cloned());
         for (index, row) in visible_rows {
             let mut row = row.clone();
@@ -95,6 +115,11 @@ impl AsRenderOperations for IndexDrawer {
             let operation = RenderOperation::RenderText { line: row.build(), alignment: Default::default() };
             operations.extend([operation, RenderOperation::RenderLineBreak]);
         }
+        if let Some(filter) = filter {
+            let row = ModalBuilder::build_line(vec![Text::from(format!("/{filter}"))], self.content_width);
+            let operation = RenderOperation::RenderText { line: row.build(), alignment: Default::default() };
+            operations.extend([operation, RenderOperation::RenderLineBreak]);
+        }
         operations.extend(self.suffix.iter().cloned());
         operations
     }
@@ -120,10 +145,24 @@ impl KeyBindingsModalBuilder {
             Self::build_line("First slide", &config.first_slide),
             Self::build_line("Last slide", &config.last_slide),
             Self::build_line("Go to slide", &config.go_to_slide),
+            Self::build_line("Jump back", &config.jump_back),
+            Self::build_line("Jump forward", &config.jump_forward),
             Self::build_line("Execute code", &config.execute_code),
             Self::build_line("Reload", &config.reload),
             Self::build_line("Toggle slide index", &config.toggle_slide_index),
+            Self::build_line("Filter slide index", &config.filter_slide_index),
             Self::build_line("Close modal", &config.close_modal),
+            Self::build_line("Scroll output up", &config.scroll_output_up),
+            Self::build_line("Scroll output down", &config.scroll_output_down),
+            Self::build_line("Snapshot slide", &config.snapshot_slide),
+            Self::build_line("Reset executed code", &config.reset_async_operations),
+            Self::build_line("List persistent processes", &config.list_persistent_processes),
+            Self::build_line("Kill persistent processes", &config.kill_persistent_processes),
+            Self::build_line("Reload theme", &config.reload_theme),
+            Self::build_line("Toggle line numbers", &config.toggle_line_numbers),
+            Self::build_line("Toggle clock", &config.toggle_clock),
+            Self::build_line("Toggle speaker notes", &config.toggle_notes),
+            Self::build_line("Copy snippet", &config.copy_snippet),
             Self::build_line("Exit", &config.exit),
         ]);
         let lines = builder.content.len();
@@ -147,6 +186,53 @@ impl KeyBindingsModalBuilder {
     }
 }
 
+#[derive(Default)]
+pub(crate) struct NotesModalBuilder {
+    notes: Vec<Vec<String>>,
+    background: Option<Image>,
+}
+
+impl NotesModalBuilder {
+    pub(crate) fn add_notes(&mut self, notes: Vec<String>) {
+        self.notes.push(notes);
+    }
+
+    pub(crate) fn set_background(&mut self, background: Image) {
+        self.background = Some(background);
+    }
+
+    pub(crate) fn build(self, theme: &PresentationTheme, state: PresentationState) -> Vec<RenderOperation> {
+        let mut slides = Vec::new();
+        for notes in self.notes {
+            let mut builder = ModalBuilder::new("Speaker notes");
+            builder.content.extend(notes.iter().flat_map(|note| note.lines()).map(Line::from));
+            if builder.content.is_empty() {
+                builder.content.push(Text::new("no speaker notes", TextStyle::default().italics()).into());
+            }
+            let lines = builder.content.len();
+            let content = builder.build(theme.modals.style);
+            let content_width = content.content_width;
+            let mut operations = content.into_operations();
+            operations.insert(0, CenterModalContent::new(content_width, lines, self.background.clone()).into());
+            slides.push(operations);
+        }
+        vec![RenderOperation::RenderDynamic(Rc::new(NotesDrawer { slides, state }))]
+    }
+}
+
+/// Draws the speaker notes for the current slide.
+#[derive(Debug)]
+struct NotesDrawer {
+    slides: Vec<Vec<RenderOperation>>,
+    state: PresentationState,
+}
+
+impl AsRenderOperations for NotesDrawer {
+    fn as_render_operations(&self, _: &WindowSize) -> Vec<RenderOperation> {
+        self.slides.get(self.state.current_slide_index()).cloned().unwrap_or_default()
+    }
+}
+
 struct ModalBuilder {
     heading: String,
     content: Vec<Line>,
@@ -310,6 +396,8 @@ impl AsRenderOperations for CenterModalContent {
                 restore_cursor: true,
                 background_color: None,
                 position: ImagePosition::Center,
+                shadow: None,
+                caption: None,
             };
             operations.push(RenderOperation::RenderImage(image.clone(), properties));
         }
@@ -322,3 +410,70 @@ impl From<CenterModalContent> for RenderOperation {
         Self::RenderDynamic(Rc::new(op))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::resource::Resources;
+    use rstest::rstest;
+
+    fn make_drawer(titles: &[&str], state: PresentationState) -> IndexDrawer {
+        let mut builder = ModalBuilder::new("Slides");
+        for title in titles {
+            builder.content.push(Line::from(*title));
+        }
+        let ModalContent { prefix, content, suffix, content_width } = builder.build(TextStyle::default());
+        IndexDrawer {
+            prefix,
+            rows: content,
+            titles: titles.iter().map(|title| title.to_string()).collect(),
+            suffix,
+            content_width,
+            state,
+            selection_style: TextStyle::default(),
+            background: None,
+        }
+    }
+
+    fn count_texts(operations: &[RenderOperation]) -> usize {
+        operations.iter().filter(|op| matches!(op, RenderOperation::RenderText { .. })).count()
+    }
+
+    #[rstest]
+    #[case::substring("Introduction", "duct", true)]
+    #[case::case_insensitive("Introduction", "INTRO", true)]
+    #[case::empty("Introduction", "", true)]
+    #[case::no_match("Introduction", "outro", false)]
+    fn matching_titles(#[case] title: &str, #[case] filter: &str, #[case] expected: bool) {
+        assert_eq!(title_matches(title, filter), expected);
+    }
+
+    #[rstest]
+    // The heading, the borders and both lines.
+    #[case::notes(vec!["first\nsecond".into()], 6)]
+    // The placeholder is displayed instead.
+    #[case::no_notes(vec![], 5)]
+    fn notes_modal(#[case] notes: Vec<String>, #[case] expected: usize) {
+        let resources = Resources::new("/tmp/foo", "/tmp/foo", Default::default());
+        let theme = PresentationTheme::new(&Default::default(), &resources, &Default::default()).unwrap();
+        let mut builder = NotesModalBuilder::default();
+        builder.add_notes(notes);
+        let operations = builder.build(&theme, PresentationState::default());
+        let RenderOperation::RenderDynamic(drawer) = &operations[0] else { panic!("not a dynamic operation") };
+        let dimensions = WindowSize { rows: 40, columns: 80, height: 0, width: 0 };
+        assert_eq!(count_texts(&drawer.as_render_operations(&dimensions)), expected);
+    }
+
+    #[test]
+    fn filtered_index() {
+        let state = PresentationState::default();
+        let drawer = make_drawer(&["Intro", "Body", "Outro"], state.clone());
+        let dimensions = WindowSize { rows: 40, columns: 80, height: 0, width: 0 };
+        let unfiltered = count_texts(&drawer.as_render_operations(&dimensions));
+
+        // Only the matching row is displayed along with the line that contains the filter.
+        state.set_slide_index_filter(Some("body".into()));
+        let filtered = count_texts(&drawer.as_render_operations(&dimensions));
+        assert_eq!(filtered, unfiltered - 1);
+    }
+}
@@ -0,0 +1,174 @@
+use crate::{
+    markdown::elements::{Line, Text},
+    presentation::Slide,
+    render::{
+        RenderError,
+        engine::RenderEngine,
+        operation::{MarginProperties, RenderOperation},
+        properties::WindowSize,
+    },
+    terminal::virt::{ImageBehavior, TerminalGrid, VirtualTerminal},
+    theme::{Alignment, Margin, ModalStyle},
+};
+use std::time::{Duration, Instant};
+
+/// The extra information displayed alongside the speaker notes when using the presenter view.
+///
+/// The speaker notes take up the top half of the screen while the bottom half contains a preview
+/// of the next slide and a timer that starts when the presentation is opened.
+pub(crate) struct PresenterView {
+    started: Instant,
+    last_elapsed: u64,
+}
+
+impl PresenterView {
+    pub(crate) fn new() -> Self {
+        Self { started: Instant::now(), last_elapsed: 0 }
+    }
+
+    /// Check whether the timer needs to be redrawn.
+    pub(crate) fn tick(&mut self) -> bool {
+        let elapsed = self.started.elapsed().as_secs();
+        let modified = elapsed != self.last_elapsed;
+        self.last_elapsed = elapsed;
+        modified
+    }
+
+    /// The margin that confines the speaker notes to the top half of the screen.
+    pub(crate) fn notes_margin(dimensions: &WindowSize) -> MarginProperties {
+        let (notes_rows, _) = Self::split_rows(dimensions);
+        let bottom = dimensions.rows.saturating_sub(notes_rows);
+        MarginProperties { horizontal: Margin::Fixed(0), top: 0, bottom }
+    }
+
+    /// Build the operations that draw the timer and the preview of the given slide.
+    pub(crate) fn build(
+        &self,
+        next_slide: Option<&Slide>,
+        style: &ModalStyle,
+        dimensions: &WindowSize,
+    ) -> Result<Vec<RenderOperation>, RenderError> {
+        let (notes_rows, preview_rows) = Self::split_rows(dimensions);
+        let label = match next_slide {
+            Some(_) => " next slide",
+            None => " end of presentation",
+        };
+        let timer = format!("{} ", format_elapsed(self.started.elapsed()));
+        let padding = (dimensions.columns as usize).saturating_sub(label.len() + timer.len());
+        let header = Line(vec![Text::new(format!("{label}{}{timer}", " ".repeat(padding)), style.style)]);
+        let mut operations = vec![
+            RenderOperation::JumpToRow { index: notes_rows },
+            RenderOperation::RenderText {
+                line: header.into(),
+                alignment: Alignment::Left { margin: Margin::Fixed(0) },
+            },
+        ];
+        let Some(slide) = next_slide else {
+            return Ok(operations);
+        };
+        let preview_dimensions = WindowSize {
+            rows: preview_rows,
+            columns: dimensions.columns,
+            height: dimensions.height / dimensions.rows.max(1) * preview_rows,
+            width: dimensions.width,
+        };
+        let mut terminal = VirtualTerminal::new(preview_dimensions, ImageBehavior::PrintAscii);
+        let engine = RenderEngine::new(&mut terminal, preview_dimensions, Default::default());
+        engine.render(slide.iter_operations())?;
+        let grid = terminal.into_contents();
+        for (index, line) in preview_lines(&grid).into_iter().enumerate() {
+            operations.extend([
+                RenderOperation::JumpToRow { index: notes_rows + 1 + index as u16 },
+                RenderOperation::RenderText {
+                    line: line.into(),
+                    alignment: Alignment::Left { margin: Margin::Fixed(0) },
+                },
+            ]);
+        }
+        Ok(operations)
+    }
+
+    fn split_rows(dimensions: &WindowSize) -> (u16, u16) {
+        let notes_rows = dimensions.rows / 2;
+        // One row is used for the header above the preview.
+        let preview_rows = dimensions.rows.saturating_sub(notes_rows + 1);
+        (notes_rows, preview_rows)
+    }
+}
+
+fn preview_lines(grid: &TerminalGrid) -> Vec<Line> {
+    let mut lines = Vec::new();
+    for row in &grid.rows {
+        let mut texts: Vec<Text> = Vec::new();
+        for cell in row {
+            let mut style = cell.style;
+            if style.colors.background.is_none() {
+                style.colors.background = grid.background_color;
+            }
+            match texts.last_mut() {
+                Some(text) if text.style == style => text.content.push(cell.character),
+                _ => texts.push(Text::new(cell.character.to_string(), style)),
+            }
+        }
+        lines.push(Line(texts));
+    }
+    lines
+}
+
+fn format_elapsed(elapsed: Duration) -> String {
+    let seconds = elapsed.as_secs();
+    format!("{:02}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::presentation::builder::utils::Test;
+    use rstest::rstest;
+
+    fn render(operations: &[RenderOperation], dimensions: WindowSize) -> Vec<String> {
+        let mut terminal = VirtualTerminal::new(dimensions, Default::default());
+        let engine = RenderEngine::new(&mut terminal, dimensions, Default::default());
+        engine.render(operations.iter()).expect("render failed");
+        let grid = terminal.into_contents();
+        grid.rows.iter().map(|row| row.iter().map(|cell| cell.character).collect()).collect()
+    }
+
+    #[test]
+    fn preview() {
+        let presentation = Test::new("hi").build();
+        let slide = presentation.iter_slides().next();
+        let dimensions = WindowSize { rows: 6, columns: 24, width: 0, height: 0 };
+        let operations =
+            PresenterView::new().build(slide, presentation.modal_style(), &dimensions).expect("build failed");
+        let lines = render(&operations, dimensions);
+        let expected = &[
+            "                        ",
+            "                        ",
+            "                        ",
+            " next slide    00:00:00 ",
+            "                        ",
+            "hi                      ",
+        ];
+        assert_eq!(lines, expected);
+    }
+
+    #[test]
+    fn end_of_presentation() {
+        let presentation = Test::new("hi").build();
+        let dimensions = WindowSize { rows: 4, columns: 30, width: 0, height: 0 };
+        let operations =
+            PresenterView::new().build(None, presentation.modal_style(), &dimensions).expect("build failed");
+        let lines = render(&operations, dimensions);
+        assert_eq!(lines[2], " end of presentation 00:00:00 ");
+        assert_eq!(lines[3], " ".repeat(30));
+    }
+
+    #[rstest]
+    #[case::seconds(5, "00:00:05")]
+    #[case::minutes(65, "00:01:05")]
+    #[case::hours(3725, "01:02:05")]
+    fn elapsed(#[case] seconds: u64, #[case] expected: &str) {
+        assert_eq!(format_elapsed(Duration::from_secs(seconds)), expected);
+    }
+}
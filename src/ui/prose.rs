@@ -0,0 +1,81 @@
+use crate::{
+    markdown::{elements::Line, text::WeightedLine},
+    render::{
+        operation::{AsRenderOperations, RenderOperation},
+        properties::WindowSize,
+    },
+    theme::{Alignment, Margin},
+};
+
+/// A line of prose that is capped to a maximum number of columns.
+///
+/// When the available width exceeds the maximum, the margins on both sides are grown so the text
+/// column is centered and lines wrap at the maximum width.
+#[derive(Debug)]
+pub(crate) struct RenderProse {
+    line: WeightedLine,
+    content: String,
+    alignment: Alignment,
+    max_columns: u16,
+}
+
+impl RenderProse {
+    pub(crate) fn new(line: Line, alignment: Alignment, max_columns: u16) -> Self {
+        let content = line.0.iter().map(|text| text.content.as_str()).collect();
+        Self { line: line.into(), content, alignment, max_columns }
+    }
+
+    fn alignment(&self, dimensions: &WindowSize) -> Alignment {
+        Self::capped_alignment(self.alignment, self.max_columns, dimensions)
+    }
+
+    /// Get the alignment to use so prose is capped to the given number of columns.
+    pub(crate) fn capped_alignment(alignment: Alignment, max_columns: u16, dimensions: &WindowSize) -> Alignment {
+        let Alignment::Left { margin } = alignment else {
+            return alignment;
+        };
+        let margin = margin.as_characters(dimensions.columns);
+        let available = dimensions.columns.saturating_sub(margin * 2);
+        if available <= max_columns {
+            return alignment;
+        }
+        let margin = (dimensions.columns - max_columns) / 2;
+        Alignment::Left { margin: Margin::Fixed(margin) }
+    }
+}
+
+impl AsRenderOperations for RenderProse {
+    fn as_render_operations(&self, dimensions: &WindowSize) -> Vec<RenderOperation> {
+        let alignment = self.alignment(dimensions);
+        vec![RenderOperation::RenderText { line: self.line.clone(), alignment }]
+    }
+
+    fn diffable_content(&self) -> Option<&str> {
+        Some(&self.content)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::narrow(Margin::Fixed(2), 50, Margin::Fixed(2))]
+    #[case::exact(Margin::Fixed(5), 100, Margin::Fixed(5))]
+    #[case::wide(Margin::Fixed(2), 130, Margin::Fixed(20))]
+    #[case::percent(Margin::Percent(10), 200, Margin::Fixed(55))]
+    fn alignment(#[case] margin: Margin, #[case] columns: u16, #[case] expected: Margin) {
+        let prose = RenderProse::new(Line::from("hi"), Alignment::Left { margin }, 90);
+        let dimensions = WindowSize { rows: 10, columns, width: 0, height: 0 };
+        assert_eq!(prose.alignment(&dimensions), Alignment::Left { margin: expected });
+    }
+
+    #[test]
+    fn centered_untouched() {
+        let alignment = Alignment::Center { minimum_margin: Margin::Fixed(1), minimum_size: 0 };
+        let prose = RenderProse::new(Line::from("hi"), alignment, 10);
+        let dimensions = WindowSize { rows: 10, columns: 100, width: 0, height: 0 };
+        assert_eq!(prose.alignment(&dimensions), alignment);
+    }
+}
@@ -0,0 +1,33 @@
+use crate::{
+    render::{
+        operation::{AsRenderOperations, MarginProperties, RenderOperation},
+        properties::WindowSize,
+    },
+    theme::QuietZone,
+};
+use std::rc::Rc;
+
+/// Shrinks the screen by the theme's quiet zone so everything rendered after it, including the
+/// footer, is inset from the edges of the screen.
+#[derive(Clone, Debug)]
+pub(crate) struct RenderQuietZone(QuietZone);
+
+impl RenderQuietZone {
+    pub(crate) fn new(quiet_zone: QuietZone) -> Self {
+        Self(quiet_zone)
+    }
+}
+
+impl From<RenderQuietZone> for RenderOperation {
+    fn from(quiet_zone: RenderQuietZone) -> Self {
+        Self::RenderDynamic(Rc::new(quiet_zone))
+    }
+}
+
+impl AsRenderOperations for RenderQuietZone {
+    fn as_render_operations(&self, dimensions: &WindowSize) -> Vec<RenderOperation> {
+        let QuietZone { horizontal, vertical } = self.0;
+        let vertical = vertical.as_characters(dimensions.rows);
+        vec![RenderOperation::ApplyMargin(MarginProperties { horizontal, top: vertical, bottom: vertical })]
+    }
+}
@@ -0,0 +1,164 @@
+use crate::render::operation::ScrollDirection;
+use std::ops::Range;
+
+pub(crate) const SCROLLBAR_TRACK: &str = " │";
+pub(crate) const SCROLLBAR_THUMB: &str = " █";
+pub(crate) const SCROLLBAR_WIDTH: u16 = 2;
+
+/// A window of a fixed number of rows over a longer list of rows.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct ScrollWindow {
+    rows: usize,
+    total: usize,
+    offset: usize,
+}
+
+impl ScrollWindow {
+    /// Construct a window that displays `rows` out of `total` rows.
+    ///
+    /// Returns `None` if all rows fit in the window.
+    pub(crate) fn new(rows: usize, total: usize) -> Option<Self> {
+        let rows = rows.max(1);
+        (total > rows).then_some(Self { rows, total, offset: 0 })
+    }
+
+    /// The rows currently visible.
+    pub(crate) fn visible(&self) -> Range<usize> {
+        self.offset..self.offset + self.rows
+    }
+
+    /// Move the window one row in the given direction, returning whether it moved.
+    pub(crate) fn scroll(&mut self, direction: ScrollDirection) -> bool {
+        let max_offset = self.total - self.rows;
+        let offset = match direction {
+            ScrollDirection::Up => self.offset.saturating_sub(1),
+            ScrollDirection::Down => (self.offset + 1).min(max_offset),
+        };
+        let modified = offset != self.offset;
+        self.offset = offset;
+        modified
+    }
+
+    /// Move the window the least amount needed to display the given rows.
+    ///
+    /// If they don't all fit in the window, it's only moved if none of them are visible.
+    pub(crate) fn reveal(&mut self, rows: Range<usize>) {
+        let visible = self.visible();
+        let max_offset = self.total - self.rows;
+        if rows.is_empty() {
+            return;
+        }
+        if rows.len() > self.rows {
+            if rows.end <= visible.start || rows.start >= visible.end {
+                self.offset = rows.start.min(max_offset);
+            }
+        } else if rows.start < visible.start {
+            self.offset = rows.start;
+        } else if rows.end > visible.end {
+            self.offset = (rows.end - self.rows).min(max_offset);
+        }
+    }
+
+    /// How faded the given row in the window is when `rows` rows at each edge fade out.
+    ///
+    /// This goes from 0, not faded, to 1 for the outermost row. Only edges that have more rows
+    /// beyond them fade.
+    pub(crate) fn fade(&self, row: usize, rows: usize) -> f32 {
+        let rows = rows.min(self.rows);
+        if rows == 0 {
+            return 0.0;
+        }
+        let top = match self.offset > 0 && row < rows {
+            true => (rows - row) as f32 / rows as f32,
+            false => 0.0,
+        };
+        let bottom_start = self.rows - rows;
+        let bottom = match self.offset + self.rows < self.total && row >= bottom_start {
+            true => (row - bottom_start + 1) as f32 / rows as f32,
+            false => 0.0,
+        };
+        top.max(bottom)
+    }
+
+    /// The scrollbar segment to display next to the given row in the window.
+    pub(crate) fn indicator(&self, row: usize) -> &'static str {
+        match scrollbar_thumb(self.rows, self.total, self.offset).contains(&row) {
+            true => SCROLLBAR_THUMB,
+            false => SCROLLBAR_TRACK,
+        }
+    }
+}
+
+/// Compute the rows within a window of `rows` rows that the scrollbar's thumb takes up.
+///
+/// The thumb is proportional to the visible fraction of the rows and it reaches the bottom of
+/// the track exactly when the window is scrolled all the way down.
+pub(crate) fn scrollbar_thumb(rows: usize, total: usize, offset: usize) -> Range<usize> {
+    let thumb_size = (rows * rows / total).max(1);
+    let thumb_start = offset * (rows - thumb_size) / (total - rows);
+    thumb_start..thumb_start + thumb_size
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn fits() {
+        assert_eq!(ScrollWindow::new(5, 5), None);
+    }
+
+    #[test]
+    fn scroll() {
+        let mut window = ScrollWindow::new(2, 4).expect("no window");
+        assert_eq!(window.visible(), 0..2);
+        assert_eq!(window.indicator(0), SCROLLBAR_THUMB);
+        assert_eq!(window.indicator(1), SCROLLBAR_TRACK);
+
+        assert!(!window.scroll(ScrollDirection::Up));
+        assert!(window.scroll(ScrollDirection::Down));
+        assert!(window.scroll(ScrollDirection::Down));
+        assert!(!window.scroll(ScrollDirection::Down));
+        assert_eq!(window.visible(), 2..4);
+        assert_eq!(window.indicator(0), SCROLLBAR_TRACK);
+        assert_eq!(window.indicator(1), SCROLLBAR_THUMB);
+    }
+
+    #[test]
+    fn reveal() {
+        let mut window = ScrollWindow::new(3, 10).expect("no window");
+        window.reveal(1..3);
+        assert_eq!(window.visible(), 0..3);
+
+        window.reveal(5..7);
+        assert_eq!(window.visible(), 4..7);
+
+        window.reveal(2..3);
+        assert_eq!(window.visible(), 2..5);
+
+        // Rows that don't fit only move the window if none of them are visible.
+        window.reveal(0..10);
+        assert_eq!(window.visible(), 2..5);
+
+        window.reveal(6..10);
+        assert_eq!(window.visible(), 6..9);
+    }
+
+    #[test]
+    fn fade() {
+        let mut window = ScrollWindow::new(4, 10).expect("no window");
+        // Nothing above so only the bottom fades.
+        let fades: Vec<_> = (0..4).map(|row| window.fade(row, 2)).collect();
+        assert_eq!(fades, &[0.0, 0.0, 0.5, 1.0]);
+
+        window.scroll(ScrollDirection::Down);
+        let fades: Vec<_> = (0..4).map(|row| window.fade(row, 2)).collect();
+        assert_eq!(fades, &[1.0, 0.5, 0.5, 1.0]);
+
+        window.reveal(6..10);
+        let fades: Vec<_> = (0..4).map(|row| window.fade(row, 2)).collect();
+        assert_eq!(fades, &[1.0, 0.5, 0.0, 0.0]);
+
+        assert_eq!(window.fade(0, 0), 0.0);
+    }
+}
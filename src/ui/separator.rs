@@ -8,9 +8,14 @@ use crate::{
         operation::{AsRenderOperations, BlockLine, RenderOperation},
         properties::WindowSize,
     },
-    theme::{Alignment, Margin},
+    theme::{Alignment, Margin, SeparatorStyle},
 };
 use std::rc::Rc;
+use unicode_width::UnicodeWidthChar;
+
+// The minimum number of separator characters to keep on each side of a heading.
+const MINIMUM_SIDE_LENGTH: usize = 2;
+const ELLIPSIS: char = '…';
 
 #[derive(Clone, Copy, Debug, Default)]
 pub(crate) enum SeparatorWidth {
@@ -25,13 +30,19 @@ pub(crate) struct RenderSeparator {
     heading: Line,
     width: SeparatorWidth,
     font_size: u8,
+    style: SeparatorStyle,
 }
 
 impl RenderSeparator {
     pub(crate) fn new<S: Into<Line>>(heading: S, width: SeparatorWidth, font_size: u8) -> Self {
         let mut heading: Line = heading.into();
         heading.apply_style(&TextStyle::default().size(font_size));
-        Self { heading, width, font_size }
+        Self { heading, width, font_size, style: Default::default() }
+    }
+
+    pub(crate) fn style(mut self, style: SeparatorStyle) -> Self {
+        self.style = style;
+        self
     }
 }
 
@@ -43,7 +54,7 @@ impl From<RenderSeparator> for RenderOperation {
 
 impl AsRenderOperations for RenderSeparator {
     fn as_render_operations(&self, dimensions: &WindowSize) -> Vec<RenderOperation> {
-        let character = "—";
+        let character = self.style.character();
         let width = match self.width {
             SeparatorWidth::Fixed(width) => {
                 let Positioning { max_line_length, .. } =
@@ -59,11 +70,12 @@ impl AsRenderOperations for RenderSeparator {
         let separator = match self.heading.width() == 0 {
             true => Line::from(Text::new(character.repeat(width), style)),
             false => {
-                let width = width.saturating_sub(self.heading.width());
+                let heading = truncate(&self.heading, width.saturating_sub(MINIMUM_SIDE_LENGTH * 2));
+                let width = width.saturating_sub(heading.width());
                 let (dashes_len, remainder) = (width / 2, width % 2);
                 let mut dashes = character.repeat(dashes_len);
                 let mut line = Line::from(Text::new(dashes.clone(), style));
-                line.0.extend(self.heading.0.iter().cloned());
+                line.0.extend(heading.0);
 
                 if remainder > 0 {
                     dashes.push_str(character);
@@ -74,12 +86,77 @@ impl AsRenderOperations for RenderSeparator {
         };
         vec![RenderOperation::RenderBlockLine(BlockLine {
             prefix: "".into(),
+            prefix_link: None,
             right_padding_length: 0,
             repeat_prefix_on_wrap: false,
             text: separator.into(),
             block_length: width as u16,
             block_color: None,
             alignment: Alignment::Center { minimum_size: 1, minimum_margin: Margin::Fixed(0) },
+            border: None,
+            overflow: Default::default(),
         })]
     }
 }
+
+/// Truncate a line so it fits in the given width, using an ellipsis to signal it was cut.
+fn truncate(line: &Line, max_width: usize) -> Line {
+    if line.width() <= max_width {
+        return line.clone();
+    }
+    if max_width == 0 {
+        return Line::default();
+    }
+    let mut output = Vec::new();
+    // Leave space for the ellipsis.
+    let mut available = max_width - 1;
+    for text in &line.0 {
+        let mut content = String::new();
+        for c in text.content.chars() {
+            let width = c.width().unwrap_or(0);
+            if width > available {
+                output.push(Text::new(content, text.style));
+                output.push(Text::new(ELLIPSIS.to_string(), text.style));
+                return Line(output);
+            }
+            available -= width;
+            content.push(c);
+        }
+        output.push(Text::new(content, text.style));
+    }
+    Line(output)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{render::engine::RenderEngine, terminal::virt::VirtualTerminal};
+    use rstest::rstest;
+
+    fn render(separator: RenderSeparator, columns: u16) -> String {
+        let dimensions = WindowSize { rows: 1, columns, width: 0, height: 0 };
+        let mut terminal = VirtualTerminal::new(dimensions, Default::default());
+        let engine = RenderEngine::new(&mut terminal, dimensions, Default::default());
+        let operations = [RenderOperation::from(separator)];
+        engine.render(operations.iter()).expect("render failed");
+        let grid = terminal.into_contents();
+        grid.rows[0].iter().map(|cell| cell.character).collect()
+    }
+
+    #[rstest]
+    #[case::fits("hello", "—————hello—————")]
+    #[case::exact("hello world", "——hello world——")]
+    #[case::truncated("hello there world", "——hello ther…——")]
+    fn render_heading(#[case] heading: &str, #[case] expected: &str) {
+        let separator = RenderSeparator::new(heading, SeparatorWidth::FitToWindow, 1);
+        assert_eq!(render(separator, 15), expected);
+    }
+
+    #[test]
+    fn truncate_styled_line() {
+        let bold = TextStyle::default().bold();
+        let line = Line(vec![Text::new("abc", TextStyle::default()), Text::new("def", bold)]);
+        let expected = Line(vec![Text::new("abc", TextStyle::default()), Text::new("d", bold), Text::new("…", bold)]);
+        assert_eq!(truncate(&line, 5), expected);
+    }
+}
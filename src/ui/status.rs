@@ -0,0 +1,64 @@
+use crate::{
+    markdown::{elements::Text, text_style::TextStyle},
+    render::{
+        operation::{AsRenderOperations, RenderOperation},
+        properties::WindowSize,
+    },
+    theme::{Alignment, Margin},
+};
+use unicode_width::UnicodeWidthChar;
+
+/// A short message displayed in the last row of the screen.
+#[derive(Clone, Debug)]
+pub(crate) struct StatusLine {
+    message: String,
+}
+
+impl StatusLine {
+    pub(crate) fn new<S: Into<String>>(message: S) -> Self {
+        Self { message: message.into() }
+    }
+
+    fn fit(&self, columns: usize) -> String {
+        let mut output = String::from(" ");
+        let mut width = 1;
+        for c in self.message.chars() {
+            let char_width = c.width().unwrap_or(0);
+            if width + char_width > columns {
+                break;
+            }
+            output.push(c);
+            width += char_width;
+        }
+        output.extend(std::iter::repeat_n(' ', columns.saturating_sub(width)));
+        output
+    }
+}
+
+impl AsRenderOperations for StatusLine {
+    fn as_render_operations(&self, dimensions: &WindowSize) -> Vec<RenderOperation> {
+        let text = Text::new(self.fit(dimensions.columns as usize), TextStyle::default().bold());
+        vec![
+            RenderOperation::JumpToBottomRow { index: 0 },
+            RenderOperation::RenderText {
+                line: vec![text].into(),
+                alignment: Alignment::Left { margin: Margin::Fixed(0) },
+            },
+        ]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::padded("hi", 6, " hi   ")]
+    #[case::exact("hello", 6, " hello")]
+    #[case::truncated("hello world", 6, " hello")]
+    #[case::wide("ほげ", 4, " ほ ")]
+    fn fit(#[case] message: &str, #[case] columns: usize, #[case] expected: &str) {
+        assert_eq!(StatusLine::new(message).fit(columns), expected);
+    }
+}
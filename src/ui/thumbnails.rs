@@ -0,0 +1,223 @@
+use crate::{
+    markdown::{
+        elements::{Line, Text},
+        text_style::Color,
+    },
+    presentation::{Presentation, Slide},
+    render::{
+        RenderError,
+        engine::{RenderEngine, RenderEngineOptions},
+        operation::{ImagePosition, ImageRenderProperties, ImageSize, RenderOperation},
+        properties::WindowSize,
+    },
+    terminal::{
+        image::{
+            Image,
+            printer::{ImageRegistry, ImageSpec, PrintImageError},
+        },
+        virt::{ImageBehavior, TerminalGrid, VirtualTerminal},
+    },
+    theme::ModalStyle,
+};
+use image::{DynamicImage, Rgb, RgbImage};
+use std::collections::HashMap;
+use unicode_width::UnicodeWidthStr;
+
+/// The number of pixels every terminal cell is turned into when rasterizing a slide.
+const CELL_WIDTH: u32 = 2;
+const CELL_HEIGHT: u32 = 4;
+
+/// The number of thumbnails displayed on every row of the grid.
+const THUMBNAILS_PER_ROW: u16 = 3;
+
+/// The number of columns between two consecutive thumbnails.
+const THUMBNAIL_SPACING: u16 = 2;
+
+/// Renders slides into thumbnails and lays them out in a grid.
+///
+/// Thumbnails are generated lazily the first time they're displayed and are kept around until
+/// either the presentation is reloaded or the terminal is resized.
+pub(crate) struct SlideThumbnails {
+    registry: ImageRegistry,
+    options: RenderEngineOptions,
+    dimensions: Option<(u16, u16)>,
+    cache: HashMap<usize, Image>,
+}
+
+impl SlideThumbnails {
+    pub(crate) fn new(registry: ImageRegistry, options: RenderEngineOptions) -> Self {
+        Self { registry, options, dimensions: None, cache: Default::default() }
+    }
+
+    /// Forget about all generated thumbnails.
+    pub(crate) fn clear(&mut self) {
+        self.cache.clear();
+    }
+
+    /// Build the operations that display the thumbnail grid for the page the current slide is in.
+    pub(crate) fn build(
+        &mut self,
+        presentation: &Presentation,
+        style: &ModalStyle,
+        dimensions: WindowSize,
+    ) -> Result<Vec<RenderOperation>, RenderError> {
+        if self.dimensions != Some((dimensions.columns, dimensions.rows)) {
+            self.cache.clear();
+            self.dimensions = Some((dimensions.columns, dimensions.rows));
+        }
+        let slides: Vec<_> = presentation.iter_slides().collect();
+        let current_slide = presentation.current_slide_index();
+        let layout = GridLayout::new(&dimensions);
+        let page_size = layout.page_size();
+        let first = current_slide / page_size * page_size;
+        let last = (first + page_size).min(slides.len());
+
+        let mut operations = vec![RenderOperation::SetColors(style.style.colors), RenderOperation::ClearScreen];
+        for row_start in (first..last).step_by(THUMBNAILS_PER_ROW as usize) {
+            let row_end = (row_start + THUMBNAILS_PER_ROW as usize).min(last);
+            let mut labels = Line::default();
+            let mut label_column = 0;
+            operations.push(RenderOperation::RenderLineBreak);
+            for (position, index) in (row_start..row_end).enumerate() {
+                let column = layout.column(position);
+                let image = self.thumbnail(index, slides[index], dimensions)?;
+                let properties = ImageRenderProperties {
+                    size: ImageSize::Specific(layout.columns, layout.rows),
+                    restore_cursor: true,
+                    position: ImagePosition::Column(column),
+                    ..Default::default()
+                };
+                operations.push(RenderOperation::RenderImage(image, properties));
+
+                let label = format!(" {} ", index + 1);
+                let label_style = if index == current_slide { style.selection_style } else { style.style };
+                let padding = column.saturating_sub(label_column) as usize;
+                labels.0.push(Text::new(" ".repeat(padding), style.style));
+                label_column = column + label.width() as u16;
+                labels.0.push(Text::new(label, label_style));
+            }
+            operations.extend((0..layout.rows).map(|_| RenderOperation::RenderLineBreak));
+            operations.push(RenderOperation::RenderText { line: labels.into(), alignment: Default::default() });
+            operations.push(RenderOperation::RenderLineBreak);
+        }
+        Ok(operations)
+    }
+
+    fn thumbnail(&mut self, index: usize, slide: &Slide, dimensions: WindowSize) -> Result<Image, RenderError> {
+        if let Some(image) = self.cache.get(&index) {
+            return Ok(image.clone());
+        }
+        let mut terminal = VirtualTerminal::new(dimensions, ImageBehavior::PrintAscii);
+        let engine = RenderEngine::new(&mut terminal, dimensions, self.options.clone());
+        engine.render(slide.iter_operations())?;
+        let image = rasterize(&terminal.into_contents());
+        let image = self
+            .registry
+            .register(ImageSpec::Generated(image))
+            .map_err(|e| PrintImageError::other(format!("generating thumbnail: {e}")))?;
+        self.cache.insert(index, image.clone());
+        Ok(image)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct GridLayout {
+    columns: u16,
+    rows: u16,
+    grid_rows: u16,
+}
+
+impl GridLayout {
+    fn new(dimensions: &WindowSize) -> Self {
+        let spacing = THUMBNAIL_SPACING * (THUMBNAILS_PER_ROW + 1);
+        let columns = (dimensions.columns.saturating_sub(spacing) / THUMBNAILS_PER_ROW).max(1);
+        // Thumbnails keep the terminal's aspect ratio.
+        let rows = (dimensions.rows as u32 * columns as u32 / dimensions.columns.max(1) as u32).max(1) as u16;
+        // Every row of thumbnails has an empty row above it and a row of labels below it.
+        let grid_rows = (dimensions.rows / (rows + 2)).max(1);
+        Self { columns, rows, grid_rows }
+    }
+
+    fn page_size(&self) -> usize {
+        (self.grid_rows * THUMBNAILS_PER_ROW) as usize
+    }
+
+    fn column(&self, position: usize) -> u16 {
+        THUMBNAIL_SPACING + position as u16 * (self.columns + THUMBNAIL_SPACING)
+    }
+}
+
+fn rasterize(grid: &TerminalGrid) -> DynamicImage {
+    let default_background = grid.background_color.unwrap_or(Color::Black);
+    let columns = grid.rows.first().map(Vec::len).unwrap_or(0).max(1) as u32;
+    let rows = grid.rows.len().max(1) as u32;
+    let mut image = RgbImage::new(columns * CELL_WIDTH, rows * CELL_HEIGHT);
+    for (row, cells) in grid.rows.iter().enumerate() {
+        for (column, cell) in cells.iter().enumerate() {
+            let background = to_rgb(cell.style.colors.background.unwrap_or(default_background));
+            let foreground = to_rgb(cell.style.colors.foreground.unwrap_or(Color::White));
+            for y in 0..CELL_HEIGHT {
+                // Images are printed using upper half blocks so keep that shape, and draw any
+                // other character as a block in the middle of the cell.
+                let is_foreground = match cell.character {
+                    '▀' => y < CELL_HEIGHT / 2,
+                    c if c.is_whitespace() => false,
+                    _ => y > 0 && y < CELL_HEIGHT - 1,
+                };
+                let color = if is_foreground { foreground } else { background };
+                for x in 0..CELL_WIDTH {
+                    image.put_pixel(column as u32 * CELL_WIDTH + x, row as u32 * CELL_HEIGHT + y, color);
+                }
+            }
+        }
+    }
+    image.into()
+}
+
+fn to_rgb(color: Color) -> Rgb<u8> {
+    let (r, g, b) = color.to_rgb();
+    Rgb([r, g, b])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{markdown::text_style::TextStyle, terminal::virt::StyledChar};
+
+    #[test]
+    fn layout() {
+        let dimensions = WindowSize { rows: 40, columns: 100, width: 100, height: 40 };
+        let layout = GridLayout::new(&dimensions);
+        // (100 - 8) / 3 = 30 columns, 40 * 30 / 100 = 12 rows, 40 / 14 = 2 rows of thumbnails
+        assert_eq!(layout, GridLayout { columns: 30, rows: 12, grid_rows: 2 });
+        assert_eq!(layout.page_size(), 6);
+        assert_eq!(layout.column(0), 2);
+        assert_eq!(layout.column(2), 66);
+    }
+
+    #[test]
+    fn tiny_layout() {
+        let dimensions = WindowSize { rows: 2, columns: 5, width: 5, height: 2 };
+        let layout = GridLayout::new(&dimensions);
+        assert_eq!(layout, GridLayout { columns: 1, rows: 1, grid_rows: 1 });
+    }
+
+    #[test]
+    fn rasterize_cells() {
+        let style = TextStyle::default().fg_color(Color::Red).bg_color(Color::Blue);
+        let grid = TerminalGrid {
+            rows: vec![vec![StyledChar::new('x', style), StyledChar::new(' ', style)]],
+            background_color: None,
+            images: Default::default(),
+        };
+        let image = rasterize(&grid).into_rgb8();
+        assert_eq!(image.dimensions(), (4, 4));
+
+        let red = Rgb([0xff, 0, 0]);
+        let blue = Rgb([0, 0, 0xff]);
+        let column: Vec<_> = (0..4).map(|y| *image.get_pixel(0, y)).collect();
+        assert_eq!(column, &[blue, red, red, blue]);
+        let column: Vec<_> = (0..4).map(|y| *image.get_pixel(3, y)).collect();
+        assert_eq!(column, &[blue, blue, blue, blue]);
+    }
+}
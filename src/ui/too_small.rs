@@ -0,0 +1,53 @@
+use crate::{
+    config::MinimumSizeConfig,
+    markdown::{elements::Text, text_style::Colors},
+    render::{
+        operation::{AsRenderOperations, RenderOperation},
+        properties::WindowSize,
+    },
+    theme::Alignment,
+};
+
+/// A message displayed instead of the presentation while the terminal is too small to display it.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct TerminalTooSmall {
+    columns: u16,
+    rows: u16,
+}
+
+impl TerminalTooSmall {
+    /// Check whether the terminal is smaller than the configured minimum size.
+    pub(crate) fn check(minimum: &MinimumSizeConfig, dimensions: &WindowSize) -> Option<Self> {
+        let too_small = dimensions.columns < minimum.columns || dimensions.rows < minimum.rows;
+        too_small.then_some(Self { columns: minimum.columns, rows: minimum.rows })
+    }
+}
+
+impl AsRenderOperations for TerminalTooSmall {
+    fn as_render_operations(&self, _: &WindowSize) -> Vec<RenderOperation> {
+        let text = Text::from(format!("terminal too small (need ≥ {}x{})", self.columns, self.rows));
+        let alignment = Alignment::Center { minimum_margin: Default::default(), minimum_size: 0 };
+        vec![
+            RenderOperation::SetColors(Colors::default()),
+            RenderOperation::ClearScreen,
+            RenderOperation::JumpToVerticalCenter,
+            RenderOperation::RenderText { line: vec![text].into(), alignment },
+        ]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::fits(80, 24, false)]
+    #[case::too_narrow(79, 30, true)]
+    #[case::too_short(100, 23, true)]
+    fn check(#[case] columns: u16, #[case] rows: u16, #[case] too_small: bool) {
+        let minimum = MinimumSizeConfig { columns: 80, rows: 24 };
+        let dimensions = WindowSize { rows, columns, height: 0, width: 0 };
+        assert_eq!(TerminalTooSmall::check(&minimum, &dimensions).is_some(), too_small);
+    }
+}